@@ -990,14 +990,15 @@ mod tests {
     use super::*;
     use crate::test_utils::TestBlockBuilder;
     use alloy_eips::eip7685::Requests;
-    use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue};
+    use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue, U256};
     use rand::Rng;
     use reth_errors::ProviderResult;
     use reth_ethereum_primitives::{EthPrimitives, Receipt};
     use reth_primitives_traits::{Account, Bytecode};
     use reth_storage_api::{
         AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider,
-        StateProofProvider, StateProvider, StateRootProvider, StorageRootProvider,
+        HashedStateRangeProvider, StateProofProvider, StateProvider, StateRootProvider,
+        StorageRootProvider,
     };
     use reth_trie::{
         AccountProof, HashedStorage, MultiProof, MultiProofTargets, StorageMultiProof,
@@ -1103,6 +1104,35 @@ mod tests {
         }
     }
 
+    impl HashedStateRangeProvider for MockStateProvider {
+        fn hashed_account_range(
+            &self,
+            _hashed_state: HashedPostState,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<Vec<(B256, Account)>> {
+            Ok(Vec::new())
+        }
+
+        fn hashed_storage_range(
+            &self,
+            _hashed_address: B256,
+            _hashed_storage: HashedStorage,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<Vec<(B256, U256)>> {
+            Ok(Vec::new())
+        }
+
+        fn hashed_storage_root(
+            &self,
+            _hashed_address: B256,
+            _hashed_storage: HashedStorage,
+        ) -> ProviderResult<B256> {
+            Ok(B256::default())
+        }
+    }
+
     impl StorageRootProvider for MockStateProvider {
         fn storage_root(
             &self,
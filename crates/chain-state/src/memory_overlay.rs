@@ -1,11 +1,14 @@
 use super::ExecutedBlockWithTrieUpdates;
 use alloy_consensus::BlockHeader;
-use alloy_primitives::{keccak256, Address, BlockNumber, Bytes, StorageKey, StorageValue, B256};
+use alloy_primitives::{
+    keccak256, Address, BlockNumber, Bytes, StorageKey, StorageValue, B256, U256,
+};
 use reth_errors::ProviderResult;
 use reth_primitives_traits::{Account, Bytecode, NodePrimitives};
 use reth_storage_api::{
-    AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider, StateProofProvider,
-    StateProvider, StateRootProvider, StorageRootProvider,
+    AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider,
+    HashedStateRangeProvider, StateProofProvider, StateProvider, StateRootProvider,
+    StorageRootProvider,
 };
 use reth_trie::{
     updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof,
@@ -202,6 +205,43 @@ impl<N: NodePrimitives> StateProofProvider for MemoryOverlayStateProviderRef<'_,
     }
 }
 
+impl<N: NodePrimitives> HashedStateRangeProvider for MemoryOverlayStateProviderRef<'_, N> {
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        let mut state = self.trie_input().state.clone();
+        state.extend(hashed_state);
+        self.historical.hashed_account_range(state, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        let state = &self.trie_input().state;
+        let mut hashed_storage = state.storages.get(&hashed_address).cloned().unwrap_or_default();
+        hashed_storage.extend(&storage);
+        self.historical.hashed_storage_range(hashed_address, hashed_storage, start, limit)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        let state = &self.trie_input().state;
+        let mut hashed_storage = state.storages.get(&hashed_address).cloned().unwrap_or_default();
+        hashed_storage.extend(&storage);
+        self.historical.hashed_storage_root(hashed_address, hashed_storage)
+    }
+}
+
 impl<N: NodePrimitives> HashedPostStateProvider for MemoryOverlayStateProviderRef<'_, N> {
     fn hashed_post_state(&self, bundle_state: &BundleState) -> HashedPostState {
         self.historical.hashed_post_state(bundle_state)
@@ -134,7 +134,8 @@ impl<C: ChainSpecParser> EnvironmentArgs<C> {
             self.chain.clone(),
             static_file_provider,
         )
-        .with_prune_modes(prune_modes.clone());
+        .with_prune_modes(prune_modes.clone())
+        .with_record_preimages(self.db.record_preimages);
 
         // Check for consistency between database and static files.
         if let Some(unwind_target) = factory
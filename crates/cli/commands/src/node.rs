@@ -10,7 +10,7 @@ use reth_db::init_db;
 use reth_node_builder::NodeBuilder;
 use reth_node_core::{
     args::{
-        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineArgs, EraArgs, NetworkArgs,
+        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineArgs, EraArgs, ExExArgs, NetworkArgs,
         PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
     },
     node_config::NodeConfig,
@@ -113,6 +113,10 @@ pub struct NodeCommand<C: ChainSpecParser, Ext: clap::Args + fmt::Debug = NoArgs
     #[command(flatten, next_help_heading = "ERA")]
     pub era: EraArgs,
 
+    /// All `ExEx` related arguments with --exex prefix
+    #[command(flatten, next_help_heading = "ExEx")]
+    pub exex: ExExArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -168,6 +172,7 @@ where
             ext,
             engine,
             era,
+            exex,
         } = self;
 
         // set up node config
@@ -187,6 +192,7 @@ where
             pruning,
             engine,
             era,
+            exex,
         };
 
         let data_dir = node_config.datadir();
@@ -0,0 +1,173 @@
+//! A handle to control the [`LocalMiner`](crate::LocalMiner).
+
+use crate::MiningMode;
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{B256, U256};
+use parking_lot::Mutex;
+use reth_payload_builder::{PendingStateChanges, StateChanges};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::{mpsc, oneshot};
+
+/// A cloneable handle to control a [`LocalMiner`](crate::LocalMiner), for example from the
+/// `anvil` RPC namespace.
+///
+/// A handle can be created before the miner it controls, its commands are processed once a miner
+/// is launched with [`LocalMiner::with_handle`](crate::LocalMiner::with_handle).
+#[derive(Debug, Clone)]
+pub struct LocalMinerHandle {
+    /// Sender half of the command channel.
+    to_miner: mpsc::UnboundedSender<LocalMinerCommand>,
+    /// Receiver half of the command channel, taken by the miner that is controlled by this
+    /// handle.
+    commands: Arc<Mutex<Option<mpsc::UnboundedReceiver<LocalMinerCommand>>>>,
+    /// State changes that the payload builder applies to the blocks mined on request.
+    pending_state_changes: PendingStateChanges,
+}
+
+impl LocalMinerHandle {
+    /// Creates a new handle that is not attached to a miner yet.
+    pub fn new() -> Self {
+        let (to_miner, commands) = mpsc::unbounded_channel();
+        Self {
+            to_miner,
+            commands: Arc::new(Mutex::new(Some(commands))),
+            pending_state_changes: PendingStateChanges::default(),
+        }
+    }
+
+    /// Returns the state changes that the payload builder must apply for the blocks mined by
+    /// [`Self::set_state`].
+    pub const fn pending_state_changes(&self) -> &PendingStateChanges {
+        &self.pending_state_changes
+    }
+
+    /// Takes the receiver of the commands sent through this handle.
+    ///
+    /// Returns `None` if the handle is already attached to a miner.
+    pub(crate) fn take_commands(&self) -> Option<mpsc::UnboundedReceiver<LocalMinerCommand>> {
+        self.commands.lock().take()
+    }
+
+    /// Mines the given number of blocks and returns their hashes.
+    ///
+    /// If `timestamp_interval` is set, the timestamp of each block is the timestamp of its parent
+    /// plus the interval.
+    pub async fn mine(
+        &self,
+        blocks: u64,
+        timestamp_interval: Option<u64>,
+    ) -> eyre::Result<Vec<B256>> {
+        self.send(|tx| LocalMinerCommand::Mine { blocks, timestamp_interval, tx }).await?
+    }
+
+    /// Mines a block that applies the given state changes and returns its hash.
+    ///
+    /// The changes are only applied by payload builders that support
+    /// [`PendingStateChanges`].
+    pub async fn set_state(&self, changes: StateChanges) -> eyre::Result<B256> {
+        self.send(|tx| LocalMinerCommand::SetState { changes, tx }).await?
+    }
+
+    /// Replaces the mining mode of the miner.
+    pub async fn set_mining_mode(&self, mode: MiningMode) -> eyre::Result<()> {
+        self.send(|tx| LocalMinerCommand::SetMiningMode { mode, tx }).await
+    }
+
+    /// Returns `true` if a block is mined for every transaction that is added to the pool.
+    pub async fn is_automine(&self) -> eyre::Result<bool> {
+        self.send(LocalMinerCommand::IsAutomine).await
+    }
+
+    /// Sets the exact timestamp of the next mined block.
+    ///
+    /// Fails if the timestamp is not greater than the timestamp of the current head.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> eyre::Result<()> {
+        self.send(|tx| LocalMinerCommand::SetNextBlockTimestamp { timestamp, tx }).await?
+    }
+
+    /// Moves the clock of the miner forward by the given number of seconds and returns the total
+    /// offset of its clock to the system time.
+    pub async fn increase_time(&self, seconds: u64) -> eyre::Result<i64> {
+        self.send(|tx| LocalMinerCommand::IncreaseTime { seconds, tx }).await
+    }
+
+    /// Sets the clock of the miner to the given timestamp and returns the number of seconds it
+    /// is ahead of the system time.
+    pub async fn set_time(&self, timestamp: u64) -> eyre::Result<u64> {
+        self.send(|tx| LocalMinerCommand::SetTime { timestamp, tx }).await
+    }
+
+    /// Sets a fixed interval between the timestamps of mined blocks, or removes it, and returns
+    /// the previous interval.
+    pub async fn set_block_timestamp_interval(
+        &self,
+        interval: Option<u64>,
+    ) -> eyre::Result<Option<u64>> {
+        self.send(|tx| LocalMinerCommand::SetBlockTimestampInterval { interval, tx }).await
+    }
+
+    /// Takes a snapshot of the current head and returns its id.
+    pub async fn snapshot(&self) -> eyre::Result<U256> {
+        self.send(LocalMinerCommand::Snapshot).await
+    }
+
+    /// Rewinds the chain to the head of the given snapshot.
+    ///
+    /// This discards the snapshot and all snapshots taken after it. Returns `false` if there is no
+    /// snapshot with the given id.
+    pub async fn revert(&self, id: U256) -> eyre::Result<bool> {
+        self.send(|tx| LocalMinerCommand::Revert { id, tx }).await?
+    }
+
+    /// Returns the heads of all snapshots by their id.
+    pub async fn snapshots(&self) -> eyre::Result<BTreeMap<U256, BlockNumHash>> {
+        self.send(LocalMinerCommand::Snapshots).await
+    }
+
+    /// Sends a command to the miner and waits for the response.
+    async fn send<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> LocalMinerCommand,
+    ) -> eyre::Result<T> {
+        let (tx, rx) = oneshot::channel();
+        self.to_miner.send(command(tx)).map_err(|_| eyre::eyre!("local miner is not running"))?;
+        rx.await.map_err(|_| eyre::eyre!("local miner is not running"))
+    }
+}
+
+impl Default for LocalMinerHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Commands sent to the [`LocalMiner`](crate::LocalMiner) through a [`LocalMinerHandle`].
+#[derive(Debug)]
+pub(crate) enum LocalMinerCommand {
+    /// Mine the given number of blocks.
+    Mine {
+        blocks: u64,
+        timestamp_interval: Option<u64>,
+        tx: oneshot::Sender<eyre::Result<Vec<B256>>>,
+    },
+    /// Mine a block that applies the given state changes.
+    SetState { changes: StateChanges, tx: oneshot::Sender<eyre::Result<B256>> },
+    /// Replace the mining mode.
+    SetMiningMode { mode: MiningMode, tx: oneshot::Sender<()> },
+    /// Return whether the miner is in [`MiningMode::Instant`].
+    IsAutomine(oneshot::Sender<bool>),
+    /// Set the timestamp of the next block.
+    SetNextBlockTimestamp { timestamp: u64, tx: oneshot::Sender<eyre::Result<()>> },
+    /// Move the clock forward.
+    IncreaseTime { seconds: u64, tx: oneshot::Sender<i64> },
+    /// Set the clock.
+    SetTime { timestamp: u64, tx: oneshot::Sender<u64> },
+    /// Set or remove the interval between block timestamps.
+    SetBlockTimestampInterval { interval: Option<u64>, tx: oneshot::Sender<Option<u64>> },
+    /// Take a snapshot of the current head.
+    Snapshot(oneshot::Sender<U256>),
+    /// Rewind the chain to a snapshot.
+    Revert { id: U256, tx: oneshot::Sender<eyre::Result<bool>> },
+    /// Return the heads of all snapshots.
+    Snapshots(oneshot::Sender<BTreeMap<U256, BlockNumHash>>),
+}
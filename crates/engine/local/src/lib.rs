@@ -8,8 +8,10 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod handle;
 pub mod miner;
 pub mod payload;
 
+pub use handle::LocalMinerHandle;
 pub use miner::{LocalMiner, MiningMode};
 pub use payload::LocalPayloadAttributesBuilder;
@@ -1,7 +1,9 @@
 //! Contains the implementation of the mining mode for the local engine.
 
+use crate::handle::{LocalMinerCommand, LocalMinerHandle};
 use alloy_consensus::BlockHeader;
-use alloy_primitives::{TxHash, B256};
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{TxHash, B256, U256};
 use alloy_rpc_types_engine::ForkchoiceState;
 use eyre::OptionExt;
 use futures_util::{stream::Fuse, StreamExt};
@@ -13,12 +15,13 @@ use reth_payload_primitives::{
 use reth_provider::BlockReader;
 use reth_transaction_pool::TransactionPool;
 use std::{
+    collections::BTreeMap,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
     time::{Duration, UNIX_EPOCH},
 };
-use tokio::time::Interval;
+use tokio::{sync::mpsc, time::Interval};
 use tokio_stream::wrappers::ReceiverStream;
 use tracing::error;
 
@@ -30,6 +33,8 @@ pub enum MiningMode {
     Instant(Fuse<ReceiverStream<TxHash>>),
     /// In this mode a block is built at a fixed interval.
     Interval(Interval),
+    /// In this mode blocks are only built on request, see [`LocalMinerHandle::mine`].
+    Manual,
 }
 
 impl MiningMode {
@@ -65,6 +70,7 @@ impl Future for MiningMode {
                 }
                 Poll::Pending
             }
+            Self::Manual => Poll::Pending,
         }
     }
 }
@@ -84,6 +90,30 @@ pub struct LocalMiner<T: PayloadTypes, B> {
     last_timestamp: u64,
     /// Stores latest mined blocks.
     last_block_hashes: Vec<B256>,
+    /// Number of the latest mined block.
+    last_block_number: u64,
+    /// Offset of the miner's clock to the system time, in seconds.
+    time_offset: i64,
+    /// Exact timestamp of the next block, if set.
+    next_timestamp: Option<u64>,
+    /// Fixed interval between block timestamps, if set.
+    timestamp_interval: Option<u64>,
+    /// Snapshots of the chain by their id.
+    snapshots: BTreeMap<U256, Snapshot>,
+    /// Id of the next snapshot.
+    next_snapshot_id: U256,
+    /// The handle that controls this miner.
+    handle: LocalMinerHandle,
+    /// Commands sent through the handle.
+    commands: mpsc::UnboundedReceiver<LocalMinerCommand>,
+}
+
+/// Head of the chain at the time a snapshot was taken.
+#[derive(Debug)]
+struct Snapshot {
+    last_timestamp: u64,
+    last_block_hashes: Vec<B256>,
+    last_block_number: u64,
 }
 
 impl<T, B> LocalMiner<T, B>
@@ -101,6 +131,8 @@ where
     ) -> Self {
         let latest_header =
             provider.sealed_header(provider.best_block_number().unwrap()).unwrap().unwrap();
+        let handle = LocalMinerHandle::new();
+        let commands = handle.take_commands().expect("new handle has a receiver");
 
         Self {
             payload_attributes_builder,
@@ -109,9 +141,33 @@ where
             payload_builder,
             last_timestamp: latest_header.timestamp(),
             last_block_hashes: vec![latest_header.hash()],
+            last_block_number: latest_header.number(),
+            time_offset: 0,
+            next_timestamp: None,
+            timestamp_interval: None,
+            snapshots: BTreeMap::new(),
+            next_snapshot_id: U256::from(1),
+            handle,
+            commands,
         }
     }
 
+    /// Lets the given handle control this miner.
+    ///
+    /// # Panics
+    ///
+    /// If the handle already controls another miner.
+    pub fn with_handle(mut self, handle: LocalMinerHandle) -> Self {
+        self.commands = handle.take_commands().expect("handle already controls a miner");
+        self.handle = handle;
+        self
+    }
+
+    /// Returns the handle that controls this miner.
+    pub const fn handle(&self) -> &LocalMinerHandle {
+        &self.handle
+    }
+
     /// Runs the [`LocalMiner`] in a loop, polling the miner and building payloads.
     pub async fn run(mut self) {
         let mut fcu_interval = tokio::time::interval(Duration::from_secs(1));
@@ -129,14 +185,149 @@ where
                         error!(target: "engine::local", "Error updating fork choice: {:?}", e);
                     }
                 }
+                // handle requests, e.g. from the `anvil` namespace
+                Some(command) = self.commands.recv() => {
+                    self.on_command(command).await;
+                }
             }
         }
     }
 
+    /// Handles a command sent through the [`LocalMinerHandle`].
+    async fn on_command(&mut self, command: LocalMinerCommand) {
+        match command {
+            LocalMinerCommand::Mine { blocks, timestamp_interval, tx } => {
+                let _ = tx.send(self.mine(blocks, timestamp_interval).await);
+            }
+            LocalMinerCommand::SetState { changes, tx } => {
+                let parent = self.head_hash();
+                let state_changes = self.handle.pending_state_changes().clone();
+                state_changes.insert(parent, changes);
+                let res = self.advance().await;
+                state_changes.remove(parent);
+                let _ = tx.send(res);
+            }
+            LocalMinerCommand::SetMiningMode { mode, tx } => {
+                self.mode = mode;
+                let _ = tx.send(());
+            }
+            LocalMinerCommand::IsAutomine(tx) => {
+                let _ = tx.send(matches!(self.mode, MiningMode::Instant(_)));
+            }
+            LocalMinerCommand::SetNextBlockTimestamp { timestamp, tx } => {
+                let res = if timestamp <= self.last_timestamp {
+                    Err(eyre::eyre!(
+                        "timestamp {timestamp} is not greater than the timestamp {} of the head",
+                        self.last_timestamp
+                    ))
+                } else {
+                    self.next_timestamp = Some(timestamp);
+                    Ok(())
+                };
+                let _ = tx.send(res);
+            }
+            LocalMinerCommand::IncreaseTime { seconds, tx } => {
+                self.time_offset = self.time_offset.saturating_add_unsigned(seconds);
+                let _ = tx.send(self.time_offset);
+            }
+            LocalMinerCommand::SetTime { timestamp, tx } => {
+                let now = unix_timestamp();
+                self.time_offset = (timestamp as i64).saturating_sub(now as i64);
+                let _ = tx.send(timestamp.saturating_sub(now));
+            }
+            LocalMinerCommand::SetBlockTimestampInterval { interval, tx } => {
+                let previous = std::mem::replace(&mut self.timestamp_interval, interval);
+                let _ = tx.send(previous);
+            }
+            LocalMinerCommand::Snapshot(tx) => {
+                let id = self.next_snapshot_id;
+                self.next_snapshot_id += U256::from(1);
+                self.snapshots.insert(
+                    id,
+                    Snapshot {
+                        last_timestamp: self.last_timestamp,
+                        last_block_hashes: self.last_block_hashes.clone(),
+                        last_block_number: self.last_block_number,
+                    },
+                );
+                let _ = tx.send(id);
+            }
+            LocalMinerCommand::Revert { id, tx } => {
+                let _ = tx.send(self.revert(id).await);
+            }
+            LocalMinerCommand::Snapshots(tx) => {
+                let snapshots = self
+                    .snapshots
+                    .iter()
+                    .map(|(id, snapshot)| {
+                        let hash = *snapshot.last_block_hashes.last().expect("at least 1 block");
+                        (*id, BlockNumHash::new(snapshot.last_block_number, hash))
+                    })
+                    .collect();
+                let _ = tx.send(snapshots);
+            }
+        }
+    }
+
+    /// Mines the given number of blocks, optionally with a fixed interval between their
+    /// timestamps.
+    async fn mine(
+        &mut self,
+        blocks: u64,
+        timestamp_interval: Option<u64>,
+    ) -> eyre::Result<Vec<B256>> {
+        let mut hashes = Vec::with_capacity(blocks as usize);
+        for _ in 0..blocks {
+            if let Some(interval) = timestamp_interval {
+                self.next_timestamp = Some(self.last_timestamp + interval.max(1));
+            }
+            hashes.push(self.advance().await?);
+        }
+        Ok(hashes)
+    }
+
+    /// Rewinds the chain to the head of the given snapshot and discards the snapshot and all
+    /// snapshots taken after it.
+    async fn revert(&mut self, id: U256) -> eyre::Result<bool> {
+        let Some(snapshot) = self.snapshots.remove(&id) else { return Ok(false) };
+        self.snapshots.split_off(&id);
+
+        let expected = *snapshot.last_block_hashes.last().expect("at least 1 block exists");
+        let head = self.to_engine.set_head(snapshot.last_block_number).await?;
+        if head.hash != expected {
+            eyre::bail!("snapshot head {expected} is no longer part of the canonical chain")
+        }
+
+        self.last_timestamp = snapshot.last_timestamp;
+        self.last_block_hashes = snapshot.last_block_hashes;
+        self.last_block_number = snapshot.last_block_number;
+        self.next_timestamp = None;
+        self.update_forkchoice_state().await?;
+
+        Ok(true)
+    }
+
+    /// Returns the hash of the latest mined block.
+    fn head_hash(&self) -> B256 {
+        *self.last_block_hashes.last().expect("at least 1 block exists")
+    }
+
+    /// Returns the timestamp of the next block.
+    fn next_block_timestamp(&mut self) -> u64 {
+        let timestamp = if let Some(timestamp) = self.next_timestamp.take() {
+            timestamp
+        } else if let Some(interval) = self.timestamp_interval {
+            self.last_timestamp + interval
+        } else {
+            unix_timestamp().saturating_add_signed(self.time_offset)
+        };
+        std::cmp::max(self.last_timestamp + 1, timestamp)
+    }
+
     /// Returns current forkchoice state.
     fn forkchoice_state(&self) -> ForkchoiceState {
         ForkchoiceState {
-            head_block_hash: *self.last_block_hashes.last().expect("at least 1 block exists"),
+            head_block_hash: self.head_hash(),
             safe_block_hash: *self
                 .last_block_hashes
                 .get(self.last_block_hashes.len().saturating_sub(32))
@@ -164,14 +355,10 @@ where
 
     /// Generates payload attributes for a new block, passes them to FCU and inserts built payload
     /// through newPayload.
-    async fn advance(&mut self) -> eyre::Result<()> {
-        let timestamp = std::cmp::max(
-            self.last_timestamp + 1,
-            std::time::SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("cannot be earlier than UNIX_EPOCH")
-                .as_secs(),
-        );
+    ///
+    /// Returns the hash of the new block.
+    async fn advance(&mut self) -> eyre::Result<B256> {
+        let timestamp = self.next_block_timestamp();
 
         let res = self
             .to_engine
@@ -205,12 +392,24 @@ where
 
         self.last_timestamp = timestamp;
         self.last_block_hashes.push(block.hash());
+        self.last_block_number = block.header().number();
         // ensure we keep at most 64 blocks
         if self.last_block_hashes.len() > 64 {
             self.last_block_hashes =
                 self.last_block_hashes.split_off(self.last_block_hashes.len() - 64);
         }
 
-        Ok(())
+        // make the block canonical right away, so it is the latest block once this returns
+        self.update_forkchoice_state().await?;
+
+        Ok(block.hash())
     }
 }
+
+/// Returns the current unix timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("cannot be earlier than UNIX_EPOCH")
+        .as_secs()
+}
@@ -0,0 +1,252 @@
+//! Bounded, persistent store of blocks rejected by the engine.
+
+use alloy_consensus::BlockHeader;
+use alloy_primitives::{Address, BlockNumber, Bytes, B256};
+use parking_lot::RwLock;
+use reth_fs_util::FsPathError;
+use reth_primitives_traits::{Block, RecoveredBlock, SealedBlock};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, warn};
+
+/// The default number of bad blocks retained by a [`BadBlockStore`].
+pub const DEFAULT_BAD_BLOCK_LIMIT: usize = 10;
+
+static FILE_EXTENSION: &str = "json";
+
+/// A block that was rejected by the engine.
+///
+/// The block is kept in its RLP encoded form, so the record does not depend on the node's block
+/// type and can be persisted as is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadBlock {
+    /// Hash of the rejected block.
+    pub hash: B256,
+    /// Number of the rejected block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub number: BlockNumber,
+    /// The RLP encoded block.
+    pub rlp: Bytes,
+    /// Recovered senders of the block's transactions, in order.
+    pub senders: Vec<Address>,
+    /// The reason the block was rejected.
+    pub reason: String,
+    /// Unix timestamp in seconds at which the block was rejected.
+    #[serde(with = "alloy_serde::quantity")]
+    pub timestamp: u64,
+}
+
+impl BadBlock {
+    /// Creates a new [`BadBlock`] record for the given block, rejected now.
+    pub fn new<B: Block>(block: &RecoveredBlock<B>, reason: impl Display) -> Self {
+        let rlp = alloy_rlp::encode(block.clone_block());
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        Self {
+            hash: block.hash(),
+            number: block.header().number(),
+            rlp: rlp.into(),
+            senders: block.senders().to_vec(),
+            reason: reason.to_string(),
+            timestamp,
+        }
+    }
+
+    /// Decodes the rejected block.
+    ///
+    /// The block is sealed with the recorded hash, without recomputing it.
+    pub fn decode_block<B: Block>(&self) -> alloy_rlp::Result<SealedBlock<B>> {
+        let block = B::decode(&mut self.rlp.as_ref())?;
+        Ok(SealedBlock::new_unchecked(block, self.hash))
+    }
+
+    /// Decodes the rejected block together with the recorded senders.
+    pub fn decode_recovered_block<B: Block>(&self) -> alloy_rlp::Result<RecoveredBlock<B>> {
+        Ok(RecoveredBlock::new_sealed(self.decode_block()?, self.senders.clone()))
+    }
+}
+
+/// A bounded store of the most recent [`BadBlock`]s.
+///
+/// The engine inserts blocks that failed validation and the `debug` RPC namespace reads them back.
+/// If the store is backed by a directory, each block is written to its own file so that the store
+/// survives restarts. Once the limit is reached, the oldest block is evicted.
+///
+/// The store is cheap to clone, all clones share the same underlying state.
+#[derive(Debug, Clone)]
+pub struct BadBlockStore {
+    inner: Arc<RwLock<BadBlockStoreInner>>,
+}
+
+#[derive(Debug)]
+struct BadBlockStoreInner {
+    /// Bad blocks ordered from oldest to newest.
+    blocks: VecDeque<BadBlock>,
+    /// The maximum number of retained blocks.
+    limit: usize,
+    /// The directory the blocks are persisted to, if any.
+    directory: Option<PathBuf>,
+}
+
+impl BadBlockStore {
+    /// Creates a new in-memory store that retains at most `limit` blocks.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(BadBlockStoreInner {
+                blocks: VecDeque::with_capacity(limit),
+                limit,
+                directory: None,
+            })),
+        }
+    }
+
+    /// Opens a store that is persisted to the given directory and retains at most `limit` blocks.
+    ///
+    /// Creates the directory if it doesn't exist and loads all previously persisted blocks. Files
+    /// that can't be decoded are skipped and blocks exceeding the limit are removed.
+    pub fn open(directory: impl AsRef<Path>, limit: usize) -> Result<Self, FsPathError> {
+        let directory = directory.as_ref();
+        reth_fs_util::create_dir_all(directory)?;
+
+        let mut blocks = Vec::new();
+        for entry in reth_fs_util::read_dir(directory)? {
+            let path = entry.map_err(|err| FsPathError::read_dir(err, directory))?.path();
+            if path.extension().is_none_or(|ext| ext != FILE_EXTENSION) {
+                continue
+            }
+            match reth_fs_util::read_json_file::<BadBlock>(&path) {
+                Ok(block) => blocks.push(block),
+                Err(err) => {
+                    warn!(target: "engine::bad_blocks", %err, "Skipping unreadable bad block")
+                }
+            }
+        }
+        blocks.sort_by_key(|block| (block.timestamp, block.number));
+
+        let excess = blocks.len().saturating_sub(limit);
+        for block in blocks.drain(..excess) {
+            reth_fs_util::remove_file(Self::file_path(directory, block.hash))?;
+        }
+
+        debug!(target: "engine::bad_blocks", count = blocks.len(), ?directory, "Loaded bad blocks");
+
+        Ok(Self {
+            inner: Arc::new(RwLock::new(BadBlockStoreInner {
+                blocks: blocks.into(),
+                limit,
+                directory: Some(directory.to_path_buf()),
+            })),
+        })
+    }
+
+    fn file_path(directory: &Path, hash: B256) -> PathBuf {
+        directory.join(format!("{hash}.{FILE_EXTENSION}"))
+    }
+
+    /// Records the given block as rejected for the given reason.
+    ///
+    /// Does nothing if the block is already in the store. Failures to persist the block are logged
+    /// and otherwise ignored, the block is still retained in memory.
+    pub fn insert<B: Block>(&self, block: &RecoveredBlock<B>, reason: impl Display) {
+        let mut inner = self.inner.write();
+        if inner.limit == 0 || inner.blocks.iter().any(|bad| bad.hash == block.hash()) {
+            return
+        }
+
+        let bad_block = BadBlock::new(block, reason);
+        if let Some(directory) = &inner.directory {
+            let path = Self::file_path(directory, bad_block.hash);
+            if let Err(err) = reth_fs_util::write_json_file(&path, &bad_block) {
+                warn!(target: "engine::bad_blocks", %err, hash = %bad_block.hash, "Failed to persist bad block");
+            }
+        }
+
+        inner.blocks.push_back(bad_block);
+        while inner.blocks.len() > inner.limit {
+            let Some(evicted) = inner.blocks.pop_front() else { break };
+            if let Some(directory) = &inner.directory {
+                let _ = reth_fs_util::remove_file(Self::file_path(directory, evicted.hash));
+            }
+        }
+    }
+
+    /// Returns all retained bad blocks, newest first.
+    pub fn blocks(&self) -> Vec<BadBlock> {
+        self.inner.read().blocks.iter().rev().cloned().collect()
+    }
+
+    /// Returns the bad block with the given hash, if it is retained.
+    pub fn get(&self, hash: &B256) -> Option<BadBlock> {
+        self.inner.read().blocks.iter().find(|block| block.hash == *hash).cloned()
+    }
+
+    /// Returns the number of retained bad blocks.
+    pub fn len(&self) -> usize {
+        self.inner.read().blocks.len()
+    }
+
+    /// Returns `true` if no bad blocks are retained.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().blocks.is_empty()
+    }
+}
+
+impl Default for BadBlockStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_BAD_BLOCK_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_ethereum_primitives::Block;
+    use reth_primitives_traits::Block as _;
+
+    fn block(number: u64) -> RecoveredBlock<Block> {
+        let mut block = Block::default();
+        block.header.number = number;
+        RecoveredBlock::new_sealed(block.seal_slow(), Vec::new())
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let store = BadBlockStore::new(2);
+        store.insert(&block(1), "one");
+        store.insert(&block(2), "two");
+        store.insert(&block(2), "duplicate");
+        store.insert(&block(3), "three");
+
+        let blocks = store.blocks();
+        assert_eq!(blocks.iter().map(|b| b.number).collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(blocks[1].reason, "two");
+    }
+
+    #[test]
+    fn survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = block(1);
+
+        let store = BadBlockStore::open(dir.path(), 2).unwrap();
+        store.insert(&first, "invalid state root");
+        store.insert(&block(2), "invalid receipts root");
+        drop(store);
+
+        let store = BadBlockStore::open(dir.path(), 1).unwrap();
+        assert_eq!(store.len(), 1);
+        assert!(store.get(&first.hash()).is_none());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let bad = store.blocks().pop().unwrap();
+        assert_eq!(bad.number, 2);
+        let decoded = bad.decode_recovered_block::<Block>().unwrap();
+        assert_eq!(decoded.hash(), bad.hash);
+    }
+}
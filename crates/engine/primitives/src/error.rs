@@ -45,3 +45,35 @@ impl BeaconForkChoiceUpdateError {
         Self::Internal(Box::new(e))
     }
 }
+
+/// Represents error cases for a request to rewind the canonical head of the engine.
+#[derive(Debug, thiserror::Error)]
+pub enum BeaconSetHeadError {
+    /// Thrown when the engine task is unavailable/stopped.
+    #[error("beacon consensus engine task stopped")]
+    EngineUnavailable,
+    /// Thrown when the engine is currently running backfill sync.
+    #[error("cannot set head while backfill sync is in progress")]
+    BackfillInProgress,
+    /// Thrown when the requested head is above the current canonical head.
+    #[error("block {target} is above the canonical head {head}")]
+    AboveCanonicalHead {
+        /// The requested head block number.
+        target: u64,
+        /// The current canonical head block number.
+        head: u64,
+    },
+    /// Thrown when the request was replaced by a newer request before it was processed.
+    #[error("set head request superseded by a newer request")]
+    Superseded,
+    /// An internal error occurred while rewinding the chain.
+    #[error(transparent)]
+    Internal(Box<dyn core::error::Error + Send + Sync>),
+}
+
+impl BeaconSetHeadError {
+    /// Create a new internal error.
+    pub fn internal<E: core::error::Error + Send + Sync + 'static>(e: E) -> Self {
+        Self::Internal(Box::new(e))
+    }
+}
@@ -39,6 +39,11 @@ pub use event::*;
 mod invalid_block_hook;
 pub use invalid_block_hook::{InvalidBlockHook, InvalidBlockHooks, NoopInvalidBlockHook};
 
+#[cfg(feature = "std")]
+mod bad_block;
+#[cfg(feature = "std")]
+pub use bad_block::{BadBlock, BadBlockStore, DEFAULT_BAD_BLOCK_LIMIT};
+
 pub mod config;
 pub use config::*;
 
@@ -1,6 +1,9 @@
 use crate::{
-    error::BeaconForkChoiceUpdateError, BeaconOnNewPayloadError, ExecutionPayload, ForkchoiceStatus,
+    error::BeaconForkChoiceUpdateError, BeaconOnNewPayloadError, BeaconSetHeadError,
+    ExecutionPayload, ForkchoiceStatus,
 };
+use alloy_eips::BlockNumHash;
+use alloy_primitives::BlockNumber;
 use alloy_rpc_types_engine::{
     ForkChoiceUpdateResult, ForkchoiceState, ForkchoiceUpdateError, ForkchoiceUpdated, PayloadId,
     PayloadStatus, PayloadStatusEnum,
@@ -11,7 +14,10 @@ use core::{
     pin::Pin,
     task::{ready, Context, Poll},
 };
-use futures::{future::Either, FutureExt, TryFutureExt};
+use futures::{
+    future::{BoxFuture, Either},
+    FutureExt, TryFutureExt,
+};
 use reth_errors::RethResult;
 use reth_payload_builder_primitives::PayloadBuilderError;
 use reth_payload_primitives::{EngineApiMessageVersion, PayloadTypes};
@@ -160,6 +166,15 @@ pub enum BeaconEngineMessage<Payload: PayloadTypes> {
         /// The sender for returning forkchoice updated result.
         tx: oneshot::Sender<RethResult<OnForkChoiceUpdated>>,
     },
+    /// Message to rewind the canonical chain to the given block.
+    ///
+    /// This is not part of the engine API, it is issued by the node itself (`debug_setHead`).
+    SetHead {
+        /// The number of the block that becomes the new canonical head.
+        number: BlockNumber,
+        /// The sender for returning the new canonical head.
+        tx: oneshot::Sender<Result<BlockNumHash, BeaconSetHeadError>>,
+    },
 }
 
 impl<Payload: PayloadTypes> Display for BeaconEngineMessage<Payload> {
@@ -183,6 +198,7 @@ impl<Payload: PayloadTypes> Display for BeaconEngineMessage<Payload> {
                     payload_attrs.is_some()
                 )
             }
+            Self::SetHead { number, .. } => write!(f, "SetHead(number: {number})"),
         }
     }
 }
@@ -253,4 +269,39 @@ where
         });
         rx
     }
+
+    /// Rewinds the canonical chain of the engine to the given block number and waits for the
+    /// in-memory state to be updated.
+    ///
+    /// The blocks above the new head are removed from disk by the persistence task afterwards.
+    pub async fn set_head(&self, number: BlockNumber) -> Result<BlockNumHash, BeaconSetHeadError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.to_engine.send(BeaconEngineMessage::SetHead { number, tx });
+        rx.await.map_err(|_| BeaconSetHeadError::EngineUnavailable)?
+    }
+}
+
+/// Type-erased access to [`BeaconConsensusEngineHandle::set_head`].
+///
+/// This allows components that are not generic over the payload types, like the `debug` RPC
+/// namespace, to rewind the canonical chain.
+pub trait EngineSetHead: fmt::Debug + Send + Sync {
+    /// Rewinds the canonical chain to the given block number and returns the new head.
+    fn set_head(
+        &self,
+        number: BlockNumber,
+    ) -> BoxFuture<'static, Result<BlockNumHash, BeaconSetHeadError>>;
+}
+
+impl<Payload> EngineSetHead for BeaconConsensusEngineHandle<Payload>
+where
+    Payload: PayloadTypes,
+{
+    fn set_head(
+        &self,
+        number: BlockNumber,
+    ) -> BoxFuture<'static, Result<BlockNumHash, BeaconSetHeadError>> {
+        let this = self.clone();
+        Box::pin(async move { Self::set_head(&this, number).await })
+    }
 }
@@ -1,13 +1,14 @@
 //! Implements a state provider that has a shared cache in front of it.
-use alloy_primitives::{Address, StorageKey, StorageValue, B256};
+use alloy_primitives::{Address, StorageKey, StorageValue, B256, U256};
 use metrics::Gauge;
 use mini_moka::sync::CacheBuilder;
 use reth_errors::ProviderResult;
 use reth_metrics::Metrics;
 use reth_primitives_traits::{Account, Bytecode};
 use reth_provider::{
-    AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider, StateProofProvider,
-    StateProvider, StateRootProvider, StorageRootProvider,
+    AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider,
+    HashedStateRangeProvider, StateProofProvider, StateProvider, StateRootProvider,
+    StorageRootProvider,
 };
 use reth_revm::db::BundleState;
 use reth_trie::{
@@ -272,6 +273,35 @@ impl<S: BlockHashReader> BlockHashReader for CachedStateProvider<S> {
     }
 }
 
+impl<S: HashedStateRangeProvider> HashedStateRangeProvider for CachedStateProvider<S> {
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        self.state_provider.hashed_account_range(hashed_state, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        self.state_provider.hashed_storage_range(hashed_address, hashed_storage, start, limit)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        self.state_provider.hashed_storage_root(hashed_address, hashed_storage)
+    }
+}
+
 impl<S: HashedPostStateProvider> HashedPostStateProvider for CachedStateProvider<S> {
     fn hashed_post_state(&self, bundle_state: &reth_revm::db::BundleState) -> HashedPostState {
         self.state_provider.hashed_post_state(bundle_state)
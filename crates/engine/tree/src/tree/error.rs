@@ -180,6 +180,15 @@ pub enum InsertBlockFatalError {
     BlockExecutionError(#[from] InternalBlockExecutionError),
 }
 
+impl From<InsertBlockFatalError> for InsertBlockErrorKind {
+    fn from(err: InsertBlockFatalError) -> Self {
+        match err {
+            InsertBlockFatalError::Provider(err) => Self::Provider(err),
+            InsertBlockFatalError::BlockExecutionError(err) => Self::Execution(err.into()),
+        }
+    }
+}
+
 /// Error variants that are caused by invalid blocks
 #[derive(Debug, thiserror::Error)]
 pub enum InsertBlockValidationError {
@@ -191,6 +200,15 @@ pub enum InsertBlockValidationError {
     Validation(#[from] BlockValidationError),
 }
 
+impl From<InsertBlockValidationError> for InsertBlockErrorKind {
+    fn from(err: InsertBlockValidationError) -> Self {
+        match err {
+            InsertBlockValidationError::Consensus(err) => Self::Consensus(err),
+            InsertBlockValidationError::Validation(err) => Self::Execution(err.into()),
+        }
+    }
+}
+
 /// Errors that may occur when inserting a payload.
 #[derive(Debug, thiserror::Error)]
 pub enum InsertPayloadError<B: Block> {
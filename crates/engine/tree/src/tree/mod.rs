@@ -7,7 +7,7 @@ use crate::{
 };
 use alloy_consensus::BlockHeader;
 use alloy_eips::{eip1898::BlockWithParent, merge::EPOCH_SLOTS, BlockNumHash, NumHash};
-use alloy_primitives::B256;
+use alloy_primitives::{BlockNumber, B256};
 use alloy_rpc_types_engine::{
     ForkchoiceState, PayloadStatus, PayloadStatusEnum, PayloadValidationError,
 };
@@ -19,8 +19,8 @@ use reth_chain_state::{
 };
 use reth_consensus::{Consensus, FullConsensus};
 use reth_engine_primitives::{
-    BeaconConsensusEngineEvent, BeaconEngineMessage, BeaconOnNewPayloadError, ExecutionPayload,
-    ForkchoiceStateTracker, OnForkChoiceUpdated,
+    BeaconConsensusEngineEvent, BeaconEngineMessage, BeaconOnNewPayloadError, BeaconSetHeadError,
+    ExecutionPayload, ForkchoiceStateTracker, OnForkChoiceUpdated,
 };
 use reth_errors::{ConsensusError, ProviderResult};
 use reth_evm::ConfigureEvm;
@@ -209,6 +209,15 @@ pub enum TreeAction {
     },
 }
 
+/// A queued request to rewind the canonical head, see [`BeaconEngineMessage::SetHead`].
+#[derive(Debug)]
+struct PendingSetHead {
+    /// The number of the block that becomes the new canonical head.
+    number: BlockNumber,
+    /// The sender for returning the new canonical head.
+    tx: oneshot::Sender<Result<BlockNumHash, BeaconSetHeadError>>,
+}
+
 /// The engine API tree handler implementation.
 ///
 /// This type is responsible for processing engine API requests, maintaining the canonical state and
@@ -243,6 +252,13 @@ where
     persistence_state: PersistenceState,
     /// Flag indicating the state of the node's backfill synchronization process.
     backfill_sync_state: BackfillSyncState,
+    /// A requested rewind of the canonical head that is applied once no persistence task is in
+    /// progress.
+    pending_set_head: Option<PendingSetHead>,
+    /// The response to an applied rewind of the canonical head that is sent once the blocks above
+    /// the new head are removed from disk.
+    unwinding_set_head:
+        Option<(BlockNumHash, oneshot::Sender<Result<BlockNumHash, BeaconSetHeadError>>)>,
     /// Keeps track of the state of the canonical chain that isn't persisted yet.
     /// This is intended to be accessed from external sources, such as rpc.
     canonical_in_memory_state: CanonicalInMemoryState<N>,
@@ -275,6 +291,8 @@ where
             .field("persistence", &self.persistence)
             .field("persistence_state", &self.persistence_state)
             .field("backfill_sync_state", &self.backfill_sync_state)
+            .field("pending_set_head", &self.pending_set_head)
+            .field("unwinding_set_head", &self.unwinding_set_head)
             .field("canonical_in_memory_state", &self.canonical_in_memory_state)
             .field("payload_builder", &self.payload_builder)
             .field("config", &self.config)
@@ -329,6 +347,8 @@ where
             persistence,
             persistence_state,
             backfill_sync_state: BackfillSyncState::Idle,
+            pending_set_head: None,
+            unwinding_set_head: None,
             state,
             canonical_in_memory_state,
             payload_builder,
@@ -984,6 +1004,9 @@ where
             match rx.try_recv() {
                 Ok(last_persisted_hash_num) => {
                     self.metrics.engine.persistence_duration.record(start_time.elapsed());
+                    if let Some((new_head, tx)) = self.unwinding_set_head.take() {
+                        let _ = tx.send(Ok(new_head));
+                    }
                     let Some(BlockNumHash {
                         hash: last_persisted_block_hash,
                         number: last_persisted_block_number,
@@ -1010,6 +1033,21 @@ where
         if !self.persistence_state.in_progress() {
             if let Some(new_tip_num) = self.find_disk_reorg()? {
                 self.remove_blocks(new_tip_num)
+            } else if let Some(PendingSetHead { number, tx }) = self.pending_set_head.take() {
+                let res = self.set_head(number);
+                // if the new head is below the persisted tip, this unwinds the blocks on disk
+                if let Some(new_tip_num) = self.find_disk_reorg()? {
+                    self.remove_blocks(new_tip_num)
+                }
+                match res {
+                    // respond once the state on disk matches the new head
+                    Ok(new_head) if self.persistence_state.in_progress() => {
+                        self.unwinding_set_head = Some((new_head, tx))
+                    }
+                    res => {
+                        let _ = tx.send(res);
+                    }
+                }
             } else if self.should_persist() {
                 let blocks_to_persist = self.get_canonical_blocks_to_persist()?;
                 self.persist_blocks(blocks_to_persist);
@@ -1120,6 +1158,9 @@ where
                                 // handle the event if any
                                 self.on_maybe_tree_event(maybe_event)?;
                             }
+                            BeaconEngineMessage::SetHead { number, tx } => {
+                                self.on_set_head(number, tx);
+                            }
                         }
                     }
                 }
@@ -1133,6 +1174,107 @@ where
         Ok(())
     }
 
+    /// Invoked when a rewind of the canonical head to the given block number is requested.
+    ///
+    /// The rewind is not applied right away, because the persistence task may be writing blocks
+    /// above the new head. Instead it is queued and applied in [`Self::advance_persistence`] once
+    /// no persistence task is in progress. A request that is still queued is superseded. If blocks
+    /// have to be removed from disk, the request is answered once they are removed.
+    fn on_set_head(
+        &mut self,
+        number: BlockNumber,
+        tx: oneshot::Sender<Result<BlockNumHash, BeaconSetHeadError>>,
+    ) {
+        if !self.backfill_sync_state.is_idle() {
+            let _ = tx.send(Err(BeaconSetHeadError::BackfillInProgress));
+            return
+        }
+
+        let head = self.state.tree_state.current_canonical_head;
+        if number > head.number {
+            let _ = tx.send(Err(BeaconSetHeadError::AboveCanonicalHead {
+                target: number,
+                head: head.number,
+            }));
+            return
+        }
+
+        debug!(target: "engine::tree", ?number, ?head, "queueing set head request");
+        if let Some(previous) = self.pending_set_head.replace(PendingSetHead { number, tx }) {
+            let _ = previous.tx.send(Err(BeaconSetHeadError::Superseded));
+        }
+    }
+
+    /// Rewinds the canonical head to the given block number.
+    ///
+    /// This updates the tree state and the canonical in-memory state. If the new head is below the
+    /// last persisted block, the tree state is reset and the blocks above the new head are removed
+    /// from disk by the persistence task afterwards, the same way the `stage unwind` command
+    /// removes them. Blocks above the new head are then synced again.
+    ///
+    /// Must only be called if no persistence task is in progress.
+    fn set_head(&mut self, number: BlockNumber) -> Result<BlockNumHash, BeaconSetHeadError> {
+        debug_assert!(!self.persistence_state.in_progress());
+
+        let head = self.state.tree_state.current_canonical_head;
+        if number >= head.number {
+            // the canonical chain may have been rewound by a reorg in the meantime
+            return Ok(head)
+        }
+
+        let persisted = self.persistence_state.last_persisted_block;
+        let new_head = if number > persisted.number {
+            self.canonical_in_memory_state
+                .state_by_number(number)
+                .map(|state| state.block_ref().recovered_block().clone_sealed_header())
+        } else {
+            self.provider.sealed_header(number).map_err(BeaconSetHeadError::internal)?
+        }
+        .ok_or_else(|| {
+            BeaconSetHeadError::internal(ProviderError::HeaderNotFound(number.into()))
+        })?;
+
+        warn!(target: "engine::tree", new_head=?new_head.num_hash(), old_head=?head, "Rewinding canonical chain");
+
+        if number < persisted.number {
+            // everything in memory is above the new head
+            self.state.tree_state.reset(new_head.num_hash());
+            self.canonical_in_memory_state.clear_state();
+        } else {
+            let old = (number + 1..=head.number)
+                .filter_map(|number| self.canonical_in_memory_state.state_by_number(number))
+                .map(|state| state.block_ref().block.clone())
+                .collect();
+            self.state.tree_state.set_canonical_head(new_head.num_hash());
+            self.canonical_in_memory_state
+                .update_chain(NewCanonicalChain::Reorg { new: Vec::new(), old });
+        }
+
+        // the safe and finalized blocks can't be above the canonical head
+        if self
+            .canonical_in_memory_state
+            .get_safe_num_hash()
+            .is_some_and(|safe| safe.number > number)
+        {
+            self.canonical_in_memory_state.set_safe(new_head.clone());
+            let _ = self.persistence.save_safe_block_number(number);
+        }
+        if self
+            .canonical_in_memory_state
+            .get_finalized_num_hash()
+            .is_some_and(|finalized| finalized.number > number)
+        {
+            self.canonical_in_memory_state.set_finalized(new_head.clone());
+            let _ = self.persistence.save_finalized_block_number(number);
+        }
+
+        self.canonical_in_memory_state.set_canonical_head(new_head.clone());
+        self.metrics.engine.executed_blocks.set(self.state.tree_state.block_count() as f64);
+        self.metrics.tree.canonical_chain_height.set(number as f64);
+
+        Ok(new_head.num_hash())
+    }
+
     /// Invoked if the backfill sync has finished to target.
     ///
     /// At this point we consider the block synced to the backfill target.
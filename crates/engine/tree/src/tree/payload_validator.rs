@@ -18,7 +18,7 @@ use reth_chain_state::{
     CanonicalInMemoryState, ExecutedBlock, ExecutedBlockWithTrieUpdates, ExecutedTrieUpdates,
 };
 use reth_consensus::{ConsensusError, FullConsensus};
-use reth_engine_primitives::{InvalidBlockHook, PayloadValidator};
+use reth_engine_primitives::{BadBlockStore, InvalidBlockHook, PayloadValidator};
 use reth_errors::ProviderResult;
 use reth_evm::{ConfigureEvm, SpecFor};
 use reth_payload_primitives::{
@@ -157,6 +157,8 @@ where
     /// Hook to call when invalid blocks are encountered.
     #[debug(skip)]
     invalid_block_hook: Box<dyn InvalidBlockHook<Evm::Primitives>>,
+    /// Store that records blocks which failed validation.
+    bad_blocks: Option<BadBlockStore>,
     /// Metrics for the engine api.
     metrics: EngineApiMetrics,
     /// Validator for the payload.
@@ -202,11 +204,18 @@ where
             precompile_cache_metrics: HashMap::new(),
             config,
             invalid_block_hook,
+            bad_blocks: None,
             metrics: EngineApiMetrics::default(),
             validator,
         }
     }
 
+    /// Configures the [`BadBlockStore`] that records blocks which failed validation.
+    pub fn with_bad_block_store(mut self, bad_blocks: BadBlockStore) -> Self {
+        self.bad_blocks = Some(bad_blocks);
+        self
+    }
+
     /// Validates a block that has already been converted from a payload.
     ///
     /// This method performs:
@@ -886,7 +895,18 @@ where
         block: RecoveredBlock<N::Block>,
         ctx: TreeCtx<'_, N>,
     ) -> ValidationOutcome<N> {
-        self.validate_block_with_state(block, ctx)
-            .map_err(|(kind, block)| InsertBlockError::new(block.into_sealed_block(), kind))
+        self.validate_block_with_state(block, ctx).map_err(|(kind, block)| {
+            let Some(bad_blocks) = &self.bad_blocks else {
+                return InsertBlockError::new(block.into_sealed_block(), kind)
+            };
+            let kind = match kind.ensure_validation_error() {
+                Ok(err) => {
+                    bad_blocks.insert(&block, &err);
+                    err.into()
+                }
+                Err(err) => err.into(),
+            };
+            InsertBlockError::new(block.into_sealed_block(), kind)
+        })
     }
 }
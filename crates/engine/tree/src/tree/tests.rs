@@ -456,6 +456,102 @@ async fn test_engine_request_during_backfill() {
     assert!(resp.payload_status.is_syncing());
 }
 
+#[tokio::test]
+async fn test_set_head_in_memory() {
+    let blocks: Vec<_> = TestBlockBuilder::eth().get_executed_blocks(0..10).collect();
+    let mut test_harness = TestHarness::new(MAINNET.clone()).with_blocks(blocks.clone());
+    test_harness.tree.persistence_state.last_persisted_block = blocks[0].recovered_block.num_hash();
+
+    let (tx, rx) = oneshot::channel();
+    test_harness
+        .tree
+        .on_engine_message(FromEngine::Request(
+            BeaconEngineMessage::SetHead { number: 5, tx }.into(),
+        ))
+        .unwrap();
+    test_harness.tree.advance_persistence().unwrap();
+
+    let new_head = blocks[5].recovered_block.num_hash();
+    assert_eq!(rx.await.unwrap().unwrap(), new_head);
+    assert_eq!(test_harness.tree.state.tree_state.current_canonical_head, new_head);
+    assert_eq!(
+        test_harness.tree.canonical_in_memory_state.get_canonical_head().num_hash(),
+        new_head
+    );
+    assert!(test_harness.tree.canonical_in_memory_state.state_by_number(6).is_none());
+    assert!(test_harness.tree.canonical_in_memory_state.state_by_number(5).is_some());
+
+    // nothing was persisted above the new head, so there is nothing to remove
+    assert!(!test_harness.tree.persistence_state.in_progress());
+    assert!(test_harness.action_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_set_head_removes_persisted_blocks() {
+    let blocks: Vec<_> = TestBlockBuilder::eth().get_executed_blocks(0..10).collect();
+    let mut test_harness = TestHarness::new(MAINNET.clone()).with_blocks(blocks.clone());
+    test_harness.tree.persistence_state.last_persisted_block = blocks[7].recovered_block.num_hash();
+
+    let (tx, mut rx) = oneshot::channel();
+    test_harness
+        .tree
+        .on_engine_message(FromEngine::Request(
+            BeaconEngineMessage::SetHead { number: 3, tx }.into(),
+        ))
+        .unwrap();
+    test_harness.tree.advance_persistence().unwrap();
+
+    let new_head = blocks[3].recovered_block.num_hash();
+    assert_eq!(test_harness.tree.state.tree_state.current_canonical_head, new_head);
+    assert_eq!(test_harness.tree.state.tree_state.block_count(), 0);
+    assert!(test_harness.tree.canonical_in_memory_state.head_state().is_none());
+
+    // the blocks above the new head are removed from disk
+    let removed_tx = assert_matches!(
+        test_harness.action_rx.try_recv(),
+        Ok(PersistenceAction::RemoveBlocksAbove(3, tx)) => tx
+    );
+    assert_matches!(
+        test_harness.tree.persistence_state.current_action(),
+        Some(CurrentPersistenceAction::RemovingBlocks { new_tip_num: 3 })
+    );
+
+    // the request is answered once the blocks are removed
+    assert!(rx.try_recv().is_err());
+    removed_tx.send(Some(new_head)).unwrap();
+    test_harness.tree.advance_persistence().unwrap();
+    assert_eq!(rx.await.unwrap().unwrap(), new_head);
+}
+
+#[tokio::test]
+async fn test_set_head_rejected() {
+    let blocks: Vec<_> = TestBlockBuilder::eth().get_executed_blocks(0..10).collect();
+    let mut test_harness = TestHarness::new(MAINNET.clone()).with_blocks(blocks);
+
+    let (tx, rx) = oneshot::channel();
+    test_harness
+        .tree
+        .on_engine_message(FromEngine::Request(
+            BeaconEngineMessage::SetHead { number: 10, tx }.into(),
+        ))
+        .unwrap();
+    assert_matches!(
+        rx.await.unwrap(),
+        Err(BeaconSetHeadError::AboveCanonicalHead { target: 10, head: 9 })
+    );
+
+    test_harness.tree.backfill_sync_state = BackfillSyncState::Active;
+    let (tx, rx) = oneshot::channel();
+    test_harness
+        .tree
+        .on_engine_message(FromEngine::Request(
+            BeaconEngineMessage::SetHead { number: 5, tx }.into(),
+        ))
+        .unwrap();
+    assert_matches!(rx.await.unwrap(), Err(BeaconSetHeadError::BackfillInProgress));
+    assert!(test_harness.tree.pending_set_head.is_none());
+}
+
 #[test]
 fn test_disconnected_payload() {
     let s = include_str!("../../test-data/holesky/2.rlp");
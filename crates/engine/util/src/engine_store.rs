@@ -86,6 +86,8 @@ impl EngineMessageStore {
                     })?,
                 )?;
             }
+            // not an engine API message, nothing to replay
+            BeaconEngineMessage::SetHead { .. } => {}
         };
         Ok(())
     }
@@ -15,6 +15,7 @@ use alloy_rpc_types_engine::{
     ExecutionPayloadV1, ExecutionPayloadV3, PayloadAttributes, PayloadId,
 };
 use core::convert::Infallible;
+use reth_chain_state::ExecutedBlockWithTrieUpdates;
 use reth_ethereum_primitives::{Block, EthPrimitives};
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
 use reth_primitives_traits::SealedBlock;
@@ -39,6 +40,9 @@ pub struct EthBuiltPayload {
     pub(crate) sidecars: BlobSidecars,
     /// The requests of the payload
     pub(crate) requests: Option<Requests>,
+    /// The executed block, only set for blocks that can't be re-executed from their
+    /// transactions.
+    pub(crate) executed_block: Option<ExecutedBlockWithTrieUpdates<EthPrimitives>>,
 }
 
 // === impl BuiltPayload ===
@@ -53,7 +57,7 @@ impl EthBuiltPayload {
         fees: U256,
         requests: Option<Requests>,
     ) -> Self {
-        Self { id, block, fees, requests, sidecars: BlobSidecars::Empty }
+        Self { id, block, fees, requests, sidecars: BlobSidecars::Empty, executed_block: None }
     }
 
     /// Returns the identifier of the payload.
@@ -82,6 +86,18 @@ impl EthBuiltPayload {
         self
     }
 
+    /// Sets the executed block of the payload.
+    ///
+    /// This is inserted into the engine before the payload is submitted, so it must be set for
+    /// blocks with irregular state changes that can't be re-executed.
+    pub fn with_executed_block(
+        mut self,
+        executed_block: ExecutedBlockWithTrieUpdates<EthPrimitives>,
+    ) -> Self {
+        self.executed_block = Some(executed_block);
+        self
+    }
+
     /// Try converting built payload into [`ExecutionPayloadEnvelopeV3`].
     ///
     /// Returns an error if the payload contains non EIP-4844 sidecar.
@@ -172,6 +188,10 @@ impl BuiltPayload for EthBuiltPayload {
     fn requests(&self) -> Option<Requests> {
         self.requests.clone()
     }
+
+    fn executed_block(&self) -> Option<ExecutedBlockWithTrieUpdates<EthPrimitives>> {
+        self.executed_block.clone()
+    }
 }
 
 // V1 engine_getPayloadV1 response
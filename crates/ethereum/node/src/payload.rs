@@ -48,11 +48,20 @@ where
         let chain = ctx.chain_spec().chain();
         let gas_limit = conf.gas_limit_for(chain);
 
+        let mut builder_config = EthereumBuilderConfig::new()
+            .with_gas_limit(gas_limit)
+            .with_miner_settings(ctx.miner_settings().clone());
+        if let Some(local_miner) = ctx.local_miner() {
+            // apply the state changes requested through the `anvil` namespace in dev mode
+            builder_config = builder_config
+                .with_pending_state_changes(local_miner.pending_state_changes().clone());
+        }
+
         Ok(reth_ethereum_payload_builder::EthereumPayloadBuilder::new(
             ctx.provider().clone(),
             pool,
             evm_config,
-            EthereumBuilderConfig::new().with_gas_limit(gas_limit),
+            builder_config,
         ))
     }
 }
@@ -1,6 +1,11 @@
+use alloy_consensus::constants::EMPTY_ROOT_HASH;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_genesis::Genesis;
-use alloy_primitives::{b256, hex};
+use alloy_primitives::{address, b256, hex, keccak256, Bytes, B256, U128, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types_trace::{
+    filter::TraceFilter, otterscan::TransactionsWithReceipts, parity::LocalizedTransactionTrace,
+};
 use futures::StreamExt;
 use reth_chainspec::ChainSpec;
 use reth_node_api::{BlockBody, FullNodeComponents, FullNodePrimitives, NodeTypes};
@@ -8,12 +13,14 @@ use reth_node_builder::{
     rpc::RethRpcAddOns, DebugNodeLauncher, EngineNodeLauncher, FullNode, NodeBuilder, NodeConfig,
     NodeHandle,
 };
-use reth_node_core::args::DevArgs;
+use reth_node_core::args::{DevArgs, RpcServerArgs};
 use reth_node_ethereum::{node::EthereumAddOns, EthereumNode};
-use reth_provider::{providers::BlockchainProvider, CanonStateSubscriptions};
+use reth_provider::{providers::BlockchainProvider, CallIndexReader, CanonStateSubscriptions};
+use reth_rpc_api::BlockStateDiff;
 use reth_rpc_eth_api::helpers::EthTransactions;
+use reth_rpc_server_types::{RethRpcModule, RpcModuleSelection};
 use reth_tasks::TaskManager;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 #[tokio::test]
 async fn can_run_dev_node() -> eyre::Result<()> {
@@ -45,6 +52,560 @@ async fn can_run_dev_node() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn can_manipulate_dev_chain_through_anvil() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config =
+        NodeConfig::test()
+            .with_chain(custom_chain())
+            .with_dev(DevArgs { dev: true, ..Default::default() })
+            .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+                RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Anvil]),
+            ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let account = address!("0x00000000000000000000000000000000000000aa");
+
+    // state changes are mined as blocks
+    let balance = U256::from(1_000_000);
+    provider.raw_request::<_, ()>("anvil_setBalance".into(), (account, balance)).await?;
+    assert_eq!(provider.get_block_number().await?, 1);
+    assert_eq!(provider.get_balance(account).await?, balance);
+
+    let snapshot: U256 = provider.raw_request("anvil_snapshot".into(), ()).await?;
+
+    let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+    provider.raw_request::<_, ()>("anvil_setCode".into(), (account, code.clone())).await?;
+    let value = B256::with_last_byte(1);
+    let stored: bool =
+        provider.raw_request("anvil_setStorageAt".into(), (account, U256::ZERO, value)).await?;
+    assert!(stored);
+    assert_eq!(provider.get_code_at(account).await?, code);
+    assert_eq!(provider.get_storage_at(account, U256::ZERO).await?, U256::from(1));
+
+    // mined blocks use the requested timestamp
+    let timestamp = provider.get_block_by_number(3.into()).await?.unwrap().header.timestamp + 100;
+    provider.raw_request::<_, ()>("anvil_setNextBlockTimestamp".into(), (timestamp,)).await?;
+    provider.raw_request::<_, ()>("anvil_mine".into(), (U256::from(2),)).await?;
+    assert_eq!(provider.get_block_number().await?, 5);
+    assert_eq!(provider.get_block_by_number(4.into()).await?.unwrap().header.timestamp, timestamp);
+
+    // reverting drops the code, the storage and the mined blocks
+    let reverted: bool = provider.raw_request("anvil_revert".into(), (snapshot,)).await?;
+    assert!(reverted);
+    assert_eq!(provider.get_block_number().await?, 1);
+    assert_eq!(provider.get_balance(account).await?, balance);
+    assert!(provider.get_code_at(account).await?.is_empty());
+    assert_eq!(provider.get_storage_at(account, U256::ZERO).await?, U256::ZERO);
+
+    // the snapshot is consumed by the revert
+    let reverted: bool = provider.raw_request("anvil_revert".into(), (snapshot,)).await?;
+    assert!(!reverted);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_manipulate_dev_chain_through_hardhat_and_evm() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+            RpcModuleSelection::from([
+                RethRpcModule::Eth,
+                RethRpcModule::Hardhat,
+                RethRpcModule::Evm,
+            ]),
+        ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let account = address!("0x00000000000000000000000000000000000000bb");
+
+    let snapshot: U256 = provider.raw_request("evm_snapshot".into(), ()).await?;
+
+    let balance = U256::from(1_000_000);
+    provider.raw_request::<_, ()>("hardhat_setBalance".into(), (account, balance)).await?;
+    assert_eq!(provider.get_balance(account).await?, balance);
+
+    provider.raw_request::<_, ()>("hardhat_mine".into(), (U256::from(2),)).await?;
+    let mined: String = provider.raw_request("evm_mine".into(), ()).await?;
+    assert_eq!(mined, "0x0");
+    assert_eq!(provider.get_block_number().await?, 4);
+
+    let reverted: bool = provider.raw_request("evm_revert".into(), (snapshot,)).await?;
+    assert!(reverted);
+    assert_eq!(provider.get_block_number().await?, 0);
+    assert_eq!(provider.get_balance(account).await?, U256::ZERO);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_change_block_building_through_miner() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+            RpcModuleSelection::from([
+                RethRpcModule::Eth,
+                RethRpcModule::Miner,
+                RethRpcModule::Anvil,
+            ]),
+        ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+
+    let extra_data = Bytes::from_static(b"miner");
+    let updated: bool = provider.raw_request("miner_setExtra".into(), (&extra_data,)).await?;
+    assert!(updated);
+    let updated: bool =
+        provider.raw_request("miner_setGasLimit".into(), (U128::from(100_000),)).await?;
+    assert!(updated);
+    // the transaction only pays a priority fee of 2 gwei
+    let updated: bool =
+        provider.raw_request("miner_setGasPrice".into(), (U128::from(3_000_000_000u64),)).await?;
+    assert!(updated);
+
+    let hash = provider.send_raw_transaction(&RAW_TX).await?.tx_hash().to_owned();
+    while provider.get_block_number().await? < 1 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let genesis = provider.get_block_by_number(0.into()).await?.unwrap();
+    let block = provider.get_block_by_number(1.into()).await?.unwrap();
+    assert_eq!(block.header.extra_data, extra_data);
+    // the gas limit moves towards the new one by the maximum allowed step
+    assert_eq!(
+        block.header.gas_limit,
+        genesis.header.gas_limit + genesis.header.gas_limit / 1024 - 1
+    );
+    assert!(block.transactions.is_empty());
+
+    let updated: bool = provider.raw_request("miner_setGasPrice".into(), (U128::ZERO,)).await?;
+    assert!(updated);
+    provider.raw_request::<_, ()>("anvil_mine".into(), ()).await?;
+    let block = provider.get_block_by_number(2.into()).await?.unwrap();
+    assert_eq!(block.transactions.hashes().collect::<Vec<_>>(), vec![hash]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_prove_state_beyond_proof_window() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut rpc = RpcServerArgs::default()
+        .with_unused_ports()
+        .with_http()
+        .with_http_api(RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Anvil]));
+    rpc.rpc_eth_proof_max_depth = Some(4);
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(rpc);
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let account = address!("0x00000000000000000000000000000000000000aa");
+    let balance = U256::from(1_000_000);
+
+    // change the account in every block after block 1
+    provider.raw_request::<_, ()>("anvil_setBalance".into(), (account, balance)).await?;
+    let stored: bool = provider
+        .raw_request("anvil_setStorageAt".into(), (account, U256::ZERO, B256::with_last_byte(1)))
+        .await?;
+    assert!(stored);
+    provider
+        .raw_request::<_, ()>("anvil_setBalance".into(), (account, balance * U256::from(2)))
+        .await?;
+    provider.raw_request::<_, ()>("anvil_mine".into(), ()).await?;
+    assert_eq!(provider.get_block_number().await?, 4);
+
+    let state_root = provider.get_block_by_number(1.into()).await?.unwrap().header.state_root;
+    let proof = provider.get_proof(account, vec![B256::ZERO]).block_id(1.into()).await?;
+    assert_eq!(proof.balance, balance);
+    assert_eq!(proof.storage_hash, EMPTY_ROOT_HASH);
+    assert_eq!(proof.storage_proof[0].value, U256::ZERO);
+    assert_eq!(keccak256(&proof.account_proof[0]), state_root);
+
+    // the reverts are extended by the new block
+    provider.raw_request::<_, ()>("anvil_mine".into(), ()).await?;
+    let extended = provider.get_proof(account, vec![B256::ZERO]).block_id(1.into()).await?;
+    assert_eq!(extended, proof);
+
+    // the genesis block is deeper than the maximum depth
+    assert!(provider.get_proof(account, vec![]).block_id(0.into()).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_search_transactions_with_call_index() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut rpc = RpcServerArgs::default()
+        .with_unused_ports()
+        .with_http()
+        .with_http_api(RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Ots]));
+    rpc.rpc_call_index = true;
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(rpc);
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let sender = address!("0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b");
+    let recipient = address!("0xab0840c0e43688012c1adb0f5e3fc665188f83d2");
+
+    assert_chain_advances(node.clone()).await;
+    while node.provider.call_index_head()?.is_none_or(|head| head.number < 1) {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    for address in [sender, recipient] {
+        let page: TransactionsWithReceipts =
+            provider.raw_request("ots_searchTransactionsBefore".into(), (address, 0, 10)).await?;
+        assert_eq!(page.txs.len(), 1);
+        assert_eq!(page.receipts[0].receipt.block_number, Some(1));
+        assert!(page.first_page && page.last_page);
+
+        let page: TransactionsWithReceipts =
+            provider.raw_request("ots_searchTransactionsAfter".into(), (address, 0, 10)).await?;
+        assert_eq!(page.txs.len(), 1);
+        assert!(page.first_page && page.last_page);
+    }
+
+    // the transaction is not part of the pages next to its block
+    let page: TransactionsWithReceipts =
+        provider.raw_request("ots_searchTransactionsBefore".into(), (sender, 1, 10)).await?;
+    assert!(page.txs.is_empty());
+    let page: TransactionsWithReceipts =
+        provider.raw_request("ots_searchTransactionsAfter".into(), (sender, 1, 10)).await?;
+    assert!(page.txs.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_filter_traces_with_call_index() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut rpc = RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+        RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Trace, RethRpcModule::Anvil]),
+    );
+    rpc.rpc_call_index = true;
+    rpc.rpc_max_trace_filter_blocks = 0;
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(rpc);
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let recipient = address!("0xab0840c0e43688012c1adb0f5e3fc665188f83d2");
+
+    assert_chain_advances(node.clone()).await;
+    provider.raw_request::<_, ()>("anvil_mine".into(), (U256::from(3),)).await?;
+    while node.provider.call_index_head()?.is_none_or(|head| head.number < 4) {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    // only the block of the transfer is traced
+    let filter = TraceFilter::default().from_block(0).to_block(4).to_address(vec![recipient]);
+    let traces: Vec<LocalizedTransactionTrace> =
+        provider.raw_request("trace_filter".into(), (&filter,)).await?;
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].block_number, Some(1));
+
+    // without addresses, all blocks of the range would have to be traced
+    let filter = TraceFilter::default().from_block(0).to_block(4);
+    assert!(provider
+        .raw_request::<_, Vec<LocalizedTransactionTrace>>("trace_filter".into(), (&filter,))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_simulate_in_session() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config =
+        NodeConfig::test()
+            .with_chain(custom_chain())
+            .with_dev(DevArgs { dev: true, ..Default::default() })
+            .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+                RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Reth]),
+            ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let sender = address!("0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b");
+    let recipient = address!("0xab0840c0e43688012c1adb0f5e3fc665188f83d2");
+    // returns the value of storage slot 0
+    let contract = address!("0x00000000000000000000000000000000000000aa");
+    let code = Bytes::from_static(&hex!("60005460005260206000f3"));
+
+    let id: String = provider.raw_request("reth_newSimulation".into(), ()).await?;
+
+    // each batch continues from the blocks simulated before
+    let transfer = serde_json::json!({
+        "blockStateCalls": [{ "calls": [{ "from": sender, "to": recipient, "value": "0x3e8" }] }]
+    });
+    for number in 1..=2u64 {
+        let blocks: Vec<serde_json::Value> =
+            provider.raw_request("reth_simulate".into(), (&id, &transfer)).await?;
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0]["number"], format!("{number:#x}"));
+        assert_eq!(blocks[0]["calls"][0]["status"], "0x1");
+    }
+    let balance: U256 =
+        provider.raw_request("reth_simulationGetBalance".into(), (&id, recipient)).await?;
+    assert_eq!(balance, U256::from(2000));
+    assert_eq!(provider.get_balance(recipient).await?, U256::ZERO);
+
+    // state overrides of a batch are kept as well
+    let deploy = serde_json::json!({
+        "blockStateCalls": [{
+            "stateOverrides": {
+                contract.to_string(): { "code": code, "stateDiff": { B256::ZERO.to_string(): B256::with_last_byte(7) } }
+            },
+            "calls": []
+        }]
+    });
+    let _: Vec<serde_json::Value> =
+        provider.raw_request("reth_simulate".into(), (&id, &deploy)).await?;
+    let value: B256 = provider
+        .raw_request("reth_simulationGetStorageAt".into(), (&id, contract, B256::ZERO))
+        .await?;
+    assert_eq!(value, B256::with_last_byte(7));
+    let output: Bytes = provider
+        .raw_request("reth_simulationCall".into(), (&id, serde_json::json!({ "to": contract })))
+        .await?;
+    assert_eq!(output[..], B256::with_last_byte(7)[..]);
+
+    // failed batches are not applied
+    let invalid = serde_json::json!({
+        "blockStateCalls": [
+            { "calls": [{ "from": sender, "to": recipient, "value": "0x3e8" }] },
+            { "calls": [{ "from": sender, "to": recipient, "gas": "0xffffffff" }] }
+        ]
+    });
+    assert!(provider
+        .raw_request::<_, Vec<serde_json::Value>>("reth_simulate".into(), (&id, &invalid))
+        .await
+        .is_err());
+    let balance: U256 =
+        provider.raw_request("reth_simulationGetBalance".into(), (&id, recipient)).await?;
+    assert_eq!(balance, U256::from(2000));
+
+    let dropped: bool = provider.raw_request("reth_dropSimulation".into(), (&id,)).await?;
+    assert!(dropped);
+    assert!(provider
+        .raw_request::<_, U256>("reth_simulationGetBalance".into(), (&id, recipient))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_get_state_diff() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config =
+        NodeConfig::test()
+            .with_chain(custom_chain())
+            .with_dev(DevArgs { dev: true, ..Default::default() })
+            .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+                RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Reth]),
+            ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let provider =
+        ProviderBuilder::new().connect_http(node.rpc_server_handle().http_url().unwrap().parse()?);
+    let sender = address!("0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b");
+    let recipient = address!("0xab0840c0e43688012c1adb0f5e3fc665188f83d2");
+
+    assert_chain_advances(node.clone()).await;
+
+    let diff: BlockStateDiff = provider.raw_request("reth_getStateDiff".into(), (1,)).await?;
+    assert_eq!(diff.block_number, 1);
+
+    let sender_diff = &diff.accounts[&sender];
+    let (before, after) = (sender_diff.before.unwrap(), sender_diff.after.unwrap());
+    assert_eq!((before.nonce, after.nonce), (0, 1));
+    assert!(after.balance < before.balance);
+
+    let recipient_diff = &diff.accounts[&recipient];
+    assert_eq!(recipient_diff.before, None);
+    assert_eq!(recipient_diff.after.unwrap().balance, provider.get_balance(recipient).await?);
+    assert!(recipient_diff.storage.is_empty());
+
+    let diffs: Vec<BlockStateDiff> =
+        provider.raw_request("reth_getStateDiffRange".into(), (0, 1)).await?;
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(diffs[1], diff);
+
+    // ranges are limited
+    assert!(provider
+        .raw_request::<_, Vec<BlockStateDiff>>("reth_getStateDiffRange".into(), (0, 1_000))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+/// A transfer from the funded account of [`custom_chain`], paying a priority fee of 2 gwei.
+const RAW_TX: [u8; 121] = hex!(
+    "02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090"
+);
+
 async fn assert_chain_advances<N, AddOns>(node: FullNode<N, AddOns>)
 where
     N: FullNodeComponents<Provider: CanonStateSubscriptions>,
@@ -54,13 +615,9 @@ where
     let mut notifications = node.provider.canonical_state_stream();
 
     // submit tx through rpc
-    let raw_tx = hex!(
-        "02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090"
-    );
-
     let eth_api = node.rpc_registry.eth_api();
 
-    let hash = eth_api.send_raw_transaction(raw_tx.into()).await.unwrap();
+    let hash = eth_api.send_raw_transaction(RAW_TX.into()).await.unwrap();
 
     let expected = b256!("0xb1c6512f4fc202c04355fbda66755e0e344b152e633010e8fd75ecec09b63398");
 
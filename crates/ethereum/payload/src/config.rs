@@ -1,4 +1,5 @@
 use alloy_eips::eip1559::ETHEREUM_BLOCK_GAS_LIMIT_30M;
+use reth_payload_builder::{MinerSettings, PendingStateChanges};
 use reth_primitives_traits::constants::GAS_LIMIT_BOUND_DIVISOR;
 
 /// Settings for the Ethereum builder.
@@ -9,6 +10,11 @@ pub struct EthereumBuilderConfig {
     /// Waits for the first payload to be built if there is no payload built when the payload is
     /// being resolved.
     pub await_payload_on_missing: bool,
+    /// State changes to apply at the start of payloads, used by the local miner in dev mode.
+    pub pending_state_changes: Option<PendingStateChanges>,
+    /// Block building parameters that can be changed while the node is running, which take
+    /// precedence over the configured ones.
+    pub miner_settings: Option<MinerSettings>,
 }
 
 impl Default for EthereumBuilderConfig {
@@ -20,7 +26,12 @@ impl Default for EthereumBuilderConfig {
 impl EthereumBuilderConfig {
     /// Create new payload builder config.
     pub const fn new() -> Self {
-        Self { desired_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT_30M, await_payload_on_missing: true }
+        Self {
+            desired_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT_30M,
+            await_payload_on_missing: true,
+            pending_state_changes: None,
+            miner_settings: None,
+        }
     }
 
     /// Set desired gas limit.
@@ -35,13 +46,36 @@ impl EthereumBuilderConfig {
         self.await_payload_on_missing = await_payload_on_missing;
         self
     }
+
+    /// Configures the [`PendingStateChanges`] that are applied to payloads built on top of the
+    /// blocks they are registered for.
+    pub fn with_pending_state_changes(
+        mut self,
+        pending_state_changes: PendingStateChanges,
+    ) -> Self {
+        self.pending_state_changes = Some(pending_state_changes);
+        self
+    }
+
+    /// Configures the [`MinerSettings`] that override the configured block building parameters.
+    pub fn with_miner_settings(mut self, miner_settings: MinerSettings) -> Self {
+        self.miner_settings = Some(miner_settings);
+        self
+    }
 }
 
 impl EthereumBuilderConfig {
     /// Returns the gas limit for the next block based
     /// on parent and desired gas limits.
+    ///
+    /// The gas limit set through the [`MinerSettings`] takes precedence over the desired one.
     pub fn gas_limit(&self, parent_gas_limit: u64) -> u64 {
-        calculate_block_gas_limit(parent_gas_limit, self.desired_gas_limit)
+        let desired_gas_limit = self
+            .miner_settings
+            .as_ref()
+            .and_then(|settings| settings.gas_limit())
+            .unwrap_or(self.desired_gas_limit);
+        calculate_block_gas_limit(parent_gas_limit, desired_gas_limit)
     }
 }
 
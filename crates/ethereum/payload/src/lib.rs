@@ -10,11 +10,12 @@
 #![allow(clippy::useless_let_if_seq)]
 
 use alloy_consensus::Transaction;
-use alloy_primitives::U256;
+use alloy_primitives::{keccak256, U256};
 use reth_basic_payload_builder::{
     is_better_payload, BuildArguments, BuildOutcome, MissingPayloadBehaviour, PayloadBuilder,
     PayloadConfig,
 };
+use reth_chain_state::{ExecutedBlock, ExecutedBlockWithTrieUpdates, ExecutedTrieUpdates};
 use reth_chainspec::{ChainSpecProvider, EthChainSpec, EthereumHardforks};
 use reth_errors::{BlockExecutionError, BlockValidationError};
 use reth_ethereum_primitives::{EthPrimitives, TransactionSigned};
@@ -23,10 +24,13 @@ use reth_evm::{
     ConfigureEvm, Evm, NextBlockEnvAttributes,
 };
 use reth_evm_ethereum::EthEvmConfig;
-use reth_payload_builder::{BlobSidecars, EthBuiltPayload, EthPayloadBuilderAttributes};
+use reth_execution_types::ExecutionOutcome;
+use reth_payload_builder::{
+    BlobSidecars, EthBuiltPayload, EthPayloadBuilderAttributes, StateChanges,
+};
 use reth_payload_builder_primitives::PayloadBuilderError;
 use reth_payload_primitives::PayloadBuilderAttributes;
-use reth_primitives_traits::transaction::error::InvalidTransactionError;
+use reth_primitives_traits::{transaction::error::InvalidTransactionError, RecoveredBlock};
 use reth_revm::{database::StateProviderDatabase, db::State};
 use reth_storage_api::StateProviderFactory;
 use reth_transaction_pool::{
@@ -34,7 +38,12 @@ use reth_transaction_pool::{
     BestTransactions, BestTransactionsAttributes, PoolTransaction, TransactionPool,
     ValidPoolTransaction,
 };
-use revm::context_interface::Block as _;
+use revm::{
+    bytecode::Bytecode,
+    context_interface::Block as _,
+    state::{Account, EvmState, EvmStorageSlot},
+    Database, DatabaseCommit,
+};
 use std::sync::Arc;
 use tracing::{debug, trace, warn};
 
@@ -127,6 +136,40 @@ where
     }
 }
 
+/// Applies the given state changes to the state of the payload, as if they were made by a
+/// transaction.
+fn apply_state_changes<DB>(db: &mut DB, changes: StateChanges) -> Result<(), PayloadBuilderError>
+where
+    DB: Database<Error: core::error::Error + Send + Sync + 'static> + DatabaseCommit,
+{
+    let mut state = EvmState::default();
+    for (address, changes) in changes {
+        let info = db.basic(address).map_err(PayloadBuilderError::other)?.unwrap_or_default();
+        let mut account = Account::from(info);
+        if let Some(balance) = changes.balance {
+            account.info.balance = balance;
+        }
+        if let Some(nonce) = changes.nonce {
+            account.info.nonce = nonce;
+        }
+        if let Some(code) = changes.code {
+            account.info.code_hash = keccak256(&code);
+            account.info.code =
+                Some(Bytecode::new_raw_checked(code).map_err(PayloadBuilderError::other)?);
+        }
+        for (slot, value) in changes.storage {
+            let slot = slot.into();
+            // the original value is recorded in the changesets, so it must be the actual one
+            let original = db.storage(address, slot).map_err(PayloadBuilderError::other)?;
+            account.storage.insert(slot, EvmStorageSlot::new_changed(original, value.into(), 0));
+        }
+        account.mark_touch();
+        state.insert(address, account);
+    }
+    db.commit(state);
+    Ok(())
+}
+
 /// Constructs an Ethereum transaction payload using the best transactions from the pool.
 ///
 /// Given build arguments including an Ethereum client, transaction pool,
@@ -155,6 +198,17 @@ where
     let mut db =
         State::builder().with_database(cached_reads.as_db_mut(state)).with_bundle_update().build();
 
+    // irregular state changes requested for dev mode blocks
+    let state_changes = builder_config
+        .pending_state_changes
+        .as_ref()
+        .and_then(|pending| pending.get(parent_header.hash()));
+    let has_state_changes = state_changes.is_some();
+    if let Some(changes) = state_changes {
+        debug!(target: "payload_builder", id=%attributes.id, accounts=changes.len(), "applying pending state changes");
+        apply_state_changes(&mut db, changes)?;
+    }
+
     let mut builder = evm_config
         .builder_for_next_block(
             &mut db,
@@ -177,6 +231,10 @@ where
     let block_gas_limit: u64 = builder.evm_mut().block().gas_limit;
     let base_fee = builder.evm_mut().block().basefee;
 
+    // minimum tip set through the `miner` namespace
+    let min_priority_fee =
+        builder_config.miner_settings.as_ref().and_then(|settings| settings.min_priority_fee());
+
     let mut best_txs = best_txs(BestTransactionsAttributes::new(
         base_fee,
         builder.evm_mut().block().blob_gasprice().map(|gasprice| gasprice as u64),
@@ -211,6 +269,20 @@ where
             continue
         }
 
+        if let Some(min_priority_fee) = min_priority_fee {
+            if pool_tx.effective_tip_per_gas(base_fee).is_none_or(|tip| tip < min_priority_fee) {
+                // the transaction doesn't pay enough to the miner, so its dependent transactions
+                // can't be included either
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::PriorityFeeBelowMinimum {
+                        minimum_priority_fee: min_priority_fee,
+                    },
+                );
+                continue
+            }
+        }
+
         // check if the job was cancelled, if so we can exit early
         if cancel.is_cancelled() {
             return Ok(BuildOutcome::Cancelled)
@@ -327,18 +399,49 @@ where
         return Ok(BuildOutcome::Aborted { fees: total_fees, cached_reads })
     }
 
-    let BlockBuilderOutcome { execution_result, block, .. } = builder.finish(&state_provider)?;
+    let BlockBuilderOutcome { execution_result, hashed_state, trie_updates, block } =
+        builder.finish(&state_provider)?;
+
+    // the block assembler uses the extra data the node was launched with, so extra data that was
+    // set through the `miner` namespace replaces it
+    let block = match builder_config.miner_settings.as_ref().and_then(|s| s.extra_data()) {
+        Some(extra_data) => {
+            let (mut block, senders) = block.split();
+            block.header.extra_data = extra_data;
+            RecoveredBlock::new_unhashed(block, senders)
+        }
+        None => block,
+    };
 
     let requests = chain_spec
         .is_prague_active_at_timestamp(attributes.timestamp)
-        .then_some(execution_result.requests);
+        .then_some(execution_result.requests.clone());
 
     let sealed_block = Arc::new(block.sealed_block().clone());
     debug!(target: "payload_builder", id=%attributes.id, sealed_block_header = ?sealed_block.sealed_header(), "sealed built block");
 
-    let payload = EthBuiltPayload::new(attributes.id, sealed_block, total_fees, requests)
+    let mut payload = EthBuiltPayload::new(attributes.id, sealed_block, total_fees, requests)
         // add blob sidecars from the executed txs
         .with_sidecars(blob_sidecars);
 
+    if has_state_changes {
+        // the state changes are not part of the block's transactions, so the engine must not
+        // re-execute the block
+        let execution_outcome = ExecutionOutcome::new(
+            db.take_bundle(),
+            vec![execution_result.receipts],
+            block.number,
+            Vec::new(),
+        );
+        payload = payload.with_executed_block(ExecutedBlockWithTrieUpdates {
+            block: ExecutedBlock {
+                recovered_block: Arc::new(block),
+                execution_output: Arc::new(execution_outcome),
+                hashed_state: Arc::new(hashed_state),
+            },
+            trie: ExecutedTrieUpdates::Present(Arc::new(trie_updates)),
+        });
+    }
+
     Ok(BuildOutcome::Better { payload, cached_reads })
 }
@@ -0,0 +1,155 @@
+//! Execution extension that maintains the call index.
+//!
+//! The call index maps every address to the blocks in which it took part in a call, either as the
+//! caller or as the callee. This includes the senders and recipients of transactions, internal
+//! calls, contract creations and selfdestruct beneficiaries. Since logs are emitted by the called
+//! contract, log emitters are covered as well.
+//!
+//! The index is used to search the transactions of an address and to narrow down the blocks that
+//! `trace_filter` re-executes, see [`CallIndexReader`](reth_provider::CallIndexReader).
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{Address, BlockNumber};
+use futures::TryStreamExt;
+use reth_evm::{execute::BlockExecutor, ConfigureEvm, Evm};
+use reth_execution_types::Chain;
+use reth_exex::{ExExContext, ExExEvent, ExExHead};
+use reth_node_api::{FullNodeComponents, PrimitivesTy};
+use reth_primitives_traits::AlloyBlockHeader;
+use reth_provider::{
+    BlockHashReader, CallIndexKind, CallIndexReader, CallIndexWriter, DBProvider,
+    DatabaseProviderFactory, ProviderError, StateProviderFactory,
+};
+use reth_revm::{database::StateProviderDatabase, db::State};
+use revm_inspectors::tracing::{types::CallTraceNode, TracingInspector, TracingInspectorConfig};
+use std::collections::BTreeMap;
+use tracing::debug;
+
+/// The ID the call index `ExEx` is installed with.
+pub const CALL_INDEX_EXEX_ID: &str = "call-index";
+
+/// Runs the `ExEx` that keeps the call index in sync with the canonical chain.
+///
+/// On first launch the whole chain is indexed, starting at genesis. Afterwards, indexing resumes at
+/// the last indexed block.
+pub async fn call_index_exex<Node: FullNodeComponents>(
+    mut ctx: ExExContext<Node>,
+) -> eyre::Result<()> {
+    let head = match ctx.provider().call_index_head()? {
+        Some(head) => head,
+        None => BlockNumHash::new(
+            0,
+            ctx.provider().block_hash(0)?.ok_or(ProviderError::HeaderNotFound(0.into()))?,
+        ),
+    };
+    debug!(target: "exex::call_index", ?head, "Starting call index");
+    ctx.set_notifications_with_head(ExExHead::new(head));
+
+    while let Some(notification) = ctx.notifications.try_next().await? {
+        if let Some(reverted_chain) = notification.reverted_chain() {
+            let participants = CallParticipants::trace_chain(&ctx, &reverted_chain)?;
+            let first_block = reverted_chain.first().number();
+
+            let provider = ctx.provider().database_provider_rw()?;
+            provider.unwind_call_index(
+                CallIndexKind::From,
+                participants.from.into_keys().collect(),
+                first_block,
+            )?;
+            provider.unwind_call_index(
+                CallIndexKind::To,
+                participants.to.into_keys().collect(),
+                first_block,
+            )?;
+            provider.save_call_index_head(reverted_chain.fork_block())?;
+            provider.commit()?;
+            debug!(target: "exex::call_index", range = ?reverted_chain.range(), "Unwound call index");
+        }
+
+        if let Some(committed_chain) = notification.committed_chain() {
+            let participants = CallParticipants::trace_chain(&ctx, &committed_chain)?;
+
+            let provider = ctx.provider().database_provider_rw()?;
+            provider.insert_call_index(CallIndexKind::From, participants.from)?;
+            provider.insert_call_index(CallIndexKind::To, participants.to)?;
+            provider.save_call_index_head(committed_chain.tip().num_hash())?;
+            provider.commit()?;
+            debug!(target: "exex::call_index", range = ?committed_chain.range(), "Indexed calls");
+
+            ctx.events.send(ExExEvent::FinishedHeight(committed_chain.tip().num_hash()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The blocks in which addresses took part in calls, by side of the call.
+#[derive(Debug, Default)]
+struct CallParticipants {
+    from: BTreeMap<Address, Vec<BlockNumber>>,
+    to: BTreeMap<Address, Vec<BlockNumber>>,
+}
+
+impl CallParticipants {
+    /// Re-executes the blocks of the chain on top of its fork block and records the participants
+    /// of all calls made by their transactions.
+    ///
+    /// System calls made before and after the transactions are not recorded.
+    fn trace_chain<Node: FullNodeComponents>(
+        ctx: &ExExContext<Node>,
+        chain: &Chain<PrimitivesTy<Node::Types>>,
+    ) -> eyre::Result<Self> {
+        let state = ctx.provider().history_by_block_hash(chain.fork_block().hash)?;
+        let mut db = State::builder().with_database(StateProviderDatabase::new(state)).build();
+        let evm_config = ctx.evm_config();
+
+        let mut participants = Self::default();
+        for block in chain.blocks_iter() {
+            let evm = evm_config.evm_with_env_and_inspector(
+                &mut db,
+                evm_config.evm_env(block.header()),
+                TracingInspector::new(TracingInspectorConfig::none()),
+            );
+            let mut executor = evm_config.create_executor(evm, evm_config.context_for_block(block));
+
+            executor.apply_pre_execution_changes()?;
+            for tx in block.transactions_recovered() {
+                executor.evm_mut().inspector_mut().fuse();
+                executor.execute_transaction(tx)?;
+                for node in executor.evm().inspector().traces().nodes() {
+                    participants.record(block.number(), node);
+                }
+            }
+            executor.apply_post_execution_changes()?;
+        }
+
+        Ok(participants)
+    }
+
+    /// Records the caller and callee of the call.
+    ///
+    /// If the callee selfdestructed, it is also recorded as sending its balance to the beneficiary.
+    fn record(&mut self, block: BlockNumber, node: &CallTraceNode) {
+        Self::push(&mut self.from, node.trace.caller, block);
+        Self::push(&mut self.to, node.trace.address, block);
+        if let Some(target) = node.trace.selfdestruct_refund_target {
+            Self::push(&mut self.from, node.trace.address, block);
+            Self::push(&mut self.to, target, block);
+        }
+    }
+
+    fn push(index: &mut BTreeMap<Address, Vec<BlockNumber>>, address: Address, block: BlockNumber) {
+        let blocks = index.entry(address).or_default();
+        if blocks.last() != Some(&block) {
+            blocks.push(block);
+        }
+    }
+}
@@ -106,6 +106,9 @@ pub use manager::*;
 mod notifications;
 pub use notifications::*;
 
+mod remote;
+pub use remote::*;
+
 mod wal;
 pub use wal::*;
 
@@ -1,5 +1,6 @@
 use crate::{
-    wal::Wal, ExExEvent, ExExNotification, ExExNotifications, FinishedExExHeight, WalHandle,
+    wal::Wal, ExExEvent, ExExHead, ExExNotification, ExExNotifications, ExExNotificationsStream,
+    FinishedExExHeight, WalHandle,
 };
 use alloy_consensus::BlockHeader;
 use alloy_eips::BlockNumHash;
@@ -12,8 +13,8 @@ use reth_evm::ConfigureEvm;
 use reth_metrics::{metrics::Counter, Metrics};
 use reth_node_api::NodePrimitives;
 use reth_primitives_traits::SealedHeader;
-use reth_provider::HeaderProvider;
-use reth_tracing::tracing::{debug, warn};
+use reth_provider::{BlockReader, HeaderProvider, StateProviderFactory};
+use reth_tracing::tracing::{debug, info, warn};
 use std::{
     collections::VecDeque,
     fmt::Debug,
@@ -56,6 +57,21 @@ pub enum ExExNotificationSource {
     BlockchainTree,
 }
 
+/// A command to change the set of `ExEx`'s of a running [`ExExManager`].
+#[derive(Debug)]
+enum ExExManagerCommand<N: NodePrimitives> {
+    /// Registers the `ExEx` with the given handle.
+    AddExEx(ExExHandle<N>),
+    /// Removes the `ExEx` with the given ID.
+    RemoveExEx(String),
+}
+
+/// The error returned when `ExEx`'s can't be added or removed, because the [`ExExManager`] is not
+/// running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("ExEx manager is not running")]
+pub struct ExExManagerNotRunning;
+
 /// Metrics for an `ExEx`.
 #[derive(Metrics)]
 #[metrics(scope = "exex")]
@@ -220,6 +236,10 @@ pub struct ExExManager<P, N: NodePrimitives> {
 
     /// [`ExExNotification`] channel from the [`ExExManagerHandle`]s.
     handle_rx: UnboundedReceiver<(ExExNotificationSource, ExExNotification<N>)>,
+    /// Channel to receive commands to add or remove `ExEx`'s from the [`ExExManagerHandle`]s.
+    command_rx: UnboundedReceiver<ExExManagerCommand<N>>,
+    /// The number of `ExEx`'s, shared with the [`ExExManagerHandle`]s.
+    num_exexs: Arc<AtomicUsize>,
 
     /// The minimum notification ID currently present in the buffer.
     min_id: usize,
@@ -275,6 +295,7 @@ where
         let num_exexs = handles.len();
 
         let (handle_tx, handle_rx) = mpsc::unbounded_channel();
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
         let (is_ready_tx, is_ready_rx) = watch::channel(true);
         let (finished_height_tx, finished_height_rx) = watch::channel(if num_exexs == 0 {
             FinishedExExHeight::NoExExs
@@ -283,10 +304,11 @@ where
         });
 
         let current_capacity = Arc::new(AtomicUsize::new(max_capacity));
+        let num_exexs = Arc::new(AtomicUsize::new(num_exexs));
 
         let metrics = ExExManagerMetrics::default();
         metrics.max_capacity.set(max_capacity as f64);
-        metrics.num_exexs.set(num_exexs as f64);
+        metrics.num_exexs.set(num_exexs.load(Ordering::Relaxed) as f64);
 
         Self {
             provider,
@@ -294,6 +316,8 @@ where
             exex_handles: handles,
 
             handle_rx,
+            command_rx,
+            num_exexs: Arc::clone(&num_exexs),
 
             min_id: 0,
             next_id: 0,
@@ -304,17 +328,20 @@ where
             is_ready: is_ready_tx,
             finished_height: finished_height_tx,
 
-            wal,
-            finalized_header_stream,
-
             handle: ExExManagerHandle {
                 exex_tx: handle_tx,
+                command_tx,
+                wal_handle: Some(wal.handle()),
                 num_exexs,
                 is_ready_receiver: is_ready_rx.clone(),
                 is_ready: ReusableBoxFuture::new(make_wait_future(is_ready_rx)),
                 current_capacity,
                 finished_height: finished_height_rx,
             },
+
+            wal,
+            finalized_header_stream,
+
             metrics,
         }
     }
@@ -337,6 +364,37 @@ where
         let _ = self.is_ready.send(capacity > 0);
     }
 
+    /// Adds or removes an `ExEx`.
+    fn handle_command(&mut self, command: ExExManagerCommand<N>) {
+        match command {
+            ExExManagerCommand::AddExEx(mut exex) => {
+                if self.exex_handles.iter().any(|handle| handle.id == exex.id) {
+                    warn!(target: "exex::manager", exex_id = %exex.id, "ExEx with the same ID is already registered, dropping the new one");
+                    return
+                }
+
+                // The `ExEx` receives the notifications that arrive after it was registered, the
+                // earlier blocks are backfilled by its notifications stream.
+                exex.next_notification_id = self.next_id;
+                info!(target: "exex::manager", exex_id = %exex.id, "Registered ExEx");
+                self.exex_handles.push(exex);
+            }
+            ExExManagerCommand::RemoveExEx(id) => {
+                let num_exexs = self.exex_handles.len();
+                self.exex_handles.retain(|exex| exex.id != id);
+                if self.exex_handles.len() == num_exexs {
+                    warn!(target: "exex::manager", exex_id = %id, "ExEx to remove is not registered");
+                    return
+                }
+
+                info!(target: "exex::manager", exex_id = %id, "Removed ExEx");
+            }
+        }
+
+        self.num_exexs.store(self.exex_handles.len(), Ordering::Relaxed);
+        self.metrics.num_exexs.set(self.exex_handles.len() as f64);
+    }
+
     /// Pushes a new notification into the managers internal buffer, assigning the notification a
     /// unique ID.
     fn push_notification(&mut self, notification: ExExNotification<N>) {
@@ -428,19 +486,25 @@ where
     type Output = eyre::Result<()>;
 
     /// Main loop of the [`ExExManager`]. The order of operations is as follows:
-    /// 1. Handle incoming ExEx events. We do it before finalizing the WAL, because it depends on
+    /// 1. Add and remove ExExes according to the [`ExExManagerHandle`] commands.
+    /// 2. Handle incoming ExEx events. We do it before finalizing the WAL, because it depends on
     ///    the latest state of [`ExExEvent::FinishedHeight`] events.
-    /// 2. Finalize the WAL with the finalized header, if necessary.
-    /// 3. Drain [`ExExManagerHandle`] notifications, push them to the internal buffer and update
+    /// 3. Finalize the WAL with the finalized header, if necessary.
+    /// 4. Drain [`ExExManagerHandle`] notifications, push them to the internal buffer and update
     ///    the internal buffer capacity.
     /// 5. Send notifications from the internal buffer to those ExExes that are ready to receive new
     ///    notifications.
-    /// 5. Remove notifications from the internal buffer that have been sent to **all** ExExes and
+    /// 6. Remove notifications from the internal buffer that have been sent to **all** ExExes and
     ///    update the internal buffer capacity.
-    /// 6. Update the channel with the lowest [`FinishedExExHeight`] among all ExExes.
+    /// 7. Update the channel with the lowest [`FinishedExExHeight`] among all ExExes.
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        // Add and remove ExExes
+        while let Poll::Ready(Some(command)) = this.command_rx.poll_recv(cx) {
+            this.handle_command(command);
+        }
+
         // Handle incoming ExEx events
         for exex in &mut this.exex_handles {
             while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
@@ -490,8 +554,8 @@ where
         // Update capacity
         this.update_capacity();
 
-        // Advance all poll senders
-        let mut min_id = usize::MAX;
+        // Advance all poll senders. Without ExExes, all notifications are processed.
+        let mut min_id = this.next_id;
         for idx in (0..this.exex_handles.len()).rev() {
             let mut exex = this.exex_handles.swap_remove(idx);
 
@@ -520,11 +584,18 @@ where
         this.update_capacity();
 
         // Update watch channel block number
-        let finished_height = this.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
-            exex.finished_height.map_or(Err(()), |height| Ok(height.number.min(curr)))
-        });
-        if let Ok(finished_height) = finished_height {
-            let _ = this.finished_height.send(FinishedExExHeight::Height(finished_height));
+        if this.exex_handles.is_empty() {
+            let _ = this.finished_height.send_if_modified(|finished_height| {
+                std::mem::replace(finished_height, FinishedExExHeight::NoExExs) !=
+                    FinishedExExHeight::NoExExs
+            });
+        } else {
+            let finished_height = this.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
+                exex.finished_height.map_or(Err(()), |height| Ok(height.number.min(curr)))
+            });
+            if let Ok(finished_height) = finished_height {
+                let _ = this.finished_height.send(FinishedExExHeight::Height(finished_height));
+            }
         }
 
         Poll::Pending
@@ -536,8 +607,12 @@ where
 pub struct ExExManagerHandle<N: NodePrimitives = EthPrimitives> {
     /// Channel to send notifications to the `ExEx` manager.
     exex_tx: UnboundedSender<(ExExNotificationSource, ExExNotification<N>)>,
+    /// Channel to send commands to add or remove `ExEx`'s to the `ExEx` manager.
+    command_tx: UnboundedSender<ExExManagerCommand<N>>,
+    /// A handle to the WAL of the `ExEx` manager, if there is one.
+    wal_handle: Option<WalHandle<N>>,
     /// The number of `ExEx`'s running on the node.
-    num_exexs: usize,
+    num_exexs: Arc<AtomicUsize>,
     /// A watch channel denoting whether the manager is ready for new notifications or not.
     ///
     /// This is stored internally alongside a `ReusableBoxFuture` representation of the same value.
@@ -561,12 +636,15 @@ impl<N: NodePrimitives> ExExManagerHandle<N> {
     /// The handle will always be ready, and have a capacity of 0.
     pub fn empty() -> Self {
         let (exex_tx, _) = mpsc::unbounded_channel();
+        let (command_tx, _) = mpsc::unbounded_channel();
         let (_, is_ready_rx) = watch::channel(true);
         let (_, finished_height_rx) = watch::channel(FinishedExExHeight::NoExExs);
 
         Self {
             exex_tx,
-            num_exexs: 0,
+            command_tx,
+            wal_handle: None,
+            num_exexs: Arc::new(AtomicUsize::new(0)),
             is_ready_receiver: is_ready_rx.clone(),
             is_ready: ReusableBoxFuture::new(make_wait_future(is_ready_rx)),
             current_capacity: Arc::new(AtomicUsize::new(0)),
@@ -612,8 +690,53 @@ impl<N: NodePrimitives> ExExManagerHandle<N> {
     }
 
     /// Returns `true` if there are `ExEx`'s installed in the node.
-    pub const fn has_exexs(&self) -> bool {
-        self.num_exexs > 0
+    pub fn has_exexs(&self) -> bool {
+        self.num_exexs.load(Ordering::Relaxed) > 0
+    }
+
+    /// Registers a new `ExEx` with the running manager.
+    ///
+    /// The `ExEx` will receive notifications for the blocks after `exex_head`: the blocks up to
+    /// `node_head`, the current head of the node, are backfilled from the database and the
+    /// [WAL](Wal), and the later blocks are sent by the manager. Returns the channels that should
+    /// be given to the `ExEx`, see [`ExExHandle::new`].
+    ///
+    /// Like for the `ExEx`'s installed at launch, the node is not pruned above the `ExEx` head
+    /// until it emits an [`ExExEvent::FinishedHeight`] event. If an `ExEx` with the same ID is
+    /// already registered, the new one is dropped and its notification stream ends.
+    pub fn add_exex<P, E>(
+        &self,
+        id: impl Into<String>,
+        node_head: BlockNumHash,
+        exex_head: ExExHead,
+        provider: P,
+        evm_config: E,
+    ) -> Result<(UnboundedSender<ExExEvent>, ExExNotifications<P, E>), ExExManagerNotRunning>
+    where
+        P: BlockReader + HeaderProvider + StateProviderFactory + Clone + Unpin + 'static,
+        E: ConfigureEvm<Primitives = N> + Clone + Unpin + 'static,
+        N: NodePrimitives<Block = P::Block>,
+    {
+        let wal_handle = self.wal_handle.clone().ok_or(ExExManagerNotRunning)?;
+        let (handle, events, notifications) =
+            ExExHandle::new(id.into(), node_head, provider, evm_config, wal_handle);
+
+        self.command_tx
+            .send(ExExManagerCommand::AddExEx(handle))
+            .map_err(|_| ExExManagerNotRunning)?;
+
+        Ok((events, notifications.with_head(exex_head)))
+    }
+
+    /// Removes the `ExEx` with the given ID from the running manager.
+    ///
+    /// The notification stream of the `ExEx` ends, and it no longer holds back the pruning of the
+    /// node and the finalization of the WAL. This can be used to remove an `ExEx` that is stuck,
+    /// without restarting the node.
+    pub fn remove_exex(&self, id: impl Into<String>) -> Result<(), ExExManagerNotRunning> {
+        self.command_tx
+            .send(ExExManagerCommand::RemoveExEx(id.into()))
+            .map_err(|_| ExExManagerNotRunning)
     }
 
     /// The finished height of all `ExEx`'s.
@@ -646,7 +769,9 @@ impl<N: NodePrimitives> Clone for ExExManagerHandle<N> {
     fn clone(&self) -> Self {
         Self {
             exex_tx: self.exex_tx.clone(),
-            num_exexs: self.num_exexs,
+            command_tx: self.command_tx.clone(),
+            wal_handle: self.wal_handle.clone(),
+            num_exexs: self.num_exexs.clone(),
             is_ready_receiver: self.is_ready_receiver.clone(),
             is_ready: ReusableBoxFuture::new(make_wait_future(self.is_ready_receiver.clone())),
             current_capacity: self.current_capacity.clone(),
@@ -1393,4 +1518,101 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_add_and_remove_exex() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+
+        let provider_factory = create_test_provider_factory();
+        let genesis_hash = init_genesis(&provider_factory).unwrap();
+        let genesis_block = provider_factory
+            .sealed_block_with_senders(genesis_hash.into(), TransactionVariant::NoHash)
+            .unwrap()
+            .ok_or_else(|| eyre::eyre!("genesis block not found"))?;
+        let provider = BlockchainProvider::new(provider_factory).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let wal = Wal::new(temp_dir.path()).unwrap();
+
+        // An ExEx that never emits a `FinishedHeight` event
+        let (stuck_exex_handle, _stuck_events_tx, mut stuck_notifications) = ExExHandle::new(
+            "stuck_exex".to_string(),
+            Default::default(),
+            provider.clone(),
+            EthEvmConfig::mainnet(),
+            wal.handle(),
+        );
+
+        let mut exex_manager = std::pin::pin!(ExExManager::new(
+            provider.clone(),
+            vec![stuck_exex_handle],
+            2,
+            wal,
+            empty_finalized_header_stream()
+        ));
+        let handle = exex_manager.handle();
+        let mut finished_height = handle.finished_height();
+
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        // Register a new ExEx at the node head
+        let (events_tx, mut notifications) = handle.add_exex(
+            "new_exex",
+            genesis_block.num_hash(),
+            ExExHead { block: genesis_block.num_hash() },
+            provider.clone(),
+            EthEvmConfig::mainnet(),
+        )?;
+        // An ExEx with the same ID is dropped
+        let (_, mut duplicate_notifications) = handle.add_exex(
+            "new_exex",
+            genesis_block.num_hash(),
+            ExExHead { block: genesis_block.num_hash() },
+            provider,
+            EthEvmConfig::mainnet(),
+        )?;
+        assert!(exex_manager.as_mut().poll(&mut cx)?.is_pending());
+        assert_eq!(exex_manager.exex_handles.len(), 2);
+        assert_eq!(duplicate_notifications.try_poll_next_unpin(&mut cx)?, Poll::Ready(None));
+
+        // The new ExEx receives the notifications sent after it was registered
+        let block = random_block(
+            &mut rng,
+            genesis_block.number + 1,
+            BlockParams { parent: Some(genesis_hash), ..Default::default() },
+        )
+        .try_recover()
+        .unwrap();
+        let notification = ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::new(vec![block.clone()], Default::default(), None)),
+        };
+        handle.send(ExExNotificationSource::BlockchainTree, notification.clone())?;
+        assert!(exex_manager.as_mut().poll(&mut cx)?.is_pending());
+        assert_eq!(
+            notifications.try_poll_next_unpin(&mut cx)?,
+            Poll::Ready(Some(notification.clone()))
+        );
+
+        // The finished height is held back by the stuck ExEx, until it's removed
+        events_tx.send(ExExEvent::FinishedHeight(block.num_hash()))?;
+        assert!(exex_manager.as_mut().poll(&mut cx)?.is_pending());
+        assert_eq!(*finished_height.borrow_and_update(), FinishedExExHeight::NotReady);
+
+        handle.remove_exex("stuck_exex")?;
+        assert!(exex_manager.as_mut().poll(&mut cx)?.is_pending());
+        assert_eq!(*finished_height.borrow_and_update(), FinishedExExHeight::Height(block.number));
+        assert_eq!(
+            stuck_notifications.try_poll_next_unpin(&mut cx)?,
+            Poll::Ready(Some(notification))
+        );
+        assert_eq!(stuck_notifications.try_poll_next_unpin(&mut cx)?, Poll::Ready(None));
+
+        // Without ExExes, nothing holds back the finished height
+        handle.remove_exex("new_exex")?;
+        assert!(exex_manager.as_mut().poll(&mut cx)?.is_pending());
+        assert!(!handle.has_exexs());
+        assert_eq!(*finished_height.borrow_and_update(), FinishedExExHeight::NoExExs);
+
+        Ok(())
+    }
 }
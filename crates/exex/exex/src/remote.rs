@@ -0,0 +1,693 @@
+//! Support for running `ExEx`es out of process.
+//!
+//! The [`RemoteExEx`] is installed in the node like any other `ExEx`, and streams the
+//! [`ExExNotification`]s it receives to the subscribers connected to its socket. Subscribers are
+//! separate processes, e.g. indexers, that can be deployed and restarted independently of the
+//! node. The [`RemoteExExClient`] implements the subscriber side of the protocol.
+//!
+//! # Protocol
+//!
+//! The node listens on a TCP address or, on Unix, on an IPC socket path. Every message is a frame
+//! prefixed with its length as a 4-byte big-endian integer, with a MessagePack payload.
+//!
+//! A subscriber opens the stream with a [`RemoteExExRequest::Subscribe`] frame, identifying
+//! itself with a unique ID. The node then sends every [`ExExNotification`], encoded like the
+//! notifications of the [WAL](crate::Wal). The subscriber acknowledges the processed blocks with
+//! [`RemoteExExRequest::FinishedHeight`] frames.
+//!
+//! # Pruning
+//!
+//! The node keeps the notifications that not all known subscribers acknowledged, and replays them
+//! when a subscriber reconnects with the same ID. The `ExEx` reports the lowest finished height of
+//! all known subscribers to the node, so the blocks that a subscriber still needs aren't pruned
+//! while it's restarted. A subscriber that's decommissioned should send
+//! [`RemoteExExRequest::Unsubscribe`] to stop holding back the node.
+//!
+//! If there are no subscribers, notifications are dropped and reported as finished.
+
+use crate::{ExExContext, ExExEvent, ExExNotification, ExExNotifications};
+use alloy_eips::BlockNumHash;
+use bytes::Bytes;
+use futures::{SinkExt, Stream, StreamExt};
+use reth_ethereum_primitives::EthPrimitives;
+use reth_evm::ConfigureEvm;
+use reth_exex_types::serde_bincode_compat;
+use reth_node_api::{FullNodeComponents, NodePrimitives};
+use reth_provider::Chain;
+use reth_tracing::tracing::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, io,
+    marker::PhantomData,
+    net::SocketAddr,
+    pin::Pin,
+    str::FromStr,
+    task::{ready, Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, error::TrySendError, UnboundedReceiver, UnboundedSender},
+};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// The maximum size of a frame, notifications of large chains can be hundreds of megabytes.
+const MAX_FRAME_LENGTH: usize = 1024 * 1024 * 1024;
+
+/// The number of notifications queued for a subscriber, after which it's disconnected.
+const SUBSCRIBER_QUEUE_SIZE: usize = 128;
+
+/// The number of buffered notifications above which a warning is logged.
+const BUFFERED_NOTIFICATIONS_WARNING: usize = 1024;
+
+/// The address of a [`RemoteExEx`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteExExAddr {
+    /// A TCP socket address.
+    Tcp(SocketAddr),
+    /// The path of an IPC socket.
+    #[cfg(unix)]
+    Ipc(std::path::PathBuf),
+}
+
+impl FromStr for RemoteExExAddr {
+    type Err = io::Error;
+
+    /// Parses a TCP socket address, or an IPC socket path otherwise.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(addr) = s.parse() {
+            return Ok(Self::Tcp(addr))
+        }
+
+        #[cfg(not(unix))]
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid socket address {s}"),
+        ));
+        #[cfg(unix)]
+        Ok(Self::Ipc(s.into()))
+    }
+}
+
+impl fmt::Display for RemoteExExAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => addr.fmt(f),
+            #[cfg(unix)]
+            Self::Ipc(path) => path.display().fmt(f),
+        }
+    }
+}
+
+/// A request sent by a subscriber of a [`RemoteExEx`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemoteExExRequest {
+    /// Subscribes to the notifications, must be the first request of a connection.
+    Subscribe {
+        /// The unique ID of the subscriber, which is kept across reconnects.
+        id: String,
+        /// The highest block processed by the subscriber, if known.
+        ///
+        /// Buffered notifications of blocks above it are replayed. If `None`, the last finished
+        /// height the node knows of for this ID is used.
+        finished_height: Option<BlockNumHash>,
+    },
+    /// The highest block processed by the subscriber, see [`ExExEvent::FinishedHeight`].
+    FinishedHeight(BlockNumHash),
+    /// Removes the subscriber, the node no longer keeps notifications for it.
+    Unsubscribe,
+}
+
+/// A connection to or from a [`RemoteExEx`].
+trait Connection: AsyncRead + AsyncWrite + fmt::Debug + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + fmt::Debug + Send + Unpin> Connection for T {}
+
+/// A framed connection.
+type RemoteExExStream = Framed<Box<dyn Connection>, LengthDelimitedCodec>;
+
+/// Frames the connection.
+fn framed(connection: Box<dyn Connection>) -> RemoteExExStream {
+    Framed::new(
+        connection,
+        LengthDelimitedCodec::builder().max_frame_length(MAX_FRAME_LENGTH).new_codec(),
+    )
+}
+
+/// Connects to the address.
+async fn connect(addr: &RemoteExExAddr) -> io::Result<Box<dyn Connection>> {
+    Ok(match addr {
+        RemoteExExAddr::Tcp(addr) => Box::new(TcpStream::connect(addr).await?),
+        #[cfg(unix)]
+        RemoteExExAddr::Ipc(path) => Box::new(tokio::net::UnixStream::connect(path).await?),
+    })
+}
+
+/// Listens for subscribers.
+#[derive(Debug)]
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Ipc(tokio::net::UnixListener),
+}
+
+impl Listener {
+    /// Binds to the address, returns the listener and the bound address.
+    async fn bind(addr: &RemoteExExAddr) -> io::Result<(Self, RemoteExExAddr)> {
+        match addr {
+            RemoteExExAddr::Tcp(addr) => {
+                let listener = TcpListener::bind(addr).await?;
+                let addr = RemoteExExAddr::Tcp(listener.local_addr()?);
+                Ok((Self::Tcp(listener), addr))
+            }
+            #[cfg(unix)]
+            RemoteExExAddr::Ipc(path) => {
+                // A socket file left by a previous run would fail the bind.
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                Ok((Self::Ipc(tokio::net::UnixListener::bind(path)?), addr.clone()))
+            }
+        }
+    }
+
+    async fn accept(&self) -> io::Result<Box<dyn Connection>> {
+        Ok(match self {
+            Self::Tcp(listener) => Box::new(listener.accept().await?.0),
+            #[cfg(unix)]
+            Self::Ipc(listener) => Box::new(listener.accept().await?.0),
+        })
+    }
+}
+
+/// An event of a subscriber connection.
+#[derive(Debug)]
+enum ConnectionEvent {
+    Subscribed {
+        connection_id: u64,
+        id: String,
+        finished_height: Option<BlockNumHash>,
+        frames: mpsc::Sender<Bytes>,
+    },
+    FinishedHeight {
+        id: String,
+        height: BlockNumHash,
+    },
+    Unsubscribed {
+        id: String,
+    },
+    Disconnected {
+        connection_id: u64,
+        id: String,
+    },
+}
+
+/// A subscriber of a [`RemoteExEx`].
+#[derive(Debug)]
+struct Subscriber {
+    /// The highest block processed by the subscriber.
+    finished_height: Option<BlockNumHash>,
+    /// The ID and the frame sender of the connection, if connected.
+    connection: Option<(u64, mpsc::Sender<Bytes>)>,
+}
+
+/// A notification that's kept until all subscribers processed it.
+#[derive(Debug)]
+struct BufferedNotification<N: NodePrimitives> {
+    notification: ExExNotification<N>,
+    frame: Bytes,
+}
+
+/// An `ExEx` that streams notifications to out-of-process subscribers.
+///
+/// See the [module documentation](self) for the protocol.
+#[derive(Debug)]
+pub struct RemoteExEx<N: NodePrimitives, S> {
+    /// The listener for subscribers.
+    listener: Listener,
+    /// The address the listener is bound to.
+    addr: RemoteExExAddr,
+    /// Channel to send [`ExExEvent`]s to the node.
+    events: UnboundedSender<ExExEvent>,
+    /// The notifications of the node.
+    notifications: S,
+    /// The subscribers by ID, including the disconnected ones.
+    subscribers: HashMap<String, Subscriber>,
+    /// The notifications that not all subscribers processed yet.
+    buffer: VecDeque<BufferedNotification<N>>,
+    /// The tip of the chain, as of the last notification.
+    tip: Option<BlockNumHash>,
+    /// The last finished height sent to the node.
+    finished_height: Option<BlockNumHash>,
+    /// The ID of the next connection.
+    next_connection_id: u64,
+    /// Channel to receive the events of the connections.
+    connection_events_tx: UnboundedSender<ConnectionEvent>,
+    connection_events_rx: UnboundedReceiver<ConnectionEvent>,
+}
+
+impl<P, E> RemoteExEx<E::Primitives, ExExNotifications<P, E>>
+where
+    E: ConfigureEvm,
+{
+    /// Binds to the address, with the event channel and notifications of the `ExEx` context.
+    pub async fn from_context<Node>(
+        addr: &RemoteExExAddr,
+        ctx: ExExContext<Node>,
+    ) -> eyre::Result<Self>
+    where
+        Node: FullNodeComponents<Provider = P, Evm = E>,
+    {
+        Self::new(addr, ctx.events, ctx.notifications).await
+    }
+}
+
+impl<N, S> RemoteExEx<N, S>
+where
+    N: NodePrimitives,
+{
+    /// Binds to the address, to stream the notifications to subscribers and report their finished
+    /// heights to the events channel.
+    pub async fn new(
+        addr: &RemoteExExAddr,
+        events: UnboundedSender<ExExEvent>,
+        notifications: S,
+    ) -> eyre::Result<Self> {
+        let (listener, addr) = Listener::bind(addr).await?;
+        info!(target: "exex::remote", %addr, "Listening for remote ExEx subscribers");
+
+        let (connection_events_tx, connection_events_rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            listener,
+            addr,
+            events,
+            notifications,
+            subscribers: HashMap::default(),
+            buffer: VecDeque::new(),
+            tip: None,
+            finished_height: None,
+            next_connection_id: 0,
+            connection_events_tx,
+            connection_events_rx,
+        })
+    }
+
+    /// Returns the address the `ExEx` is listening on.
+    pub const fn local_addr(&self) -> &RemoteExExAddr {
+        &self.addr
+    }
+
+    /// Runs the `ExEx`, until the notification stream ends.
+    pub async fn run(mut self) -> eyre::Result<()>
+    where
+        S: Stream<Item = eyre::Result<ExExNotification<N>>> + Unpin,
+    {
+        loop {
+            tokio::select! {
+                connection = self.listener.accept() => match connection {
+                    Ok(connection) => self.on_connection(connection),
+                    Err(err) => debug!(target: "exex::remote", %err, "Failed to accept connection"),
+                },
+                notification = self.notifications.next() => {
+                    let Some(notification) = notification else { return Ok(()) };
+                    self.on_notification(notification?)?;
+                }
+                Some(event) = self.connection_events_rx.recv() => self.on_connection_event(event),
+            }
+
+            self.prune_buffer();
+            self.update_finished_height()?;
+        }
+    }
+
+    /// Spawns a task that serves the connection.
+    fn on_connection(&mut self, connection: Box<dyn Connection>) {
+        let connection_id = self.next_connection_id;
+        self.next_connection_id += 1;
+        tokio::spawn(serve_connection(
+            connection_id,
+            framed(connection),
+            self.connection_events_tx.clone(),
+        ));
+    }
+
+    /// Buffers the notification and sends it to the connected subscribers.
+    fn on_notification(&mut self, notification: ExExNotification<N>) -> eyre::Result<()> {
+        self.tip = Some(match (notification.committed_chain(), notification.reverted_chain()) {
+            (Some(new), _) => new.tip().num_hash(),
+            (None, Some(old)) => old.fork_block(),
+            (None, None) => return Ok(()),
+        });
+
+        let frame = Bytes::from(rmp_serde::to_vec(
+            &serde_bincode_compat::ExExNotification::<N>::from(&notification),
+        )?);
+        let notification = BufferedNotification { notification, frame };
+
+        for (id, subscriber) in &mut self.subscribers {
+            subscriber.send(id, &notification);
+        }
+
+        self.buffer.push_back(notification);
+        if self.buffer.len() == BUFFERED_NOTIFICATIONS_WARNING {
+            warn!(target: "exex::remote", buffered = self.buffer.len(), "Remote ExEx subscribers are lagging behind, notifications are buffered in memory");
+        }
+
+        Ok(())
+    }
+
+    fn on_connection_event(&mut self, event: ConnectionEvent) {
+        match event {
+            ConnectionEvent::Subscribed { connection_id, id, finished_height, frames } => {
+                info!(target: "exex::remote", %id, ?finished_height, "Remote ExEx subscribed");
+                let subscriber = self
+                    .subscribers
+                    .entry(id.clone())
+                    .or_insert(Subscriber { finished_height: None, connection: None });
+                subscriber.finished_height = finished_height.or(subscriber.finished_height);
+                // Replaces the previous connection of the subscriber, if any.
+                subscriber.connection = Some((connection_id, frames));
+
+                for notification in &self.buffer {
+                    subscriber.send(&id, notification);
+                }
+            }
+            ConnectionEvent::FinishedHeight { id, height } => {
+                if let Some(subscriber) = self.subscribers.get_mut(&id) {
+                    debug!(target: "exex::remote", %id, ?height, "Remote ExEx finished height");
+                    subscriber.finished_height = Some(height);
+                }
+            }
+            ConnectionEvent::Unsubscribed { id } => {
+                info!(target: "exex::remote", %id, "Remote ExEx unsubscribed");
+                self.subscribers.remove(&id);
+            }
+            ConnectionEvent::Disconnected { connection_id, id } => {
+                if let Some(subscriber) = self.subscribers.get_mut(&id) {
+                    if subscriber
+                        .connection
+                        .as_ref()
+                        .is_some_and(|(current, _)| *current == connection_id)
+                    {
+                        info!(target: "exex::remote", %id, "Remote ExEx disconnected");
+                        subscriber.connection = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drops the notifications that all subscribers processed.
+    fn prune_buffer(&mut self) {
+        while self.buffer.front().is_some_and(|buffered| {
+            self.subscribers.values().all(|subscriber| {
+                subscriber
+                    .finished_height
+                    .is_some_and(|height| is_processed(&buffered.notification, height))
+            })
+        }) {
+            self.buffer.pop_front();
+        }
+    }
+
+    /// Reports the lowest finished height of the subscribers to the node.
+    fn update_finished_height(&mut self) -> eyre::Result<()> {
+        let finished_height = if self.subscribers.is_empty() {
+            self.tip
+        } else {
+            self.subscribers
+                .values()
+                .map(|subscriber| subscriber.finished_height)
+                .collect::<Option<Vec<_>>>()
+                .and_then(|heights| heights.into_iter().min_by_key(|height| height.number))
+        };
+
+        if finished_height.is_some() && finished_height != self.finished_height {
+            self.finished_height = finished_height;
+            self.events.send(ExExEvent::FinishedHeight(finished_height.expect("is some")))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Subscriber {
+    /// Sends the notification, unless the subscriber already processed it.
+    ///
+    /// Disconnects the subscriber if it doesn't keep up.
+    fn send<N: NodePrimitives>(&mut self, id: &str, notification: &BufferedNotification<N>) {
+        let Some((_, frames)) = &self.connection else { return };
+        if self
+            .finished_height
+            .is_some_and(|height| is_processed(&notification.notification, height))
+        {
+            return
+        }
+
+        match frames.try_send(notification.frame.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                warn!(target: "exex::remote", %id, "Remote ExEx is lagging behind, disconnecting");
+                self.connection = None;
+            }
+            Err(TrySendError::Closed(_)) => self.connection = None,
+        }
+    }
+}
+
+/// Returns `true` if a subscriber at the finished height doesn't need the notification.
+///
+/// Notifications of reverted blocks are needed until the subscriber moved to the new chain.
+fn is_processed<N: NodePrimitives>(
+    notification: &ExExNotification<N>,
+    finished_height: BlockNumHash,
+) -> bool {
+    let on_reverted = |old: &Chain<N>| {
+        old.blocks()
+            .get(&finished_height.number)
+            .is_some_and(|block| block.hash() == finished_height.hash)
+    };
+
+    match notification {
+        ExExNotification::ChainCommitted { new } => finished_height.number >= new.tip().number(),
+        ExExNotification::ChainReorged { old, new } => {
+            finished_height.number >= new.tip().number() && !on_reverted(old)
+        }
+        ExExNotification::ChainReverted { old } => {
+            finished_height.number >= old.fork_block().number && !on_reverted(old)
+        }
+    }
+}
+
+/// Serves the connection of a subscriber, until it disconnects or the node stops sending frames.
+async fn serve_connection(
+    connection_id: u64,
+    stream: RemoteExExStream,
+    events: UnboundedSender<ConnectionEvent>,
+) {
+    let (mut sink, mut requests) = stream.split();
+
+    let id = match requests.next().await.map(|request| decode_request(request?)) {
+        Some(Ok(RemoteExExRequest::Subscribe { id, finished_height })) => {
+            let (frames_tx, mut frames) = mpsc::channel(SUBSCRIBER_QUEUE_SIZE);
+            let subscribed = ConnectionEvent::Subscribed {
+                connection_id,
+                id: id.clone(),
+                finished_height,
+                frames: frames_tx,
+            };
+            if events.send(subscribed).is_err() {
+                return
+            }
+
+            loop {
+                tokio::select! {
+                    frame = frames.recv() => {
+                        let Some(frame) = frame else { break };
+                        if let Err(err) = sink.send(frame).await {
+                            debug!(target: "exex::remote", %id, %err, "Failed to send notification");
+                            break
+                        }
+                    }
+                    request = requests.next() => match request.map(decode_request) {
+                        Some(Ok(RemoteExExRequest::FinishedHeight(height))) => {
+                            let _ = events.send(ConnectionEvent::FinishedHeight { id: id.clone(), height });
+                        }
+                        Some(Ok(RemoteExExRequest::Unsubscribe)) => {
+                            let _ = events.send(ConnectionEvent::Unsubscribed { id: id.clone() });
+                            break
+                        }
+                        Some(Ok(RemoteExExRequest::Subscribe { .. })) => {
+                            debug!(target: "exex::remote", %id, "Unexpected subscribe request");
+                            break
+                        }
+                        Some(Err(err)) => {
+                            debug!(target: "exex::remote", %id, %err, "Invalid request");
+                            break
+                        }
+                        None => break,
+                    },
+                }
+            }
+
+            id
+        }
+        Some(Ok(request)) => {
+            debug!(target: "exex::remote", ?request, "Expected subscribe request");
+            return
+        }
+        Some(Err(err)) => {
+            debug!(target: "exex::remote", %err, "Invalid subscribe request");
+            return
+        }
+        None => return,
+    };
+
+    let _ = events.send(ConnectionEvent::Disconnected { connection_id, id });
+}
+
+fn decode_request(frame: impl AsRef<[u8]>) -> eyre::Result<RemoteExExRequest> {
+    Ok(rmp_serde::from_slice(frame.as_ref())?)
+}
+
+/// A subscriber of a [`RemoteExEx`], streams the [`ExExNotification`]s of the node.
+///
+/// The finished height must be sent for processed notifications, see
+/// [`RemoteExExClient::send_finished_height`].
+#[derive(Debug)]
+pub struct RemoteExExClient<N: NodePrimitives = EthPrimitives> {
+    stream: RemoteExExStream,
+    _primitives: PhantomData<N>,
+}
+
+impl<N: NodePrimitives> RemoteExExClient<N> {
+    /// Connects to the [`RemoteExEx`] at the address and subscribes with the given ID.
+    ///
+    /// Notifications of blocks above the finished height are replayed, if the node still has
+    /// them. See [`RemoteExExRequest::Subscribe`].
+    pub async fn connect(
+        addr: &RemoteExExAddr,
+        id: impl Into<String>,
+        finished_height: Option<BlockNumHash>,
+    ) -> eyre::Result<Self> {
+        let mut client = Self { stream: framed(connect(addr).await?), _primitives: PhantomData };
+        client.send(&RemoteExExRequest::Subscribe { id: id.into(), finished_height }).await?;
+        Ok(client)
+    }
+
+    /// Sends the highest processed block to the node, which may prune the blocks below it.
+    pub async fn send_finished_height(&mut self, height: BlockNumHash) -> eyre::Result<()> {
+        self.send(&RemoteExExRequest::FinishedHeight(height)).await
+    }
+
+    /// Unsubscribes, the node no longer keeps notifications for this subscriber.
+    pub async fn unsubscribe(mut self) -> eyre::Result<()> {
+        self.send(&RemoteExExRequest::Unsubscribe).await
+    }
+
+    async fn send(&mut self, request: &RemoteExExRequest) -> eyre::Result<()> {
+        self.stream.send(Bytes::from(rmp_serde::to_vec(request)?)).await?;
+        Ok(())
+    }
+}
+
+impl<N: NodePrimitives> Stream for RemoteExExClient<N> {
+    type Item = eyre::Result<ExExNotification<N>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let frame = ready!(self.get_mut().stream.poll_next_unpin(cx));
+        Poll::Ready(frame.map(|frame| {
+            let notification: serde_bincode_compat::ExExNotification<'_, N> =
+                rmp_serde::from_slice(&frame?)?;
+            Ok(notification.into())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_ethereum_primitives::Block;
+    use reth_primitives_traits::RecoveredBlock;
+    use reth_testing_utils::generators::{self, random_block, BlockParams};
+    use std::sync::Arc;
+
+    fn committed(block: &RecoveredBlock<Block>) -> ExExNotification {
+        ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::new(vec![block.clone()], Default::default(), None)),
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_notifications_to_subscribers() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+        let genesis = random_block(&mut rng, 0, BlockParams::default()).try_recover()?;
+        let block_1 = random_block(
+            &mut rng,
+            1,
+            BlockParams { parent: Some(genesis.hash()), ..Default::default() },
+        )
+        .try_recover()?;
+        let block_2 = random_block(
+            &mut rng,
+            2,
+            BlockParams { parent: Some(block_1.hash()), ..Default::default() },
+        )
+        .try_recover()?;
+
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let (notifications_tx, notifications_rx) =
+            futures::channel::mpsc::unbounded::<eyre::Result<ExExNotification>>();
+        let exex = RemoteExEx::new(&"127.0.0.1:0".parse()?, events_tx, notifications_rx).await?;
+        let addr = exex.local_addr().clone();
+        tokio::spawn(exex.run());
+
+        // The finished height of the subscriber is reported once it's subscribed.
+        let mut client =
+            RemoteExExClient::connect(&addr, "indexer", Some(genesis.num_hash())).await?;
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(genesis.num_hash())));
+
+        notifications_tx.unbounded_send(Ok(committed(&block_1)))?;
+        assert_eq!(client.next().await.transpose()?, Some(committed(&block_1)));
+        client.send_finished_height(block_1.num_hash()).await?;
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(block_1.num_hash())));
+
+        // The notification sent while the subscriber is disconnected is replayed on reconnect,
+        // and the finished height is held back until it's processed.
+        drop(client);
+        notifications_tx.unbounded_send(Ok(committed(&block_2)))?;
+        let mut client = RemoteExExClient::connect(&addr, "indexer", None).await?;
+        assert_eq!(client.next().await.transpose()?, Some(committed(&block_2)));
+        client.send_finished_height(block_2.num_hash()).await?;
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(block_2.num_hash())));
+
+        // Without subscribers, notifications are reported as finished right away.
+        client.unsubscribe().await?;
+        let block_3 = random_block(
+            &mut rng,
+            3,
+            BlockParams { parent: Some(block_2.hash()), ..Default::default() },
+        )
+        .try_recover()?;
+        notifications_tx.unbounded_send(Ok(committed(&block_3)))?;
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(block_3.num_hash())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reverted_notifications_are_processed_on_the_new_chain() {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 1, BlockParams::default()).try_recover().unwrap();
+        let reverted = ExExNotification::ChainReverted {
+            old: Arc::new(Chain::new(vec![block.clone()], Default::default(), None)),
+        };
+
+        assert!(!is_processed(&reverted, block.num_hash()));
+        assert!(is_processed(&reverted, BlockNumHash::new(1, Default::default())));
+        assert!(is_processed(&reverted, BlockNumHash::new(0, block.parent_hash())));
+        assert!(is_processed(&committed(&block), block.num_hash()));
+        assert!(!is_processed(&committed(&block), BlockNumHash::new(0, block.parent_hash())));
+    }
+}
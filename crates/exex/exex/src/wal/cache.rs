@@ -8,6 +8,7 @@ use alloy_eips::BlockNumHash;
 use alloy_primitives::{map::FbHashMap, BlockNumber, B256};
 use reth_exex_types::ExExNotification;
 use reth_node_api::NodePrimitives;
+use serde::{Deserialize, Serialize};
 
 /// The block cache of the WAL.
 ///
@@ -92,31 +93,22 @@ impl BlockCache {
         self.committed_blocks.get(block_hash).map(|entry| entry.0)
     }
 
-    /// Inserts the blocks from the notification into the cache with the given file ID.
-    pub(super) fn insert_notification_blocks_with_file_id<N: NodePrimitives>(
+    /// Inserts the blocks of a notification into the cache with the given file ID.
+    pub(super) fn insert_notification_blocks_with_file_id(
         &mut self,
         file_id: u32,
-        notification: &ExExNotification<N>,
+        blocks: &NotificationBlocks,
     ) {
-        let reverted_chain = notification.reverted_chain();
-        let committed_chain = notification.committed_chain();
-
-        let max_block =
-            reverted_chain.iter().chain(&committed_chain).map(|chain| chain.tip().number()).max();
-        if let Some(max_block) = max_block {
+        if let Some(max_block) = blocks.max_block {
             self.notification_max_blocks.push(Reverse((max_block, file_id)));
         }
 
-        if let Some(committed_chain) = &committed_chain {
-            for block in committed_chain.blocks().values() {
-                let cached_block = CachedBlock {
-                    block: (block.number(), block.hash()).into(),
-                    parent_hash: block.parent_hash(),
-                };
-                self.committed_blocks.insert(block.hash(), (file_id, cached_block));
-            }
+        for cached_block in &blocks.committed_blocks {
+            self.committed_blocks.insert(cached_block.block.hash, (file_id, *cached_block));
+        }
 
-            self.highest_committed_block_height = Some(committed_chain.tip().number());
+        if let Some(tip) = blocks.committed_blocks.last() {
+            self.highest_committed_block_height = Some(tip.block.number);
         }
     }
 
@@ -142,7 +134,38 @@ impl BlockCache {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The blocks of a notification that are tracked by the [`BlockCache`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct NotificationBlocks {
+    /// The highest block in the notification, chosen between both the committed and reverted
+    /// chains.
+    pub(super) max_block: Option<BlockNumber>,
+    /// The committed blocks in the notification, in ascending order.
+    pub(super) committed_blocks: Vec<CachedBlock>,
+}
+
+impl NotificationBlocks {
+    /// Collects the blocks of the notification.
+    pub(super) fn new<N: NodePrimitives>(notification: &ExExNotification<N>) -> Self {
+        let reverted_chain = notification.reverted_chain();
+        let committed_chain = notification.committed_chain();
+
+        let max_block =
+            reverted_chain.iter().chain(&committed_chain).map(|chain| chain.tip().number()).max();
+        let committed_blocks = committed_chain
+            .iter()
+            .flat_map(|chain| chain.blocks().values())
+            .map(|block| CachedBlock {
+                block: (block.number(), block.hash()).into(),
+                parent_hash: block.parent_hash(),
+            })
+            .collect();
+
+        Self { max_block, committed_blocks }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(super) struct CachedBlock {
     /// The block number and hash of the block.
     pub(super) block: BlockNumHash,
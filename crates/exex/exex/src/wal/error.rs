@@ -26,4 +26,13 @@ pub enum WalError {
     /// Decode error
     #[error("failed to decode notification {0} from {1}: {2}")]
     Decode(u32, PathBuf, rmp_serde::decode::Error),
+    /// Encode error
+    #[error("failed to encode notification {0}: {1}")]
+    Encode(u32, rmp_serde::encode::Error),
+    /// Compression error
+    #[error("failed to compress notification {0}: {1}")]
+    Compress(u32, std::io::Error),
+    /// Decompression error
+    #[error("failed to decompress notification {0} from {1}: {2}")]
+    Decompress(u32, PathBuf, std::io::Error),
 }
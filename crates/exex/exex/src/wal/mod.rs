@@ -2,6 +2,7 @@
 
 mod cache;
 pub use cache::BlockCache;
+use cache::NotificationBlocks;
 mod storage;
 use reth_ethereum_primitives::EthPrimitives;
 use reth_node_api::NodePrimitives;
@@ -27,9 +28,9 @@ use reth_tracing::tracing::{debug, instrument};
 
 /// WAL is a write-ahead log (WAL) that stores the notifications sent to ExExes.
 ///
-/// WAL is backed by a directory of binary files represented by [`Storage`] and a block cache
-/// represented by [`BlockCache`]. The role of the block cache is to avoid walking the WAL directory
-/// and decoding notifications every time we want to iterate or finalize the WAL.
+/// WAL is backed by a directory of compressed segment files represented by [`Storage`] and a block
+/// cache represented by [`BlockCache`]. The role of the block cache is to avoid walking the WAL
+/// directory and decoding notifications every time we want to iterate or finalize the WAL.
 ///
 /// The expected mode of operation is as follows:
 /// 1. On every new canonical chain notification, call [`Wal::commit`].
@@ -102,7 +103,7 @@ where
             block_cache: RwLock::new(BlockCache::default()),
             metrics: Metrics::default(),
         };
-        wal.fill_block_cache()?;
+        wal.fill_block_cache();
         Ok(wal)
     }
 
@@ -111,36 +112,31 @@ where
     }
 
     /// Fills the block cache with the notifications from the storage.
+    ///
+    /// Only the storage index is read, the notifications are not decoded.
     #[instrument(skip(self))]
-    fn fill_block_cache(&self) -> WalResult<()> {
-        let Some(files_range) = self.storage.files_range()? else { return Ok(()) };
-        self.next_file_id.store(files_range.end() + 1, Ordering::Relaxed);
+    fn fill_block_cache(&self) {
+        let Some(notifications_range) = self.storage.notifications_range() else { return };
+        self.next_file_id.store(notifications_range.end() + 1, Ordering::Relaxed);
 
         let mut block_cache = self.block_cache.write();
         let mut notifications_size = 0;
 
-        for entry in self.storage.iter_notifications(files_range) {
-            let (file_id, size, notification) = entry?;
-
-            notifications_size += size;
-
-            let committed_chain = notification.committed_chain();
-            let reverted_chain = notification.reverted_chain();
+        for entry in self.storage.index_entries() {
+            notifications_size += entry.size;
 
             debug!(
                 target: "exex::wal",
-                ?file_id,
-                reverted_block_range = ?reverted_chain.as_ref().map(|chain| chain.range()),
-                committed_block_range = ?committed_chain.as_ref().map(|chain| chain.range()),
+                file_id = ?entry.id,
+                max_block = ?entry.blocks.max_block,
+                committed_blocks = entry.blocks.committed_blocks.len(),
                 "Inserting block cache entries"
             );
 
-            block_cache.insert_notification_blocks_with_file_id(file_id, &notification);
+            block_cache.insert_notification_blocks_with_file_id(entry.id, &entry.blocks);
         }
 
         self.update_metrics(&block_cache, notifications_size as i64);
-
-        Ok(())
     }
 
     #[instrument(skip_all, fields(
@@ -151,10 +147,11 @@ where
         let mut block_cache = self.block_cache.write();
 
         let file_id = self.next_file_id.fetch_add(1, Ordering::Relaxed);
-        let size = self.storage.write_notification(file_id, notification)?;
+        let blocks = NotificationBlocks::new(notification);
+        let size = self.storage.write_notification(file_id, notification, &blocks)?;
 
         debug!(target: "exex::wal", ?file_id, "Inserting notification blocks into the block cache");
-        block_cache.insert_notification_blocks_with_file_id(file_id, notification);
+        block_cache.insert_notification_blocks_with_file_id(file_id, &blocks);
 
         self.update_metrics(&block_cache, size as i64);
 
@@ -198,11 +195,7 @@ where
     fn iter_notifications(
         &self,
     ) -> WalResult<Box<dyn Iterator<Item = WalResult<ExExNotification<N>>> + '_>> {
-        let Some(range) = self.storage.files_range()? else {
-            return Ok(Box::new(std::iter::empty()))
-        };
-
-        Ok(Box::new(self.storage.iter_notifications(range).map(|entry| Ok(entry?.2))))
+        Ok(Box::new(self.storage.iter_notifications().map(|entry| Ok(entry?.2))))
     }
 }
 
@@ -212,6 +205,12 @@ pub struct WalHandle<N: NodePrimitives> {
     wal: Arc<WalInner<N>>,
 }
 
+impl<N: NodePrimitives> Clone for WalHandle<N> {
+    fn clone(&self) -> Self {
+        Self { wal: self.wal.clone() }
+    }
+}
+
 impl<N> WalHandle<N>
 where
     N: NodePrimitives,
@@ -246,13 +245,7 @@ mod tests {
     use std::sync::Arc;
 
     fn read_notifications(wal: &Wal) -> WalResult<Vec<ExExNotification>> {
-        wal.inner.storage.files_range()?.map_or(Ok(Vec::new()), |range| {
-            wal.inner
-                .storage
-                .iter_notifications(range)
-                .map(|entry| entry.map(|(_, _, n)| n))
-                .collect()
-        })
+        wal.inner.storage.iter_notifications().map(|entry| entry.map(|(_, _, n)| n)).collect()
     }
 
     fn sort_committed_blocks(
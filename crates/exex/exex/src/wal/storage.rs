@@ -1,123 +1,313 @@
 use std::{
-    fs::File,
+    collections::{BTreeMap, BTreeSet},
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
     ops::RangeInclusive,
     path::{Path, PathBuf},
 };
 
-use crate::wal::{WalError, WalResult};
+use crate::wal::{cache::NotificationBlocks, WalError, WalResult};
+use parking_lot::RwLock;
 use reth_ethereum_primitives::EthPrimitives;
 use reth_exex_types::ExExNotification;
+use reth_fs_util::FsPathError;
 use reth_node_api::NodePrimitives;
-use reth_tracing::tracing::debug;
+use reth_tracing::tracing::{debug, info, warn};
+use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
-static FILE_EXTENSION: &str = "wal";
+/// The extension of the files with a single uncompressed notification, written by the previous
+/// versions of the WAL.
+static LEGACY_FILE_EXTENSION: &str = "wal";
+static SEGMENT_FILE_EXTENSION: &str = "seg";
+static INDEX_FILE_EXTENSION: &str = "idx";
 
-/// The underlying WAL storage backed by a directory of files.
+/// The size of a segment file in bytes, after which a new segment is started.
+const MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The zstd compression level of the notifications, `0` selects the zstd default.
+const COMPRESSION_LEVEL: i32 = 0;
+
+/// The underlying WAL storage backed by a directory of segment files.
+///
+/// Notifications are MessagePack-encoded, compressed with zstd and appended to the latest segment
+/// file, until it grows over [`MAX_SEGMENT_SIZE`]. Each segment file `{id}.seg` is named after the
+/// ID of its first notification, and has an index file `{id}.idx` with the location and the
+/// blocks of its notifications, so the block cache can be filled without decoding the
+/// notifications.
+///
+/// A segment file is removed once all its notifications are removed.
 ///
-/// Each notification is represented by a single file that contains a MessagePack-encoded
-/// notification.
-#[derive(Debug, Clone)]
+/// Files with a single uncompressed notification written by the previous versions of the WAL are
+/// migrated to segment files on startup.
+#[derive(Debug)]
 pub struct Storage<N: NodePrimitives = EthPrimitives> {
-    /// The path to the WAL file.
+    /// The path to the WAL directory.
     path: PathBuf,
+    /// The size of a segment file in bytes, after which a new segment is started.
+    max_segment_size: u64,
+    /// The segments by the ID of their first notification.
+    segments: RwLock<BTreeMap<u32, Segment>>,
     _pd: std::marker::PhantomData<N>,
 }
 
+/// A segment file with its index.
+#[derive(Debug, Default)]
+struct Segment {
+    /// The size of the segment file in bytes.
+    size: u64,
+    /// The index entries of the notifications in the segment by ID.
+    entries: BTreeMap<u32, IndexEntry>,
+}
+
+/// The index entry of a notification in a segment file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct IndexEntry {
+    /// The ID of the notification.
+    pub(super) id: u32,
+    /// The offset of the compressed notification in the segment file.
+    offset: u64,
+    /// The size of the compressed notification in bytes.
+    pub(super) size: u64,
+    /// The blocks of the notification.
+    pub(super) blocks: NotificationBlocks,
+}
+
 impl<N> Storage<N>
 where
     N: NodePrimitives,
 {
-    /// Creates a new instance of [`Storage`] backed by the file at the given path and creates
+    /// Creates a new instance of [`Storage`] backed by the directory at the given path and creates
     /// it doesn't exist.
+    ///
+    /// Loads the segment indices, and migrates the notification files of the previous versions of
+    /// the WAL.
     pub(super) fn new(path: impl AsRef<Path>) -> WalResult<Self> {
         reth_fs_util::create_dir_all(&path)?;
 
-        Ok(Self { path: path.as_ref().to_path_buf(), _pd: std::marker::PhantomData })
+        let storage = Self {
+            path: path.as_ref().to_path_buf(),
+            max_segment_size: MAX_SEGMENT_SIZE,
+            segments: RwLock::default(),
+            _pd: std::marker::PhantomData,
+        };
+        storage.load_segments()?;
+        storage.migrate_legacy_files()?;
+
+        Ok(storage)
     }
 
-    fn file_path(&self, id: u32) -> PathBuf {
-        self.path.join(format!("{id}.{FILE_EXTENSION}"))
+    fn legacy_file_path(&self, id: u32) -> PathBuf {
+        self.path.join(format!("{id}.{LEGACY_FILE_EXTENSION}"))
     }
 
-    fn parse_filename(filename: &str) -> WalResult<u32> {
+    fn segment_path(&self, first_id: u32) -> PathBuf {
+        self.path.join(format!("{first_id}.{SEGMENT_FILE_EXTENSION}"))
+    }
+
+    fn index_path(&self, first_id: u32) -> PathBuf {
+        self.path.join(format!("{first_id}.{INDEX_FILE_EXTENSION}"))
+    }
+
+    fn parse_filename(filename: &str, extension: &str) -> WalResult<u32> {
         filename
-            .strip_suffix(".wal")
+            .strip_suffix(extension)
+            .and_then(|s| s.strip_suffix('.'))
             .and_then(|s| s.parse().ok())
             .ok_or_else(|| WalError::Parse(filename.to_string()))
     }
 
-    /// Removes notification for the given file ID from the storage.
-    ///
-    /// # Returns
+    /// Returns the sorted IDs of the files with the given extension in the storage.
+    fn file_ids(&self, extension: &str) -> WalResult<Vec<u32>> {
+        let mut ids = Vec::new();
+
+        for entry in reth_fs_util::read_dir(&self.path)? {
+            let entry = entry.map_err(|err| WalError::DirEntry(self.path.clone(), err))?;
+
+            if entry.path().extension() == Some(extension.as_ref()) {
+                let file_name = entry.file_name();
+                ids.push(Self::parse_filename(&file_name.to_string_lossy(), extension)?);
+            }
+        }
+
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    /// Loads the segments from their index files.
     ///
-    /// The size of the file that was removed in bytes, if any.
-    #[instrument(skip(self))]
-    fn remove_notification(&self, file_id: u32) -> Option<u64> {
-        let path = self.file_path(file_id);
-        let size = path.metadata().ok()?.len();
-
-        match reth_fs_util::remove_file(self.file_path(file_id)) {
-            Ok(()) => {
-                debug!(target: "exex::wal::storage", "Notification was removed from the storage");
-                Some(size)
+    /// Segment files without an index, and index entries that weren't fully written, are left by
+    /// an interrupted write and are dropped.
+    fn load_segments(&self) -> WalResult<()> {
+        let mut segments = self.segments.write();
+
+        let index_ids = self.file_ids(INDEX_FILE_EXTENSION)?;
+        for first_id in self.file_ids(SEGMENT_FILE_EXTENSION)? {
+            if index_ids.binary_search(&first_id).is_err() {
+                debug!(target: "exex::wal::storage", first_id, "Removing segment without an index");
+                reth_fs_util::remove_file(self.segment_path(first_id))?;
+            }
+        }
+
+        for first_id in index_ids {
+            let index_path = self.index_path(first_id);
+            let index = reth_fs_util::read(&index_path)?;
+
+            let mut segment = Segment::default();
+            let mut remaining = index.as_slice();
+            while !remaining.is_empty() {
+                match rmp_serde::decode::from_read::<_, IndexEntry>(&mut remaining) {
+                    Ok(entry) => {
+                        segment.entries.insert(entry.id, entry);
+                    }
+                    Err(err) => {
+                        warn!(target: "exex::wal::storage", ?index_path, %err, "Failed to decode WAL index entry, dropping the rest of the index");
+                        break
+                    }
+                }
             }
-            Err(err) => {
-                debug!(target: "exex::wal::storage", ?err, "Failed to remove notification from the storage");
-                None
+
+            if segment.entries.is_empty() {
+                self.remove_segment_files(first_id)?;
+                continue
+            }
+
+            if !remaining.is_empty() {
+                self.write_index(first_id, &segment)?;
             }
+
+            let segment_path = self.segment_path(first_id);
+            segment.size = reth_fs_util::metadata(&segment_path)?.len();
+            segments.insert(first_id, segment);
         }
+
+        Ok(())
     }
 
-    /// Returns the range of file IDs in the storage.
-    ///
-    /// If there are no files in the storage, returns `None`.
-    pub(super) fn files_range(&self) -> WalResult<Option<RangeInclusive<u32>>> {
-        let mut min_id = None;
-        let mut max_id = None;
+    /// Moves the notifications from the files of the previous versions of the WAL into segment
+    /// files, and removes the old files.
+    fn migrate_legacy_files(&self) -> WalResult<()> {
+        let legacy_ids = self.file_ids(LEGACY_FILE_EXTENSION)?;
+        if legacy_ids.is_empty() {
+            return Ok(())
+        }
 
-        for entry in reth_fs_util::read_dir(&self.path)? {
-            let entry = entry.map_err(|err| WalError::DirEntry(self.path.clone(), err))?;
+        info!(target: "exex::wal::storage", notifications = legacy_ids.len(), "Migrating WAL notifications to segment files");
+
+        // Notifications up to the last one in the segments were migrated before a previous
+        // migration was interrupted.
+        let migrated = self.notifications_range().map(|range| *range.end());
+        for id in legacy_ids {
+            if migrated.is_none_or(|migrated| id > migrated) {
+                let (notification, _) =
+                    self.read_legacy_notification(id)?.ok_or(WalError::FileNotFound(id))?;
+                self.write_notification(
+                    id,
+                    &notification,
+                    &NotificationBlocks::new(&notification),
+                )?;
+            }
 
-            if entry.path().extension() == Some(FILE_EXTENSION.as_ref()) {
-                let file_name = entry.file_name();
-                let file_id = Self::parse_filename(&file_name.to_string_lossy())?;
+            reth_fs_util::remove_file(self.legacy_file_path(id))?;
+        }
 
-                min_id = min_id.map_or(Some(file_id), |min_id: u32| Some(min_id.min(file_id)));
-                max_id = max_id.map_or(Some(file_id), |max_id: u32| Some(max_id.max(file_id)));
+        Ok(())
+    }
+
+    /// Writes the index of the segment, replacing the existing one.
+    fn write_index(&self, first_id: u32, segment: &Segment) -> WalResult<()> {
+        reth_fs_util::atomic_write_file(&self.index_path(first_id), |file| {
+            segment.entries.values().try_for_each(|entry| rmp_serde::encode::write(file, entry))
+        })?;
+
+        Ok(())
+    }
+
+    /// Removes the segment file and its index.
+    fn remove_segment_files(&self, first_id: u32) -> WalResult<()> {
+        for path in [self.index_path(first_id), self.segment_path(first_id)] {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(FsPathError::remove_file(err, &path).into()),
             }
         }
 
-        Ok(min_id.zip(max_id).map(|(min_id, max_id)| min_id..=max_id))
+        debug!(target: "exex::wal::storage", first_id, "Segment was removed from the storage");
+        Ok(())
     }
 
-    /// Removes notifications from the storage according to the given list of file IDs.
+    /// Returns the range of notification IDs in the storage.
+    ///
+    /// If there are no notifications in the storage, returns `None`.
+    pub(super) fn notifications_range(&self) -> Option<RangeInclusive<u32>> {
+        let segments = self.segments.read();
+        let first = segments.first_key_value()?.1.entries.first_key_value()?.0;
+        let last = segments.last_key_value()?.1.entries.last_key_value()?.0;
+
+        Some(*first..=*last)
+    }
+
+    /// Returns the index entries of all notifications in the storage, in the order of their IDs.
+    pub(super) fn index_entries(&self) -> Vec<IndexEntry> {
+        self.segments
+            .read()
+            .values()
+            .flat_map(|segment| segment.entries.values().cloned())
+            .collect()
+    }
+
+    /// Removes notifications from the storage according to the given list of IDs.
+    ///
+    /// Segments that are left without notifications are removed, and the indices of the other
+    /// segments are rewritten.
     ///
     /// # Returns
     ///
-    /// Number of removed notifications and the total size of the removed files in bytes.
+    /// Number of removed notifications and the total size of the removed notifications in bytes.
     pub(super) fn remove_notifications(
         &self,
-        file_ids: impl IntoIterator<Item = u32>,
+        ids: impl IntoIterator<Item = u32>,
     ) -> WalResult<(usize, u64)> {
+        let mut segments = self.segments.write();
         let mut deleted_total = 0;
         let mut deleted_size = 0;
+        let mut modified_segments = BTreeSet::new();
+
+        for id in ids {
+            let Some((first_id, segment)) = segments.range_mut(..=id).next_back() else { continue };
 
-        for id in file_ids {
-            if let Some(size) = self.remove_notification(id) {
+            if let Some(entry) = segment.entries.remove(&id) {
                 deleted_total += 1;
-                deleted_size += size;
+                deleted_size += entry.size;
+                modified_segments.insert(*first_id);
+            }
+        }
+
+        for first_id in modified_segments {
+            if segments[&first_id].entries.is_empty() {
+                segments.remove(&first_id);
+                self.remove_segment_files(first_id)?;
+            } else {
+                self.write_index(first_id, &segments[&first_id])?;
             }
         }
 
         Ok((deleted_total, deleted_size))
     }
 
+    /// Returns an iterator over all notifications in the storage, in the order of their IDs.
     pub(super) fn iter_notifications(
         &self,
-        range: RangeInclusive<u32>,
     ) -> impl Iterator<Item = WalResult<(u32, u64, ExExNotification<N>)>> + '_ {
-        range.map(move |id| {
+        let ids = self
+            .segments
+            .read()
+            .values()
+            .flat_map(|segment| segment.entries.keys().copied())
+            .collect::<Vec<_>>();
+
+        ids.into_iter().map(move |id| {
             let (notification, size) =
                 self.read_notification(id)?.ok_or(WalError::FileNotFound(id))?;
 
@@ -125,19 +315,59 @@ where
         })
     }
 
-    /// Reads the notification from the file with the given ID.
+    /// Reads the notification with the given ID from its segment.
     #[instrument(skip(self))]
     pub(super) fn read_notification(
+        &self,
+        id: u32,
+    ) -> WalResult<Option<(ExExNotification<N>, u64)>> {
+        let Some((first_id, offset, size)) =
+            self.segments.read().range(..=id).next_back().and_then(|(first_id, segment)| {
+                let entry = segment.entries.get(&id)?;
+                Some((*first_id, entry.offset, entry.size))
+            })
+        else {
+            return Ok(None)
+        };
+
+        let segment_path = self.segment_path(first_id);
+        debug!(target: "exex::wal::storage", ?segment_path, offset, "Reading notification from WAL");
+
+        let mut file = match File::open(&segment_path) {
+            Ok(file) => file,
+            // The segment was removed after the lookup
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(FsPathError::open(err, &segment_path).into()),
+        };
+        let mut compressed = vec![0; size as usize];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut compressed))
+            .map_err(|err| FsPathError::read(err, &segment_path))?;
+
+        let encoded = zstd::decode_all(compressed.as_slice())
+            .map_err(|err| WalError::Decompress(id, segment_path.clone(), err))?;
+
+        // Deserialize using the bincode- and msgpack-compatible serde wrapper
+        let notification: reth_exex_types::serde_bincode_compat::ExExNotification<'_, N> =
+            rmp_serde::decode::from_slice(&encoded)
+                .map_err(|err| WalError::Decode(id, segment_path, err))?;
+
+        Ok(Some((notification.into(), size)))
+    }
+
+    /// Reads the notification from the file of the previous versions of the WAL with the given ID.
+    #[instrument(skip(self))]
+    fn read_legacy_notification(
         &self,
         file_id: u32,
     ) -> WalResult<Option<(ExExNotification<N>, u64)>> {
-        let file_path = self.file_path(file_id);
+        let file_path = self.legacy_file_path(file_id);
         debug!(target: "exex::wal::storage", ?file_path, "Reading notification from WAL");
 
         let mut file = match File::open(&file_path) {
             Ok(file) => file,
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
-            Err(err) => return Err(reth_fs_util::FsPathError::open(err, &file_path).into()),
+            Err(err) => return Err(FsPathError::open(err, &file_path).into()),
         };
         let size = file.metadata().map_err(|err| WalError::FileMetadata(file_id, err))?.len();
 
@@ -149,39 +379,82 @@ where
         Ok(Some((notification.into(), size)))
     }
 
-    /// Writes the notification to the file with the given ID.
+    /// Appends the notification with the given ID to the latest segment, or starts a new segment
+    /// if the latest one is full.
+    ///
+    /// IDs must be increasing.
     ///
     /// # Returns
     ///
-    /// The size of the file that was written in bytes.
-    #[instrument(skip(self, notification))]
+    /// The size of the compressed notification in bytes.
+    #[instrument(skip(self, notification, blocks))]
     pub(super) fn write_notification(
         &self,
-        file_id: u32,
+        id: u32,
         notification: &ExExNotification<N>,
+        blocks: &NotificationBlocks,
     ) -> WalResult<u64> {
-        let file_path = self.file_path(file_id);
-        debug!(target: "exex::wal::storage", ?file_path, "Writing notification to WAL");
+        let mut segments = self.segments.write();
+        let first_id = match segments.last_key_value() {
+            Some((first_id, segment)) if segment.size < self.max_segment_size => *first_id,
+            _ => id,
+        };
+
+        let segment_path = self.segment_path(first_id);
+        debug!(target: "exex::wal::storage", ?segment_path, "Writing notification to WAL");
 
         // Serialize using the bincode- and msgpack-compatible serde wrapper
         let notification =
             reth_exex_types::serde_bincode_compat::ExExNotification::<N>::from(notification);
-
-        reth_fs_util::atomic_write_file(&file_path, |file| {
-            rmp_serde::encode::write(file, &notification)
-        })?;
-
-        Ok(file_path.metadata().map_err(|err| WalError::FileMetadata(file_id, err))?.len())
+        let encoded =
+            rmp_serde::encode::to_vec(&notification).map_err(|err| WalError::Encode(id, err))?;
+        let compressed = zstd::encode_all(encoded.as_slice(), COMPRESSION_LEVEL)
+            .map_err(|err| WalError::Compress(id, err))?;
+
+        // The notification is only visible once its index entry is written, so an interrupted
+        // write leaves unreferenced bytes at the end of the segment file.
+        let offset = append(&segment_path, &compressed)?;
+        let entry =
+            IndexEntry { id, offset, size: compressed.len() as u64, blocks: blocks.clone() };
+        let index_entry =
+            rmp_serde::encode::to_vec(&entry).map_err(|err| WalError::Encode(id, err))?;
+        append(&self.index_path(first_id), &index_entry)?;
+
+        let segment = segments.entry(first_id).or_default();
+        segment.size = offset + entry.size;
+        segment.entries.insert(id, entry);
+
+        Ok(compressed.len() as u64)
     }
 }
 
+/// Appends the data to the file, creating it if it doesn't exist.
+///
+/// # Returns
+///
+/// The offset the data was written at.
+fn append(path: &Path, data: &[u8]) -> WalResult<u64> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| FsPathError::open(err, path))?;
+    let offset = file.metadata().map_err(|err| FsPathError::metadata(err, path))?.len();
+
+    file.write_all(data).map_err(|err| FsPathError::write(err, path))?;
+    file.sync_data().map_err(|err| FsPathError::fsync(err, path))?;
+
+    Ok(offset)
+}
+
 #[cfg(test)]
 mod tests {
     use super::Storage;
+    use crate::wal::cache::NotificationBlocks;
     use reth_exex_types::ExExNotification;
     use reth_provider::Chain;
     use reth_testing_utils::generators::{self, random_block};
-    use std::{fs::File, sync::Arc};
+    use std::sync::Arc;
 
     // wal with 1 block and tx
     // <https://github.com/paradigmxyz/reth/issues/15012>
@@ -219,7 +492,11 @@ mod tests {
 
         // Do a round trip serialization and deserialization
         let file_id = 0;
-        storage.write_notification(file_id, &notification)?;
+        storage.write_notification(
+            file_id,
+            &notification,
+            &NotificationBlocks::new(&notification),
+        )?;
         let deserialized_notification = storage.read_notification(file_id)?;
         assert_eq!(
             deserialized_notification.map(|(notification, _)| notification),
@@ -230,21 +507,73 @@ mod tests {
     }
 
     #[test]
-    fn test_files_range() -> eyre::Result<()> {
+    fn test_segments() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+
         let temp_dir = tempfile::tempdir()?;
+        let mut storage: Storage = Storage::new(&temp_dir)?;
+
+        let notifications = (0..5)
+            .map(|number| {
+                let block = random_block(&mut rng, number, Default::default()).try_recover()?;
+                Ok(ExExNotification::ChainCommitted {
+                    new: Arc::new(Chain::new(vec![block], Default::default(), None)),
+                })
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        // Write the first three notifications into separate segments, and the rest into the
+        // segment of the third one
+        storage.max_segment_size = 1;
+        for (id, notification) in notifications.iter().enumerate() {
+            if id == 3 {
+                storage.max_segment_size = u64::MAX;
+            }
+            storage.write_notification(
+                id as u32,
+                notification,
+                &NotificationBlocks::new(notification),
+            )?;
+        }
+        assert_eq!(storage.segments.read().keys().copied().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(storage.notifications_range(), Some(0..=4));
+
+        // Removing all notifications of a segment removes its files, removing some notifications
+        // rewrites the index
+        assert_eq!(storage.remove_notifications([0, 3])?.0, 2);
+        assert!(!storage.segment_path(0).exists());
+        assert!(!storage.index_path(0).exists());
+        assert_eq!(storage.read_notification(3)?, None);
+
+        // Re-open the storage and verify that the segments are loaded from the indices
         let storage: Storage = Storage::new(&temp_dir)?;
+        assert_eq!(storage.notifications_range(), Some(1..=4));
+        assert_eq!(
+            storage.index_entries().into_iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            [1, 2, 4]
+        );
+        assert_eq!(
+            storage
+                .iter_notifications()
+                .map(|entry| entry.map(|(_, _, notification)| notification))
+                .collect::<Result<Vec<_>, _>>()?,
+            [notifications[1].clone(), notifications[2].clone(), notifications[4].clone()]
+        );
 
-        // Create WAL files
-        File::create(storage.file_path(1))?;
-        File::create(storage.file_path(2))?;
-        File::create(storage.file_path(3))?;
+        Ok(())
+    }
 
-        // Create non-WAL files that should be ignored
-        File::create(temp_dir.path().join("0.tmp"))?;
-        File::create(temp_dir.path().join("4.tmp"))?;
+    #[test]
+    fn test_migrate_legacy_files() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let legacy_path = temp_dir.path().join("28.wal");
+        std::fs::write(&legacy_path, include_bytes!("../../test-data/28.wal"))?;
 
-        // Check files range
-        assert_eq!(storage.files_range()?, Some(1..=3));
+        let storage: Storage = Storage::new(&temp_dir)?;
+        let (notification, _) = storage.read_notification(28)?.expect("migrated notification");
+        assert_eq!(notification.committed_chain().map(|chain| chain.blocks().len()), Some(1));
+        assert_eq!(storage.notifications_range(), Some(28..=28));
+        assert!(!legacy_path.exists());
 
         Ok(())
     }
@@ -27,7 +27,7 @@ pub use alloy_rpc_types_admin::EthProtocolInfo;
 pub use reth_network_p2p::{BlockClient, HeadersClient};
 pub use reth_network_types::{PeerKind, Reputation, ReputationChangeKind};
 
-pub use downloaders::BlockDownloaderProvider;
+pub use downloaders::{BlockDownloaderProvider, SnapDownloaderProvider};
 pub use error::NetworkError;
 pub use events::{
     DiscoveredEvent, DiscoveryEvent, NetworkEvent, NetworkEventListenerProvider, PeerRequest,
@@ -49,7 +49,8 @@ pub type PeerId = alloy_primitives::B512;
 pub trait FullNetwork:
     BlockDownloaderProvider<
         Client: BlockClient<Block = <Self::Primitives as NetworkPrimitives>::Block>,
-    > + NetworkSyncUpdater
+    > + SnapDownloaderProvider
+    + NetworkSyncUpdater
     + NetworkInfo
     + NetworkEventListenerProvider
     + Peers
@@ -63,7 +64,8 @@ pub trait FullNetwork:
 impl<T> FullNetwork for T where
     T: BlockDownloaderProvider<
             Client: BlockClient<Block = <Self::Primitives as NetworkPrimitives>::Block>,
-        > + NetworkSyncUpdater
+        > + SnapDownloaderProvider
+        + NetworkSyncUpdater
         + NetworkInfo
         + NetworkEventListenerProvider
         + Peers
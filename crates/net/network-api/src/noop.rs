@@ -11,14 +11,16 @@ use crate::{
     test_utils::{PeersHandle, PeersHandleProvider},
     BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
     NetworkEventListenerProvider, NetworkInfo, NetworkStatus, PeerId, PeerInfo, PeerRequest, Peers,
-    PeersInfo,
+    PeersInfo, SnapDownloaderProvider,
 };
 use alloy_rpc_types_admin::EthProtocolInfo;
 use enr::{secp256k1::SecretKey, Enr};
 use reth_eth_wire_types::{
     DisconnectReason, EthNetworkPrimitives, NetworkPrimitives, ProtocolVersion,
 };
-use reth_network_p2p::{sync::NetworkSyncUpdater, NoopFullBlockClient};
+use reth_network_p2p::{
+    snap::client::NoopSnapClient, sync::NetworkSyncUpdater, NoopFullBlockClient,
+};
 use reth_network_peers::NodeRecord;
 use reth_network_types::{PeerKind, Reputation, ReputationChangeKind};
 use reth_tokio_util::{EventSender, EventStream};
@@ -172,6 +174,14 @@ where
     }
 }
 
+impl<Net> SnapDownloaderProvider for NoopNetwork<Net> {
+    type SnapClient = NoopSnapClient;
+
+    fn snap_client(&self) -> Self::SnapClient {
+        NoopSnapClient
+    }
+}
+
 impl<Net> NetworkSyncUpdater for NoopNetwork<Net>
 where
     Net: fmt::Debug + Send + Sync + 'static,
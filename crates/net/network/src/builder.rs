@@ -4,6 +4,7 @@ use std::fmt::Debug;
 
 use crate::{
     eth_requests::EthRequestHandler,
+    snap_requests::{SnapProtocolHandler, SnapRequestHandler},
     transactions::{
         config::{StrictEthAnnouncementFilter, TransactionPropagationKind},
         policy::NetworkPolicies,
@@ -20,6 +21,9 @@ use tokio::sync::mpsc;
 /// 256 requests with malicious 10MB body requests is 2.6GB which can be absorbed by the node.
 pub(crate) const ETH_REQUEST_CHANNEL_CAPACITY: usize = 256;
 
+/// We set the max channel capacity of the `SnapRequestHandler` to 256 as well.
+pub(crate) const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
 /// A builder that can configure all components of the network.
 #[expect(missing_debug_implementations)]
 pub struct NetworkBuilder<Tx, Eth, N: NetworkPrimitives = EthNetworkPrimitives> {
@@ -72,6 +76,28 @@ impl<Tx, Eth, N: NetworkPrimitives> NetworkBuilder<Tx, Eth, N> {
         NetworkBuilder { network, request_handler, transactions }
     }
 
+    /// Creates a new [`SnapRequestHandler`] and announces the `snap/1` protocol to peers, whose
+    /// requests are served by the returned handler.
+    ///
+    /// State can be requested from the peers with the
+    /// [`SnapFetchClient`](crate::SnapFetchClient) of the network handle.
+    pub fn snap_request_handler<Client>(&mut self, client: Client) -> SnapRequestHandler<Client> {
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        let peers = self.network.handle().snap_peers().clone();
+        self.network.add_rlpx_sub_protocol(SnapProtocolHandler::new(peers, Some(tx)));
+        SnapRequestHandler::new(client, rx)
+    }
+
+    /// Announces the `snap/1` protocol to peers without serving their requests, so that state can
+    /// be requested from them with the [`SnapFetchClient`](crate::SnapFetchClient) of the network
+    /// handle.
+    ///
+    /// This must not be combined with [`Self::snap_request_handler`].
+    pub fn snap_client_only(&mut self) {
+        let peers = self.network.handle().snap_peers().clone();
+        self.network.add_rlpx_sub_protocol(SnapProtocolHandler::new(peers, None));
+    }
+
     /// Creates a new [`TransactionsManager`] and wires it to the network.
     pub fn transactions<Pool: TransactionPool>(
         self,
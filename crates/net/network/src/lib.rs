@@ -129,6 +129,8 @@ pub mod import;
 pub mod message;
 pub mod peers;
 pub mod protocol;
+pub mod snap_client;
+pub mod snap_requests;
 pub mod transactions;
 
 mod budget;
@@ -150,6 +152,7 @@ pub use reth_eth_wire_types::{primitives, EthNetworkPrimitives, NetworkPrimitive
 pub use reth_network_api::{
     events, BlockDownloaderProvider, DiscoveredEvent, DiscoveryEvent, NetworkEvent,
     NetworkEventListenerProvider, NetworkInfo, PeerRequest, PeerRequestSender, Peers, PeersInfo,
+    SnapDownloaderProvider,
 };
 pub use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState};
 pub use reth_network_types::{PeersConfig, SessionsConfig};
@@ -167,6 +170,7 @@ pub use flattened_response::FlattenedResponse;
 pub use manager::NetworkManager;
 pub use metrics::TxTypesCounter;
 pub use network::{NetworkHandle, NetworkProtocols};
+pub use snap_client::SnapFetchClient;
 pub use swarm::NetworkConnectionState;
 
 /// re-export p2p interfaces
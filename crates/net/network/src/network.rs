@@ -1,6 +1,11 @@
 use crate::{
-    config::NetworkMode, message::PeerMessage, protocol::RlpxSubProtocol,
-    swarm::NetworkConnectionState, transactions::TransactionsHandle, FetchClient,
+    config::NetworkMode,
+    message::PeerMessage,
+    protocol::RlpxSubProtocol,
+    snap_client::{SnapFetchClient, SnapPeers},
+    swarm::NetworkConnectionState,
+    transactions::TransactionsHandle,
+    FetchClient,
 };
 use alloy_primitives::B256;
 use enr::Enr;
@@ -18,7 +23,7 @@ use reth_network_api::{
     test_utils::{PeersHandle, PeersHandleProvider},
     BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
     NetworkEventListenerProvider, NetworkInfo, NetworkStatus, PeerInfo, PeerRequest, Peers,
-    PeersInfo,
+    PeersInfo, SnapDownloaderProvider,
 };
 use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_network_peers::{NodeRecord, PeerId};
@@ -83,6 +88,7 @@ impl<N: NetworkPrimitives> NetworkHandle<N> {
             discv5,
             event_sender,
             nat,
+            snap_peers: Default::default(),
         };
         Self { inner: Arc::new(inner) }
     }
@@ -96,6 +102,11 @@ impl<N: NetworkPrimitives> NetworkHandle<N> {
         &self.inner.to_manager_tx
     }
 
+    /// Returns the peers with an active `snap` connection.
+    pub(crate) fn snap_peers(&self) -> &SnapPeers {
+        &self.inner.snap_peers
+    }
+
     /// Returns the mode of the network, either pow, or pos
     pub fn mode(&self) -> &NetworkMode {
         &self.inner.network_mode
@@ -432,6 +443,14 @@ impl<N: NetworkPrimitives> BlockDownloaderProvider for NetworkHandle<N> {
     }
 }
 
+impl<N: NetworkPrimitives> SnapDownloaderProvider for NetworkHandle<N> {
+    type SnapClient = SnapFetchClient;
+
+    fn snap_client(&self) -> Self::SnapClient {
+        SnapFetchClient::new(self.snap_peers().clone(), self.peers_handle().clone())
+    }
+}
+
 #[derive(Debug)]
 struct NetworkInner<N: NetworkPrimitives = EthNetworkPrimitives> {
     /// Number of active peer sessions the node's currently handling.
@@ -464,6 +483,8 @@ struct NetworkInner<N: NetworkPrimitives = EthNetworkPrimitives> {
     event_sender: EventSender<NetworkEvent<PeerRequest<N>>>,
     /// The NAT resolver
     nat: Option<NatResolver>,
+    /// The peers with an active `snap` connection.
+    snap_peers: SnapPeers,
 }
 
 /// Provides access to modify the network's additional protocol handlers.
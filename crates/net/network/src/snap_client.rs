@@ -0,0 +1,248 @@
+//! Requesting state from peers over the `snap/1` protocol.
+//!
+//! Every `snap` connection registers itself with the shared [`SnapPeers`], the
+//! [`SnapFetchClient`] hands requests to the least busy of them.
+
+use crate::flattened_response::FlattenedResponse;
+use futures::{future, future::Either};
+use parking_lot::Mutex;
+use reth_eth_wire_types::snap::{
+    GetAccountRangeMessage, GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage,
+    SnapProtocolMessage,
+};
+use reth_network_api::test_utils::PeersHandle;
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError},
+    priority::Priority,
+    snap::client::{SnapClient, SnapResponse},
+};
+use reth_network_peers::PeerId;
+use reth_network_types::ReputationChangeKind;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
+
+/// A request to be sent over the `snap` connection of a peer.
+#[derive(Debug)]
+pub(crate) struct SnapPeerRequest {
+    /// The request, its request id is assigned by the connection.
+    pub(crate) request: SnapProtocolMessage,
+    /// The channel sender for the response.
+    pub(crate) response: oneshot::Sender<PeerRequestResult<SnapResponse>>,
+}
+
+/// The registered `snap` connection of a peer.
+#[derive(Debug)]
+struct SnapPeer {
+    /// Identifies the connection, a peer may reconnect before its previous connection is dropped.
+    connection_id: u64,
+    /// Sender half of the request channel of the connection.
+    to_connection: UnboundedSender<SnapPeerRequest>,
+    /// Number of requests the peer has not answered yet.
+    inflight: Arc<AtomicUsize>,
+}
+
+/// The peers with an active `snap` connection.
+#[derive(Debug, Clone, Default)]
+pub struct SnapPeers {
+    peers: Arc<Mutex<HashMap<PeerId, SnapPeer>>>,
+    next_connection_id: Arc<AtomicU64>,
+}
+
+impl SnapPeers {
+    /// Returns the number of peers with an active `snap` connection.
+    pub fn len(&self) -> usize {
+        self.peers.lock().len()
+    }
+
+    /// Returns `true` if there are no peers with an active `snap` connection.
+    pub fn is_empty(&self) -> bool {
+        self.peers.lock().is_empty()
+    }
+
+    /// Registers a new connection of the peer, replacing any previous one.
+    pub(crate) fn register(&self, peer_id: PeerId) -> SnapPeerRegistration {
+        let connection_id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        let (to_connection, requests) = mpsc::unbounded_channel();
+        let inflight = Arc::new(AtomicUsize::new(0));
+        self.peers.lock().insert(
+            peer_id,
+            SnapPeer { connection_id, to_connection, inflight: Arc::clone(&inflight) },
+        );
+        SnapPeerRegistration { peers: self.clone(), peer_id, connection_id, requests, inflight }
+    }
+
+    /// Hands the request to the peer with the fewest unanswered requests.
+    ///
+    /// Returns the request if there is no such peer.
+    fn send(&self, request: SnapPeerRequest) -> Result<(), SnapPeerRequest> {
+        let peers = self.peers.lock();
+        let Some(peer) = peers.values().min_by_key(|peer| peer.inflight.load(Ordering::Relaxed))
+        else {
+            return Err(request)
+        };
+
+        peer.inflight.fetch_add(1, Ordering::Relaxed);
+        peer.to_connection.send(request).map_err(|err| {
+            peer.inflight.fetch_sub(1, Ordering::Relaxed);
+            err.0
+        })
+    }
+}
+
+/// The registration of a `snap` connection, which is removed from the [`SnapPeers`] on drop.
+#[derive(Debug)]
+pub(crate) struct SnapPeerRegistration {
+    peers: SnapPeers,
+    peer_id: PeerId,
+    connection_id: u64,
+    /// Requests to send to the peer.
+    pub(crate) requests: UnboundedReceiver<SnapPeerRequest>,
+    /// Number of requests the peer has not answered yet.
+    pub(crate) inflight: Arc<AtomicUsize>,
+}
+
+impl Drop for SnapPeerRegistration {
+    fn drop(&mut self) {
+        let mut peers = self.peers.peers.lock();
+        if peers.get(&self.peer_id).is_some_and(|peer| peer.connection_id == self.connection_id) {
+            peers.remove(&self.peer_id);
+        }
+    }
+}
+
+/// Front-end API for fetching state from peers over the `snap` protocol.
+///
+/// Requests fail with [`RequestError::UnsupportedCapability`] if there is no peer with an active
+/// `snap` connection.
+#[derive(Debug, Clone)]
+pub struct SnapFetchClient {
+    /// The peers with an active `snap` connection.
+    peers: SnapPeers,
+    /// The handle to the peers
+    peers_handle: PeersHandle,
+}
+
+impl SnapFetchClient {
+    /// Creates a new client that sends requests to the given peers.
+    pub const fn new(peers: SnapPeers, peers_handle: PeersHandle) -> Self {
+        Self { peers, peers_handle }
+    }
+
+    /// Sends the request to an available peer.
+    fn send_request(&self, request: SnapProtocolMessage) -> SnapClientFuture {
+        let (response, rx) = oneshot::channel();
+        match self.peers.send(SnapPeerRequest { request, response }) {
+            Ok(()) => Either::Left(FlattenedResponse::from(rx)),
+            Err(_) => Either::Right(future::err(RequestError::UnsupportedCapability)),
+        }
+    }
+}
+
+impl DownloadClient for SnapFetchClient {
+    fn report_bad_message(&self, peer_id: PeerId) {
+        self.peers_handle.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.peers.len()
+    }
+}
+
+// The `Output` future of the [SnapClient] impl of [SnapFetchClient] that either returns a response
+// or an error.
+type SnapClientFuture = Either<
+    FlattenedResponse<PeerRequestResult<SnapResponse>>,
+    future::Ready<PeerRequestResult<SnapResponse>>,
+>;
+
+/// Requests are sent to the least busy peer, the priority is not taken into account.
+impl SnapClient for SnapFetchClient {
+    type Output = SnapClientFuture;
+
+    fn get_account_range_with_priority(
+        &self,
+        request: GetAccountRangeMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(SnapProtocolMessage::GetAccountRange(request))
+    }
+
+    fn get_storage_ranges_with_priority(
+        &self,
+        request: GetStorageRangesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(SnapProtocolMessage::GetStorageRanges(request))
+    }
+
+    fn get_byte_codes_with_priority(
+        &self,
+        request: GetByteCodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(SnapProtocolMessage::GetByteCodes(request))
+    }
+
+    fn get_trie_nodes_with_priority(
+        &self,
+        request: GetTrieNodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(SnapProtocolMessage::GetTrieNodes(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn request() -> (SnapPeerRequest, oneshot::Receiver<PeerRequestResult<SnapResponse>>) {
+        let (response, rx) = oneshot::channel();
+        let request = SnapProtocolMessage::GetByteCodes(GetByteCodesMessage {
+            request_id: 0,
+            hashes: vec![B256::ZERO],
+            response_bytes: 0,
+        });
+        (SnapPeerRequest { request, response }, rx)
+    }
+
+    #[test]
+    fn sends_to_least_busy_peer() {
+        let peers = SnapPeers::default();
+        assert!(peers.send(request().0).is_err());
+
+        let (first, second) = (PeerId::random(), PeerId::random());
+        let mut first_registration = peers.register(first);
+        let mut second_registration = peers.register(second);
+        assert_eq!(peers.len(), 2);
+
+        peers.send(request().0).unwrap();
+        peers.send(request().0).unwrap();
+        assert!(first_registration.requests.try_recv().is_ok());
+        assert!(second_registration.requests.try_recv().is_ok());
+        assert_eq!(first_registration.inflight.load(Ordering::Relaxed), 1);
+        assert_eq!(second_registration.inflight.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn reconnect_keeps_registration() {
+        let peers = SnapPeers::default();
+        let peer_id = PeerId::random();
+
+        let previous = peers.register(peer_id);
+        let _current = peers.register(peer_id);
+        drop(previous);
+        assert_eq!(peers.len(), 1);
+    }
+}
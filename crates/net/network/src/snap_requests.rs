@@ -0,0 +1,955 @@
+//! State snapshot serving for the `snap/1` protocol.
+//!
+//! The `snap` protocol runs as an additional `RLPx` sub-protocol next to `eth`, see
+//! [snap protocol](https://github.com/ethereum/devp2p/blob/master/caps/snap.md).
+//! [`SnapProtocolHandler`] announces it to peers and forwards their requests to the
+//! [`SnapRequestHandler`], which answers them from the hashed state and trie tables. Requests of
+//! the [`SnapFetchClient`](crate::snap_client::SnapFetchClient) are sent over the same
+//! connections.
+
+use crate::{
+    budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+    eth_requests::SOFT_RESPONSE_LIMIT,
+    metered_poll_nested_stream_with_budget,
+    metrics::SnapRequestHandlerMetrics,
+    protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler},
+    snap_client::{SnapPeerRegistration, SnapPeerRequest, SnapPeers},
+};
+use alloy_consensus::{constants::KECCAK_EMPTY, BlockHeader};
+use alloy_primitives::{bytes::BytesMut, Bytes, B256, U256};
+use alloy_rlp::RlpEncodable;
+use futures::{
+    future::{self, BoxFuture},
+    stream::FuturesUnordered,
+    FutureExt, Stream, StreamExt,
+};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol, Capability,
+};
+use reth_eth_wire_types::snap::{
+    AccountData, AccountRangeMessage, ByteCodesMessage, GetAccountRangeMessage,
+    GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage, SnapProtocolMessage,
+    StorageData, StorageRangesMessage, TrieNodesMessage, TriePath,
+};
+use reth_network_api::Direction;
+use reth_network_p2p::{
+    error::{PeerRequestResult, RequestError, RequestResult},
+    snap::client::SnapResponse,
+};
+use reth_network_peers::{PeerId, WithPeerId};
+use reth_primitives_traits::Account;
+use reth_storage_api::{
+    errors::provider::ProviderResult, BlockNumReader, BytecodeReader, HashedStateRangeProvider,
+    HeaderProvider, StateProofProvider, StateProvider, StateProviderBox, StateProviderFactory,
+};
+use reth_trie_common::{
+    proof::ProofNodes, HashedPostState, HashedStorage, MultiProofTargets, Nibbles, TrieInput,
+    EMPTY_ROOT_HASH,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::atomic::Ordering,
+    task::{ready, Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{
+        mpsc::{Receiver, Sender},
+        oneshot,
+    },
+    time::Interval,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::trace;
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/v1.15.11/eth/protocols/snap/handler.go#L35-L55>
+
+/// Maximum number of bytecodes to serve.
+///
+/// Used to limit lookups.
+pub const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to serve.
+///
+/// Used to limit lookups.
+pub const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Number of recent blocks whose state is served.
+///
+/// Requests for a state root that is older than this are answered with an empty response.
+pub const SNAP_STATE_LOOKBACK: u64 = 128;
+
+/// Number of hashed entries read from the database at once when serving ranges.
+const RANGE_READ_BATCH: usize = 256;
+
+/// Time a peer has to answer a request.
+pub const SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the `snap/1` protocol.
+pub const fn snap_protocol() -> Protocol {
+    Protocol::new(Capability::new_static("snap", 1), 8)
+}
+
+/// Serves `snap` requests on top of the p2p network.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Incoming requests from the `snap` connections.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// Metrics for the snap request handler.
+    metrics: SnapRequestHandlerMetrics,
+}
+
+// === impl SnapRequestHandler ===
+
+impl<C> SnapRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(client: C, incoming: Receiver<IncomingSnapRequest>) -> Self {
+        Self {
+            client,
+            incoming_requests: ReceiverStream::new(incoming),
+            metrics: Default::default(),
+        }
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: StateProviderFactory + HeaderProvider + BlockNumReader,
+{
+    /// Returns the state of one of the recent [`SNAP_STATE_LOOKBACK`] blocks with the given state
+    /// root.
+    fn state_by_root(&self, root: B256) -> ProviderResult<Option<StateProviderBox>> {
+        let best = self.client.best_block_number()?;
+        for number in (best.saturating_sub(SNAP_STATE_LOOKBACK)..=best).rev() {
+            let Some(header) = self.client.sealed_header(number)? else { break };
+            if header.state_root() == root {
+                return self.client.state_by_block_hash(header.hash()).map(Some)
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the accounts of the requested range, proven against the requested root.
+    fn get_account_range(&self, request: &GetAccountRangeMessage) -> ProviderResult<AccountRange> {
+        let Some(state) = self.state_by_root(request.root_hash)? else {
+            return Ok(Default::default())
+        };
+
+        let response_limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut accounts = Vec::new();
+        let mut total_bytes = 0;
+
+        let mut next = Some(request.starting_hash);
+        'outer: while let Some(start) = next {
+            let batch =
+                state.hashed_account_range(HashedPostState::default(), start, RANGE_READ_BATCH)?;
+            next = if batch.len() < RANGE_READ_BATCH {
+                None
+            } else {
+                batch.last().and_then(|(hash, _)| next_key(*hash))
+            };
+
+            for (hash, account) in batch {
+                let body = slim_account(&*state, hash, account)?;
+                total_bytes += B256::len_bytes() + body.len();
+                accounts.push(AccountData { hash, body });
+
+                if hash >= request.limit_hash || total_bytes > response_limit {
+                    break 'outer
+                }
+            }
+        }
+
+        // prove the origin and the last account, which also proves there are no gaps in between
+        let mut targets = MultiProofTargets::account(request.starting_hash);
+        if let Some(last) = accounts.last() {
+            targets.insert(last.hash, Default::default());
+        }
+        let proof = state.multiproof(TrieInput::default(), targets)?;
+
+        Ok(AccountRange { accounts, proof: proof_nodes(proof.account_subtree) })
+    }
+
+    /// Returns the storage slots of the requested accounts, proven against their storage roots
+    /// if a range only partially covers an account's storage.
+    fn get_storage_ranges(
+        &self,
+        request: &GetStorageRangesMessage,
+    ) -> ProviderResult<StorageRanges> {
+        let Some(state) = self.state_by_root(request.root_hash)? else {
+            return Ok(Default::default())
+        };
+
+        let response_limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut slots = Vec::new();
+        let mut proof = Vec::new();
+        let mut total_bytes = 0;
+
+        for (idx, &hashed_address) in request.account_hashes.iter().enumerate() {
+            if total_bytes >= response_limit {
+                break
+            }
+
+            // the origin and limit only apply to the first requested account
+            let (origin, limit) = if idx == 0 {
+                let limit = if request.limit_hash.is_zero() {
+                    B256::repeat_byte(0xff)
+                } else {
+                    request.limit_hash
+                };
+                (request.starting_hash, limit)
+            } else {
+                (B256::ZERO, B256::repeat_byte(0xff))
+            };
+
+            let mut storage = Vec::new();
+            let mut abort = false;
+
+            let mut next = Some(origin);
+            'outer: while let Some(start) = next {
+                let batch = state.hashed_storage_range(
+                    hashed_address,
+                    HashedStorage::default(),
+                    start,
+                    RANGE_READ_BATCH,
+                )?;
+                next = if batch.len() < RANGE_READ_BATCH {
+                    None
+                } else {
+                    batch.last().and_then(|(hash, _)| next_key(*hash))
+                };
+
+                for (hash, value) in batch {
+                    if total_bytes > response_limit {
+                        abort = true;
+                        break 'outer
+                    }
+
+                    let data = Bytes::from(alloy_rlp::encode(value));
+                    total_bytes += B256::len_bytes() + data.len();
+                    storage.push(StorageData { hash, data });
+
+                    if hash >= limit {
+                        break 'outer
+                    }
+                }
+            }
+
+            // a partial range of an account's storage must be proven, a range that was served
+            // completely can be verified against the storage root of the account instead
+            if !origin.is_zero() || (abort && !storage.is_empty()) {
+                let targets = MultiProofTargets::account_with_slots(
+                    hashed_address,
+                    std::iter::once(origin).chain(storage.last().map(|slot| slot.hash)),
+                );
+                let mut multiproof = state.multiproof(TrieInput::default(), targets)?;
+                if let Some(storage_proof) = multiproof.storages.remove(&hashed_address) {
+                    proof = proof_nodes(storage_proof.subtree);
+                }
+                slots.push(storage);
+                break
+            }
+
+            slots.push(storage);
+        }
+
+        Ok(StorageRanges { slots, proof })
+    }
+
+    /// Returns the requested bytecodes, skipping unknown ones.
+    fn get_byte_codes(&self, request: &GetByteCodesMessage) -> ProviderResult<Vec<Bytes>> {
+        let state = self.client.latest()?;
+
+        let response_limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut codes = Vec::new();
+        let mut total_bytes = 0;
+
+        for hash in request.hashes.iter().take(MAX_CODE_LOOKUPS) {
+            let code = if *hash == KECCAK_EMPTY {
+                Bytes::new()
+            } else if let Some(bytecode) = state.bytecode_by_hash(hash)? {
+                bytecode.original_bytes()
+            } else {
+                continue
+            };
+
+            total_bytes += code.len();
+            codes.push(code);
+
+            if total_bytes > response_limit {
+                break
+            }
+        }
+
+        Ok(codes)
+    }
+
+    /// Returns the trie nodes at the requested paths.
+    ///
+    /// Nodes that don't exist are answered with an empty entry.
+    fn get_trie_nodes(&self, request: &GetTrieNodesMessage) -> ProviderResult<Vec<Bytes>> {
+        let Some(state) = self.state_by_root(request.root_hash)? else {
+            return Ok(Default::default())
+        };
+
+        let response_limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut nodes = Vec::new();
+        let mut total_bytes = 0;
+
+        for TriePath { account_path, slot_paths } in &request.paths {
+            if slot_paths.is_empty() {
+                // a single path refers to a node of the account trie
+                let node = match compact_to_nibbles(account_path) {
+                    Some(path) => state
+                        .multiproof(
+                            TrieInput::default(),
+                            MultiProofTargets::account(path_key(&path)),
+                        )?
+                        .account_subtree
+                        .get(&path)
+                        .cloned()
+                        .unwrap_or_default(),
+                    None => Bytes::new(),
+                };
+                total_bytes += node.len();
+                nodes.push(node);
+            } else {
+                // otherwise the account path is the hashed address of the storage trie
+                if account_path.len() != B256::len_bytes() {
+                    break
+                }
+                let hashed_address = B256::from_slice(account_path);
+                let paths =
+                    slot_paths.iter().map(|path| compact_to_nibbles(path)).collect::<Vec<_>>();
+                let targets = MultiProofTargets::account_with_slots(
+                    hashed_address,
+                    paths.iter().flatten().map(path_key),
+                );
+                let mut multiproof = state.multiproof(TrieInput::default(), targets)?;
+                let storage_proof = multiproof.storages.remove(&hashed_address);
+
+                for path in paths {
+                    let node = path
+                        .zip(storage_proof.as_ref())
+                        .and_then(|(path, proof)| proof.subtree.get(&path).cloned())
+                        .unwrap_or_default();
+                    total_bytes += node.len();
+                    nodes.push(node);
+                }
+            }
+
+            if nodes.len() >= MAX_TRIE_NODE_LOOKUPS || total_bytes > response_limit {
+                break
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    fn on_account_range_request(
+        &self,
+        _peer_id: PeerId,
+        request: GetAccountRangeMessage,
+        response: oneshot::Sender<RequestResult<AccountRangeMessage>>,
+    ) {
+        self.metrics.snap_account_range_requests_received_total.increment(1);
+        let AccountRange { accounts, proof } =
+            self.get_account_range(&request).unwrap_or_else(|err| {
+                trace!(target: "net::snap", %err, "Failed to serve account range");
+                Default::default()
+            });
+        let _ = response.send(Ok(AccountRangeMessage {
+            request_id: request.request_id,
+            accounts,
+            proof,
+        }));
+    }
+
+    fn on_storage_ranges_request(
+        &self,
+        _peer_id: PeerId,
+        request: GetStorageRangesMessage,
+        response: oneshot::Sender<RequestResult<StorageRangesMessage>>,
+    ) {
+        self.metrics.snap_storage_ranges_requests_received_total.increment(1);
+        let StorageRanges { slots, proof } =
+            self.get_storage_ranges(&request).unwrap_or_else(|err| {
+                trace!(target: "net::snap", %err, "Failed to serve storage ranges");
+                Default::default()
+            });
+        let _ = response.send(Ok(StorageRangesMessage {
+            request_id: request.request_id,
+            slots,
+            proof,
+        }));
+    }
+
+    fn on_byte_codes_request(
+        &self,
+        _peer_id: PeerId,
+        request: GetByteCodesMessage,
+        response: oneshot::Sender<RequestResult<ByteCodesMessage>>,
+    ) {
+        self.metrics.snap_byte_codes_requests_received_total.increment(1);
+        let codes = self.get_byte_codes(&request).unwrap_or_else(|err| {
+            trace!(target: "net::snap", %err, "Failed to serve bytecodes");
+            Default::default()
+        });
+        let _ = response.send(Ok(ByteCodesMessage { request_id: request.request_id, codes }));
+    }
+
+    fn on_trie_nodes_request(
+        &self,
+        _peer_id: PeerId,
+        request: GetTrieNodesMessage,
+        response: oneshot::Sender<RequestResult<TrieNodesMessage>>,
+    ) {
+        self.metrics.snap_trie_nodes_requests_received_total.increment(1);
+        let nodes = self.get_trie_nodes(&request).unwrap_or_else(|err| {
+            trace!(target: "net::snap", %err, "Failed to serve trie nodes");
+            Default::default()
+        });
+        let _ = response.send(Ok(TrieNodesMessage { request_id: request.request_id, nodes }));
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: StateProviderFactory + HeaderProvider + BlockNumReader + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let mut acc = Duration::ZERO;
+        let maybe_more_incoming_requests = metered_poll_nested_stream_with_budget!(
+            acc,
+            "net::snap",
+            "Incoming snap requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| {
+                match incoming {
+                    IncomingSnapRequest::GetAccountRange { peer_id, request, response } => {
+                        this.on_account_range_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetStorageRanges { peer_id, request, response } => {
+                        this.on_storage_ranges_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetByteCodes { peer_id, request, response } => {
+                        this.on_byte_codes_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetTrieNodes { peer_id, request, response } => {
+                        this.on_trie_nodes_request(peer_id, request, response)
+                    }
+                }
+            },
+        );
+
+        this.metrics.acc_duration_poll_snap_req_handler.set(acc.as_secs_f64());
+
+        // stream is fully drained and import futures pending
+        if maybe_more_incoming_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+        }
+
+        Poll::Pending
+    }
+}
+
+/// All `snap` requests delegated by the `snap` connections.
+#[derive(Debug)]
+pub enum IncomingSnapRequest {
+    /// Request a range of accounts from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetAccountRange {
+        /// The ID of the peer that requested the accounts.
+        peer_id: PeerId,
+        /// The requested account range.
+        request: GetAccountRangeMessage,
+        /// The channel sender for the response containing the accounts.
+        response: oneshot::Sender<RequestResult<AccountRangeMessage>>,
+    },
+    /// Request storage ranges from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetStorageRanges {
+        /// The ID of the peer that requested the storage slots.
+        peer_id: PeerId,
+        /// The requested storage ranges.
+        request: GetStorageRangesMessage,
+        /// The channel sender for the response containing the storage slots.
+        response: oneshot::Sender<RequestResult<StorageRangesMessage>>,
+    },
+    /// Request bytecodes from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetByteCodes {
+        /// The ID of the peer that requested the bytecodes.
+        peer_id: PeerId,
+        /// The requested bytecode hashes.
+        request: GetByteCodesMessage,
+        /// The channel sender for the response containing the bytecodes.
+        response: oneshot::Sender<RequestResult<ByteCodesMessage>>,
+    },
+    /// Request trie nodes from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetTrieNodes {
+        /// The ID of the peer that requested the trie nodes.
+        peer_id: PeerId,
+        /// The requested trie node paths.
+        request: GetTrieNodesMessage,
+        /// The channel sender for the response containing the trie nodes.
+        response: oneshot::Sender<RequestResult<TrieNodesMessage>>,
+    },
+}
+
+/// Announces the `snap/1` protocol to peers and forwards their requests to the
+/// [`SnapRequestHandler`].
+///
+/// The `snap` connections are registered with the [`SnapPeers`], so that state can be requested
+/// from the peers.
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler {
+    peers: SnapPeers,
+    to_request_handler: Option<Sender<IncomingSnapRequest>>,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new protocol handler that registers the connections with the given peers and
+    /// forwards requests to the given channel.
+    ///
+    /// If there is no request handler, all requests are answered with empty responses, which
+    /// signals peers that the requested state is not available.
+    pub const fn new(
+        peers: SnapPeers,
+        to_request_handler: Option<Sender<IncomingSnapRequest>>,
+    ) -> Self {
+        Self { peers, to_request_handler }
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler {
+            peers: self.peers.clone(),
+            to_request_handler: self.to_request_handler.clone(),
+        }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// Sets up the `snap` connection of a peer.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    peers: SnapPeers,
+    to_request_handler: Option<Sender<IncomingSnapRequest>>,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        snap_protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        SnapConnection {
+            peer_id,
+            conn,
+            to_request_handler: self.to_request_handler,
+            pending_responses: Default::default(),
+            registration: self.peers.register(peer_id),
+            inflight_requests: Default::default(),
+            next_request_id: 0,
+            timeout_interval: tokio::time::interval(SNAP_REQUEST_TIMEOUT / 4),
+        }
+    }
+}
+
+/// A request sent to the peer that has not been answered yet.
+#[derive(Debug)]
+struct InflightSnapRequest {
+    /// The time the peer has to answer the request.
+    deadline: Instant,
+    /// The channel sender for the response.
+    response: oneshot::Sender<PeerRequestResult<SnapResponse>>,
+}
+
+/// The `snap` connection of a peer.
+///
+/// Yields the encoded responses to the requests of the peer, and the requests sent to the peer.
+#[must_use = "Streams do nothing unless polled."]
+pub struct SnapConnection {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    to_request_handler: Option<Sender<IncomingSnapRequest>>,
+    pending_responses: FuturesUnordered<BoxFuture<'static, Option<SnapProtocolMessage>>>,
+    /// The registration with the [`SnapPeers`], receives the requests to send to the peer.
+    registration: SnapPeerRegistration,
+    /// Requests sent to the peer by their request id.
+    inflight_requests: HashMap<u64, InflightSnapRequest>,
+    next_request_id: u64,
+    /// Interval at which the inflight requests are checked for timeouts.
+    timeout_interval: Interval,
+}
+
+impl SnapConnection {
+    /// Forwards a request of the peer to the [`SnapRequestHandler`].
+    ///
+    /// Requests are dropped if the handler is overloaded, the peer will time out on them.
+    fn on_request(&self, msg: SnapProtocolMessage) {
+        let Some(to_request_handler) = &self.to_request_handler else {
+            // requests are not served, which is signaled by empty responses
+            if let Some(response) = empty_response(&msg) {
+                self.pending_responses.push(future::ready(Some(response)).boxed());
+            }
+            return
+        };
+
+        let peer_id = self.peer_id;
+        let (request, response) = match msg {
+            SnapProtocolMessage::GetAccountRange(request) => {
+                let (tx, response) = response_channel(SnapProtocolMessage::AccountRange);
+                (IncomingSnapRequest::GetAccountRange { peer_id, request, response: tx }, response)
+            }
+            SnapProtocolMessage::GetStorageRanges(request) => {
+                let (tx, response) = response_channel(SnapProtocolMessage::StorageRanges);
+                (IncomingSnapRequest::GetStorageRanges { peer_id, request, response: tx }, response)
+            }
+            SnapProtocolMessage::GetByteCodes(request) => {
+                let (tx, response) = response_channel(SnapProtocolMessage::ByteCodes);
+                (IncomingSnapRequest::GetByteCodes { peer_id, request, response: tx }, response)
+            }
+            SnapProtocolMessage::GetTrieNodes(request) => {
+                let (tx, response) = response_channel(SnapProtocolMessage::TrieNodes);
+                (IncomingSnapRequest::GetTrieNodes { peer_id, request, response: tx }, response)
+            }
+            // responses are handled by `on_response`
+            _ => return,
+        };
+
+        if to_request_handler.try_send(request).is_ok() {
+            self.pending_responses.push(response);
+        } else {
+            trace!(target: "net::snap", peer_id=%self.peer_id, "Dropping snap request");
+        }
+    }
+
+    /// Assigns a request id to the request and returns the encoded request to send to the peer.
+    fn on_outgoing_request(&mut self, request: SnapPeerRequest) -> BytesMut {
+        let SnapPeerRequest { mut request, response } = request;
+
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        set_request_id(&mut request, request_id);
+
+        let deadline = Instant::now() + SNAP_REQUEST_TIMEOUT;
+        self.inflight_requests.insert(request_id, InflightSnapRequest { deadline, response });
+
+        BytesMut::from(&request.encode()[..])
+    }
+
+    /// Resolves the inflight request the response belongs to.
+    ///
+    /// Responses to unknown requests, like those that timed out already, are ignored.
+    fn on_response(&mut self, request_id: u64, response: SnapResponse) {
+        if let Some(request) = self.inflight_requests.remove(&request_id) {
+            self.registration.inflight.fetch_sub(1, Ordering::Relaxed);
+            let _ = request.response.send(Ok(WithPeerId::new(self.peer_id, response)));
+        }
+    }
+
+    /// Fails all inflight requests whose deadline has passed.
+    fn on_timeout(&mut self) {
+        let now = Instant::now();
+        let timed_out = self
+            .inflight_requests
+            .iter()
+            .filter(|(_, request)| request.deadline <= now)
+            .map(|(request_id, _)| *request_id)
+            .collect::<Vec<_>>();
+
+        for request_id in timed_out {
+            if let Some(request) = self.inflight_requests.remove(&request_id) {
+                trace!(target: "net::snap", peer_id=%self.peer_id, request_id, "Snap request timed out");
+                self.registration.inflight.fetch_sub(1, Ordering::Relaxed);
+                let _ = request.response.send(Err(RequestError::Timeout));
+            }
+        }
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(response)) = this.pending_responses.poll_next_unpin(cx) {
+                if let Some(response) = response {
+                    return Poll::Ready(Some(BytesMut::from(&response.encode()[..])))
+                }
+                continue
+            }
+
+            if let Poll::Ready(Some(request)) = this.registration.requests.poll_recv(cx) {
+                return Poll::Ready(Some(this.on_outgoing_request(request)))
+            }
+
+            while this.timeout_interval.poll_tick(cx).is_ready() {
+                this.on_timeout();
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+            let Some((&id, mut payload)) = msg.split_first() else { return Poll::Ready(None) };
+            let Ok(msg) = SnapProtocolMessage::decode(id, &mut payload) else {
+                trace!(target: "net::snap", peer_id=%this.peer_id, "Invalid snap message");
+                return Poll::Ready(None)
+            };
+
+            match into_response(msg) {
+                Ok((request_id, response)) => this.on_response(request_id, response),
+                Err(request) => this.on_request(request),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .field("inflight_requests", &self.inflight_requests.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Creates the channel for the response to a request, resolving to the response as
+/// [`SnapProtocolMessage`] if there is one.
+fn response_channel<T: Send + 'static>(
+    into_message: fn(T) -> SnapProtocolMessage,
+) -> (oneshot::Sender<RequestResult<T>>, BoxFuture<'static, Option<SnapProtocolMessage>>) {
+    let (tx, rx) = oneshot::channel();
+    (tx, rx.map(move |res| Some(into_message(res.ok()?.ok()?))).boxed())
+}
+
+/// Returns the empty response to the request, which signals that the requested state is not
+/// available.
+const fn empty_response(request: &SnapProtocolMessage) -> Option<SnapProtocolMessage> {
+    Some(match request {
+        SnapProtocolMessage::GetAccountRange(request) => {
+            SnapProtocolMessage::AccountRange(AccountRangeMessage {
+                request_id: request.request_id,
+                accounts: Vec::new(),
+                proof: Vec::new(),
+            })
+        }
+        SnapProtocolMessage::GetStorageRanges(request) => {
+            SnapProtocolMessage::StorageRanges(StorageRangesMessage {
+                request_id: request.request_id,
+                slots: Vec::new(),
+                proof: Vec::new(),
+            })
+        }
+        SnapProtocolMessage::GetByteCodes(request) => {
+            SnapProtocolMessage::ByteCodes(ByteCodesMessage {
+                request_id: request.request_id,
+                codes: Vec::new(),
+            })
+        }
+        SnapProtocolMessage::GetTrieNodes(request) => {
+            SnapProtocolMessage::TrieNodes(TrieNodesMessage {
+                request_id: request.request_id,
+                nodes: Vec::new(),
+            })
+        }
+        _ => return None,
+    })
+}
+
+/// Sets the request id of a request.
+const fn set_request_id(request: &mut SnapProtocolMessage, request_id: u64) {
+    match request {
+        SnapProtocolMessage::GetAccountRange(request) => request.request_id = request_id,
+        SnapProtocolMessage::GetStorageRanges(request) => request.request_id = request_id,
+        SnapProtocolMessage::GetByteCodes(request) => request.request_id = request_id,
+        SnapProtocolMessage::GetTrieNodes(request) => request.request_id = request_id,
+        _ => {}
+    }
+}
+
+/// Returns the request id and the response if the message is a response, otherwise the message.
+fn into_response(msg: SnapProtocolMessage) -> Result<(u64, SnapResponse), SnapProtocolMessage> {
+    Ok(match msg {
+        SnapProtocolMessage::AccountRange(response) => {
+            (response.request_id, SnapResponse::AccountRange(response))
+        }
+        SnapProtocolMessage::StorageRanges(response) => {
+            (response.request_id, SnapResponse::StorageRanges(response))
+        }
+        SnapProtocolMessage::ByteCodes(response) => {
+            (response.request_id, SnapResponse::ByteCodes(response))
+        }
+        SnapProtocolMessage::TrieNodes(response) => {
+            (response.request_id, SnapResponse::TrieNodes(response))
+        }
+        request => return Err(request),
+    })
+}
+
+/// The accounts and proof of an [`AccountRangeMessage`].
+#[derive(Debug, Default)]
+struct AccountRange {
+    accounts: Vec<AccountData>,
+    proof: Vec<Bytes>,
+}
+
+/// The slots and proof of a [`StorageRangesMessage`].
+#[derive(Debug, Default)]
+struct StorageRanges {
+    slots: Vec<Vec<StorageData>>,
+    proof: Vec<Bytes>,
+}
+
+/// An account in the slim format of the snap protocol, which omits the empty storage root and
+/// code hash.
+#[derive(RlpEncodable)]
+struct SlimAccount {
+    nonce: u64,
+    balance: U256,
+    storage_root: Bytes,
+    code_hash: Bytes,
+}
+
+/// Encodes the account in the slim format.
+fn slim_account(
+    state: &dyn StateProvider,
+    hashed_address: B256,
+    account: Account,
+) -> ProviderResult<Bytes> {
+    let storage_root = state.hashed_storage_root(hashed_address, HashedStorage::default())?;
+    let code_hash = account.get_bytecode_hash();
+    let slim = SlimAccount {
+        nonce: account.nonce,
+        balance: account.balance,
+        storage_root: if storage_root == EMPTY_ROOT_HASH {
+            Bytes::new()
+        } else {
+            storage_root.into()
+        },
+        code_hash: if code_hash == KECCAK_EMPTY { Bytes::new() } else { code_hash.into() },
+    };
+    Ok(alloy_rlp::encode(slim).into())
+}
+
+/// Returns the proof nodes ordered by their path.
+fn proof_nodes(nodes: ProofNodes) -> Vec<Bytes> {
+    nodes.into_nodes_sorted().into_iter().map(|(_, node)| node).collect()
+}
+
+/// Returns the key following the given one, if any.
+fn next_key(key: B256) -> Option<B256> {
+    U256::from_be_bytes(key.0).checked_add(U256::from(1)).map(|key| B256::from(key.to_be_bytes()))
+}
+
+/// Decodes a hex-prefix encoded trie path.
+///
+/// Returns `None` if the path is malformed.
+fn compact_to_nibbles(compact: &[u8]) -> Option<Nibbles> {
+    let Some((&first, rest)) = compact.split_first() else { return Some(Nibbles::new()) };
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    // the odd flag signals that the first byte carries the first nibble
+    if first & 0x10 != 0 {
+        nibbles.push(first & 0x0f);
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+
+    (nibbles.len() <= 64).then(|| Nibbles::from_nibbles(nibbles))
+}
+
+/// Returns a key whose proof passes through the node at the given path.
+fn path_key(path: &Nibbles) -> B256 {
+    let mut nibbles = path.to_vec();
+    nibbles.resize(64, 0);
+    B256::from_slice(&Nibbles::from_nibbles(nibbles).pack())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_compact_paths() {
+        assert_eq!(compact_to_nibbles(&[]), Some(Nibbles::new()));
+        // even extension path
+        assert_eq!(compact_to_nibbles(&[0x00, 0x12]), Some(Nibbles::from_nibbles([1, 2])));
+        // odd extension path
+        assert_eq!(compact_to_nibbles(&[0x11, 0x23]), Some(Nibbles::from_nibbles([1, 2, 3])));
+        // odd leaf path
+        assert_eq!(compact_to_nibbles(&[0x3a]), Some(Nibbles::from_nibbles([0xa])));
+        // longer than a full key
+        assert_eq!(compact_to_nibbles(&[0x00; 34]), None);
+    }
+
+    #[test]
+    fn path_keys() {
+        let key = path_key(&Nibbles::from_nibbles([0xa, 0xb, 0xc]));
+        assert_eq!(key[..2], [0xab, 0xc0]);
+        assert!(key[2..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn next_keys() {
+        assert_eq!(next_key(B256::ZERO), Some(B256::with_last_byte(1)));
+        assert_eq!(next_key(B256::repeat_byte(0xff)), None);
+    }
+}
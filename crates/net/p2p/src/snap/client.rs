@@ -1,15 +1,36 @@
-use crate::{download::DownloadClient, error::PeerRequestResult, priority::Priority};
-use futures::Future;
+use crate::{
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError},
+    priority::Priority,
+};
+use futures::{
+    future::{ready, Ready},
+    Future,
+};
 use reth_eth_wire_types::snap::{
-    AccountRangeMessage, GetAccountRangeMessage, GetByteCodesMessage, GetStorageRangesMessage,
-    GetTrieNodesMessage,
+    AccountRangeMessage, ByteCodesMessage, GetAccountRangeMessage, GetByteCodesMessage,
+    GetStorageRangesMessage, GetTrieNodesMessage, StorageRangesMessage, TrieNodesMessage,
 };
+use reth_network_peers::PeerId;
+
+/// The response to a snap request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapResponse {
+    /// Response to a [`GetAccountRangeMessage`].
+    AccountRange(AccountRangeMessage),
+    /// Response to a [`GetStorageRangesMessage`].
+    StorageRanges(StorageRangesMessage),
+    /// Response to a [`GetByteCodesMessage`].
+    ByteCodes(ByteCodesMessage),
+    /// Response to a [`GetTrieNodesMessage`].
+    TrieNodes(TrieNodesMessage),
+}
 
 /// The snap sync downloader client
 #[auto_impl::auto_impl(&, Arc, Box)]
 pub trait SnapClient: DownloadClient {
-    /// The output future type for account range requests
-    type Output: Future<Output = PeerRequestResult<AccountRangeMessage>> + Send + Sync + Unpin;
+    /// The output future type for snap requests
+    type Output: Future<Output = PeerRequestResult<SnapResponse>> + Send + Sync + Unpin;
 
     /// Sends the account range request to the p2p network and returns the account range
     /// response received from a peer.
@@ -27,7 +48,9 @@ pub trait SnapClient: DownloadClient {
 
     /// Sends the storage ranges request to the p2p network and returns the storage ranges
     /// response received from a peer.
-    fn get_storage_ranges(&self, request: GetStorageRangesMessage) -> Self::Output;
+    fn get_storage_ranges(&self, request: GetStorageRangesMessage) -> Self::Output {
+        self.get_storage_ranges_with_priority(request, Priority::Normal)
+    }
 
     /// Sends the storage ranges request to the p2p network with priority set and returns
     /// the storage ranges response received from a peer.
@@ -39,7 +62,9 @@ pub trait SnapClient: DownloadClient {
 
     /// Sends the byte codes request to the p2p network and returns the byte codes
     /// response received from a peer.
-    fn get_byte_codes(&self, request: GetByteCodesMessage) -> Self::Output;
+    fn get_byte_codes(&self, request: GetByteCodesMessage) -> Self::Output {
+        self.get_byte_codes_with_priority(request, Priority::Normal)
+    }
 
     /// Sends the byte codes request to the p2p network with priority set and returns
     /// the byte codes response received from a peer.
@@ -51,7 +76,9 @@ pub trait SnapClient: DownloadClient {
 
     /// Sends the trie nodes request to the p2p network and returns the trie nodes
     /// response received from a peer.
-    fn get_trie_nodes(&self, request: GetTrieNodesMessage) -> Self::Output;
+    fn get_trie_nodes(&self, request: GetTrieNodesMessage) -> Self::Output {
+        self.get_trie_nodes_with_priority(request, Priority::Normal)
+    }
 
     /// Sends the trie nodes request to the p2p network with priority set and returns
     /// the trie nodes response received from a peer.
@@ -61,3 +88,52 @@ pub trait SnapClient: DownloadClient {
         priority: Priority,
     ) -> Self::Output;
 }
+
+/// A [`SnapClient`] without any peers, all requests fail with
+/// [`RequestError::UnsupportedCapability`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopSnapClient;
+
+impl DownloadClient for NoopSnapClient {
+    fn report_bad_message(&self, _peer_id: PeerId) {}
+
+    fn num_connected_peers(&self) -> usize {
+        0
+    }
+}
+
+impl SnapClient for NoopSnapClient {
+    type Output = Ready<PeerRequestResult<SnapResponse>>;
+
+    fn get_account_range_with_priority(
+        &self,
+        _request: GetAccountRangeMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        ready(Err(RequestError::UnsupportedCapability))
+    }
+
+    fn get_storage_ranges_with_priority(
+        &self,
+        _request: GetStorageRangesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        ready(Err(RequestError::UnsupportedCapability))
+    }
+
+    fn get_byte_codes_with_priority(
+        &self,
+        _request: GetByteCodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        ready(Err(RequestError::UnsupportedCapability))
+    }
+
+    fn get_trie_nodes_with_priority(
+        &self,
+        _request: GetTrieNodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        ready(Err(RequestError::UnsupportedCapability))
+    }
+}
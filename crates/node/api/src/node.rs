@@ -5,12 +5,15 @@ use alloy_rpc_types_engine::JwtSecret;
 use reth_basic_payload_builder::PayloadBuilder;
 use reth_consensus::{ConsensusError, FullConsensus};
 use reth_db_api::{database_metrics::DatabaseMetrics, Database};
-use reth_engine_primitives::{BeaconConsensusEngineEvent, BeaconConsensusEngineHandle};
+use reth_engine_local::LocalMinerHandle;
+use reth_engine_primitives::{
+    BadBlockStore, BeaconConsensusEngineEvent, BeaconConsensusEngineHandle,
+};
 use reth_evm::ConfigureEvm;
 use reth_network_api::FullNetwork;
 use reth_node_core::node_config::NodeConfig;
 use reth_node_types::{NodeTypes, NodeTypesWithDBAdapter, TxTy};
-use reth_payload_builder::PayloadBuilderHandle;
+use reth_payload_builder::{MinerSettings, PayloadBuilderHandle};
 use reth_provider::FullProvider;
 use reth_tasks::TaskExecutor;
 use reth_tokio_util::EventSender;
@@ -118,6 +121,12 @@ pub struct AddOnsContext<'a, N: FullNodeComponents> {
     pub engine_events: EventSender<BeaconConsensusEngineEvent<<N::Types as NodeTypes>::Primitives>>,
     /// JWT secret for the node.
     pub jwt_secret: JwtSecret,
+    /// Store of blocks rejected by the engine.
+    pub bad_blocks: BadBlockStore,
+    /// Handle to the local miner, only set in dev mode.
+    pub local_miner: Option<LocalMinerHandle>,
+    /// Block building parameters that can be changed through the `miner` namespace.
+    pub miner_settings: MinerSettings,
 }
 
 /// Customizable node add-on types.
@@ -14,6 +14,7 @@ use futures::Future;
 use reth_chainspec::{EthChainSpec, EthereumHardforks, Hardforks};
 use reth_cli_util::get_secret_key;
 use reth_db_api::{database::Database, database_metrics::DatabaseMetrics};
+use reth_engine_local::LocalMinerHandle;
 use reth_exex::ExExContext;
 use reth_network::{
     transactions::{TransactionPropagationPolicy, TransactionsManagerConfig},
@@ -30,6 +31,7 @@ use reth_node_core::{
     node_config::NodeConfig,
     primitives::Head,
 };
+use reth_payload_builder::MinerSettings;
 use reth_provider::{
     providers::{BlockchainProvider, NodeTypesForProvider},
     ChainSpecProvider, FullProvider,
@@ -703,17 +705,34 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
+    /// Handle to the local miner, only set in dev mode.
+    pub(crate) local_miner: Option<LocalMinerHandle>,
+    /// Block building parameters that can be changed while the node is running.
+    pub(crate) miner_settings: MinerSettings,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// Create a new instance of [`BuilderContext`]
-    pub const fn new(
+    pub fn new(
         head: Head,
         provider: Node::Provider,
         executor: TaskExecutor,
         config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self {
+            head,
+            provider,
+            executor,
+            config_container,
+            local_miner: None,
+            miner_settings: MinerSettings::default(),
+        }
+    }
+
+    /// Sets the handle to the local miner that drives the chain in dev mode.
+    pub fn with_local_miner(mut self, local_miner: LocalMinerHandle) -> Self {
+        self.local_miner = Some(local_miner);
+        self
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -753,6 +772,20 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         self.config().dev.dev
     }
 
+    /// Returns the handle to the local miner, if the node is configured as --dev.
+    ///
+    /// Payload builders can use this to apply the state changes requested through the handle.
+    pub const fn local_miner(&self) -> Option<&LocalMinerHandle> {
+        self.local_miner.as_ref()
+    }
+
+    /// Returns the block building parameters that can be changed through the `miner` namespace.
+    ///
+    /// Payload builders should prefer these over the configured parameters once they are set.
+    pub const fn miner_settings(&self) -> &MinerSettings {
+        &self.miner_settings
+    }
+
     /// Returns the transaction pool config of the node.
     pub fn pool_config(&self) -> PoolConfig {
         self.config().txpool.pool_config()
@@ -804,7 +837,7 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// connected to that network.
     pub fn start_network_with<Pool, N, Policy>(
         &self,
-        builder: NetworkBuilder<(), (), N>,
+        mut builder: NetworkBuilder<(), (), N>,
         pool: Pool,
         tx_config: TransactionsManagerConfig,
         propagation_policy: Policy,
@@ -821,6 +854,13 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         Node::Provider: BlockReaderFor<N>,
         Policy: TransactionPropagationPolicy + Debug,
     {
+        if self.config().network.serve_snap {
+            let snap = builder.snap_request_handler(self.provider().clone());
+            self.executor.spawn_critical("p2p snap request handler", Box::pin(snap));
+        } else if self.config().network.snap_sync {
+            builder.snap_client_only();
+        }
+
         let (handle, network, txpool, eth) = builder
             .transactions_with_policy(pool, tx_config, propagation_policy)
             .request_handler(self.provider().clone())
@@ -45,9 +45,10 @@ use reth_consensus::noop::NoopConsensus;
 use reth_db_api::{database::Database, database_metrics::DatabaseMetrics};
 use reth_db_common::init::{init_genesis, InitStorageError};
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
-use reth_engine_local::MiningMode;
+use reth_engine_local::{LocalMinerHandle, MiningMode};
 use reth_evm::{noop::NoopEvmConfig, ConfigureEvm};
 use reth_exex::ExExManagerHandle;
+use reth_exex_call_index::{call_index_exex, CALL_INDEX_EXEX_ID};
 use reth_fs_util as fs;
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::{FullNodeTypes, NodeTypes, NodeTypesWithDB, NodeTypesWithDBAdapter};
@@ -68,13 +69,14 @@ use reth_node_metrics::{
     server::{MetricServer, MetricServerConfig},
     version::VersionInfo,
 };
+use reth_payload_builder::MinerSettings;
 use reth_provider::{
     providers::{NodeTypesForProvider, ProviderNodeTypes, StaticFileProvider},
     BlockHashReader, BlockNumReader, BlockReaderIdExt, ChainSpecProvider, ProviderError,
     ProviderFactory, ProviderResult, StageCheckpointReader, StateProviderFactory,
     StaticFileProviderFactory,
 };
-use reth_prune::{PruneModes, PrunerBuilder};
+use reth_prune::{PruneMode, PruneModes, PrunerBuilder};
 use reth_rpc_builder::config::RethRpcServerConfig;
 use reth_rpc_layer::JwtSecret;
 use reth_stages::{
@@ -409,14 +411,26 @@ impl<R, ChainSpec: EthChainSpec> LaunchContextWith<Attached<WithConfigs<ChainSpe
     where
         ChainSpec: reth_chainspec::EthereumHardforks,
     {
-        let Some(mut node_prune_config) = self.node_config().prune_config() else {
+        let mut prune_config = match self.node_config().prune_config() {
+            Some(mut node_prune_config) => {
+                // Use the CLI configuration and merge with toml config.
+                node_prune_config.merge(self.toml_config().prune.clone());
+                Some(node_prune_config)
+            }
             // No CLI config is set, use the toml config.
-            return self.toml_config().prune.clone();
+            None => self.toml_config().prune.clone(),
         };
 
-        // Otherwise, use the CLI configuration and merge with toml config.
-        node_prune_config.merge(self.toml_config().prune.clone());
-        Some(node_prune_config)
+        // Receipts below the snap sync pivot are never downloaded, so they're written to the
+        // database instead of the static files, which can't have gaps.
+        if self.node_config().network.snap_sync {
+            let config = prune_config.get_or_insert_with(Default::default);
+            if !config.has_receipts_pruning() {
+                config.segments.receipts = Some(PruneMode::Before(1));
+            }
+        }
+
+        prune_config
     }
 
     /// Returns the configured [`PruneModes`], returning the default if no config was available.
@@ -473,6 +487,7 @@ where
             StaticFileProvider::read_write(self.data_dir().static_files())?,
         )
         .with_prune_modes(self.prune_modes())
+        .with_record_preimages(self.node_config().db.record_preimages)
         .with_static_files_metrics();
 
         let has_receipt_pruning =
@@ -758,12 +773,15 @@ where
         // fetch the head block from the database
         let head = self.lookup_head()?;
 
-        let builder_ctx = BuilderContext::new(
+        let mut builder_ctx = BuilderContext::new(
             head,
             self.blockchain_db().clone(),
             self.task_executor().clone(),
             self.configs().clone(),
         );
+        if self.is_dev() {
+            builder_ctx = builder_ctx.with_local_miner(LocalMinerHandle::new());
+        }
 
         debug!(target: "reth::cli", "creating components");
         let components = components_builder.build_components(&builder_ctx).await?;
@@ -786,6 +804,8 @@ where
             },
             node_adapter,
             head,
+            local_miner: builder_ctx.local_miner,
+            miner_settings: builder_ctx.miner_settings,
         };
 
         let ctx = LaunchContextWith {
@@ -843,6 +863,16 @@ where
         &self.right().node_adapter
     }
 
+    /// Returns the handle to the local miner, only set in dev mode.
+    pub const fn local_miner(&self) -> Option<&LocalMinerHandle> {
+        self.right().local_miner.as_ref()
+    }
+
+    /// Returns the block building parameters that can be changed through the `miner` namespace.
+    pub const fn miner_settings(&self) -> &MinerSettings {
+        &self.right().miner_settings
+    }
+
     /// Returns mutable reference to the configured `NodeAdapter`.
     pub const fn node_adapter_mut(&mut self) -> &mut NodeAdapter<T, CB::Components> {
         &mut self.right_mut().node_adapter
@@ -988,14 +1018,23 @@ where
     }
 
     /// Launches ExEx (Execution Extensions) and returns the ExEx manager handle.
+    ///
+    /// The call index `ExEx` is launched in addition to the installed ones if it is enabled.
     #[allow(clippy::type_complexity)]
     pub async fn launch_exex(
         &self,
-        installed_exex: Vec<(
+        mut installed_exex: Vec<(
             String,
             Box<dyn crate::exex::BoxedLaunchExEx<NodeAdapter<T, CB::Components>>>,
         )>,
     ) -> eyre::Result<Option<ExExManagerHandle<PrimitivesTy<T::Types>>>> {
+        if self.node_config().rpc.rpc_call_index {
+            installed_exex.push((
+                CALL_INDEX_EXEX_ID.to_string(),
+                Box::new(|ctx| async move { Ok(call_index_exex(ctx)) }),
+            ));
+        }
+
         ExExLauncher::new(
             self.head(),
             self.node_adapter().clone(),
@@ -1175,6 +1214,8 @@ where
     db_provider_container: WithMeteredProvider<NodeTypesWithDBAdapter<T::Types, T::DB>>,
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
+    local_miner: Option<LocalMinerHandle>,
+    miner_settings: MinerSettings,
 }
 
 #[cfg(test)]
@@ -13,6 +13,7 @@ use alloy_consensus::BlockHeader;
 use futures::{stream_select, StreamExt};
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_db_api::{database_metrics::DatabaseMetrics, Database};
+use reth_engine_primitives::{BadBlockStore, DEFAULT_BAD_BLOCK_LIMIT};
 use reth_engine_service::service::{ChainEvent, EngineService};
 use reth_engine_tree::{
     engine::{EngineApiRequest, EngineRequestHandler},
@@ -21,7 +22,7 @@ use reth_engine_tree::{
 use reth_engine_util::EngineMessageStreamExt;
 use reth_exex::ExExManagerHandle;
 use reth_network::{types::BlockRangeUpdate, NetworkSyncUpdater, SyncState};
-use reth_network_api::BlockDownloaderProvider;
+use reth_network_api::{BlockDownloaderProvider, SnapDownloaderProvider};
 use reth_node_api::{
     BeaconConsensusEngineHandle, BuiltPayload, FullNodeTypes, NodeTypes, NodeTypesWithDBAdapter,
 };
@@ -161,6 +162,7 @@ where
             ctx.components().evm_config().clone(),
             maybe_exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty),
             ctx.era_import_source(),
+            node_config.network.snap_sync.then(|| network_handle.snap_client()),
         )?;
 
         // The new engine writes directly to static files. This ensures that they're up to the tip.
@@ -184,12 +186,18 @@ where
         // extract the jwt secret from the args if possible
         let jwt_secret = ctx.auth_jwt_secret()?;
 
+        // blocks rejected by the engine, served by `debug_getBadBlocks`
+        let bad_blocks = BadBlockStore::open(ctx.data_dir().bad_blocks(), DEFAULT_BAD_BLOCK_LIMIT)?;
+
         let add_ons_ctx = AddOnsContext {
             node: ctx.node_adapter().clone(),
             config: ctx.node_config(),
             beacon_engine_handle: beacon_engine_handle.clone(),
             jwt_secret,
             engine_events: event_sender.clone(),
+            bad_blocks: bad_blocks.clone(),
+            local_miner: ctx.local_miner().cloned(),
+            miner_settings: ctx.miner_settings().clone(),
         };
         let engine_payload_validator = add_ons.engine_validator(&add_ons_ctx).await?;
 
@@ -215,7 +223,8 @@ where
             engine_payload_validator,
             engine_tree_config.clone(),
             add_ons_ctx.create_invalid_block_hook(ctx.data_dir()).await?,
-        );
+        )
+        .with_bad_block_store(bad_blocks);
 
         let mut engine_service = EngineService::new(
             consensus.clone(),
@@ -253,8 +262,13 @@ where
             )),
         );
 
-        let RpcHandle { rpc_server_handles, rpc_registry, engine_events, beacon_engine_handle } =
-            add_ons.launch_add_ons(add_ons_ctx).await?;
+        let RpcHandle {
+            rpc_server_handles,
+            rpc_registry,
+            engine_events,
+            beacon_engine_handle,
+            local_miner,
+        } = add_ons.launch_add_ons(add_ons_ctx).await?;
 
         // Run consensus engine to completion
         let initial_target = ctx.initial_backfill_target()?;
@@ -284,6 +298,10 @@ where
             // advance the chain and await payloads built locally to add into the engine api tree handler to prevent re-execution if that block is received as payload from the CL
             loop {
                 tokio::select! {
+                    // built payloads go first, so that a locally built block is known to the tree
+                    // before the local miner submits it as new payload
+                    biased;
+
                     payload = built_payloads.select_next_some() => {
                         if let Some(executed_block) = payload.executed_block() {
                             debug!(target: "reth::cli", block=?executed_block.recovered_block().num_hash(),  "inserting built payload");
@@ -352,7 +370,9 @@ where
                 rpc_registry,
                 engine_events,
                 beacon_engine_handle,
+                local_miner,
             },
+            exex_manager_handle: maybe_exex_manager_handle,
         };
         // Notify on node started
         on_node_started.on_event(FullNode::clone(&full_node))?;
@@ -5,8 +5,8 @@ use futures::future;
 use reth_chain_state::ForkChoiceSubscriptions;
 use reth_chainspec::EthChainSpec;
 use reth_exex::{
-    ExExContext, ExExHandle, ExExManager, ExExManagerHandle, ExExNotificationSource, Wal,
-    DEFAULT_EXEX_MANAGER_CAPACITY,
+    ExExContext, ExExHandle, ExExManager, ExExManagerHandle, ExExNotificationSource, RemoteExEx,
+    RemoteExExAddr, Wal, DEFAULT_EXEX_MANAGER_CAPACITY,
 };
 use reth_node_api::{FullNodeComponents, NodeTypes, PrimitivesTy};
 use reth_provider::CanonStateSubscriptions;
@@ -42,9 +42,19 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
     pub async fn launch(
         self,
     ) -> eyre::Result<Option<ExExManagerHandle<PrimitivesTy<Node::Types>>>> {
-        let Self { head, extensions, components, config_container } = self;
+        let Self { head, mut extensions, components, config_container } = self;
         let head = BlockNumHash::new(head.number, head.hash);
 
+        if let Some(addr) = &config_container.config.exex.remote {
+            let addr: RemoteExExAddr = addr.parse()?;
+            extensions.push((
+                "remote".to_string(),
+                Box::new(move |ctx: ExExContext<Node>| async move {
+                    Ok(RemoteExEx::from_context(&addr, ctx).await?.run())
+                }),
+            ));
+        }
+
         if extensions.is_empty() {
             // nothing to launch
             return Ok(None)
@@ -2,7 +2,8 @@
 pub use reth_node_api::{FullNodeTypes, NodeTypes};
 
 use crate::{components::NodeComponentsBuilder, rpc::RethRpcAddOns, NodeAdapter, NodeAddOns};
-use reth_node_api::{EngineTypes, FullNodeComponents, PayloadTypes};
+use reth_exex::ExExManagerHandle;
+use reth_node_api::{EngineTypes, FullNodeComponents, PayloadTypes, PrimitivesTy};
 use reth_node_core::{
     dirs::{ChainPath, DataDirPath},
     node_config::NodeConfig,
@@ -121,6 +122,10 @@ pub struct FullNode<Node: FullNodeComponents, AddOns: NodeAddOns<Node>> {
     pub data_dir: ChainPath<DataDirPath>,
     /// The handle to launched add-ons
     pub add_ons_handle: AddOns::Handle,
+    /// Handle to the `ExEx` manager, to add and remove `ExEx`'s at runtime.
+    ///
+    /// This is `None` if no `ExEx`'s were installed at launch.
+    pub exex_manager_handle: Option<ExExManagerHandle<PrimitivesTy<Node::Types>>>,
 }
 
 impl<Node: FullNodeComponents, AddOns: NodeAddOns<Node>> Clone for FullNode<Node, AddOns> {
@@ -135,6 +140,7 @@ impl<Node: FullNodeComponents, AddOns: NodeAddOns<Node>> Clone for FullNode<Node
             config: self.config.clone(),
             data_dir: self.data_dir.clone(),
             add_ons_handle: self.add_ons_handle.clone(),
+            exex_manager_handle: self.exex_manager_handle.clone(),
         }
     }
 }
@@ -9,6 +9,7 @@ use alloy_rpc_types_engine::ExecutionData;
 use jsonrpsee::{core::middleware::layer::Either, RpcModule};
 use reth_chain_state::CanonStateSubscriptions;
 use reth_chainspec::{ChainSpecProvider, EthereumHardforks};
+use reth_engine_local::LocalMinerHandle;
 use reth_node_api::{
     AddOnsContext, BlockTy, EngineTypes, EngineValidator, FullNodeComponents, FullNodeTypes,
     NodeAddOns, NodeTypes, PayloadTypes, PayloadValidator, PrimitivesTy,
@@ -34,6 +35,7 @@ use std::{
     fmt::{self, Debug},
     future::Future,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 /// Contains the handles to the spawned RPC servers.
@@ -326,6 +328,8 @@ pub struct RpcHandle<Node: FullNodeComponents, EthApi: EthApiTypes> {
         EventSender<BeaconConsensusEngineEvent<<Node::Types as NodeTypes>::Primitives>>,
     /// Handle to the beacon consensus engine.
     pub beacon_engine_handle: BeaconConsensusEngineHandle<<Node::Types as NodeTypes>::Payload>,
+    /// Handle to the local miner, only set in dev mode.
+    pub local_miner: Option<LocalMinerHandle>,
 }
 
 impl<Node: FullNodeComponents, EthApi: EthApiTypes> Clone for RpcHandle<Node, EthApi> {
@@ -335,6 +339,7 @@ impl<Node: FullNodeComponents, EthApi: EthApiTypes> Clone for RpcHandle<Node, Et
             rpc_registry: self.rpc_registry.clone(),
             engine_events: self.engine_events.clone(),
             beacon_engine_handle: self.beacon_engine_handle.clone(),
+            local_miner: self.local_miner.clone(),
         }
     }
 }
@@ -406,6 +411,7 @@ struct RpcSetupContext<'a, Node: FullNodeComponents, EthApi: EthApiTypes> {
     on_rpc_started: Box<dyn OnRpcStarted<Node, EthApi>>,
     engine_events: EventSender<BeaconConsensusEngineEvent<<Node::Types as NodeTypes>::Primitives>>,
     engine_handle: BeaconConsensusEngineHandle<<Node::Types as NodeTypes>::Payload>,
+    local_miner: Option<LocalMinerHandle>,
 }
 
 /// Node add-ons containing RPC server configuration, with customizable eth API handler.
@@ -657,6 +663,7 @@ where
             on_rpc_started,
             engine_events,
             engine_handle,
+            local_miner: _,
         } = setup_ctx;
 
         let server_config = config.rpc.rpc_server_config().set_rpc_middleware(rpc_middleware);
@@ -723,6 +730,7 @@ where
             on_rpc_started,
             engine_events,
             engine_handle,
+            local_miner,
         } = setup_ctx;
 
         let server_config = config.rpc.rpc_server_config().set_rpc_middleware(rpc_middleware);
@@ -759,6 +767,7 @@ where
             rpc_registry: registry,
             engine_events,
             beacon_engine_handle: engine_handle,
+            local_miner,
         })
     }
 
@@ -774,7 +783,16 @@ where
         let Self { eth_api_builder, engine_api_builder, hooks, .. } = self;
 
         let engine_api = engine_api_builder.build_engine_api(&ctx).await?;
-        let AddOnsContext { node, config, beacon_engine_handle, jwt_secret, engine_events } = ctx;
+        let AddOnsContext {
+            node,
+            config,
+            beacon_engine_handle,
+            jwt_secret,
+            engine_events,
+            bad_blocks,
+            local_miner,
+            miner_settings,
+        } = ctx;
 
         info!(target: "reth::cli", "Engine API handler initialized");
 
@@ -797,17 +815,26 @@ where
         let eth_api = eth_api_builder.build_eth_api(ctx).await?;
 
         let auth_config = config.rpc.auth_server_config(jwt_secret)?;
-        let module_config = config.rpc.transport_rpc_module_config();
+        let mut module_config = config.rpc.transport_rpc_module_config();
+        module_config.config_mut().get_or_insert_default().debug_mut().trace_dir =
+            Some(config.datadir().traces());
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-        let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
+        let mut rpc_module_builder = RpcModuleBuilder::default()
             .with_provider(node.provider().clone())
             .with_pool(node.pool().clone())
             .with_network(node.network().clone())
             .with_executor(Box::new(node.task_executor().clone()))
             .with_evm_config(node.evm_config().clone())
             .with_consensus(node.consensus().clone())
-            .build_with_auth_server(module_config, engine_api, eth_api);
+            .with_bad_block_store(bad_blocks)
+            .with_engine_set_head(Arc::new(beacon_engine_handle.clone()))
+            .with_miner_settings(miner_settings);
+        if let Some(local_miner) = &local_miner {
+            rpc_module_builder = rpc_module_builder.with_local_miner(local_miner.clone());
+        }
+        let (mut modules, mut auth_module, registry) =
+            rpc_module_builder.build_with_auth_server(module_config, engine_api, eth_api);
 
         // in dev mode we generate 20 random dev-signer accounts
         if config.dev.dev {
@@ -842,6 +869,7 @@ where
             on_rpc_started,
             engine_events,
             engine_handle: beacon_engine_handle,
+            local_miner,
         })
     }
 
@@ -965,6 +993,7 @@ impl<'a, N: FullNodeComponents<Types: NodeTypes<ChainSpec: EthereumHardforks>>>
             .gas_cap(self.config.rpc_gas_cap.into())
             .max_simulate_blocks(self.config.rpc_max_simulate_blocks)
             .eth_proof_window(self.config.eth_proof_window)
+            .eth_proof_max_depth(self.config.eth_proof_max_depth)
             .fee_history_cache_config(self.config.fee_history_cache)
             .proof_permits(self.config.proof_permits)
             .gas_oracle_config(self.config.gas_oracle)
@@ -13,14 +13,15 @@ use reth_downloaders::{
 use reth_evm::ConfigureEvm;
 use reth_exex::ExExManagerHandle;
 use reth_network_p2p::{
-    bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader, BlockClient,
+    bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader,
+    snap::client::SnapClient, BlockClient,
 };
 use reth_node_api::HeaderTy;
 use reth_provider::{providers::ProviderNodeTypes, ProviderFactory};
 use reth_stages::{
     prelude::DefaultStages,
-    stages::{EraImportSource, ExecutionStage},
-    Pipeline, StageSet,
+    stages::{EraImportSource, ExecutionStage, SnapSyncStage},
+    Pipeline, StageId, StageSet,
 };
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
@@ -28,8 +29,11 @@ use reth_tracing::tracing::debug;
 use tokio::sync::watch;
 
 /// Constructs a [Pipeline] that's wired to the network
+///
+/// If a [`SnapClient`] is given, the pipeline downloads the state with the [`SnapSyncStage`]
+/// instead of executing all blocks.
 #[expect(clippy::too_many_arguments)]
-pub fn build_networked_pipeline<N, Client, Evm>(
+pub fn build_networked_pipeline<N, Client, Snap, Evm>(
     config: &StageConfig,
     client: Client,
     consensus: Arc<dyn FullConsensus<N::Primitives, Error = ConsensusError>>,
@@ -42,10 +46,12 @@ pub fn build_networked_pipeline<N, Client, Evm>(
     evm_config: Evm,
     exex_manager_handle: ExExManagerHandle<N::Primitives>,
     era_import_source: Option<EraImportSource>,
+    snap_client: Option<Snap>,
 ) -> eyre::Result<Pipeline<N>>
 where
     N: ProviderNodeTypes,
     Client: BlockClient<Block = BlockTy<N>> + 'static,
+    Snap: SnapClient + 'static,
     Evm: ConfigureEvm<Primitives = N::Primitives> + 'static,
 {
     // building network downloaders using the fetch client
@@ -70,6 +76,7 @@ where
         evm_config,
         exex_manager_handle,
         era_import_source,
+        snap_client,
     )?;
 
     Ok(pipeline)
@@ -77,7 +84,7 @@ where
 
 /// Builds the [Pipeline] with the given [`ProviderFactory`] and downloaders.
 #[expect(clippy::too_many_arguments)]
-pub fn build_pipeline<N, H, B, Evm>(
+pub fn build_pipeline<N, H, B, Snap, Evm>(
     provider_factory: ProviderFactory<N>,
     stage_config: &StageConfig,
     header_downloader: H,
@@ -90,11 +97,13 @@ pub fn build_pipeline<N, H, B, Evm>(
     evm_config: Evm,
     exex_manager_handle: ExExManagerHandle<N::Primitives>,
     era_import_source: Option<EraImportSource>,
+    snap_client: Option<Snap>,
 ) -> eyre::Result<Pipeline<N>>
 where
     N: ProviderNodeTypes,
     H: HeaderDownloader<Header = HeaderTy<N>> + 'static,
     B: BodyDownloader<Block = BlockTy<N>> + 'static,
+    Snap: SnapClient + 'static,
     Evm: ConfigureEvm<Primitives = N::Primitives> + 'static,
 {
     let mut builder = Pipeline::<N>::builder();
@@ -108,29 +117,33 @@ where
 
     let prune_modes = prune_config.map(|prune| prune.segments).unwrap_or_default();
 
+    let mut stages = DefaultStages::new(
+        provider_factory.clone(),
+        tip_rx,
+        Arc::clone(&consensus),
+        header_downloader,
+        body_downloader,
+        evm_config.clone(),
+        stage_config.clone(),
+        prune_modes,
+        era_import_source,
+    )
+    .set(ExecutionStage::new(
+        evm_config,
+        consensus,
+        stage_config.execution.into(),
+        stage_config.execution_external_clean_threshold(),
+        exex_manager_handle,
+    ));
+    if let Some(snap_client) = snap_client {
+        debug!(target: "reth::cli", "Configuring pipeline to snap sync the state");
+        stages = stages.add_before(SnapSyncStage::new(snap_client), StageId::SenderRecovery);
+    }
+
     let pipeline = builder
         .with_tip_sender(tip_tx)
         .with_metrics_tx(metrics_tx)
-        .add_stages(
-            DefaultStages::new(
-                provider_factory.clone(),
-                tip_rx,
-                Arc::clone(&consensus),
-                header_downloader,
-                body_downloader,
-                evm_config.clone(),
-                stage_config.clone(),
-                prune_modes,
-                era_import_source,
-            )
-            .set(ExecutionStage::new(
-                evm_config,
-                consensus,
-                stage_config.execution.into(),
-                stage_config.execution_external_clean_threshold(),
-                exex_manager_handle,
-            )),
-        )
+        .add_stages(stages)
         .build(provider_factory, static_file_producer);
 
     Ok(pipeline)
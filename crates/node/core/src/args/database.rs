@@ -34,6 +34,11 @@ pub struct DatabaseArgs {
     /// Maximum number of readers allowed to access the database concurrently.
     #[arg(long = "db.max-readers")]
     pub max_readers: Option<u64>,
+    /// Record keccak256 preimages of hashed addresses and storage keys.
+    ///
+    /// Preimages are only recorded for state that is hashed after this is enabled.
+    #[arg(long = "db.record-preimages")]
+    pub record_preimages: bool,
 }
 
 impl DatabaseArgs {
@@ -0,0 +1,35 @@
+use clap::Args;
+
+/// Parameters for execution extensions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Args)]
+pub struct ExExArgs {
+    /// Stream the `ExEx` notifications of the node to out-of-process subscribers.
+    ///
+    /// Accepts a TCP socket address, e.g. `127.0.0.1:10000`, or the path of an IPC socket.
+    /// Subscribers acknowledge the processed blocks, which are not pruned until then.
+    #[arg(long = "exex.remote", value_name = "ADDR", verbatim_doc_comment)]
+    pub remote: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_exex_args() {
+        let args = CommandParser::<ExExArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, ExExArgs::default());
+
+        let args =
+            CommandParser::<ExExArgs>::parse_from(["reth", "--exex.remote", "/tmp/exex.ipc"]).args;
+        assert_eq!(args.remote.as_deref(), Some("/tmp/exex.ipc"));
+    }
+}
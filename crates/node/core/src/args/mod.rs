@@ -68,5 +68,9 @@ pub use ress_args::RessArgs;
 mod era;
 pub use era::{DefaultEraHost, EraArgs, EraSourceArgs};
 
+/// `ExExArgs` for configuring execution extensions.
+mod exex;
+pub use exex::ExExArgs;
+
 mod error;
 pub mod types;
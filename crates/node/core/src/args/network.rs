@@ -161,6 +161,19 @@ pub struct NetworkArgs {
     /// The policy determines which peers transactions are gossiped to.
     #[arg(long = "tx-propagation-policy", default_value_t = TransactionPropagationKind::All)]
     pub tx_propagation_policy: TransactionPropagationKind,
+
+    /// Serve the `snap/1` protocol to peers.
+    ///
+    /// Peers can then download the state of the most recent 128 blocks from this node.
+    #[arg(long = "snap.serve")]
+    pub serve_snap: bool,
+
+    /// Download the state of a recent block from peers over the `snap/1` protocol, instead of
+    /// executing all blocks from genesis.
+    ///
+    /// Only applies to the initial sync of an empty database.
+    #[arg(long = "snap.sync")]
+    pub snap_sync: bool,
 }
 
 impl NetworkArgs {
@@ -342,7 +355,9 @@ impl Default for NetworkArgs {
             max_seen_tx_history: DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             max_capacity_cache_txns_pending_fetch: DEFAULT_MAX_CAPACITY_CACHE_PENDING_FETCH,
             net_if: None,
-            tx_propagation_policy: TransactionPropagationKind::default()
+            tx_propagation_policy: TransactionPropagationKind::default(),
+            serve_snap: false,
+            snap_sync: false,
         }
     }
 }
@@ -169,6 +169,10 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-trace-filter-blocks", alias = "rpc-max-trace-filter-blocks", value_name = "COUNT", default_value_t = constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS)]
     pub rpc_max_trace_filter_blocks: u64,
 
+    /// Maximum number of blocks for `debug_getModifiedAccountsBy*` requests.
+    #[arg(long = "rpc.max-modified-accounts-blocks", value_name = "COUNT", default_value_t = constants::DEFAULT_MAX_MODIFIED_ACCOUNTS_BLOCKS)]
+    pub rpc_max_modified_accounts_blocks: u64,
+
     /// Maximum number of blocks that could be scanned per filter request. (0 = entire chain)
     #[arg(long = "rpc.max-blocks-per-filter", alias = "rpc-max-blocks-per-filter", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_BLOCKS_PER_FILTER))]
     pub rpc_max_blocks_per_filter: ZeroAsNoneU64,
@@ -215,10 +219,26 @@ pub struct RpcServerArgs {
     )]
     pub rpc_eth_proof_window: u64,
 
+    /// The maximum number of blocks behind the tip for historical proofs beyond the proof window.
+    ///
+    /// These proofs revert the state at the tip with the account and storage changesets of all
+    /// later blocks, so they are only available for blocks whose history is not pruned. The
+    /// reverts of the last requested block are kept in memory and reused by subsequent proofs.
+    #[arg(long = "rpc.eth-proof-max-depth", value_name = "BLOCKS")]
+    pub rpc_eth_proof_max_depth: Option<u64>,
+
     /// Maximum number of concurrent getproof requests.
     #[arg(long = "rpc.proof-permits", alias = "rpc-proof-permits", value_name = "COUNT", default_value_t = constants::DEFAULT_PROOF_PERMITS)]
     pub rpc_proof_permits: usize,
 
+    /// Index the blocks in which each address took part in a call.
+    ///
+    /// Required by `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter`, and used by
+    /// `trace_filter` to only re-execute the blocks that match its addresses. On first launch, the
+    /// index is built by re-executing the whole chain.
+    #[arg(long = "rpc.call-index", default_value_t = false)]
+    pub rpc_call_index: bool,
+
     /// Path to file containing disallowed addresses, json-encoded list of strings. Block
     /// validation API will reject blocks containing transactions from these addresses.
     #[arg(long = "builder.disallow", value_name = "PATH", value_parser = reth_cli_util::parsers::read_json_from_file::<HashSet<Address>>)]
@@ -357,15 +377,18 @@ impl Default for RpcServerArgs {
             rpc_max_connections: RPC_DEFAULT_MAX_CONNECTIONS.into(),
             rpc_max_tracing_requests: constants::default_max_tracing_requests(),
             rpc_max_trace_filter_blocks: constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            rpc_max_modified_accounts_blocks: constants::DEFAULT_MAX_MODIFIED_ACCOUNTS_BLOCKS,
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: constants::gas_oracle::RPC_DEFAULT_GAS_CAP,
             rpc_tx_fee_cap: constants::DEFAULT_TX_FEE_CAP_WEI,
             rpc_max_simulate_blocks: constants::DEFAULT_MAX_SIMULATE_BLOCKS,
             rpc_eth_proof_window: constants::DEFAULT_ETH_PROOF_WINDOW,
+            rpc_eth_proof_max_depth: None,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
             rpc_proof_permits: constants::DEFAULT_PROOF_PERMITS,
+            rpc_call_index: false,
             builder_disallow: Default::default(),
         }
     }
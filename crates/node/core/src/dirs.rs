@@ -351,6 +351,21 @@ impl<D> ChainPath<D> {
         self.data_dir().join("invalid_block_hooks")
     }
 
+    /// Returns the path to the directory of blocks rejected by the engine for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/bad_blocks`
+    pub fn bad_blocks(&self) -> PathBuf {
+        self.data_dir().join("bad_blocks")
+    }
+
+    /// Returns the path to the directory of standard JSON traces written by the `debug` namespace
+    /// for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/traces`
+    pub fn traces(&self) -> PathBuf {
+        self.data_dir().join("traces")
+    }
+
     /// Returns the path to the ExEx WAL directory for this chain.
     pub fn exex_wal(&self) -> PathBuf {
         self.data_dir().join("exex/wal")
@@ -28,7 +28,7 @@ use reth_rpc_eth_api::{
     EthApiTypes, FromEvmError, FullEthApiServer, RpcConvert, RpcConverter, RpcNodeCore,
     RpcNodeCoreExt, RpcTypes, SignableTxRequest,
 };
-use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, GasPriceOracle};
+use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, GasPriceOracle, HistoricalProofs};
 use reth_storage_api::{ProviderHeader, ProviderTx};
 use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
@@ -222,6 +222,11 @@ where
     fn max_proof_window(&self) -> u64 {
         self.inner.eth_api.eth_proof_window()
     }
+
+    #[inline]
+    fn historical_proofs(&self) -> Option<&HistoricalProofs> {
+        self.inner.eth_api.historical_proofs()
+    }
 }
 
 impl<N, Rpc> EthFees for OpEthApi<N, Rpc>
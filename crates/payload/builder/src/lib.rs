@@ -104,7 +104,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 mod metrics;
+mod miner;
 mod service;
+mod state_changes;
 mod traits;
 
 pub mod noop;
@@ -113,11 +115,13 @@ pub mod noop;
 pub mod test_utils;
 
 pub use alloy_rpc_types::engine::PayloadId;
+pub use miner::MinerSettings;
 pub use reth_payload_builder_primitives::PayloadBuilderError;
 pub use reth_payload_primitives::PayloadKind;
 pub use service::{
     PayloadBuilderHandle, PayloadBuilderService, PayloadServiceCommand, PayloadStore,
 };
+pub use state_changes::{AccountChanges, PendingStateChanges, StateChanges};
 pub use traits::{KeepPayloadJobAlive, PayloadJob, PayloadJobGenerator};
 
 // re-export the Ethereum engine primitives for convenience
@@ -0,0 +1,61 @@
+//! Block building parameters that can be adjusted while the node is running.
+
+use alloy_primitives::Bytes;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Block building parameters that override the configured ones of payload builders.
+///
+/// These are set through the `miner` namespace, so that operators can adjust how blocks are built
+/// without restarting the node. Unset parameters fall back to the builder's configuration.
+#[derive(Debug, Clone, Default)]
+pub struct MinerSettings {
+    inner: Arc<RwLock<MinerSettingsInner>>,
+}
+
+#[derive(Debug, Default)]
+struct MinerSettingsInner {
+    extra_data: Option<Bytes>,
+    gas_limit: Option<u64>,
+    min_priority_fee: Option<u128>,
+}
+
+impl MinerSettings {
+    /// Returns the extra data to include in built blocks, if set.
+    pub fn extra_data(&self) -> Option<Bytes> {
+        self.inner.read().extra_data.clone()
+    }
+
+    /// Sets the extra data to include in built blocks.
+    pub fn set_extra_data(&self, extra_data: Bytes) {
+        self.inner.write().extra_data = Some(extra_data);
+    }
+
+    /// Returns the gas limit that built blocks should move towards, if set.
+    pub fn gas_limit(&self) -> Option<u64> {
+        self.inner.read().gas_limit
+    }
+
+    /// Sets the gas limit that built blocks should move towards.
+    pub fn set_gas_limit(&self, gas_limit: u64) {
+        self.inner.write().gas_limit = Some(gas_limit);
+    }
+
+    /// Returns the minimum priority fee of transactions to include in built blocks, if set.
+    pub fn min_priority_fee(&self) -> Option<u128> {
+        self.inner.read().min_priority_fee
+    }
+
+    /// Sets the minimum priority fee of transactions to include in built blocks.
+    pub fn set_min_priority_fee(&self, min_priority_fee: u128) {
+        self.inner.write().min_priority_fee = Some(min_priority_fee);
+    }
+}
+
+impl PartialEq for MinerSettings {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for MinerSettings {}
@@ -0,0 +1,63 @@
+//! Irregular state changes that are applied by locally built payloads.
+
+use alloy_primitives::{
+    map::{AddressMap, B256Map},
+    Bytes, B256, U256,
+};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Changes to the state of a single account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountChanges {
+    /// The new balance of the account.
+    pub balance: Option<U256>,
+    /// The new nonce of the account.
+    pub nonce: Option<u64>,
+    /// The new code of the account.
+    pub code: Option<Bytes>,
+    /// The storage slots to overwrite.
+    pub storage: B256Map<B256>,
+}
+
+/// State changes keyed by the changed account.
+pub type StateChanges = AddressMap<AccountChanges>;
+
+/// State changes that payload builders apply at the start of a payload that is built on top of a
+/// given parent block.
+///
+/// This is how the local miner turns state manipulations, like `anvil_setBalance`, into regular
+/// blocks: it registers the changes for its current head and builds a block on top of it, which
+/// then contains the changes as an irregular state change.
+///
+/// Since such a block can't be re-executed from its transactions, it must be inserted into the
+/// engine as an already executed block.
+#[derive(Debug, Clone, Default)]
+pub struct PendingStateChanges {
+    changes: Arc<RwLock<B256Map<StateChanges>>>,
+}
+
+impl PendingStateChanges {
+    /// Returns the state changes for payloads built on top of the given parent block.
+    pub fn get(&self, parent: B256) -> Option<StateChanges> {
+        self.changes.read().get(&parent).cloned()
+    }
+
+    /// Sets the state changes for payloads built on top of the given parent block.
+    pub fn insert(&self, parent: B256, changes: StateChanges) {
+        self.changes.write().insert(parent, changes);
+    }
+
+    /// Removes the state changes for payloads built on top of the given parent block.
+    pub fn remove(&self, parent: B256) -> Option<StateChanges> {
+        self.changes.write().remove(&parent)
+    }
+}
+
+impl PartialEq for PendingStateChanges {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.changes, &other.changes)
+    }
+}
+
+impl Eq for PendingStateChanges {}
@@ -4,8 +4,9 @@ use alloy_primitives::{
 };
 use reth_primitives_traits::{Account, Bytecode};
 use reth_storage_api::{
-    AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider, StateProofProvider,
-    StateProvider, StateRootProvider, StorageRootProvider,
+    AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider,
+    HashedStateRangeProvider, StateProofProvider, StateProvider, StateRootProvider,
+    StorageRootProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
@@ -93,6 +94,35 @@ impl StateRootProvider for StateProviderTest {
     }
 }
 
+impl HashedStateRangeProvider for StateProviderTest {
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        unimplemented!("hashed state ranges are not supported")
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        unimplemented!("hashed state ranges are not supported")
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        unimplemented!("hashed state ranges are not supported")
+    }
+}
+
 impl StorageRootProvider for StateProviderTest {
     fn storage_root(
         &self,
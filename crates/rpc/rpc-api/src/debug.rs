@@ -3,12 +3,142 @@ use alloy_genesis::ChainConfig;
 use alloy_json_rpc::RpcObject;
 use alloy_primitives::{Address, Bytes, B256};
 use alloy_rpc_types_debug::ExecutionWitness;
-use alloy_rpc_types_eth::{Block, Bundle, StateContext};
+use alloy_rpc_types_eth::{Bundle, StateContext};
 use alloy_rpc_types_trace::geth::{
     BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, TraceResult,
 };
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_db_api::database_metrics::DatabaseStats;
+use reth_static_file_types::StaticFileStats;
 use reth_trie_common::{updates::TrieUpdates, HashedPostState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Options for the `debug_standardTrace*ToFile` methods.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StdTraceConfig {
+    /// Include the memory in each step of the trace.
+    #[serde(default)]
+    pub enable_memory: bool,
+    /// Only trace the transaction with the given hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<B256>,
+}
+
+/// A block rejected by the engine, as returned by `debug_getBadBlocks`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BadBlockResult {
+    /// Hash of the rejected block.
+    pub hash: B256,
+    /// The rejected block, with full transaction objects.
+    pub block: serde_json::Value,
+    /// The RLP encoded block.
+    pub rlp: Bytes,
+}
+
+/// Options for the `debug_intermediateRoots` method.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntermediateRootsConfig {
+    /// Stop after the transaction with the given hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<B256>,
+}
+
+/// Result of `debug_storageRangeAt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots of the page, keyed by the hashed slot.
+    pub storage: BTreeMap<B256, StorageRangeEntry>,
+    /// The hashed slot to continue from, `None` if the page includes the last slot.
+    pub next_key: Option<B256>,
+}
+
+/// A single storage slot returned by `debug_storageRangeAt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageRangeEntry {
+    /// The preimage of the hashed slot, if known.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: B256,
+}
+
+/// Result of `debug_chaindbProperty`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChaindbProperty {
+    /// Page statistics of the database and its tables, returned for `mdbx.stats`.
+    Database(DatabaseStats),
+    /// Statistics of all static files, returned for `staticfiles.stats`.
+    StaticFiles(Vec<StaticFileStats>),
+}
+
+/// Allocator statistics returned by `debug_memStats`, in bytes.
+///
+/// See the `stats.*` entries of the [jemalloc documentation](https://jemalloc.net/jemalloc.3.html)
+/// for the meaning of each field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemStats {
+    /// Bytes allocated by the application.
+    pub allocated: u64,
+    /// Bytes in active pages allocated by the application.
+    pub active: u64,
+    /// Bytes dedicated to allocator metadata.
+    pub metadata: u64,
+    /// Bytes in physically resident data pages mapped by the allocator.
+    pub resident: u64,
+    /// Bytes in active extents mapped by the allocator.
+    pub mapped: u64,
+    /// Bytes in virtual memory mappings that were retained rather than returned to the OS.
+    pub retained: u64,
+}
+
+/// Result of `debug_accountRange`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRangeResult {
+    /// The state root of the block.
+    pub root: B256,
+    /// The accounts of the page, keyed by address if the preimage of the hashed address is known
+    /// and by `pre(<hashed address>)` otherwise.
+    pub accounts: BTreeMap<String, DumpAccount>,
+    /// The hashed address to continue from, `None` if the page includes the last account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<B256>,
+}
+
+/// An account returned by `debug_accountRange`.
+///
+/// The encoding of the fields follows geth's state dump format.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpAccount {
+    /// Balance of the account, as a decimal string.
+    pub balance: String,
+    /// Nonce of the account.
+    pub nonce: u64,
+    /// Storage root of the account.
+    pub root: B256,
+    /// Hash of the account's bytecode.
+    pub code_hash: B256,
+    /// The account's bytecode, unless omitted by the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Storage of the account keyed by slot, or by hashed slot if the preimage is unknown, unless
+    /// omitted by the request.
+    ///
+    /// Values are hex encoded without leading zero bytes and without `0x` prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, String>>,
+    /// The address of the account, if the preimage of the hashed address is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The hashed address of the account.
+    #[serde(rename = "key")]
+    pub hashed_address: B256,
+}
 
 /// Debug rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
@@ -37,8 +167,10 @@ pub trait DebugApi<TxReq: RpcObject> {
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>>;
 
     /// Returns an array of recent bad blocks that the client has seen on the network.
+    ///
+    /// Each entry contains the hash of the block, the block itself and its RLP encoding.
     #[method(name = "getBadBlocks")]
-    async fn bad_blocks(&self) -> RpcResult<Vec<Block>>;
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlockResult>>;
 
     /// Returns the structured logs created during the execution of EVM between two blocks
     /// (excluding start) as a JSON object.
@@ -176,7 +308,7 @@ pub trait DebugApi<TxReq: RpcObject> {
         nocode: bool,
         nostorage: bool,
         incompletes: bool,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<AccountRangeResult>;
 
     /// Turns on block profiling for the given duration and writes profile data to disk. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
@@ -193,9 +325,12 @@ pub trait DebugApi<TxReq: RpcObject> {
     #[method(name = "chainConfig")]
     async fn debug_chain_config(&self) -> RpcResult<ChainConfig>;
 
-    /// Returns leveldb properties of the key-value database.
+    /// Returns statistics of the database, like `reth db stats`.
+    ///
+    /// Supported properties are `mdbx.stats` for the page statistics of the MDBX tables and
+    /// `staticfiles.stats` for the block and transaction ranges of all static files.
     #[method(name = "chaindbProperty")]
-    async fn debug_chaindb_property(&self, property: String) -> RpcResult<()>;
+    async fn debug_chaindb_property(&self, property: String) -> RpcResult<ChaindbProperty>;
 
     /// Returns the code associated with a given hash at the specified block ID.
     /// If no block ID is provided, it defaults to the latest block.
@@ -206,23 +341,33 @@ pub trait DebugApi<TxReq: RpcObject> {
         block_id: Option<BlockId>,
     ) -> RpcResult<Option<Bytes>>;
 
-    /// Turns on CPU profiling for the given duration and writes profile data to disk.
+    /// Turns on CPU profiling for the given duration and writes a pprof profile to disk.
     #[method(name = "cpuProfile")]
     async fn debug_cpu_profile(&self, file: String, seconds: u64) -> RpcResult<()>;
 
-    /// Retrieves an ancient binary blob from the freezer. The freezer is a collection of
-    /// append-only immutable files. The first argument `kind` specifies which table to look up data
-    /// from. The list of all table kinds are as follows:
+    /// Retrieves the raw rows of a block from the static files. The first argument `kind`
+    /// specifies the static file segment to look up data from: `headers`, `transactions`,
+    /// `receipts` or `blockmeta`.
+    ///
+    /// Each row is returned as the list of its raw columns. For the `transactions` and `receipts`
+    /// segments, the rows of all transactions of the block are returned.
     #[method(name = "dbAncient")]
-    async fn debug_db_ancient(&self, kind: String, number: u64) -> RpcResult<()>;
+    async fn debug_db_ancient(&self, kind: String, number: u64) -> RpcResult<Vec<Vec<Bytes>>>;
 
-    /// Returns the number of ancient items in the ancient store.
+    /// Returns the number of blocks in the static files.
     #[method(name = "dbAncients")]
-    async fn debug_db_ancients(&self) -> RpcResult<()>;
+    async fn debug_db_ancients(&self) -> RpcResult<u64>;
 
-    /// Returns the raw value of a key stored in the database.
+    /// Returns the raw value stored under the encoded key in the given database table.
+    ///
+    /// For dupsort tables, the optional encoded subkey selects the duplicate to return.
     #[method(name = "dbGet")]
-    async fn debug_db_get(&self, key: String) -> RpcResult<()>;
+    async fn debug_db_get(
+        &self,
+        table: String,
+        key: Bytes,
+        subkey: Option<Bytes>,
+    ) -> RpcResult<Option<Bytes>>;
 
     /// Retrieves the state that corresponds to the block number and returns a list of accounts
     /// (including storage and code).
@@ -253,24 +398,25 @@ pub trait DebugApi<TxReq: RpcObject> {
         to: BlockNumberOrTag,
     ) -> RpcResult<()>;
 
-    /// Returns all accounts that have changed between the two blocks specified. A change is defined
-    /// as a difference in nonce, balance, code hash, or storage hash. With one parameter, returns
-    /// the list of accounts modified in the specified block.
+    /// Returns all accounts that have changed in the blocks between `start_hash` and `end_hash`,
+    /// both inclusive. A change is defined as a difference in nonce, balance or code hash. With one
+    /// parameter, returns the list of accounts modified in the specified block.
     #[method(name = "getModifiedAccountsByHash")]
     async fn debug_get_modified_accounts_by_hash(
         &self,
         start_hash: B256,
-        end_hash: B256,
-    ) -> RpcResult<()>;
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>>;
 
-    /// Returns all accounts that have changed between the two blocks specified. A change is defined
-    /// as a difference in nonce, balance, code hash or storage hash.
+    /// Returns all accounts that have changed in the blocks between `start_number` and
+    /// `end_number`, both inclusive. A change is defined as a difference in nonce, balance or code
+    /// hash. With one parameter, returns the list of accounts modified in the specified block.
     #[method(name = "getModifiedAccountsByNumber")]
     async fn debug_get_modified_accounts_by_number(
         &self,
         start_number: u64,
-        end_number: u64,
-    ) -> RpcResult<()>;
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Turns on Go runtime tracing for the given duration and writes trace data to disk.
     #[method(name = "goTrace")]
@@ -278,16 +424,20 @@ pub trait DebugApi<TxReq: RpcObject> {
 
     /// Executes a block (bad- or canon- or side-), and returns a list of intermediate roots: the
     /// stateroot after each transaction.
+    ///
+    /// If a transaction hash is configured, execution stops after that transaction.
     #[method(name = "intermediateRoots")]
     async fn debug_intermediate_roots(
         &self,
         block_hash: B256,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        opts: Option<IntermediateRootsConfig>,
+    ) -> RpcResult<Vec<B256>>;
 
     /// Returns detailed runtime memory statistics.
+    ///
+    /// Only available if the node uses jemalloc as its allocator.
     #[method(name = "memStats")]
-    async fn debug_mem_stats(&self) -> RpcResult<()>;
+    async fn debug_mem_stats(&self) -> RpcResult<MemStats>;
 
     /// Turns on mutex profiling for `nsec` seconds and writes profile data to file. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
@@ -296,8 +446,10 @@ pub trait DebugApi<TxReq: RpcObject> {
     async fn debug_mutex_profile(&self, file: String, nsec: u64) -> RpcResult<()>;
 
     /// Returns the preimage for a sha3 hash, if known.
+    ///
+    /// Preimages of hashed addresses and storage keys are only recorded if enabled on the node.
     #[method(name = "preimage")]
-    async fn debug_preimage(&self, hash: B256) -> RpcResult<()>;
+    async fn debug_preimage(&self, hash: B256) -> RpcResult<Bytes>;
 
     /// Retrieves a block and returns its pretty printed form.
     #[method(name = "printBlock")]
@@ -336,24 +488,26 @@ pub trait DebugApi<TxReq: RpcObject> {
     #[method(name = "stacks")]
     async fn debug_stacks(&self) -> RpcResult<()>;
 
-    /// Used to obtain info about a block.
+    /// This method is similar to `debug_standardTraceBlockToFile`, but can be used to obtain info
+    /// about a block which has been rejected as invalid (for some reason).
     #[method(name = "standardTraceBadBlockToFile")]
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        block: BlockNumberOrTag,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        block_hash: B256,
+        opts: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>>;
 
-    /// This method is similar to `debug_standardTraceBlockToFile`, but can be used to obtain info
-    /// about a block which has been rejected as invalid (for some reason).
+    /// Replays the block with the given hash and writes an EIP-3155 trace of each transaction to
+    /// a separate file. Returns the paths of the written files.
     #[method(name = "standardTraceBlockToFile")]
     async fn debug_standard_trace_block_to_file(
         &self,
-        block: BlockNumberOrTag,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        block_hash: B256,
+        opts: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>>;
 
-    /// Turns on CPU profiling indefinitely, writing to the given file.
+    /// Turns on CPU profiling indefinitely, writing a pprof profile to the given file once
+    /// `debug_stopCPUProfile` is called.
     #[method(name = "startCPUProfile")]
     async fn debug_start_cpu_profile(&self, file: String) -> RpcResult<()>;
 
@@ -389,7 +543,7 @@ pub trait DebugApi<TxReq: RpcObject> {
         contract_address: Address,
         key_start: B256,
         max_result: u64,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<StorageRangeResult>;
 
     /// Returns the structured logs created during the execution of EVM against a block pulled
     /// from the pool of bad ones and returns them as a JSON object. For the second parameter see
@@ -398,15 +552,16 @@ pub trait DebugApi<TxReq: RpcObject> {
     async fn debug_trace_bad_block(
         &self,
         block_hash: B256,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>>;
 
     /// Sets the logging verbosity ceiling. Log messages with level up to and including the given
     /// level will be printed.
     #[method(name = "verbosity")]
     async fn debug_verbosity(&self, level: usize) -> RpcResult<()>;
 
-    /// Sets the logging verbosity pattern.
+    /// Sets the logging verbosity pattern, given as comma-separated per-target directives like
+    /// `engine::tree=trace`.
     #[method(name = "vmodule")]
     async fn debug_vmodule(&self, pattern: String) -> RpcResult<()>;
 
@@ -414,7 +569,10 @@ pub trait DebugApi<TxReq: RpcObject> {
     #[method(name = "writeBlockProfile")]
     async fn debug_write_block_profile(&self, file: String) -> RpcResult<()>;
 
-    /// Writes an allocation profile to the given file.
+    /// Writes a jemalloc heap profile to the given file.
+    ///
+    /// Requires heap profiling to be enabled, e.g. by running a node built with the
+    /// `jemalloc-prof` feature with `MALLOC_CONF=prof:true`.
     #[method(name = "writeMemProfile")]
     async fn debug_write_mem_profile(&self, file: String) -> RpcResult<()>;
 
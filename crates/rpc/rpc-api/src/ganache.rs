@@ -62,7 +62,7 @@ pub trait GanacheApi {
     ///
     /// Returns the amount of seconds between the given timestamp and now.
     #[method(name = "setTime")]
-    async fn evm_set_time(&self, timestamp: u64) -> RpcResult<bool>;
+    async fn evm_set_time(&self, timestamp: u64) -> RpcResult<u64>;
 
     /// Snapshot the state of the blockchain at the current block. Takes no parameters. Returns the
     /// id of the snapshot that was created. A snapshot can only be reverted once. After a
@@ -31,6 +31,15 @@ mod txpool;
 mod validation;
 mod web3;
 
+pub use debug::{
+    AccountRangeResult, BadBlockResult, ChaindbProperty, DumpAccount, IntermediateRootsConfig,
+    MemStats, StdTraceConfig, StorageRangeEntry, StorageRangeResult,
+};
+pub use reth::{
+    AccountState, AccountStateDiff, BlockStateDiff, RethSubscriptionKind, StateDiffFilter,
+    StateDiffNotification, StorageSlotDiff,
+};
+
 /// re-export of all server traits
 pub use servers::*;
 
@@ -38,13 +47,16 @@ pub use servers::*;
 pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
+        anvil::AnvilApiServer,
         debug::{DebugApiServer, DebugExecutionWitnessApiServer},
         engine::{EngineApiServer, EngineEthApiServer, IntoEngineApiRpcModule},
+        ganache::GanacheApiServer,
+        hardhat::HardhatApiServer,
         mev::{MevFullApiServer, MevSimApiServer},
         miner::MinerApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
-        reth::RethApiServer,
+        reth::{RethApiServer, RethSimulationApiServer},
         rpc::RpcApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -75,7 +87,7 @@ pub mod clients {
         miner::MinerApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
-        reth::RethApiClient,
+        reth::{RethApiClient, RethSimulationApiClient},
         rpc::RpcApiServer,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
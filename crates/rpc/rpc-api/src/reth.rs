@@ -1,11 +1,92 @@
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, U256};
+use alloy_json_rpc::RpcObject;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types_eth::simulate::{SimulatePayload, SimulatedBlock};
+use alloy_serde::JsonStorageKey;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 // Required for the subscription attribute below
 use reth_chain_state as _;
 
+/// State changes of a single block, as returned by `reth_getStateDiff`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStateDiff {
+    /// Number of the block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: u64,
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// The accounts changed by the block, by address.
+    pub accounts: BTreeMap<Address, AccountStateDiff>,
+}
+
+/// Changes of a single account in a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountStateDiff {
+    /// The account before the block, `None` if it did not exist.
+    pub before: Option<AccountState>,
+    /// The account after the block, `None` if it does not exist anymore.
+    pub after: Option<AccountState>,
+    /// The changed storage slots of the account, by slot.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, StorageSlotDiff>,
+}
+
+/// The fields of an account tracked by [`AccountStateDiff`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountState {
+    /// Nonce of the account.
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    /// Balance of the account.
+    pub balance: U256,
+    /// Hash of the account's bytecode.
+    pub code_hash: B256,
+}
+
+/// Change of a single storage slot in a block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageSlotDiff {
+    /// The value of the slot before the block.
+    pub before: B256,
+    /// The value of the slot after the block.
+    pub after: B256,
+}
+
+/// Subscription kinds of `reth_subscribe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RethSubscriptionKind {
+    /// State changes of new canonical blocks, see [`StateDiffNotification`].
+    StateDiffs,
+}
+
+/// Filter of `stateDiffs` subscriptions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiffFilter {
+    /// Only the changes of these accounts are included. All changes are included if empty.
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+}
+
+/// The state changes of a block, as pushed to `stateDiffs` subscriptions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffNotification {
+    /// Whether the block was removed from the canonical chain by a reorg.
+    ///
+    /// The changes of a removed block are undone, so the state goes back from `after` to `before`.
+    /// Removed blocks are sent from the highest to the lowest block.
+    pub removed: bool,
+    /// The state changes of the block.
+    #[serde(flatten)]
+    pub diff: BlockStateDiff,
+}
+
 /// Reth API namespace for reth-specific methods
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
@@ -17,6 +98,19 @@ pub trait RethApi {
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
 
+    /// Returns all account and storage changes of a block.
+    #[method(name = "getStateDiff")]
+    async fn reth_get_state_diff(&self, block_id: BlockId) -> RpcResult<BlockStateDiff>;
+
+    /// Returns all account and storage changes of the blocks between `start_number` and
+    /// `end_number`, both inclusive, one entry per block.
+    #[method(name = "getStateDiffRange")]
+    async fn reth_get_state_diff_range(
+        &self,
+        start_number: u64,
+        end_number: u64,
+    ) -> RpcResult<Vec<BlockStateDiff>>;
+
     /// Subscribe to json `ChainNotifications`
     #[subscription(
         name = "subscribeChainNotifications",
@@ -24,4 +118,63 @@ pub trait RethApi {
         item = reth_chain_state::CanonStateNotification
     )]
     async fn reth_subscribe_chain_notifications(&self) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Subscribe to typed notifications of the given kind.
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = StateDiffNotification
+    )]
+    async fn reth_subscribe(
+        &self,
+        kind: RethSubscriptionKind,
+        filter: Option<StateDiffFilter>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+}
+
+/// Reth API namespace for simulation sessions.
+///
+/// A session holds the state of successive `eth_simulateV1` requests on top of a base block, so
+/// that each request continues from the blocks simulated by the previous ones. Sessions expire
+/// when they are not used for a while.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
+pub trait RethSimulationApi<TxReq: RpcObject, B: RpcObject> {
+    /// Creates a new simulation session on top of the given block and returns its id.
+    ///
+    /// Defaults to the latest block.
+    #[method(name = "newSimulation")]
+    async fn reth_new_simulation(&self, block_id: Option<BlockId>) -> RpcResult<String>;
+
+    /// Simulates the given blocks on top of the blocks already simulated in the session, like
+    /// `eth_simulateV1`, and keeps their state changes.
+    #[method(name = "simulate")]
+    async fn reth_simulate(
+        &self,
+        id: String,
+        payload: SimulatePayload<TxReq>,
+    ) -> RpcResult<Vec<SimulatedBlock<B>>>;
+
+    /// Executes a message call on top of the blocks simulated in the session, like `eth_call`.
+    #[method(name = "simulationCall")]
+    async fn reth_simulation_call(&self, id: String, request: TxReq) -> RpcResult<Bytes>;
+
+    /// Returns the balance of the account after the blocks simulated in the session.
+    #[method(name = "simulationGetBalance")]
+    async fn reth_simulation_get_balance(&self, id: String, address: Address) -> RpcResult<U256>;
+
+    /// Returns the value of the storage slot after the blocks simulated in the session.
+    #[method(name = "simulationGetStorageAt")]
+    async fn reth_simulation_get_storage_at(
+        &self,
+        id: String,
+        address: Address,
+        index: JsonStorageKey,
+    ) -> RpcResult<B256>;
+
+    /// Drops the simulation session.
+    ///
+    /// Returns `true` if the session existed.
+    #[method(name = "dropSimulation")]
+    async fn reth_drop_simulation(&self, id: String) -> RpcResult<bool>;
 }
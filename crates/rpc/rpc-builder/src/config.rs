@@ -1,6 +1,6 @@
 use jsonrpsee::server::ServerConfigBuilder;
 use reth_node_core::{args::RpcServerArgs, utils::get_or_create_jwt_secret_from_path};
-use reth_rpc::ValidationApiConfig;
+use reth_rpc::{DebugApiConfig, ValidationApiConfig};
 use reth_rpc_eth_types::{EthConfig, EthStateCacheConfig, GasPriceOracleConfig};
 use reth_rpc_layer::{JwtError, JwtSecret};
 use reth_rpc_server_types::RpcModuleSelection;
@@ -30,6 +30,9 @@ pub trait RethRpcServerConfig {
     /// The configured ethereum RPC settings.
     fn flashbots_config(&self) -> ValidationApiConfig;
 
+    /// The configured `debug` namespace settings.
+    fn debug_config(&self) -> DebugApiConfig;
+
     /// Returns state cache configuration.
     fn state_cache_config(&self) -> EthStateCacheConfig;
 
@@ -98,6 +101,7 @@ impl RethRpcServerConfig for RpcServerArgs {
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
             .eth_proof_window(self.rpc_eth_proof_window)
+            .eth_proof_max_depth(self.rpc_eth_proof_max_depth)
             .rpc_gas_cap(self.rpc_gas_cap)
             .rpc_max_simulate_blocks(self.rpc_max_simulate_blocks)
             .state_cache(self.state_cache_config())
@@ -112,6 +116,13 @@ impl RethRpcServerConfig for RpcServerArgs {
         }
     }
 
+    fn debug_config(&self) -> DebugApiConfig {
+        DebugApiConfig {
+            max_modified_accounts_blocks: self.rpc_max_modified_accounts_blocks,
+            ..Default::default()
+        }
+    }
+
     fn state_cache_config(&self) -> EthStateCacheConfig {
         EthStateCacheConfig {
             max_blocks: self.rpc_state_cache.max_blocks,
@@ -134,8 +145,13 @@ impl RethRpcServerConfig for RpcServerArgs {
     }
 
     fn transport_rpc_module_config(&self) -> TransportRpcModuleConfig {
-        let mut config = TransportRpcModuleConfig::default()
-            .with_config(RpcModuleConfig::new(self.eth_config(), self.flashbots_config()));
+        let mut config = TransportRpcModuleConfig::default().with_config(
+            RpcModuleConfig::builder()
+                .eth(self.eth_config())
+                .flashbots(self.flashbots_config())
+                .debug(self.debug_config())
+                .build(),
+        );
 
         if self.http {
             config = config.with_http(
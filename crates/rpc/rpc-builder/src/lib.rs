@@ -32,18 +32,22 @@ use jsonrpsee::{
 };
 use reth_chainspec::{ChainSpecProvider, EthereumHardforks};
 use reth_consensus::{ConsensusError, FullConsensus};
+use reth_engine_local::LocalMinerHandle;
+use reth_engine_primitives::{BadBlockStore, EngineSetHead};
 use reth_evm::ConfigureEvm;
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
+use reth_payload_builder::MinerSettings;
 use reth_primitives_traits::NodePrimitives;
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthApi, EthApiBuilder, EthBundle, MinerApi, NetApi,
-    OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, ValidationApiConfig, Web3Api,
+    AdminApi, AnvilApi, DebugApi, DebugApiConfig, EngineEthApi, EthApi, EthApiBuilder, EthBundle,
+    EthSimulationSessions, GanacheApi, HardhatApi, MinerApi, NetApi, OtterscanApi, RPCApi, RethApi,
+    TraceApi, TxPoolApi, ValidationApiConfig, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
     helpers::{
-        pending_block::PendingEnvBuilder, Call, EthApiSpec, EthTransactions, LoadPendingBlock,
-        TraceExt,
+        pending_block::PendingEnvBuilder, Call, EthApiSpec, EthCall, EthTransactions,
+        LoadPendingBlock, TraceExt,
     },
     node::RpcNodeCoreAdapter,
     EthApiServer, EthApiTypes, FullEthApiServer, RpcBlock, RpcConvert, RpcConverter, RpcHeader,
@@ -53,7 +57,7 @@ use reth_rpc_eth_types::{receipt::EthReceiptConverter, EthConfig, EthSubscriptio
 use reth_rpc_layer::{AuthLayer, Claims, CompressionLayer, JwtAuthValidator, JwtSecret};
 use reth_storage_api::{
     AccountReader, BlockReader, ChangeSetReader, FullRpcProvider, ProviderBlock,
-    StateProviderFactory,
+    StateProviderFactory, StorageChangeSetReader,
 };
 use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
@@ -62,6 +66,7 @@ use std::{
     collections::HashMap,
     fmt::Debug,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tower_http::cors::CorsLayer;
@@ -123,6 +128,14 @@ pub struct RpcModuleBuilder<N, Provider, Pool, Network, EvmConfig, Consensus> {
     evm_config: EvmConfig,
     /// The consensus implementation.
     consensus: Consensus,
+    /// Store of recently rejected blocks served by the `debug` namespace.
+    bad_blocks: Option<BadBlockStore>,
+    /// Handle to rewind the canonical chain, used by `debug_setHead`.
+    engine_set_head: Option<Arc<dyn EngineSetHead>>,
+    /// Handle to the local miner of a dev node, used by the `anvil` namespace.
+    local_miner: Option<LocalMinerHandle>,
+    /// Block building parameters that are changed by the `miner` namespace.
+    miner_settings: Option<MinerSettings>,
     /// Node data primitives.
     _primitives: PhantomData<N>,
 }
@@ -141,7 +154,43 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
         evm_config: EvmConfig,
         consensus: Consensus,
     ) -> Self {
-        Self { provider, pool, network, executor, evm_config, consensus, _primitives: PhantomData }
+        Self {
+            provider,
+            pool,
+            network,
+            executor,
+            evm_config,
+            consensus,
+            bad_blocks: None,
+            engine_set_head: None,
+            local_miner: None,
+            miner_settings: None,
+            _primitives: PhantomData,
+        }
+    }
+
+    /// Configure the [`BadBlockStore`] that is served by the `debug` namespace.
+    pub fn with_bad_block_store(mut self, bad_blocks: BadBlockStore) -> Self {
+        self.bad_blocks = Some(bad_blocks);
+        self
+    }
+
+    /// Configure the handle that `debug_setHead` uses to rewind the canonical chain.
+    pub fn with_engine_set_head(mut self, engine_set_head: Arc<dyn EngineSetHead>) -> Self {
+        self.engine_set_head = Some(engine_set_head);
+        self
+    }
+
+    /// Configure the handle to the local miner that the `anvil` namespace drives in dev mode.
+    pub fn with_local_miner(mut self, local_miner: LocalMinerHandle) -> Self {
+        self.local_miner = Some(local_miner);
+        self
+    }
+
+    /// Configure the block building parameters that the `miner` namespace changes.
+    pub fn with_miner_settings(mut self, miner_settings: MinerSettings) -> Self {
+        self.miner_settings = Some(miner_settings);
+        self
     }
 
     /// Configure the provider instance.
@@ -149,8 +198,32 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
         self,
         provider: P,
     ) -> RpcModuleBuilder<N, P, Pool, Network, EvmConfig, Consensus> {
-        let Self { pool, network, executor, evm_config, consensus, _primitives, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, evm_config, consensus, _primitives }
+        let Self {
+            pool,
+            network,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+        }
     }
 
     /// Configure the transaction pool instance.
@@ -158,8 +231,32 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
         self,
         pool: P,
     ) -> RpcModuleBuilder<N, Provider, P, Network, EvmConfig, Consensus> {
-        let Self { provider, network, executor, evm_config, consensus, _primitives, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, evm_config, consensus, _primitives }
+        let Self {
+            provider,
+            network,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+        }
     }
 
     /// Configure a [`NoopTransactionPool`] instance.
@@ -170,7 +267,19 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
     pub fn with_noop_pool(
         self,
     ) -> RpcModuleBuilder<N, Provider, NoopTransactionPool, Network, EvmConfig, Consensus> {
-        let Self { provider, executor, network, evm_config, consensus, _primitives, .. } = self;
+        let Self {
+            provider,
+            executor,
+            network,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             executor,
@@ -179,6 +288,10 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
             pool: NoopTransactionPool::default(),
             consensus,
             _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
         }
     }
 
@@ -187,8 +300,32 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
         self,
         network: Net,
     ) -> RpcModuleBuilder<N, Provider, Pool, Net, EvmConfig, Consensus> {
-        let Self { provider, pool, executor, evm_config, consensus, _primitives, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, evm_config, consensus, _primitives }
+        let Self {
+            provider,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+        }
     }
 
     /// Configure a [`NoopNetwork`] instance.
@@ -199,7 +336,19 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
     pub fn with_noop_network(
         self,
     ) -> RpcModuleBuilder<N, Provider, Pool, NoopNetwork, EvmConfig, Consensus> {
-        let Self { provider, pool, executor, evm_config, consensus, _primitives, .. } = self;
+        let Self {
+            provider,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             pool,
@@ -208,13 +357,41 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
             evm_config,
             consensus,
             _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
         }
     }
 
     /// Configure the task executor to use for additional tasks.
     pub fn with_executor(self, executor: Box<dyn TaskSpawner + 'static>) -> Self {
-        let Self { pool, network, provider, evm_config, consensus, _primitives, .. } = self;
-        Self { provider, network, pool, executor, evm_config, consensus, _primitives }
+        let Self {
+            pool,
+            network,
+            provider,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        Self {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+        }
     }
 
     /// Configure [`TokioTaskExecutor`] as the task executor to use for additional tasks.
@@ -222,7 +399,19 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
     /// This will spawn additional tasks directly via `tokio::task::spawn`, See
     /// [`TokioTaskExecutor`].
     pub fn with_tokio_executor(self) -> Self {
-        let Self { pool, network, provider, evm_config, consensus, _primitives, .. } = self;
+        let Self {
+            pool,
+            network,
+            provider,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
         Self {
             provider,
             network,
@@ -231,6 +420,10 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
             evm_config,
             consensus,
             _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
         }
     }
 
@@ -239,8 +432,32 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
         self,
         evm_config: E,
     ) -> RpcModuleBuilder<N, Provider, Pool, Network, E, Consensus> {
-        let Self { provider, pool, executor, network, consensus, _primitives, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, evm_config, consensus, _primitives }
+        let Self {
+            provider,
+            pool,
+            executor,
+            network,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+        }
     }
 
     /// Configure the consensus implementation.
@@ -248,8 +465,32 @@ impl<N, Provider, Pool, Network, EvmConfig, Consensus>
         self,
         consensus: C,
     ) -> RpcModuleBuilder<N, Provider, Pool, Network, EvmConfig, C> {
-        let Self { provider, network, pool, executor, evm_config, _primitives, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, evm_config, consensus, _primitives }
+        let Self {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            evm_config,
+            consensus,
+            _primitives,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+        }
     }
 
     /// Instantiates a new [`EthApiBuilder`] from the configured components.
@@ -309,7 +550,8 @@ where
     Provider: FullRpcProvider<Block = N::Block, Receipt = N::Receipt, Header = N::BlockHeader>
         + CanonStateSubscriptions<Primitives = N>
         + AccountReader
-        + ChangeSetReader,
+        + ChangeSetReader
+        + StorageChangeSetReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     EvmConfig: ConfigureEvm<Primitives = N> + 'static,
@@ -334,13 +576,29 @@ where
     where
         EthApi: FullEthApiServer<Provider = Provider, Pool = Pool>,
     {
-        let Self { provider, pool, network, executor, consensus, evm_config, .. } = self;
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            consensus,
+            evm_config,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
 
         let config = module_config.config.clone().unwrap_or_default();
 
         let mut registry = RpcRegistryInner::new(
             provider, pool, network, executor, consensus, config, evm_config, eth,
-        );
+        )
+        .with_bad_block_store(bad_blocks.unwrap_or_default());
+        registry.engine_set_head = engine_set_head;
+        registry.local_miner = local_miner;
+        registry.miner_settings = miner_settings;
 
         let modules = registry.create_transport_rpc_modules(module_config);
 
@@ -361,8 +619,27 @@ where
     where
         EthApi: EthApiTypes + 'static,
     {
-        let Self { provider, pool, network, executor, consensus, evm_config, .. } = self;
-        RpcRegistryInner::new(provider, pool, network, executor, consensus, config, evm_config, eth)
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            consensus,
+            evm_config,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
+        let mut registry = RpcRegistryInner::new(
+            provider, pool, network, executor, consensus, config, evm_config, eth,
+        )
+        .with_bad_block_store(bad_blocks.unwrap_or_default());
+        registry.engine_set_head = engine_set_head;
+        registry.local_miner = local_miner;
+        registry.miner_settings = miner_settings;
+        registry
     }
 
     /// Configures all [`RpcModule`]s specific to the given [`TransportRpcModuleConfig`] which can
@@ -377,7 +654,19 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, consensus, evm_config, .. } = self;
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            consensus,
+            evm_config,
+            bad_blocks,
+            engine_set_head,
+            local_miner,
+            miner_settings,
+            ..
+        } = self;
 
         if !module_config.is_empty() {
             let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
@@ -391,7 +680,11 @@ where
                 config.unwrap_or_default(),
                 evm_config,
                 eth,
-            );
+            )
+            .with_bad_block_store(bad_blocks.unwrap_or_default());
+            registry.engine_set_head = engine_set_head;
+            registry.local_miner = local_miner;
+            registry.miner_settings = miner_settings;
 
             modules.config = module_config;
             modules.http = registry.maybe_module(http.as_ref());
@@ -416,6 +709,8 @@ pub struct RpcModuleConfig {
     eth: EthConfig,
     /// `flashbots` namespace settings
     flashbots: ValidationApiConfig,
+    /// `debug` namespace settings
+    debug: DebugApiConfig,
 }
 
 // === impl RpcModuleConfig ===
@@ -428,7 +723,7 @@ impl RpcModuleConfig {
 
     /// Returns a new RPC module config given the eth namespace config
     pub const fn new(eth: EthConfig, flashbots: ValidationApiConfig) -> Self {
-        Self { eth, flashbots }
+        Self { eth, flashbots, debug: DebugApiConfig::new() }
     }
 
     /// Get a reference to the eth namespace config
@@ -440,6 +735,16 @@ impl RpcModuleConfig {
     pub const fn eth_mut(&mut self) -> &mut EthConfig {
         &mut self.eth
     }
+
+    /// Get a reference to the debug namespace config
+    pub const fn debug(&self) -> &DebugApiConfig {
+        &self.debug
+    }
+
+    /// Get a mutable reference to the debug namespace config
+    pub const fn debug_mut(&mut self) -> &mut DebugApiConfig {
+        &mut self.debug
+    }
 }
 
 /// Configures [`RpcModuleConfig`]
@@ -447,6 +752,7 @@ impl RpcModuleConfig {
 pub struct RpcModuleConfigBuilder {
     eth: Option<EthConfig>,
     flashbots: Option<ValidationApiConfig>,
+    debug: Option<DebugApiConfig>,
 }
 
 // === impl RpcModuleConfigBuilder ===
@@ -464,10 +770,20 @@ impl RpcModuleConfigBuilder {
         self
     }
 
+    /// Configures a custom debug namespace config
+    pub fn debug(mut self, debug: DebugApiConfig) -> Self {
+        self.debug = Some(debug);
+        self
+    }
+
     /// Consumes the type and creates the [`RpcModuleConfig`]
     pub fn build(self) -> RpcModuleConfig {
-        let Self { eth, flashbots } = self;
-        RpcModuleConfig { eth: eth.unwrap_or_default(), flashbots: flashbots.unwrap_or_default() }
+        let Self { eth, flashbots, debug } = self;
+        RpcModuleConfig {
+            eth: eth.unwrap_or_default(),
+            flashbots: flashbots.unwrap_or_default(),
+            debug: debug.unwrap_or_default(),
+        }
     }
 
     /// Get a reference to the eth namespace config, if any
@@ -511,6 +827,16 @@ pub struct RpcRegistryInner<
     modules: HashMap<RethRpcModule, Methods>,
     /// eth config settings
     eth_config: EthConfig,
+    /// debug config settings
+    debug_config: DebugApiConfig,
+    /// Store of recently rejected blocks served by the `debug` namespace.
+    bad_blocks: BadBlockStore,
+    /// Handle to rewind the canonical chain, used by `debug_setHead`.
+    engine_set_head: Option<Arc<dyn EngineSetHead>>,
+    /// Handle to the local miner of a dev node, used by the `anvil` namespace.
+    local_miner: Option<LocalMinerHandle>,
+    /// Block building parameters that are changed by the `miner` namespace.
+    miner_settings: Option<MinerSettings>,
 }
 
 // === impl RpcRegistryInner ===
@@ -559,7 +885,12 @@ where
             modules: Default::default(),
             blocking_pool_guard,
             eth_config: config.eth,
+            debug_config: config.debug,
             evm_config,
+            bad_blocks: BadBlockStore::default(),
+            engine_set_head: None,
+            local_miner: None,
+            miner_settings: None,
         }
     }
 }
@@ -580,6 +911,35 @@ where
         &self.eth
     }
 
+    /// Configures the [`BadBlockStore`] that is served by the `debug` namespace.
+    pub fn with_bad_block_store(mut self, bad_blocks: BadBlockStore) -> Self {
+        self.bad_blocks = bad_blocks;
+        self
+    }
+
+    /// Returns a reference to the [`BadBlockStore`] served by the `debug` namespace.
+    pub const fn bad_blocks(&self) -> &BadBlockStore {
+        &self.bad_blocks
+    }
+
+    /// Configures the handle that `debug_setHead` uses to rewind the canonical chain.
+    pub fn with_engine_set_head(mut self, engine_set_head: Arc<dyn EngineSetHead>) -> Self {
+        self.engine_set_head = Some(engine_set_head);
+        self
+    }
+
+    /// Configures the handle to the local miner that the `anvil` namespace drives in dev mode.
+    pub fn with_local_miner(mut self, local_miner: LocalMinerHandle) -> Self {
+        self.local_miner = Some(local_miner);
+        self
+    }
+
+    /// Configures the block building parameters that the `miner` namespace changes.
+    pub fn with_miner_settings(mut self, miner_settings: MinerSettings) -> Self {
+        self.miner_settings = Some(miner_settings);
+        self
+    }
+
     /// Returns a reference to the pool
     pub const fn pool(&self) -> &Pool {
         &self.pool
@@ -660,6 +1020,7 @@ where
             Transaction = N::SignedTx,
         > + AccountReader
         + ChangeSetReader
+        + StorageChangeSetReader
         + CanonStateSubscriptions,
     Network: NetworkInfo + Peers + Clone + 'static,
     EthApi: EthApiServer<
@@ -748,9 +1109,13 @@ where
     /// # Panics
     ///
     /// If called outside of the tokio runtime.
-    pub fn register_reth(&mut self) -> &mut Self {
-        let rethapi = self.reth_api();
-        self.modules.insert(RethRpcModule::Reth, rethapi.into_rpc().into());
+    pub fn register_reth(&mut self) -> &mut Self
+    where
+        EthApi: EthCall,
+    {
+        let mut module = self.reth_api().into_rpc();
+        module.merge(self.simulation_sessions().into_rpc()).expect("No conflicts");
+        self.modules.insert(RethRpcModule::Reth, module.into());
         self
     }
 
@@ -775,7 +1140,8 @@ where
             Transaction = N::SignedTx,
             Receipt = N::Receipt,
         > + AccountReader
-        + ChangeSetReader,
+        + ChangeSetReader
+        + StorageChangeSetReader,
     Network: NetworkInfo + Peers + Clone + 'static,
     EthApi: EthApiTypes,
     EvmConfig: ConfigureEvm<Primitives = N>,
@@ -808,7 +1174,13 @@ where
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn debug_api(&self) -> DebugApi<EthApi> {
-        DebugApi::new(self.eth_api().clone(), self.blocking_pool_guard.clone())
+        DebugApi::new(
+            self.eth_api().clone(),
+            self.blocking_pool_guard.clone(),
+            self.bad_blocks.clone(),
+            self.engine_set_head.clone(),
+            self.debug_config.clone(),
+        )
     }
 
     /// Instantiates `NetApi`
@@ -828,6 +1200,22 @@ where
     pub fn reth_api(&self) -> RethApi<Provider> {
         RethApi::new(self.provider.clone(), self.executor.clone())
     }
+
+    /// Instantiates `EthSimulationSessions`
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn simulation_sessions(&self) -> EthSimulationSessions<EthApi>
+    where
+        EthApi: EthCall,
+    {
+        EthSimulationSessions::new(
+            self.eth_api().clone(),
+            self.eth_config.simulation_session_ttl,
+            self.executor.clone(),
+        )
+    }
 }
 
 impl<N, Provider, Pool, Network, EthApi, EvmConfig, Consensus>
@@ -837,7 +1225,8 @@ where
     Provider: FullRpcProvider<Block = N::Block>
         + CanonStateSubscriptions<Primitives = N>
         + AccountReader
-        + ChangeSetReader,
+        + ChangeSetReader
+        + StorageChangeSetReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     EthApi: FullEthApiServer,
@@ -925,11 +1314,15 @@ where
                                 .into_rpc()
                                 .into()
                         }
-                        RethRpcModule::Debug => {
-                            DebugApi::new(eth_api.clone(), self.blocking_pool_guard.clone())
-                                .into_rpc()
-                                .into()
-                        }
+                        RethRpcModule::Debug => DebugApi::new(
+                            eth_api.clone(),
+                            self.blocking_pool_guard.clone(),
+                            self.bad_blocks.clone(),
+                            self.engine_set_head.clone(),
+                            self.debug_config.clone(),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::Eth => {
                             // merge all eth handlers
                             let mut module = eth_api.clone().into_rpc();
@@ -974,20 +1367,50 @@ where
                         .into(),
                         RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
                         RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), self.executor.clone())
-                                .into_rpc()
-                                .into()
+                            let mut module =
+                                RethApi::new(self.provider.clone(), self.executor.clone())
+                                    .into_rpc();
+                            module
+                                .merge(
+                                    EthSimulationSessions::new(
+                                        eth_api.clone(),
+                                        self.eth_config.simulation_session_ttl,
+                                        self.executor.clone(),
+                                    )
+                                    .into_rpc(),
+                                )
+                                .expect("No conflicts");
+                            module.into()
                         }
                         // only relevant for Ethereum and configured in `EthereumAddOns`
                         // implementation
                         // TODO: can we get rid of this here?
                         RethRpcModule::Flashbots => Default::default(),
-                        RethRpcModule::Miner => MinerApi::default().into_rpc().into(),
+                        RethRpcModule::Miner => {
+                            MinerApi::new(self.miner_settings.clone()).into_rpc().into()
+                        }
                         RethRpcModule::Mev => {
                             EthSimBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
                                 .into_rpc()
                                 .into()
                         }
+                        RethRpcModule::Anvil => {
+                            AnvilApi::new(eth_api.clone(), self.local_miner.clone())
+                                .into_rpc()
+                                .into()
+                        }
+                        RethRpcModule::Hardhat => HardhatApi::new(AnvilApi::new(
+                            eth_api.clone(),
+                            self.local_miner.clone(),
+                        ))
+                        .into_rpc()
+                        .into(),
+                        RethRpcModule::Evm => GanacheApi::new(AnvilApi::new(
+                            eth_api.clone(),
+                            self.local_miner.clone(),
+                        ))
+                        .into_rpc()
+                        .into(),
                     })
                     .clone()
             })
@@ -1,14 +1,20 @@
 #![allow(unreachable_pub)]
 //! Standalone http tests
 
-use crate::utils::{launch_http, launch_http_ws, launch_ws};
+use crate::utils::{launch_http, launch_http_with, launch_http_ws, launch_ws, test_rpc_builder};
+use alloy_consensus::{SignableTransaction, TxLegacy};
 use alloy_eips::{eip1898::LenientBlockNumberOrTag, BlockId, BlockNumberOrTag};
-use alloy_primitives::{hex_literal::hex, Address, Bytes, TxHash, B256, B64, U256, U64};
+use alloy_primitives::{
+    bytes, hex_literal::hex, Address, Bytes, Signature, TxHash, TxKind, B256, B64, U256, U64,
+};
 use alloy_rpc_types_eth::{
     transaction::TransactionRequest, Block, FeeHistory, Filter, Header, Index, Log,
     PendingTransactionFilterKind, SyncStatus, Transaction, TransactionReceipt,
 };
-use alloy_rpc_types_trace::filter::TraceFilter;
+use alloy_rpc_types_trace::{
+    filter::TraceFilter,
+    geth::{GethTrace, TraceResult},
+};
 use jsonrpsee::{
     core::{
         client::{ClientT, SubscriptionClientT},
@@ -18,13 +24,17 @@ use jsonrpsee::{
     rpc_params,
     types::error::ErrorCode,
 };
-use reth_ethereum_primitives::Receipt;
+use reth_engine_primitives::BadBlockStore;
+use reth_ethereum_primitives::{Block as EthBlock, BlockBody, Receipt, TransactionSigned};
 use reth_network_peers::NodeRecord;
+use reth_primitives_traits::{Block as _, RecoveredBlock};
+use reth_rpc::DebugApiConfig;
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
     DebugApiClient, EthCallBundleApiClient, EthFilterApiClient, NetApiClient, OtterscanClient,
     TraceApiClient, Web3ApiClient,
 };
+use reth_rpc_builder::RpcModuleConfig;
 use reth_rpc_server_types::RethRpcModule;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
@@ -415,6 +425,88 @@ where
     DebugApiClient::<TransactionRequest>::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::<TransactionRequest>::raw_receipts(client, block_id).await.unwrap();
     DebugApiClient::<TransactionRequest>::bad_blocks(client).await.unwrap();
+    DebugApiClient::<TransactionRequest>::debug_trace_bad_block(client, B256::default(), None)
+        .await
+        .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_standard_trace_bad_block_to_file(
+        client,
+        B256::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_storage_range_at(
+        client,
+        B256::default(),
+        0,
+        Address::default(),
+        B256::default(),
+        10,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_account_range(
+        client,
+        BlockNumberOrTag::default(),
+        Bytes::default(),
+        10,
+        false,
+        false,
+        true,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_intermediate_roots(client, B256::default(), None)
+        .await
+        .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_preimage(client, B256::default())
+        .await
+        .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_set_head(client, 0).await.unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_stop_cpu_profile(client).await.unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_mem_stats(client).await.unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_db_get(
+        client,
+        "Headers".to_string(),
+        Bytes::default(),
+        None,
+    )
+    .await
+    .unwrap();
+    DebugApiClient::<TransactionRequest>::debug_db_get(
+        client,
+        "Unknown".to_string(),
+        Bytes::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_db_ancient(client, "headers".to_string(), 0)
+        .await
+        .unwrap();
+    DebugApiClient::<TransactionRequest>::debug_db_ancient(client, "unknown".to_string(), 0)
+        .await
+        .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_db_ancients(client).await.unwrap();
+    DebugApiClient::<TransactionRequest>::debug_chaindb_property(client, "mdbx.stats".to_string())
+        .await
+        .unwrap();
+    DebugApiClient::<TransactionRequest>::debug_chaindb_property(client, "unknown".to_string())
+        .await
+        .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_get_modified_accounts_by_number(client, 0, None)
+        .await
+        .unwrap();
+    DebugApiClient::<TransactionRequest>::debug_get_modified_accounts_by_number(client, 1, Some(0))
+        .await
+        .unwrap_err();
+    DebugApiClient::<TransactionRequest>::debug_get_modified_accounts_by_hash(
+        client,
+        B256::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -541,28 +633,22 @@ where
     .err()
     .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::<Transaction, Header>::search_transactions_before(
-            client,
-            address,
-            LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
-            page_size,
-        )
-        .await
-        .err()
-        .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::<Transaction, Header>::search_transactions_after(
-            client,
-            address,
-            LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
-            page_size,
-        )
-        .await
-        .err()
-        .unwrap()
-    ));
+    OtterscanClient::<Transaction, Header>::search_transactions_before(
+        client,
+        address,
+        LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
+        page_size,
+    )
+    .await
+    .unwrap_err();
+    OtterscanClient::<Transaction, Header>::search_transactions_after(
+        client,
+        address,
+        LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
+        page_size,
+    )
+    .await
+    .unwrap_err();
     assert!(OtterscanClient::<Transaction, Header>::get_transaction_by_sender_and_nonce(
         client, sender, nonce
     )
@@ -575,6 +661,92 @@ where
         .is_none());
 }
 
+/// Returns a block with a single contract creation that executes `PUSH1 1 PUSH1 1 ADD STOP` on
+/// top of an empty state.
+fn bad_block() -> RecoveredBlock<EthBlock> {
+    let tx = TxLegacy {
+        gas_limit: 100_000,
+        to: TxKind::Create,
+        input: bytes!("600160010100"),
+        ..Default::default()
+    };
+    let tx = TransactionSigned::from(tx.into_signed(Signature::test_signature()));
+    let block = EthBlock {
+        header: alloy_consensus::Header { number: 1, gas_limit: 1_000_000, ..Default::default() },
+        body: BlockBody { transactions: vec![tx], ..Default::default() },
+    };
+    RecoveredBlock::new_sealed(block.seal_slow(), vec![Address::with_last_byte(1)])
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_bad_block_http() {
+    reth_tracing::init_test_tracing();
+
+    let trace_dir = tempfile::tempdir().unwrap();
+    let bad_blocks = BadBlockStore::default();
+    let block = bad_block();
+    bad_blocks.insert(&block, "invalid state root");
+
+    let config = RpcModuleConfig::builder()
+        .debug(DebugApiConfig {
+            trace_dir: Some(trace_dir.path().to_path_buf()),
+            ..Default::default()
+        })
+        .build();
+    let handle = launch_http_with(
+        test_rpc_builder().with_bad_block_store(bad_blocks),
+        vec![RethRpcModule::Debug],
+        config,
+    )
+    .await;
+    let client = handle.http_client().unwrap();
+
+    // the bad block is returned in the same shape as geth
+    let bad = DebugApiClient::<TransactionRequest>::bad_blocks(&client).await.unwrap();
+    assert_eq!(bad.len(), 1);
+    assert_eq!(bad[0].hash, block.hash());
+    assert_eq!(bad[0].rlp, Bytes::from(alloy_rlp::encode(block.clone_block())));
+    assert_eq!(bad[0].block["hash"], serde_json::json!(block.hash()));
+    assert_eq!(
+        bad[0].block["transactions"][0]["from"],
+        serde_json::json!(Address::with_last_byte(1))
+    );
+
+    // the block is replayed on top of its parent with the default struct logger
+    let traces =
+        DebugApiClient::<TransactionRequest>::debug_trace_bad_block(&client, block.hash(), None)
+            .await
+            .unwrap();
+    assert_eq!(traces.len(), 1);
+    let TraceResult::Success { result: GethTrace::Default(frame), .. } = &traces[0] else {
+        panic!("unexpected trace: {:?}", traces[0]);
+    };
+    let ops = frame.struct_logs.iter().map(|log| log.op.as_str()).collect::<Vec<_>>();
+    assert_eq!(ops, ["PUSH1", "PUSH1", "ADD", "STOP"]);
+
+    DebugApiClient::<TransactionRequest>::debug_trace_bad_block(&client, B256::default(), None)
+        .await
+        .unwrap_err();
+
+    // the trace of each transaction is written to the configured directory
+    let files = DebugApiClient::<TransactionRequest>::debug_standard_trace_bad_block_to_file(
+        &client,
+        block.hash(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(files.len(), 1);
+    assert!(std::path::Path::new(&files[0]).starts_with(trace_dir.path()));
+
+    let trace = std::fs::read_to_string(&files[0]).unwrap();
+    let lines =
+        trace.lines().map(|line| serde_json::from_str::<Value>(line).unwrap()).collect::<Vec<_>>();
+    let ops = lines.iter().filter_map(|line| line["opName"].as_str()).collect::<Vec<_>>();
+    assert_eq!(ops, ["PUSH1", "PUSH1", "ADD", "STOP"]);
+    assert!(lines.last().unwrap().get("gasUsed").is_some());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_filter_functions_http() {
     reth_tracing::init_test_tracing();
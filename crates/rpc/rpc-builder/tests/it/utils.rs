@@ -13,7 +13,7 @@ use reth_payload_builder::test_utils::spawn_test_payload_service;
 use reth_provider::test_utils::NoopProvider;
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerConfig, AuthServerHandle},
-    RpcModuleBuilder, RpcServerConfig, RpcServerHandle, TransportRpcModuleConfig,
+    RpcModuleBuilder, RpcModuleConfig, RpcServerConfig, RpcServerHandle, TransportRpcModuleConfig,
 };
 use reth_rpc_engine_api::{capabilities::EngineCapabilities, EngineApi};
 use reth_rpc_layer::JwtSecret;
@@ -70,6 +70,30 @@ pub async fn launch_http(modules: impl Into<RpcModuleSelection>) -> RpcServerHan
         .unwrap()
 }
 
+/// Launches a new server with http only with the given modules, built from the given builder and
+/// module config.
+pub async fn launch_http_with(
+    builder: RpcModuleBuilder<
+        EthPrimitives,
+        NoopProvider,
+        TestPool,
+        NoopNetwork,
+        EthEvmConfig,
+        NoopConsensus,
+    >,
+    modules: impl Into<RpcModuleSelection>,
+    config: RpcModuleConfig,
+) -> RpcServerHandle {
+    let eth_api = builder.bootstrap_eth_api();
+    let server =
+        builder.build(TransportRpcModuleConfig::set_http(modules).with_config(config), eth_api);
+    RpcServerConfig::http(Default::default())
+        .with_http_address(test_address())
+        .start(&server)
+        .await
+        .unwrap()
+}
+
 /// Launches a new server with ws only with the given modules
 pub async fn launch_ws(modules: impl Into<RpcModuleSelection>) -> RpcServerHandle {
     let builder = test_rpc_builder();
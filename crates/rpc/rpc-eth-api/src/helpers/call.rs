@@ -27,7 +27,7 @@ use reth_evm::{
     TxEnvFor,
 };
 use reth_node_api::BlockBody;
-use reth_primitives_traits::{Recovered, SignedTransaction};
+use reth_primitives_traits::{Recovered, SealedHeader, SignedTransaction};
 use reth_revm::{
     database::StateProviderDatabase,
     db::{CacheDB, State},
@@ -39,7 +39,7 @@ use reth_rpc_eth_types::{
     simulate::{self, EthSimulateError},
     EthApiError, RevertError, RpcInvalidTransactionError, StateCacheDb,
 };
-use reth_storage_api::{BlockIdReader, ProviderTx};
+use reth_storage_api::{BlockIdReader, ProviderHeader, ProviderTx};
 use revm::{
     context_interface::{
         result::{ExecutionResult, ResultAndState},
@@ -76,23 +76,8 @@ pub trait EthCall: EstimateCall + Call + LoadPendingBlock + LoadBlock + FullEthA
         block: Option<BlockId>,
     ) -> impl Future<Output = SimulatedBlocksResult<Self::NetworkTypes, Self::Error>> + Send {
         async move {
-            if payload.block_state_calls.len() > self.max_simulate_blocks() as usize {
-                return Err(EthApiError::InvalidParams("too many blocks.".to_string()).into())
-            }
-
             let block = block.unwrap_or_default();
 
-            let SimulatePayload {
-                block_state_calls,
-                trace_transfers,
-                validation,
-                return_full_transactions,
-            } = payload;
-
-            if block_state_calls.is_empty() {
-                return Err(EthApiError::InvalidParams(String::from("calls are empty.")).into())
-            }
-
             let base_block =
                 self.recovered_block(block).await?.ok_or(EthApiError::HeaderNotFound(block))?;
             let mut parent = base_block.sealed_header().clone();
@@ -101,114 +86,140 @@ pub trait EthCall: EstimateCall + Call + LoadPendingBlock + LoadBlock + FullEthA
             self.spawn_with_state_at_block(block, move |state| {
                 let mut db =
                     State::builder().with_database(StateProviderDatabase::new(state)).build();
-                let mut blocks: Vec<SimulatedBlock<RpcBlock<Self::NetworkTypes>>> =
-                    Vec::with_capacity(block_state_calls.len());
-                for block in block_state_calls {
-                    let mut evm_env = this
-                        .evm_config()
-                        .next_evm_env(&parent, &this.next_env_attributes(&parent)?)
-                        .map_err(RethError::other)
-                        .map_err(Self::Error::from_eth_err)?;
-
-                    // Always disable EIP-3607
-                    evm_env.cfg_env.disable_eip3607 = true;
-
-                    if !validation {
-                        // If not explicitly required, we disable nonce check <https://github.com/paradigmxyz/reth/issues/16108>
-                        evm_env.cfg_env.disable_nonce_check = true;
-                        evm_env.cfg_env.disable_base_fee = true;
-                        evm_env.block_env.basefee = 0;
-                    }
+                this.simulate_blocks(&mut db, &mut parent, payload)
+            })
+            .await
+        }
+    }
 
-                    let SimBlock { block_overrides, state_overrides, calls } = block;
-
-                    if let Some(block_overrides) = block_overrides {
-                        // ensure we don't allow uncapped gas limit per block
-                        if let Some(gas_limit_override) = block_overrides.gas_limit {
-                            if gas_limit_override > evm_env.block_env.gas_limit &&
-                                gas_limit_override > this.call_gas_limit()
-                            {
-                                return Err(
-                                    EthApiError::other(EthSimulateError::GasLimitReached).into()
-                                )
-                            }
-                        }
-                        apply_block_overrides(block_overrides, &mut db, &mut evm_env.block_env);
-                    }
-                    if let Some(state_overrides) = state_overrides {
-                        apply_state_overrides(state_overrides, &mut db)
-                            .map_err(Self::Error::from_eth_err)?;
+    /// Executes the blocks of an `eth_simulateV1` request on top of `parent`.
+    ///
+    /// The state changes of the simulated blocks are committed to `db` and `parent` is advanced to
+    /// the last simulated block, so that subsequent requests can continue from there.
+    fn simulate_blocks<DB>(
+        &self,
+        db: &mut State<DB>,
+        parent: &mut SealedHeader<ProviderHeader<Self::Provider>>,
+        payload: SimulatePayload<RpcTxReq<<Self::RpcConvert as RpcConvert>::Network>>,
+    ) -> SimulatedBlocksResult<Self::NetworkTypes, Self::Error>
+    where
+        DB: Database<Error = ProviderError> + fmt::Debug,
+    {
+        if payload.block_state_calls.len() > self.max_simulate_blocks() as usize {
+            return Err(EthApiError::InvalidParams("too many blocks.".to_string()).into())
+        }
+
+        let SimulatePayload {
+            block_state_calls,
+            trace_transfers,
+            validation,
+            return_full_transactions,
+        } = payload;
+
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("calls are empty.")).into())
+        }
+
+        let mut blocks: Vec<SimulatedBlock<RpcBlock<Self::NetworkTypes>>> =
+            Vec::with_capacity(block_state_calls.len());
+        for block in block_state_calls {
+            let mut evm_env = self
+                .evm_config()
+                .next_evm_env(parent, &self.next_env_attributes(parent)?)
+                .map_err(RethError::other)
+                .map_err(Self::Error::from_eth_err)?;
+
+            // Always disable EIP-3607
+            evm_env.cfg_env.disable_eip3607 = true;
+
+            if !validation {
+                // If not explicitly required, we disable nonce check <https://github.com/paradigmxyz/reth/issues/16108>
+                evm_env.cfg_env.disable_nonce_check = true;
+                evm_env.cfg_env.disable_base_fee = true;
+                evm_env.block_env.basefee = 0;
+            }
+
+            let SimBlock { block_overrides, state_overrides, calls } = block;
+
+            if let Some(block_overrides) = block_overrides {
+                // ensure we don't allow uncapped gas limit per block
+                if let Some(gas_limit_override) = block_overrides.gas_limit {
+                    if gas_limit_override > evm_env.block_env.gas_limit &&
+                        gas_limit_override > self.call_gas_limit()
+                    {
+                        return Err(EthApiError::other(EthSimulateError::GasLimitReached).into())
                     }
+                }
+                apply_block_overrides(block_overrides, db, &mut evm_env.block_env);
+            }
+            if let Some(state_overrides) = state_overrides {
+                apply_state_overrides(state_overrides, db).map_err(Self::Error::from_eth_err)?;
+            }
 
-                    let block_gas_limit = evm_env.block_env.gas_limit;
-                    let chain_id = evm_env.cfg_env.chain_id;
+            let block_gas_limit = evm_env.block_env.gas_limit;
+            let chain_id = evm_env.cfg_env.chain_id;
 
-                    let default_gas_limit = {
-                        let total_specified_gas =
-                            calls.iter().filter_map(|tx| tx.as_ref().gas_limit()).sum::<u64>();
-                        let txs_without_gas_limit =
-                            calls.iter().filter(|tx| tx.as_ref().gas_limit().is_none()).count();
+            let default_gas_limit = {
+                let total_specified_gas =
+                    calls.iter().filter_map(|tx| tx.as_ref().gas_limit()).sum::<u64>();
+                let txs_without_gas_limit =
+                    calls.iter().filter(|tx| tx.as_ref().gas_limit().is_none()).count();
 
-                        if total_specified_gas > block_gas_limit {
-                            return Err(EthApiError::Other(Box::new(
-                                EthSimulateError::BlockGasLimitExceeded,
-                            ))
-                            .into())
-                        }
+                if total_specified_gas > block_gas_limit {
+                    return Err(EthApiError::Other(Box::new(
+                        EthSimulateError::BlockGasLimitExceeded,
+                    ))
+                    .into())
+                }
 
-                        if txs_without_gas_limit > 0 {
-                            (block_gas_limit - total_specified_gas) / txs_without_gas_limit as u64
-                        } else {
-                            0
-                        }
-                    };
-
-                    let ctx = this
-                        .evm_config()
-                        .context_for_next_block(&parent, this.next_env_attributes(&parent)?);
-                    let (result, results) = if trace_transfers {
-                        // prepare inspector to capture transfer inside the evm so they are recorded
-                        // and included in logs
-                        let inspector = TransferInspector::new(false).with_logs(true);
-                        let evm = this
-                            .evm_config()
-                            .evm_with_env_and_inspector(&mut db, evm_env, inspector);
-                        let builder = this.evm_config().create_block_builder(evm, &parent, ctx);
-                        simulate::execute_transactions(
-                            builder,
-                            calls,
-                            default_gas_limit,
-                            chain_id,
-                            this.tx_resp_builder(),
-                        )?
-                    } else {
-                        let evm = this.evm_config().evm_with_env(&mut db, evm_env);
-                        let builder = this.evm_config().create_block_builder(evm, &parent, ctx);
-                        simulate::execute_transactions(
-                            builder,
-                            calls,
-                            default_gas_limit,
-                            chain_id,
-                            this.tx_resp_builder(),
-                        )?
-                    };
-
-                    parent = result.block.clone_sealed_header();
-
-                    let block = simulate::build_simulated_block(
-                        result.block,
-                        results,
-                        return_full_transactions.into(),
-                        this.tx_resp_builder(),
-                    )?;
-
-                    blocks.push(block);
+                if txs_without_gas_limit > 0 {
+                    (block_gas_limit - total_specified_gas) / txs_without_gas_limit as u64
+                } else {
+                    0
                 }
+            };
 
-                Ok(blocks)
-            })
-            .await
+            let ctx =
+                self.evm_config().context_for_next_block(parent, self.next_env_attributes(parent)?);
+            let (result, results) = if trace_transfers {
+                // prepare inspector to capture transfer inside the evm so they are recorded
+                // and included in logs
+                let inspector = TransferInspector::new(false).with_logs(true);
+                let evm =
+                    self.evm_config().evm_with_env_and_inspector(&mut *db, evm_env, inspector);
+                let builder = self.evm_config().create_block_builder(evm, parent, ctx);
+                simulate::execute_transactions(
+                    builder,
+                    calls,
+                    default_gas_limit,
+                    chain_id,
+                    self.tx_resp_builder(),
+                )?
+            } else {
+                let evm = self.evm_config().evm_with_env(&mut *db, evm_env);
+                let builder = self.evm_config().create_block_builder(evm, parent, ctx);
+                simulate::execute_transactions(
+                    builder,
+                    calls,
+                    default_gas_limit,
+                    chain_id,
+                    self.tx_resp_builder(),
+                )?
+            };
+
+            *parent = result.block.clone_sealed_header();
+
+            let block = simulate::build_simulated_block(
+                result.block,
+                results,
+                return_full_transactions.into(),
+                self.tx_resp_builder(),
+            )?;
+
+            blocks.push(block);
         }
+
+        Ok(blocks)
     }
 
     /// Executes the call request (`eth_call`) and returns the output
@@ -3,24 +3,30 @@
 use super::{EthApiSpec, LoadPendingBlock, SpawnBlocking};
 use crate::{EthApiTypes, FromEthApiError, RpcNodeCore, RpcNodeCoreExt};
 use alloy_consensus::constants::KECCAK_EMPTY;
-use alloy_eips::BlockId;
+use alloy_eips::{BlockId, BlockNumHash};
 use alloy_primitives::{Address, Bytes, B256, U256};
 use alloy_rpc_types_eth::{Account, AccountInfo, EIP1186AccountProofResponse};
 use alloy_serde::JsonStorageKey;
 use futures::Future;
 use reth_errors::RethError;
 use reth_evm::{ConfigureEvm, EvmEnvFor};
-use reth_rpc_eth_types::{EthApiError, PendingBlockEnv, RpcInvalidTransactionError};
+use reth_rpc_eth_types::{
+    EthApiError, HistoricalProofs, PendingBlockEnv, RpcInvalidTransactionError,
+};
 use reth_storage_api::{
     BlockIdReader, BlockNumReader, StateProvider, StateProviderBox, StateProviderFactory,
 };
 use reth_transaction_pool::TransactionPool;
+use reth_trie_common::TrieInput;
 
 /// Helper methods for `eth_` methods relating to state (accounts).
 pub trait EthState: LoadState + SpawnBlocking {
     /// Returns the maximum number of blocks into the past for generating state proofs.
     fn max_proof_window(&self) -> u64;
 
+    /// Returns the proofs of state beyond the proof window, if enabled.
+    fn historical_proofs(&self) -> Option<&HistoricalProofs>;
+
     /// Returns the number of transactions sent from an address at the given block identifier.
     ///
     /// If this is [`BlockNumberOrTag::Pending`](alloy_eips::BlockNumberOrTag) then this will
@@ -105,15 +111,35 @@ pub trait EthState: LoadState + SpawnBlocking {
                 .map_err(Self::Error::from_eth_err)?
                 .ok_or(EthApiError::HeaderNotFound(block_id))?;
             let max_window = self.max_proof_window();
-            if chain_info.best_number.saturating_sub(block_number) > max_window {
-                return Err(EthApiError::ExceedsMaxProofWindow.into())
+            let distance = chain_info.best_number.saturating_sub(block_number);
+            if distance <= max_window {
+                return self
+                    .spawn_blocking_io(move |this| {
+                        let state = this.state_at_block_id(block_id)?;
+                        let storage_keys = keys.iter().map(|key| key.as_b256()).collect::<Vec<_>>();
+                        let proof = state
+                            .proof(Default::default(), address, &storage_keys)
+                            .map_err(Self::Error::from_eth_err)?;
+                        Ok(proof.into_eip1186_response(keys))
+                    })
+                    .await
             }
 
+            // Beyond the window, the proof is generated by reverting the state at the tip.
+            let Some(historical_proofs) =
+                self.historical_proofs().filter(|proofs| distance <= proofs.max_depth()).cloned()
+            else {
+                return Err(EthApiError::ExceedsMaxProofWindow.into())
+            };
             self.spawn_blocking_io(move |this| {
-                let state = this.state_at_block_id(block_id)?;
+                let tip = BlockNumHash::new(chain_info.best_number, chain_info.best_hash);
+                let reverts = historical_proofs
+                    .reverts(this.provider(), block_number, tip)
+                    .map_err(Self::Error::from_eth_err)?;
+                let state = this.state_at_hash(tip.hash)?;
                 let storage_keys = keys.iter().map(|key| key.as_b256()).collect::<Vec<_>>();
                 let proof = state
-                    .proof(Default::default(), address, &storage_keys)
+                    .proof(TrieInput::from_state(reverts), address, &storage_keys)
                     .map_err(Self::Error::from_eth_err)?;
                 Ok(proof.into_eip1186_response(keys))
             })
@@ -8,7 +8,9 @@ use reth_node_api::{FullNodeComponents, NodePrimitives, PrimitivesTy};
 use reth_primitives_traits::{BlockTy, HeaderTy, ReceiptTy, TxTy};
 use reth_rpc_eth_types::EthStateCache;
 use reth_storage_api::{
-    BlockReader, BlockReaderIdExt, StageCheckpointReader, StateProviderFactory,
+    BlockReader, BlockReaderIdExt, CallIndexReader, ChangeSetReader, PreimageReader,
+    PruneCheckpointReader, RawDatabaseReader, StageCheckpointReader, StateProviderFactory,
+    StorageChangeSetReader,
 };
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
 
@@ -35,6 +37,12 @@ pub trait RpcNodeCore: Clone + Send + Sync + Unpin + 'static {
         > + StateProviderFactory
         + CanonStateSubscriptions<Primitives = Self::Primitives>
         + StageCheckpointReader
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + PruneCheckpointReader
+        + CallIndexReader
+        + PreimageReader
+        + RawDatabaseReader
         + Send
         + Sync
         + Clone
@@ -126,6 +134,12 @@ where
         > + StateProviderFactory
         + CanonStateSubscriptions<Primitives = Evm::Primitives>
         + StageCheckpointReader
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + PruneCheckpointReader
+        + CallIndexReader
+        + PreimageReader
+        + RawDatabaseReader
         + Send
         + Sync
         + Unpin
@@ -15,6 +15,9 @@ use serde::{Deserialize, Serialize};
 /// Default value for stale filter ttl
 pub const DEFAULT_STALE_FILTER_TTL: Duration = Duration::from_secs(5 * 60);
 
+/// Default value for simulation session ttl
+pub const DEFAULT_SIMULATION_SESSION_TTL: Duration = Duration::from_secs(5 * 60);
+
 /// Additional config values for the eth namespace.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct EthConfig {
@@ -24,6 +27,9 @@ pub struct EthConfig {
     pub gas_oracle: GasPriceOracleConfig,
     /// The maximum number of blocks into the past for generating state proofs.
     pub eth_proof_window: u64,
+    /// The maximum number of blocks into the past for generating state proofs beyond the proof
+    /// window, if enabled.
+    pub eth_proof_max_depth: Option<u64>,
     /// The maximum number of tracing calls that can be executed in concurrently.
     pub max_tracing_requests: usize,
     /// Maximum number of blocks for `trace_filter` requests.
@@ -41,6 +47,8 @@ pub struct EthConfig {
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: Duration,
+    /// Duration since the last use of a simulation session, after which it is dropped.
+    pub simulation_session_ttl: Duration,
     /// Settings for the fee history cache
     pub fee_history_cache: FeeHistoryCacheConfig,
     /// The maximum number of getproof calls that can be executed concurrently.
@@ -63,6 +71,7 @@ impl Default for EthConfig {
             cache: EthStateCacheConfig::default(),
             gas_oracle: GasPriceOracleConfig::default(),
             eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+            eth_proof_max_depth: None,
             max_tracing_requests: default_max_tracing_requests(),
             max_trace_filter_blocks: DEFAULT_MAX_TRACE_FILTER_BLOCKS,
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
@@ -70,6 +79,7 @@ impl Default for EthConfig {
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
             rpc_max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
+            simulation_session_ttl: DEFAULT_SIMULATION_SESSION_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
             proof_permits: DEFAULT_PROOF_PERMITS,
         }
@@ -131,11 +141,23 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum depth for historical proofs beyond the proof window.
+    pub const fn eth_proof_max_depth(mut self, max_depth: Option<u64>) -> Self {
+        self.eth_proof_max_depth = max_depth;
+        self
+    }
+
     /// Configures the number of getproof requests
     pub const fn proof_permits(mut self, permits: usize) -> Self {
         self.proof_permits = permits;
         self
     }
+
+    /// Configures how long a simulation session remains valid after its last use.
+    pub const fn simulation_session_ttl(mut self, ttl: Duration) -> Self {
+        self.simulation_session_ttl = ttl;
+        self
+    }
 }
 
 /// Config for the filter
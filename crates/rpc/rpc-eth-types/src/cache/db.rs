@@ -140,6 +140,35 @@ impl reth_storage_api::BlockHashReader for StateProviderTraitObjWrapper<'_> {
     }
 }
 
+impl reth_storage_api::HashedStateRangeProvider for StateProviderTraitObjWrapper<'_> {
+    fn hashed_account_range(
+        &self,
+        hashed_state: reth_trie::HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, reth_primitives_traits::Account)>> {
+        self.0.hashed_account_range(hashed_state, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, alloy_primitives::U256)>> {
+        self.0.hashed_storage_range(hashed_address, hashed_storage, start, limit)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        self.0.hashed_storage_root(hashed_address, hashed_storage)
+    }
+}
+
 impl HashedPostStateProvider for StateProviderTraitObjWrapper<'_> {
     fn hashed_post_state(&self, bundle_state: &BundleState) -> reth_trie::HashedPostState {
         self.0.hashed_post_state(bundle_state)
@@ -411,6 +411,7 @@ impl From<reth_errors::ProviderError> for EthApiError {
             ProviderError::TotalDifficultyNotFound(num) => Self::HeaderNotFound(num.into()),
             ProviderError::FinalizedBlockNotFound => Self::HeaderNotFound(BlockId::finalized()),
             ProviderError::SafeBlockNotFound => Self::HeaderNotFound(BlockId::safe()),
+            ProviderError::StateAtBlockPruned(_) => Self::PrunedHistoryUnavailable,
             err => Self::Internal(err.into()),
         }
     }
@@ -16,6 +16,7 @@ pub mod gas_oracle;
 pub mod id_provider;
 pub mod logs_utils;
 pub mod pending_block;
+pub mod proof;
 pub mod receipt;
 pub mod simulate;
 pub mod transaction;
@@ -33,4 +34,5 @@ pub use gas_oracle::{
 };
 pub use id_provider::EthSubscriptionIdProvider;
 pub use pending_block::{PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin};
+pub use proof::HistoricalProofs;
 pub use transaction::TransactionSource;
@@ -0,0 +1,147 @@
+//! Proofs of historical state beyond the proof window.
+
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{
+    keccak256,
+    map::{AddressMap, B256Map},
+    BlockNumber, U256,
+};
+use parking_lot::Mutex;
+use reth_primitives_traits::Account;
+use reth_storage_api::{
+    errors::provider::{ConsistentViewError, ProviderResult},
+    BlockHashReader, ChangeSetReader, StorageChangeSetReader,
+};
+use reth_trie::{HashedPostState, HashedStorage};
+use std::{ops::RangeInclusive, sync::Arc};
+
+/// Serves proofs for blocks that are deeper than the proof window.
+///
+/// The state of a historical block is restored by reverting the state at the tip with the account
+/// and storage changesets of all later blocks, so proofs are only available for blocks whose
+/// history is not pruned.
+///
+/// Loading the changesets of deep history is expensive. The reverts of the last requested block
+/// are therefore kept and reused by subsequent proofs for the same block, and only the changesets
+/// of blocks that were added to the chain since are loaded.
+#[derive(Debug, Clone)]
+pub struct HistoricalProofs {
+    /// The maximum number of blocks behind the tip that proofs are served for.
+    max_depth: u64,
+    /// The reverts of the last requested block.
+    reverts: Arc<Mutex<Option<StateReverts>>>,
+}
+
+impl HistoricalProofs {
+    /// Creates a new instance that serves proofs up to `max_depth` blocks behind the tip.
+    pub fn new(max_depth: u64) -> Self {
+        Self { max_depth, reverts: Default::default() }
+    }
+
+    /// Returns the maximum number of blocks behind the tip that proofs are served for.
+    pub const fn max_depth(&self) -> u64 {
+        self.max_depth
+    }
+
+    /// Returns the reverts that restore the state after `block` when applied to the state at the
+    /// canonical `tip`.
+    pub fn reverts<Provider>(
+        &self,
+        provider: &Provider,
+        block: BlockNumber,
+        tip: BlockNumHash,
+    ) -> ProviderResult<HashedPostState>
+    where
+        Provider: BlockHashReader + ChangeSetReader + StorageChangeSetReader,
+    {
+        let mut cached = self.reverts.lock();
+        let mut reverts = match cached.take() {
+            Some(reverts) if reverts.block == block && reverts.is_canonical(provider, tip)? => {
+                reverts
+            }
+            _ => StateReverts { block, tip: None, state: HashedPostState::default() },
+        };
+
+        let first_block = reverts.tip.map_or(block, |loaded| loaded.number) + 1;
+        reverts.extend(provider, first_block..=tip.number)?;
+
+        // The chain could have been reorged while the changesets were loaded.
+        if provider.block_hash(tip.number)? != Some(tip.hash) {
+            return Err(ConsistentViewError::Reorged { block: tip.hash }.into())
+        }
+        reverts.tip = Some(tip);
+
+        let state = reverts.state.clone();
+        *cached = Some(reverts);
+        Ok(state)
+    }
+}
+
+/// The reverts of the state after a block.
+#[derive(Debug)]
+struct StateReverts {
+    /// The block whose state is restored.
+    block: BlockNumber,
+    /// The last block whose changesets are included, if any.
+    tip: Option<BlockNumHash>,
+    /// The state before the first change of every account and storage slot after `block`.
+    state: HashedPostState,
+}
+
+impl StateReverts {
+    /// Returns `true` if the reverts can be extended up to `tip`, i.e. the changesets that are
+    /// already included are still canonical and not above `tip`.
+    fn is_canonical<Provider: BlockHashReader>(
+        &self,
+        provider: &Provider,
+        tip: BlockNumHash,
+    ) -> ProviderResult<bool> {
+        let Some(loaded) = self.tip else { return Ok(true) };
+        Ok(loaded.number <= tip.number && provider.block_hash(loaded.number)? == Some(loaded.hash))
+    }
+
+    /// Includes the changesets of the given blocks, which must directly follow the included ones.
+    ///
+    /// Changes of accounts and storage slots that were already reverted are skipped, since an
+    /// earlier changeset holds their value after `block`.
+    fn extend<Provider>(
+        &mut self,
+        provider: &Provider,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()>
+    where
+        Provider: ChangeSetReader + StorageChangeSetReader,
+    {
+        // Record the first value in the range before hashing, since most keys change repeatedly.
+        let mut accounts = AddressMap::<Option<Account>>::default();
+        let mut storages = AddressMap::<B256Map<U256>>::default();
+        for block in range {
+            for change in provider.account_block_changeset(block)? {
+                accounts.entry(change.address).or_insert(change.info);
+            }
+            for (block_address, entry) in provider.storage_changeset(block)? {
+                storages
+                    .entry(block_address.address())
+                    .or_default()
+                    .entry(entry.key)
+                    .or_insert(entry.value);
+            }
+        }
+
+        for (address, info) in accounts {
+            self.state.accounts.entry(keccak256(address)).or_insert(info);
+        }
+        for (address, storage) in storages {
+            let reverted = self
+                .state
+                .storages
+                .entry(keccak256(address))
+                .or_insert_with(|| HashedStorage::new(false));
+            for (slot, value) in storage {
+                reverted.storage.entry(keccak256(slot)).or_insert(value);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -18,6 +18,9 @@ pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 /// The default maximum number of blocks for `trace_filter` requests.
 pub const DEFAULT_MAX_TRACE_FILTER_BLOCKS: u64 = 100;
 
+/// The default maximum number of blocks for `debug_getModifiedAccountsBy*` requests.
+pub const DEFAULT_MAX_MODIFIED_ACCOUNTS_BLOCKS: u64 = 1_000;
+
 /// The default maximum number tracing requests we're allowing concurrently.
 /// Tracing is mostly CPU bound so we're limiting the number of concurrent requests to something
 /// lower that the number of cores, in order to minimize the impact on the rest of the system.
@@ -273,6 +273,12 @@ pub enum RethRpcModule {
     Miner,
     /// `mev_` module
     Mev,
+    /// `anvil_` module
+    Anvil,
+    /// `hardhat_` module
+    Hardhat,
+    /// `evm_` module, as served by Ganache
+    Evm,
 }
 
 // === impl RethRpcModule ===
@@ -324,6 +330,9 @@ impl FromStr for RethRpcModule {
             "flashbots" => Self::Flashbots,
             "miner" => Self::Miner,
             "mev" => Self::Mev,
+            "anvil" => Self::Anvil,
+            "hardhat" => Self::Hardhat,
+            "evm" => Self::Evm,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
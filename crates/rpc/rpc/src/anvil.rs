@@ -0,0 +1,429 @@
+use alloy_consensus::BlockHeader;
+use alloy_eips::BlockNumberOrTag;
+use alloy_primitives::{map::AddressMap, Address, Bytes, B256, U256};
+use alloy_rpc_types_anvil::{Forking, Metadata, MineOptions, NodeEnvironment, NodeInfo};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_chainspec::{ChainSpecProvider, EthChainSpec, EthereumHardfork, EthereumHardforks};
+use reth_engine_local::{LocalMinerHandle, MiningMode};
+use reth_errors::{ProviderResult, RethError};
+use reth_payload_builder::AccountChanges;
+use reth_primitives_traits::SealedHeader;
+use reth_rpc_api::AnvilApiServer;
+use reth_rpc_eth_api::{
+    helpers::{EthBlocks, SpawnBlocking},
+    EthApiTypes, FromEthApiError, FullEthApiTypes, RpcBlock, RpcNodeCore,
+};
+use reth_rpc_eth_types::EthApiError;
+use reth_storage_api::{BlockReaderIdExt, ProviderHeader, StateProvider, StateProviderFactory};
+use reth_transaction_pool::TransactionPool;
+use std::{sync::Arc, time::Duration};
+
+/// `anvil` API implementation.
+///
+/// This drives the [`LocalMiner`](reth_engine_local::LocalMiner) of a node in dev mode, so that
+/// test suites written against anvil can run against a reth dev node. State manipulations, like
+/// `anvil_setBalance`, are mined as regular blocks that contain the changes.
+#[derive(Debug, Clone)]
+pub struct AnvilApi<Eth> {
+    inner: Arc<AnvilApiInner<Eth>>,
+}
+
+impl<Eth> AnvilApi<Eth> {
+    /// Creates a new instance of `AnvilApi`.
+    ///
+    /// Without a [`LocalMinerHandle`] all methods that control the chain are unsupported.
+    pub fn new(eth_api: Eth, local_miner: Option<LocalMinerHandle>) -> Self {
+        let inner = Arc::new(AnvilApiInner { eth_api, local_miner, instance_id: B256::random() });
+        Self { inner }
+    }
+
+    /// Access the underlying `Eth` API.
+    pub fn eth_api(&self) -> &Eth {
+        &self.inner.eth_api
+    }
+}
+
+impl<Eth> AnvilApi<Eth>
+where
+    Eth: RpcNodeCore + EthApiTypes + SpawnBlocking,
+{
+    /// Returns the handle to the local miner, or an error if the node is not in dev mode.
+    fn local_miner(&self) -> Result<&LocalMinerHandle, Eth::Error> {
+        self.inner.local_miner.as_ref().ok_or_else(|| {
+            EthApiError::Unsupported("controlling the chain requires dev mode").into()
+        })
+    }
+
+    /// Mines a block that applies the given changes to the account.
+    ///
+    /// Fails if the payload builder of the node ignored the changes.
+    pub async fn set_account(
+        &self,
+        address: Address,
+        changes: AccountChanges,
+    ) -> Result<(), Eth::Error> {
+        let block = self
+            .local_miner()?
+            .set_state(AddressMap::from_iter([(address, changes.clone())]))
+            .await
+            .map_err(miner_err)?;
+
+        let applied = self
+            .eth_api()
+            .spawn_blocking_io(move |this| {
+                let state =
+                    this.provider().state_by_block_hash(block).map_err(Eth::Error::from_eth_err)?;
+                account_changes_applied(&state, address, &changes).map_err(Eth::Error::from_eth_err)
+            })
+            .await?;
+        if !applied {
+            return Err(EthApiError::Unsupported(
+                "the payload builder of the node does not support state changes",
+            )
+            .into())
+        }
+
+        Ok(())
+    }
+
+    /// Mines blocks with the given options and returns their hashes.
+    pub async fn mine_with_options(
+        &self,
+        opts: Option<MineOptions>,
+    ) -> Result<Vec<B256>, Eth::Error> {
+        let miner = self.local_miner()?;
+        let (timestamp, blocks) = match opts.unwrap_or_default() {
+            MineOptions::Options { timestamp, blocks } => (timestamp, blocks.unwrap_or(1)),
+            MineOptions::Timestamp(timestamp) => (timestamp, 1),
+        };
+        if let Some(timestamp) = timestamp {
+            miner.set_next_block_timestamp(timestamp).await.map_err(miner_err)?;
+        }
+        Ok(miner.mine(blocks, None).await.map_err(miner_err)?)
+    }
+
+    /// Returns the latest header of the chain.
+    fn latest_header(&self) -> Result<SealedHeader<ProviderHeader<Eth::Provider>>, Eth::Error> {
+        self.eth_api()
+            .provider()
+            .sealed_header_by_id(BlockNumberOrTag::Latest.into())
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or_else(|| EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()).into())
+    }
+}
+
+#[async_trait]
+impl<Eth> AnvilApiServer<RpcBlock<Eth::NetworkTypes>> for AnvilApi<Eth>
+where
+    Eth: RpcNodeCore + FullEthApiTypes + EthBlocks + SpawnBlocking,
+{
+    async fn anvil_impersonate_account(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_impersonateAccount is not supported").into())
+    }
+
+    async fn anvil_stop_impersonating_account(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_stopImpersonatingAccount is not supported").into())
+    }
+
+    async fn anvil_auto_impersonate_account(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_autoImpersonateAccount is not supported").into())
+    }
+
+    /// Handler for `anvil_getAutomine`
+    async fn anvil_get_automine(&self) -> RpcResult<bool> {
+        Ok(self.local_miner().map_err(Into::into)?.is_automine().await.map_err(miner_err)?)
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        let blocks = blocks.map(quantity).transpose()?.unwrap_or(1);
+        let interval = interval.map(quantity).transpose()?;
+        self.local_miner().map_err(Into::into)?.mine(blocks, interval).await.map_err(miner_err)?;
+        Ok(())
+    }
+
+    /// Handler for `anvil_setAutomine`
+    async fn anvil_set_automine(&self, enabled: bool) -> RpcResult<()> {
+        let miner = self.local_miner().map_err(Into::into)?;
+        let mode = if enabled {
+            MiningMode::instant(self.eth_api().pool().clone())
+        } else {
+            MiningMode::Manual
+        };
+        Ok(miner.set_mining_mode(mode).await.map_err(miner_err)?)
+    }
+
+    /// Handler for `anvil_setIntervalMining`
+    async fn anvil_set_interval_mining(&self, interval: u64) -> RpcResult<()> {
+        let miner = self.local_miner().map_err(Into::into)?;
+        // an interval of 0 disables interval mining
+        let mode = if interval == 0 {
+            MiningMode::Manual
+        } else {
+            MiningMode::interval(Duration::from_secs(interval))
+        };
+        Ok(miner.set_mining_mode(mode).await.map_err(miner_err)?)
+    }
+
+    /// Handler for `anvil_dropTransaction`
+    async fn anvil_drop_transaction(&self, tx_hash: B256) -> RpcResult<Option<B256>> {
+        let removed = self.eth_api().pool().remove_transactions(vec![tx_hash]);
+        Ok(removed.first().map(|tx| *tx.hash()))
+    }
+
+    async fn anvil_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_reset is not supported").into())
+    }
+
+    async fn anvil_set_rpc_url(&self, _url: String) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_setRpcUrl is not supported").into())
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        let changes = AccountChanges { balance: Some(balance), ..Default::default() };
+        Ok(self.set_account(address, changes).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        let changes = AccountChanges { code: Some(code), ..Default::default() };
+        Ok(self.set_account(address, changes).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `anvil_setNonce`
+    async fn anvil_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        let changes = AccountChanges { nonce: Some(quantity(nonce)?), ..Default::default() };
+        Ok(self.set_account(address, changes).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        let changes = AccountChanges {
+            storage: std::iter::once((B256::from(slot), value)).collect(),
+            ..Default::default()
+        };
+        self.set_account(address, changes).await.map_err(Into::into)?;
+        Ok(true)
+    }
+
+    async fn anvil_set_coinbase(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_setCoinbase is not supported").into())
+    }
+
+    async fn anvil_set_chain_id(&self, _chain_id: u64) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_setChainId is not supported").into())
+    }
+
+    async fn anvil_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_setLoggingEnabled is not supported").into())
+    }
+
+    async fn anvil_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_setMinGasPrice is not supported").into())
+    }
+
+    async fn anvil_set_next_block_base_fee_per_gas(&self, _base_fee: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_setNextBlockBaseFeePerGas is not supported").into())
+    }
+
+    /// Handler for `anvil_setTime`
+    async fn anvil_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        Ok(self.local_miner().map_err(Into::into)?.set_time(timestamp).await.map_err(miner_err)?)
+    }
+
+    async fn anvil_dump_state(&self) -> RpcResult<Bytes> {
+        Err(EthApiError::Unsupported("anvil_dumpState is not supported").into())
+    }
+
+    async fn anvil_load_state(&self, _state: Bytes) -> RpcResult<bool> {
+        Err(EthApiError::Unsupported("anvil_loadState is not supported").into())
+    }
+
+    /// Handler for `anvil_nodeInfo`
+    async fn anvil_node_info(&self) -> RpcResult<NodeInfo> {
+        let header = self.latest_header().map_err(Into::into)?;
+        let chain_spec = self.eth_api().provider().chain_spec();
+        let hard_fork = EthereumHardfork::VARIANTS
+            .iter()
+            .rev()
+            .find(|fork| {
+                chain_spec
+                    .ethereum_fork_activation(**fork)
+                    .active_at_timestamp_or_number(header.timestamp(), header.number())
+            })
+            .map(|fork| fork.name().to_string())
+            .unwrap_or_default();
+        let base_fee = header.base_fee_per_gas().unwrap_or_default() as u128;
+
+        Ok(NodeInfo {
+            current_block_number: header.number(),
+            current_block_timestamp: header.timestamp(),
+            current_block_hash: header.hash(),
+            hard_fork,
+            transaction_order: "fees".to_string(),
+            environment: NodeEnvironment {
+                base_fee,
+                chain_id: chain_spec.chain_id(),
+                gas_limit: header.gas_limit(),
+                gas_price: base_fee,
+            },
+            fork_config: Default::default(),
+        })
+    }
+
+    /// Handler for `anvil_metadata`
+    async fn anvil_metadata(&self) -> RpcResult<Metadata> {
+        let header = self.latest_header().map_err(Into::into)?;
+        let snapshots = match &self.inner.local_miner {
+            Some(miner) => miner
+                .snapshots()
+                .await
+                .map_err(miner_err)?
+                .into_iter()
+                .map(|(id, head)| (id, (head.number, head.hash)))
+                .collect(),
+            None => Default::default(),
+        };
+
+        Ok(Metadata {
+            client_version: concat!("reth/v", env!("CARGO_PKG_VERSION")).to_string(),
+            chain_id: self.eth_api().provider().chain_spec().chain_id(),
+            instance_id: self.inner.instance_id,
+            latest_block_number: header.number(),
+            latest_block_hash: header.hash(),
+            forked_network: None,
+            snapshots,
+        })
+    }
+
+    /// Handler for `anvil_snapshot`
+    async fn anvil_snapshot(&self) -> RpcResult<U256> {
+        Ok(self.local_miner().map_err(Into::into)?.snapshot().await.map_err(miner_err)?)
+    }
+
+    /// Handler for `anvil_revert`
+    async fn anvil_revert(&self, id: U256) -> RpcResult<bool> {
+        Ok(self.local_miner().map_err(Into::into)?.revert(id).await.map_err(miner_err)?)
+    }
+
+    /// Handler for `anvil_increaseTime`
+    async fn anvil_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        let seconds = quantity(seconds)?;
+        Ok(self
+            .local_miner()
+            .map_err(Into::into)?
+            .increase_time(seconds)
+            .await
+            .map_err(miner_err)?)
+    }
+
+    /// Handler for `anvil_setNextBlockTimestamp`
+    async fn anvil_set_next_block_timestamp(&self, seconds: u64) -> RpcResult<()> {
+        let miner = self.local_miner().map_err(Into::into)?;
+        Ok(miner.set_next_block_timestamp(seconds).await.map_err(miner_err)?)
+    }
+
+    async fn anvil_set_block_gas_limit(&self, _gas_limit: U256) -> RpcResult<bool> {
+        Err(EthApiError::Unsupported("anvil_setBlockGasLimit is not supported").into())
+    }
+
+    /// Handler for `anvil_setBlockTimestampInterval`
+    async fn anvil_set_block_timestamp_interval(&self, seconds: u64) -> RpcResult<()> {
+        let miner = self.local_miner().map_err(Into::into)?;
+        miner.set_block_timestamp_interval(Some(seconds)).await.map_err(miner_err)?;
+        Ok(())
+    }
+
+    /// Handler for `anvil_removeBlockTimestampInterval`
+    async fn anvil_remove_block_timestamp_interval(&self) -> RpcResult<bool> {
+        let miner = self.local_miner().map_err(Into::into)?;
+        let previous = miner.set_block_timestamp_interval(None).await.map_err(miner_err)?;
+        Ok(previous.is_some())
+    }
+
+    /// Handler for `anvil_mine_detailed`
+    async fn anvil_mine_detailed(
+        &self,
+        opts: Option<MineOptions>,
+    ) -> RpcResult<Vec<RpcBlock<Eth::NetworkTypes>>> {
+        let mut mined = Vec::new();
+        for hash in self.mine_with_options(opts).await.map_err(Into::into)? {
+            let block = self
+                .eth_api()
+                .rpc_block(hash.into(), true)
+                .await
+                .map_err(Into::into)?
+                .ok_or_else(|| EthApiError::HeaderNotFound(hash.into()))?;
+            mined.push(block);
+        }
+        Ok(mined)
+    }
+
+    async fn anvil_enable_traces(&self) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("anvil_enableTraces is not supported").into())
+    }
+
+    /// Handler for `anvil_removePoolTransactions`
+    async fn anvil_remove_pool_transactions(&self, address: Address) -> RpcResult<()> {
+        self.eth_api().pool().remove_transactions_by_sender(address);
+        Ok(())
+    }
+}
+
+/// Container type for `AnvilApi`
+#[derive(Debug)]
+struct AnvilApiInner<Eth> {
+    /// The implementation of `eth` API
+    eth_api: Eth,
+    /// Handle to the local miner, only set in dev mode.
+    local_miner: Option<LocalMinerHandle>,
+    /// Random id of this instance, reported by `anvil_metadata`.
+    instance_id: B256,
+}
+
+/// Returns `true` if the state reflects the given changes to the account.
+fn account_changes_applied(
+    state: &impl StateProvider,
+    address: Address,
+    changes: &AccountChanges,
+) -> ProviderResult<bool> {
+    if let Some(balance) = changes.balance {
+        if state.account_balance(&address)?.unwrap_or_default() != balance {
+            return Ok(false)
+        }
+    }
+    if let Some(nonce) = changes.nonce {
+        if state.account_nonce(&address)?.unwrap_or_default() != nonce {
+            return Ok(false)
+        }
+    }
+    if let Some(code) = &changes.code {
+        let applied = state.account_code(&address)?.map(|code| code.original_bytes());
+        if applied.unwrap_or_default() != *code {
+            return Ok(false)
+        }
+    }
+    for (slot, value) in &changes.storage {
+        if state.storage(address, *slot)?.unwrap_or_default() != U256::from_be_bytes(value.0) {
+            return Ok(false)
+        }
+    }
+    Ok(true)
+}
+
+/// Converts a quantity parameter to `u64`.
+pub(crate) fn quantity(value: U256) -> Result<u64, EthApiError> {
+    u64::try_from(value).map_err(|_| EthApiError::InvalidParams(format!("{value} exceeds u64")))
+}
+
+/// Converts an error of the local miner into an internal error.
+pub(crate) fn miner_err(err: eyre::Report) -> EthApiError {
+    EthApiError::Internal(RethError::msg(err))
+}
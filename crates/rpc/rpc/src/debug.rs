@@ -1,11 +1,14 @@
+use crate::profiling;
 use alloy_consensus::{transaction::SignerRecoverable, BlockHeader};
 use alloy_eips::{eip2718::Encodable2718, BlockId, BlockNumberOrTag};
 use alloy_genesis::ChainConfig;
-use alloy_primitives::{uint, Address, Bytes, B256};
+use alloy_primitives::{
+    hex, keccak256, map::B256Map, uint, Address, BlockNumber, Bytes, B256, U256,
+};
 use alloy_rlp::{Decodable, Encodable};
 use alloy_rpc_types_debug::ExecutionWitness;
 use alloy_rpc_types_eth::{
-    state::EvmOverrides, Block as RpcBlock, BlockError, Bundle, StateContext, TransactionInfo,
+    state::EvmOverrides, BlockError, BlockTransactionsKind, Bundle, StateContext, TransactionInfo,
 };
 use alloy_rpc_types_trace::geth::{
     call::FlatCallFrame, BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType,
@@ -15,36 +18,68 @@ use alloy_rpc_types_trace::geth::{
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_chainspec::{ChainSpecProvider, EthChainSpec, EthereumHardforks};
+use reth_db_api::Tables;
+use reth_engine_primitives::{BadBlockStore, BeaconSetHeadError, EngineSetHead};
+use reth_errors::RethError;
 use reth_evm::{execute::Executor, ConfigureEvm, EvmEnvFor, TxEnvFor};
 use reth_primitives_traits::{
     Block as _, BlockBody, ReceiptWithBloom, RecoveredBlock, SignedTransaction,
 };
+use reth_prune_types::PruneSegment;
 use reth_revm::{
     database::StateProviderDatabase,
-    db::{CacheDB, State},
+    db::{states::bundle_state::BundleRetention, AccountState, CacheDB, State},
     witness::ExecutionWitnessRecord,
 };
-use reth_rpc_api::DebugApiServer;
-use reth_rpc_convert::RpcTxReq;
+use reth_rpc_api::{
+    AccountRangeResult, BadBlockResult, ChaindbProperty, DebugApiServer, DumpAccount,
+    IntermediateRootsConfig, MemStats, StdTraceConfig, StorageRangeEntry, StorageRangeResult,
+};
+use reth_rpc_convert::{RpcConvert, RpcTxReq};
 use reth_rpc_eth_api::{
     helpers::{EthTransactions, TraceExt},
-    EthApiTypes, FromEthApiError, RpcNodeCore,
+    EthApiTypes, FromEthApiError, FullEthApiTypes, RpcNodeCore,
 };
 use reth_rpc_eth_types::{EthApiError, StateCacheDb};
-use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use reth_rpc_server_types::{
+    constants::DEFAULT_MAX_MODIFIED_ACCOUNTS_BLOCKS, result::internal_rpc_err, ToRpcResult,
+};
+use reth_static_file_types::StaticFileSegment;
 use reth_storage_api::{
-    BlockIdReader, BlockReaderIdExt, HeaderProvider, ProviderBlock, ReceiptProviderIdExt,
+    BlockIdReader, BlockNumReader, BlockReaderIdExt, BytecodeReader, ChangeSetReader,
+    HashedPostStateProvider, HashedStateRangeProvider, HeaderProvider, PreimageReader,
+    ProviderBlock, PruneCheckpointReader, RawDatabaseReader, ReceiptProviderIdExt,
     StateProofProvider, StateProviderFactory, StateRootProvider, TransactionVariant,
 };
 use reth_tasks::pool::BlockingTaskGuard;
-use reth_trie_common::{updates::TrieUpdates, HashedPostState};
-use revm::{context_interface::Transaction, state::EvmState, DatabaseCommit};
+use reth_tracing::{log_filter_handle, tracing::level_filters::LevelFilter, LogFilterHandle};
+use reth_trie_common::{updates::TrieUpdates, HashedPostState, HashedStorage};
+use revm::{
+    context_interface::Transaction, inspector::inspectors::TracerEip3155, state::EvmState,
+    DatabaseCommit,
+};
 use revm_inspectors::tracing::{
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig, TransactionContext,
 };
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// The maximum number of accounts returned by a single `debug_accountRange` call.
+///
+/// This matches the limit used by geth.
+pub const ACCOUNT_RANGE_MAX_RESULTS: u64 = 256;
+
+/// The default time a single transaction may spend in a JS tracer.
+///
+/// This matches the default tracing timeout of geth.
+pub const DEFAULT_JS_TRACER_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
@@ -56,8 +91,22 @@ pub struct DebugApi<Eth> {
 
 impl<Eth> DebugApi<Eth> {
     /// Create a new instance of the [`DebugApi`]
-    pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(DebugApiInner { eth_api, blocking_task_guard });
+    pub fn new(
+        eth_api: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        bad_blocks: BadBlockStore,
+        engine_set_head: Option<Arc<dyn EngineSetHead>>,
+        config: DebugApiConfig,
+    ) -> Self {
+        let inner = Arc::new(DebugApiInner {
+            eth_api,
+            blocking_task_guard,
+            bad_blocks,
+            engine_set_head,
+            #[cfg(feature = "js-tracer")]
+            js_tracers: crate::js_tracer::JsTracers::new(config.js_tracer),
+            config,
+        });
         Self { inner }
     }
 
@@ -85,6 +134,21 @@ where
         self.inner.blocking_task_guard.clone().acquire_owned().await
     }
 
+    /// Rejects a JS tracer that can't be evaluated, before any state is replayed for it.
+    #[allow(clippy::missing_const_for_fn)]
+    fn ensure_valid_tracer(&self, opts: &GethDebugTracingOptions) -> Result<(), Eth::Error> {
+        #[cfg(feature = "js-tracer")]
+        if let Some(GethDebugTracerType::JsTracer(code)) = &opts.tracer {
+            let config = opts.tracer_config.clone().into_json();
+            let js_tracers = &self.inner.js_tracers;
+            js_tracers.timeout(opts.timeout.as_deref()).map_err(Eth::Error::from_eth_err)?;
+            js_tracers.ensure_valid(code, &config).map_err(Eth::Error::from_eth_err)?;
+        }
+        #[cfg(not(feature = "js-tracer"))]
+        let _ = opts;
+        Ok(())
+    }
+
     /// Trace the entire block asynchronously
     async fn trace_block(
         &self,
@@ -92,6 +156,8 @@ where
         evm_env: EvmEnvFor<Eth::Evm>,
         opts: GethDebugTracingOptions,
     ) -> Result<Vec<TraceResult>, Eth::Error> {
+        self.ensure_valid_tracer(&opts)?;
+
         // replay all transactions of the block
         let this = self.clone();
         self.eth_api()
@@ -177,6 +243,156 @@ where
         self.trace_block(Arc::new(block.into_recovered_with_signers(senders)), evm_env, opts).await
     }
 
+    /// Returns the bad block with the given hash from the [`BadBlockStore`].
+    fn bad_block(
+        &self,
+        block_hash: B256,
+    ) -> Result<RecoveredBlock<ProviderBlock<Eth::Provider>>, Eth::Error> {
+        let bad_block = self
+            .inner
+            .bad_blocks
+            .get(&block_hash)
+            .ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+
+        bad_block
+            .decode_recovered_block()
+            .map_err(BlockError::RlpDecodeRawBlock)
+            .map_err(Eth::Error::from_eth_err)
+    }
+
+    /// Returns all blocks in the [`BadBlockStore`], newest first.
+    pub fn bad_blocks(&self) -> Result<Vec<BadBlockResult>, Eth::Error>
+    where
+        Eth: FullEthApiTypes,
+    {
+        let mut bad_blocks = Vec::new();
+        for bad_block in self.inner.bad_blocks.blocks() {
+            let block = bad_block
+                .decode_recovered_block::<ProviderBlock<Eth::Provider>>()
+                .map_err(BlockError::RlpDecodeRawBlock)
+                .map_err(Eth::Error::from_eth_err)?;
+            let block = block.into_rpc_block(
+                BlockTransactionsKind::Full,
+                |tx, tx_info| self.eth_api().tx_resp_builder().fill(tx, tx_info),
+                |header, size| self.eth_api().tx_resp_builder().convert_header(header, size),
+            )?;
+            let block = serde_json::to_value(block)
+                .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+            bad_blocks.push(BadBlockResult { hash: bad_block.hash, block, rlp: bad_block.rlp });
+        }
+        Ok(bad_blocks)
+    }
+
+    /// Replays a block that was rejected by the engine and returns the trace of each transaction.
+    ///
+    /// Note, the parent of this block must be present, or it will fail.
+    pub async fn debug_trace_bad_block(
+        &self,
+        block_hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, Eth::Error> {
+        let block = self.bad_block(block_hash)?;
+        let evm_env = self.eth_api().evm_config().evm_env(block.header());
+
+        self.trace_block(Arc::new(block), evm_env, opts).await
+    }
+
+    /// Replays the given block and writes an [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155)
+    /// trace of each transaction to a separate file in the configured trace directory.
+    ///
+    /// Returns the paths of the written files, in transaction order.
+    async fn standard_trace_block_to_file(
+        &self,
+        block: Arc<RecoveredBlock<ProviderBlock<Eth::Provider>>>,
+        evm_env: EvmEnvFor<Eth::Evm>,
+        opts: StdTraceConfig,
+    ) -> Result<Vec<PathBuf>, Eth::Error> {
+        let StdTraceConfig { enable_memory, tx_hash } = opts;
+        if let Some(tx_hash) = tx_hash {
+            if !block.body().contains_transaction(&tx_hash) {
+                return Err(EthApiError::InvalidParams(format!(
+                    "transaction {tx_hash} not found in block {}",
+                    block.hash()
+                ))
+                .into())
+            }
+        }
+
+        let dir = self.inner.config.trace_dir.clone().unwrap_or_else(std::env::temp_dir);
+        reth_fs_util::create_dir_all(&dir)
+            .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let mut files = Vec::new();
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+                this.eth_api().apply_pre_execution_changes(&block, &mut db, &evm_env)?;
+
+                for (index, tx) in block.transactions_recovered().enumerate() {
+                    let hash = *tx.tx_hash();
+                    let tx_env = this.eth_api().evm_config().tx_env(tx);
+
+                    let res = if tx_hash.is_none_or(|target| target == hash) {
+                        let path = dir.join(format!("block_{}-{index}-{hash}.jsonl", block.hash()));
+                        let file = reth_fs_util::create_file(&path)
+                            .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+
+                        let mut inspector = TracerEip3155::buffered(file);
+                        if enable_memory {
+                            inspector = inspector.with_memory();
+                        }
+                        let (res, _) =
+                            this.eth_api().inspect(&mut db, evm_env.clone(), tx_env, inspector)?;
+                        files.push(path);
+                        res
+                    } else {
+                        this.eth_api().transact(&mut db, evm_env.clone(), tx_env)?
+                    };
+
+                    // stop once the requested transaction is traced
+                    if tx_hash == Some(hash) {
+                        break
+                    }
+                    db.commit(res.state);
+                }
+
+                Ok(files)
+            })
+            .await
+    }
+
+    /// Replays the block with the given hash and writes an
+    /// [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155) trace of each transaction to a file.
+    pub async fn debug_standard_trace_block_to_file(
+        &self,
+        block_hash: B256,
+        opts: StdTraceConfig,
+    ) -> Result<Vec<PathBuf>, Eth::Error> {
+        let ((evm_env, _), block) = futures::try_join!(
+            self.eth_api().evm_env_at(block_hash.into()),
+            self.eth_api().recovered_block(block_hash.into()),
+        )?;
+
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+
+        self.standard_trace_block_to_file(block, evm_env, opts).await
+    }
+
+    /// Replays a block that was rejected by the engine and writes an
+    /// [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155) trace of each transaction to a file.
+    pub async fn debug_standard_trace_bad_block_to_file(
+        &self,
+        block_hash: B256,
+        opts: StdTraceConfig,
+    ) -> Result<Vec<PathBuf>, Eth::Error> {
+        let block = self.bad_block(block_hash)?;
+        let evm_env = self.eth_api().evm_config().evm_env(block.header());
+
+        self.standard_trace_block_to_file(Arc::new(block), evm_env, opts).await
+    }
+
     /// Replays a block and returns the trace of each transaction.
     pub async fn debug_trace_block(
         &self,
@@ -207,6 +423,8 @@ where
         tx_hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, Eth::Error> {
+        self.ensure_valid_tracer(&opts)?;
+
         let (transaction, block) = match self.eth_api().transaction_and_block(tx_hash).await? {
             None => return Err(EthApiError::TransactionNotFound.into()),
             Some(res) => res,
@@ -417,6 +635,11 @@ where
                 #[cfg(feature = "js-tracer")]
                 GethDebugTracerType::JsTracer(code) => {
                     let config = tracer_config.into_json();
+                    let js_tracers = &self.inner.js_tracers;
+                    js_tracers.ensure_valid(&code, &config).map_err(Eth::Error::from_eth_err)?;
+                    let timeout = js_tracers
+                        .timeout(tracing_options.timeout.as_deref())
+                        .map_err(Eth::Error::from_eth_err)?;
 
                     let (_, at) = self.eth_api().evm_env_at(at).await?;
 
@@ -427,15 +650,19 @@ where
                             // <https://github.com/rust-lang/rust/issues/100013>
                             let db = db.0;
 
-                            let mut inspector =
-                                revm_inspectors::tracing::js::JsInspector::new(code, config)
-                                    .map_err(Eth::Error::from_eth_err)?;
+                            let mut inspector = this
+                                .inner
+                                .js_tracers
+                                .inspector(code, config, Default::default(), timeout)
+                                .map_err(Eth::Error::from_eth_err)?;
                             let (res, _) = this.eth_api().inspect(
                                 &mut *db,
                                 evm_env.clone(),
                                 tx_env.clone(),
                                 &mut inspector,
                             )?;
+                            let mut inspector =
+                                inspector.into_inner().map_err(Eth::Error::from_eth_err)?;
                             inspector
                                 .json_result(res, &tx_env, &evm_env.block_env, db)
                                 .map_err(Eth::Error::from_eth_err)
@@ -500,6 +727,7 @@ where
         let opts = opts.unwrap_or_default();
         let block = block.ok_or(EthApiError::HeaderNotFound(target_block))?;
         let GethDebugTracingCallOptions { tracing_options, mut state_overrides, .. } = opts;
+        self.ensure_valid_tracer(&tracing_options)?;
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -700,6 +928,587 @@ where
             .map(|b| b.original_bytes()))
     }
 
+    /// Returns a page of the storage of the given account, as it is after executing the first
+    /// `tx_idx` transactions of the block with the given hash.
+    ///
+    /// Slots are returned in the order of their hashes, starting at `key_start`. Preimages of the
+    /// hashed slots are known if the slot is touched while replaying the transactions or if it was
+    /// recorded by the node.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> Result<StorageRangeResult, Eth::Error> {
+        let ((evm_env, _), block) = futures::try_join!(
+            self.eth_api().evm_env_at(block_hash.into()),
+            self.eth_api().recovered_block(block_hash.into()),
+        )?;
+
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+        if tx_idx > block.body().transaction_count() {
+            return Err(EthApiError::InvalidParams(format!(
+                "transaction index {tx_idx} out of range for block {block_hash}"
+            ))
+            .into())
+        }
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(&state));
+
+                this.eth_api().apply_pre_execution_changes(&block, &mut db, &evm_env)?;
+
+                for tx in block.transactions_recovered().take(tx_idx) {
+                    let tx_env = this.eth_api().evm_config().tx_env(tx);
+                    let res = this.eth_api().transact(&mut db, evm_env.clone(), tx_env)?;
+                    db.commit(res.state);
+                }
+
+                // overlay the storage changes of the replayed transactions
+                let mut preimages = B256Map::default();
+                let mut hashed_storage = HashedStorage::default();
+                if let Some(account) = db.cache.accounts.get(&contract_address) {
+                    hashed_storage.wiped = matches!(
+                        account.account_state,
+                        AccountState::StorageCleared | AccountState::NotExisting
+                    );
+                    for (slot, value) in &account.storage {
+                        let slot = B256::from(*slot);
+                        let hashed_slot = keccak256(slot);
+                        preimages.insert(hashed_slot, slot);
+                        hashed_storage.storage.insert(hashed_slot, *value);
+                    }
+                }
+
+                // fetch one more slot to determine the key of the next page
+                let limit = usize::try_from(max_result).unwrap_or(usize::MAX).saturating_add(1);
+                let mut slots = state
+                    .hashed_storage_range(
+                        keccak256(contract_address),
+                        hashed_storage,
+                        key_start,
+                        limit,
+                    )
+                    .map_err(EthApiError::from)?;
+                let next_key =
+                    if slots.len() == limit { slots.pop().map(|(key, _)| key) } else { None };
+
+                let mut storage = BTreeMap::new();
+                for (hashed_slot, value) in slots {
+                    let key = match preimages.get(&hashed_slot) {
+                        Some(slot) => Some(*slot),
+                        None => this.storage_key_preimage(hashed_slot)?,
+                    };
+                    storage.insert(hashed_slot, StorageRangeEntry { key, value: value.into() });
+                }
+
+                Ok(StorageRangeResult { storage, next_key })
+            })
+            .await
+    }
+
+    /// Returns a page of the accounts at the given block, in the order of their hashed addresses.
+    ///
+    /// The page starts at the hashed address `start`, right padded with zeros, and contains at most
+    /// `max_results` accounts, capped at [`ACCOUNT_RANGE_MAX_RESULTS`]. Accounts for which the
+    /// preimage of the hashed address is unknown are skipped, unless `incompletes` is set.
+    pub async fn debug_account_range(
+        &self,
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> Result<AccountRangeResult, Eth::Error> {
+        if start.len() > B256::len_bytes() {
+            return Err(EthApiError::InvalidParams(format!(
+                "start key must be at most {} bytes",
+                B256::len_bytes()
+            ))
+            .into())
+        }
+        let mut start_key = B256::ZERO;
+        start_key[..start.len()].copy_from_slice(&start);
+
+        let max_results = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        } as usize;
+
+        let header = self
+            .provider()
+            .sealed_header_by_number_or_tag(block_number)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or(EthApiError::HeaderNotFound(block_number.into()))?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(header.hash().into(), move |state| {
+                let mut result =
+                    AccountRangeResult { root: header.state_root(), ..Default::default() };
+
+                let mut next_start = Some(start_key);
+                'pages: while let Some(start) = next_start.take() {
+                    let accounts = state
+                        .hashed_account_range(HashedPostState::default(), start, max_results)
+                        .map_err(EthApiError::from)?;
+                    if accounts.len() == max_results {
+                        next_start = accounts.last().and_then(|(hashed_address, _)| {
+                            U256::from_be_bytes(hashed_address.0)
+                                .checked_add(U256::from(1))
+                                .map(B256::from)
+                        });
+                    }
+
+                    for (hashed_address, account) in accounts {
+                        if result.accounts.len() == max_results {
+                            result.next = Some(hashed_address);
+                            break 'pages
+                        }
+
+                        let address = this.address_preimage(hashed_address)?;
+                        if address.is_none() && !incompletes {
+                            continue
+                        }
+
+                        let code = match account.bytecode_hash {
+                            Some(code_hash) if !nocode => state
+                                .bytecode_by_hash(&code_hash)
+                                .map_err(EthApiError::from)?
+                                .map(|code| code.original_bytes()),
+                            _ => None,
+                        };
+                        let storage = if nostorage {
+                            None
+                        } else {
+                            let slots = state
+                                .hashed_storage_range(
+                                    hashed_address,
+                                    HashedStorage::default(),
+                                    B256::ZERO,
+                                    usize::MAX,
+                                )
+                                .map_err(EthApiError::from)?;
+                            let mut storage = BTreeMap::new();
+                            for (hashed_slot, value) in slots {
+                                let key =
+                                    this.storage_key_preimage(hashed_slot)?.unwrap_or(hashed_slot);
+                                storage.insert(key, hex::encode(value.to_be_bytes_trimmed_vec()));
+                            }
+                            Some(storage)
+                        };
+                        let root = state
+                            .hashed_storage_root(hashed_address, HashedStorage::default())
+                            .map_err(EthApiError::from)?;
+
+                        let key = address.map_or_else(
+                            || format!("pre({hashed_address})"),
+                            |address| address.to_string(),
+                        );
+                        let dump = DumpAccount {
+                            balance: account.balance.to_string(),
+                            nonce: account.nonce,
+                            root,
+                            code_hash: account.get_bytecode_hash(),
+                            code,
+                            storage,
+                            address,
+                            hashed_address,
+                        };
+                        result.accounts.insert(key, dump);
+                    }
+                }
+
+                Ok(result)
+            })
+            .await
+    }
+
+    /// Returns the recorded keccak256 preimage of the given hash.
+    pub fn debug_preimage(&self, hash: B256) -> Result<Bytes, Eth::Error> {
+        self.provider()
+            .preimage(hash)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or_else(|| EthApiError::InvalidParams(format!("unknown preimage {hash}")).into())
+    }
+
+    /// Rewinds the canonical chain to the given block.
+    ///
+    /// The blocks above the new head are unwound the same way the `stage unwind` command does and
+    /// are synced again afterwards. Unwinding requires the changesets of these blocks, so the head
+    /// can't be set below the pruned account or storage history.
+    pub async fn debug_set_head(&self, number: BlockNumber) -> Result<(), Eth::Error> {
+        let Some(engine) = self.inner.engine_set_head.clone() else {
+            return Err(EthApiError::Unsupported("debug_setHead requires a running engine").into())
+        };
+
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            let pruned_block = self
+                .provider()
+                .get_prune_checkpoint(segment)
+                .map_err(Eth::Error::from_eth_err)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if let Some(pruned_block) = pruned_block.filter(|pruned| *pruned > number) {
+                return Err(EthApiError::InvalidParams(format!(
+                    "cannot set head to block {number}, {segment} is pruned up to block {pruned_block}"
+                ))
+                .into())
+            }
+        }
+
+        engine.set_head(number).await.map_err(|err| match err {
+            BeaconSetHeadError::EngineUnavailable | BeaconSetHeadError::Internal(_) => {
+                EthApiError::Internal(RethError::other(err))
+            }
+            _ => EthApiError::InvalidParams(err.to_string()),
+        })?;
+
+        Ok(())
+    }
+
+    /// Returns the raw value stored under the encoded `key` in the given database table.
+    ///
+    /// For dupsort tables, `subkey` selects the first duplicate whose value starts with it.
+    pub async fn debug_db_get(
+        &self,
+        table: &str,
+        key: Bytes,
+        subkey: Option<Bytes>,
+    ) -> Result<Option<Bytes>, Eth::Error> {
+        let table = table.parse::<Tables>().map_err(EthApiError::InvalidParams)?;
+        self.eth_api()
+            .spawn_blocking_io(move |this| {
+                this.provider()
+                    .raw_table_value(table, &key, subkey.as_ref().map(|subkey| subkey.as_ref()))
+                    .map_err(Eth::Error::from_eth_err)
+            })
+            .await
+    }
+
+    /// Returns the raw columns of all rows of the given block in the static file segment `kind`.
+    pub async fn debug_db_ancient(
+        &self,
+        kind: &str,
+        number: BlockNumber,
+    ) -> Result<Vec<Vec<Bytes>>, Eth::Error> {
+        let segment = kind.parse::<StaticFileSegment>().map_err(|_| {
+            EthApiError::InvalidParams(format!("unknown static file segment: {kind:?}"))
+        })?;
+        self.eth_api()
+            .spawn_blocking_io(move |this| {
+                this.provider()
+                    .raw_static_file_rows(segment, number)
+                    .map_err(Eth::Error::from_eth_err)
+            })
+            .await
+    }
+
+    /// Returns the number of blocks in the static files.
+    pub async fn debug_db_ancients(&self) -> Result<u64, Eth::Error> {
+        let stats = self
+            .eth_api()
+            .spawn_blocking_io(|this| {
+                this.provider().static_file_stats().map_err(Eth::Error::from_eth_err)
+            })
+            .await?;
+
+        Ok(stats
+            .iter()
+            .filter(|stats| stats.segment.is_headers())
+            .map(|stats| stats.block_range.end() + 1)
+            .max()
+            .unwrap_or_default())
+    }
+
+    /// Returns the statistics of the database selected by `property`, see
+    /// [`ChaindbProperty`].
+    pub async fn debug_chaindb_property(
+        &self,
+        property: &str,
+    ) -> Result<ChaindbProperty, Eth::Error> {
+        match property {
+            "mdbx.stats" => {
+                self.eth_api()
+                    .spawn_blocking_io(|this| {
+                        this.provider()
+                            .database_stats()
+                            .map(ChaindbProperty::Database)
+                            .map_err(Eth::Error::from_eth_err)
+                    })
+                    .await
+            }
+            "staticfiles.stats" => {
+                self.eth_api()
+                    .spawn_blocking_io(|this| {
+                        this.provider()
+                            .static_file_stats()
+                            .map(ChaindbProperty::StaticFiles)
+                            .map_err(Eth::Error::from_eth_err)
+                    })
+                    .await
+            }
+            _ => Err(EthApiError::InvalidParams(format!("unknown property: {property:?}")).into()),
+        }
+    }
+
+    /// Samples the CPU usage of the node for the given number of seconds and writes a pprof
+    /// profile to `file`.
+    pub async fn debug_cpu_profile(&self, file: PathBuf, seconds: u64) -> Result<(), Eth::Error> {
+        self.debug_start_cpu_profile(file)?;
+        tokio::time::sleep(Duration::from_secs(seconds)).await;
+        self.debug_stop_cpu_profile().await?;
+        Ok(())
+    }
+
+    /// Starts sampling the CPU usage of the node until [`Self::debug_stop_cpu_profile`] is called.
+    pub fn debug_start_cpu_profile(&self, file: PathBuf) -> Result<(), Eth::Error> {
+        profiling::start_cpu_profile(file).map_err(Into::into)
+    }
+
+    /// Stops the running CPU profile and writes it to its file in pprof format.
+    ///
+    /// Returns the path of the written file.
+    pub async fn debug_stop_cpu_profile(&self) -> Result<PathBuf, Eth::Error> {
+        self.eth_api().spawn_blocking_io(|_| Ok(profiling::stop_cpu_profile()?)).await
+    }
+
+    /// Writes a jemalloc heap profile to `file`.
+    pub async fn debug_write_mem_profile(&self, file: PathBuf) -> Result<(), Eth::Error> {
+        self.eth_api().spawn_blocking_io(move |_| Ok(profiling::write_heap_profile(&file)?)).await
+    }
+
+    /// Returns the current allocator statistics of the node.
+    pub fn debug_mem_stats(&self) -> Result<MemStats, Eth::Error> {
+        profiling::mem_stats().map_err(Into::into)
+    }
+
+    /// Sets the global log level of the node's stdout and file logs.
+    ///
+    /// The level follows geth's verbosity levels: 0 = silent, 1 = error, 2 = warn, 3 = info,
+    /// 4 = debug and 5 = trace.
+    pub fn debug_verbosity(&self, level: usize) -> Result<(), Eth::Error> {
+        let level = match level {
+            0 => LevelFilter::OFF,
+            1 => LevelFilter::ERROR,
+            2 => LevelFilter::WARN,
+            3 => LevelFilter::INFO,
+            4 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        };
+        Self::log_filter()?
+            .set_verbosity(level)
+            .map_err(|err| EthApiError::Internal(RethError::msg(err)).into())
+    }
+
+    /// Replaces the per-target log directives of the node's stdout and file logs, e.g.
+    /// `engine::tree=trace,net=debug`.
+    pub fn debug_vmodule(&self, pattern: &str) -> Result<(), Eth::Error> {
+        Self::log_filter()?
+            .set_vmodule(pattern)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()).into())
+    }
+
+    /// Returns the handle to the log filters of the node.
+    fn log_filter() -> Result<&'static LogFilterHandle, EthApiError> {
+        log_filter_handle().ok_or(EthApiError::Unsupported("logging is not configured by the node"))
+    }
+
+    /// Returns the address with the given hash, if its preimage was recorded.
+    fn address_preimage(&self, hashed_address: B256) -> Result<Option<Address>, EthApiError> {
+        Ok(self
+            .provider()
+            .preimage(hashed_address)?
+            .filter(|preimage| preimage.len() == Address::len_bytes())
+            .map(|preimage| Address::from_slice(&preimage)))
+    }
+
+    /// Returns the storage key with the given hash, if its preimage was recorded.
+    fn storage_key_preimage(&self, hashed_slot: B256) -> Result<Option<B256>, EthApiError> {
+        Ok(self
+            .provider()
+            .preimage(hashed_slot)?
+            .filter(|preimage| preimage.len() == B256::len_bytes())
+            .map(|preimage| B256::from_slice(&preimage)))
+    }
+
+    /// Returns the addresses of all accounts that were modified in the blocks `start..=end`, in
+    /// ascending order.
+    ///
+    /// Modified accounts are read from the account changesets. Blocks whose changesets were pruned
+    /// are re-executed instead, which requires the state of their parent to be available.
+    pub async fn debug_get_modified_accounts(
+        &self,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        if start > end {
+            return Err(EthApiError::InvalidParams(format!(
+                "start block {start} is after end block {end}"
+            ))
+            .into())
+        }
+        let max_blocks = self.inner.config.max_modified_accounts_blocks;
+        if end - start >= max_blocks {
+            return Err(EthApiError::InvalidParams(format!(
+                "block range exceeds the limit of {max_blocks} blocks"
+            ))
+            .into())
+        }
+
+        let best_block = self.provider().best_block_number().map_err(Eth::Error::from_eth_err)?;
+        if end > best_block {
+            return Err(EthApiError::HeaderNotFound(end.into()).into())
+        }
+
+        let pruned_block = self
+            .provider()
+            .get_prune_checkpoint(PruneSegment::AccountHistory)
+            .map_err(Eth::Error::from_eth_err)?
+            .and_then(|checkpoint| checkpoint.block_number);
+        let changesets_start = pruned_block.map_or(start, |pruned| pruned.saturating_add(1));
+
+        let mut modified = BTreeSet::new();
+        for number in start..changesets_start.min(end + 1) {
+            modified.extend(self.modified_accounts_by_execution(number).await?);
+        }
+        if changesets_start <= end {
+            let addresses = self
+                .eth_api()
+                .spawn_blocking_io(move |this| {
+                    let mut addresses = Vec::new();
+                    for number in changesets_start.max(start)..=end {
+                        let changeset = this
+                            .provider()
+                            .account_block_changeset(number)
+                            .map_err(Eth::Error::from_eth_err)?;
+                        addresses.extend(changeset.into_iter().map(|change| change.address));
+                    }
+                    Ok(addresses)
+                })
+                .await?;
+            modified.extend(addresses);
+        }
+
+        Ok(modified.into_iter().collect())
+    }
+
+    /// Re-executes the given block and returns the addresses of all accounts it modified.
+    async fn modified_accounts_by_execution(
+        &self,
+        number: BlockNumber,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        let block = self
+            .eth_api()
+            .recovered_block(number.into())
+            .await?
+            .ok_or(EthApiError::HeaderNotFound(number.into()))?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let db = StateProviderDatabase::new(&state);
+                let output = this
+                    .eth_api()
+                    .evm_config()
+                    .batch_executor(db)
+                    .execute(&block)
+                    .map_err(|err| EthApiError::Internal(err.into()))?;
+
+                Ok(output
+                    .state
+                    .state
+                    .into_iter()
+                    .filter(|(_, account)| account.is_info_changed())
+                    .map(|(address, _)| address)
+                    .collect())
+            })
+            .await
+    }
+
+    /// Returns the addresses of all accounts that were modified in the blocks between the given
+    /// hashes, both inclusive.
+    ///
+    /// See [`Self::debug_get_modified_accounts`].
+    pub async fn debug_get_modified_accounts_by_hash(
+        &self,
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        let block_number = |hash: B256| -> Result<BlockNumber, Eth::Error> {
+            self.provider()
+                .block_number(hash)
+                .map_err(Eth::Error::from_eth_err)?
+                .ok_or_else(|| Eth::Error::from_eth_err(EthApiError::HeaderNotFound(hash.into())))
+        };
+        let start = block_number(start_hash)?;
+        let end = end_hash.map(block_number).transpose()?.unwrap_or(start);
+
+        self.debug_get_modified_accounts(start, end).await
+    }
+
+    /// Re-executes the block with the given hash and returns the state root after each
+    /// transaction.
+    ///
+    /// The block is looked up in the chain first and in the [`BadBlockStore`] second. If a
+    /// transaction hash is configured, execution stops after that transaction.
+    pub async fn debug_intermediate_roots(
+        &self,
+        block_hash: B256,
+        opts: IntermediateRootsConfig,
+    ) -> Result<Vec<B256>, Eth::Error> {
+        let block = match self.eth_api().recovered_block(block_hash.into()).await? {
+            Some(block) => block,
+            None => Arc::new(self.bad_block(block_hash)?),
+        };
+
+        let IntermediateRootsConfig { tx_hash } = opts;
+        if let Some(tx_hash) = tx_hash {
+            if !block.body().contains_transaction(&tx_hash) {
+                return Err(EthApiError::InvalidParams(format!(
+                    "transaction {tx_hash} not found in block {block_hash}"
+                ))
+                .into())
+            }
+        }
+
+        let evm_env = self.eth_api().evm_config().evm_env(block.header());
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let mut roots = Vec::with_capacity(block.body().transaction_count());
+                let mut db = State::builder()
+                    .with_database(StateProviderDatabase::new(&state))
+                    .with_bundle_update()
+                    .build();
+
+                this.eth_api().apply_pre_execution_changes(&block, &mut db, &evm_env)?;
+
+                for tx in block.transactions_recovered() {
+                    let hash = *tx.tx_hash();
+                    let tx_env = this.eth_api().evm_config().tx_env(tx);
+                    let res = this.eth_api().transact(&mut db, evm_env.clone(), tx_env)?;
+                    db.commit(res.state);
+                    db.merge_transitions(BundleRetention::PlainState);
+
+                    let hashed_state = state.hashed_post_state(&db.bundle_state);
+                    roots.push(state.state_root(hashed_state).map_err(EthApiError::from)?);
+
+                    if tx_hash == Some(hash) {
+                        break
+                    }
+                }
+
+                Ok(roots)
+            })
+            .await
+    }
+
     /// Executes the configured transaction with the environment on the given database.
     ///
     /// It optionally takes fused inspector ([`TracingInspector::fused`]) to avoid re-creating the
@@ -837,15 +1646,21 @@ where
                 #[cfg(feature = "js-tracer")]
                 GethDebugTracerType::JsTracer(code) => {
                     let config = tracer_config.clone().into_json();
-                    let mut inspector =
-                        revm_inspectors::tracing::js::JsInspector::with_transaction_context(
+                    let js_tracers = &self.inner.js_tracers;
+                    let timeout = js_tracers
+                        .timeout(opts.timeout.as_deref())
+                        .map_err(Eth::Error::from_eth_err)?;
+                    let mut inspector = js_tracers
+                        .inspector(
                             code.clone(),
                             config,
                             transaction_context.unwrap_or_default(),
+                            timeout,
                         )
                         .map_err(Eth::Error::from_eth_err)?;
                     let (res, (evm_env, tx_env)) =
                         self.eth_api().inspect(&mut *db, evm_env, tx_env, &mut inspector)?;
+                    let mut inspector = inspector.into_inner().map_err(Eth::Error::from_eth_err)?;
 
                     let state = res.state.clone();
                     let result = inspector
@@ -969,8 +1784,8 @@ where
     }
 
     /// Handler for `debug_getBadBlocks`
-    async fn bad_blocks(&self) -> RpcResult<Vec<RpcBlock>> {
-        Ok(vec![])
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlockResult>> {
+        Self::bad_blocks(self).map_err(Into::into)
     }
 
     /// Handler for `debug_traceChain`
@@ -1075,16 +1890,28 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_accountRange`
     async fn debug_account_range(
         &self,
-        _block_number: BlockNumberOrTag,
-        _start: Bytes,
-        _max_results: u64,
-        _nocode: bool,
-        _nostorage: bool,
-        _incompletes: bool,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> RpcResult<AccountRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_account_range(
+            self,
+            block_number,
+            start,
+            max_results,
+            nocode,
+            nostorage,
+            incompletes,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
@@ -1099,8 +1926,9 @@ where
         Ok(self.provider().chain_spec().genesis().config.clone())
     }
 
-    async fn debug_chaindb_property(&self, _property: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_chaindbProperty`
+    async fn debug_chaindb_property(&self, property: String) -> RpcResult<ChaindbProperty> {
+        Self::debug_chaindb_property(self, &property).await.map_err(Into::into)
     }
 
     async fn debug_code_by_hash(
@@ -1111,20 +1939,29 @@ where
         Self::debug_code_by_hash(self, hash, block_id).await.map_err(Into::into)
     }
 
-    async fn debug_cpu_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_cpuProfile`
+    async fn debug_cpu_profile(&self, file: String, seconds: u64) -> RpcResult<()> {
+        Self::debug_cpu_profile(self, file.into(), seconds).await.map_err(Into::into)
     }
 
-    async fn debug_db_ancient(&self, _kind: String, _number: u64) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dbAncient`
+    async fn debug_db_ancient(&self, kind: String, number: u64) -> RpcResult<Vec<Vec<Bytes>>> {
+        Self::debug_db_ancient(self, &kind, number).await.map_err(Into::into)
     }
 
-    async fn debug_db_ancients(&self) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dbAncients`
+    async fn debug_db_ancients(&self) -> RpcResult<u64> {
+        Self::debug_db_ancients(self).await.map_err(Into::into)
     }
 
-    async fn debug_db_get(&self, _key: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dbGet`
+    async fn debug_db_get(
+        &self,
+        table: String,
+        key: Bytes,
+        subkey: Option<Bytes>,
+    ) -> RpcResult<Option<Bytes>> {
+        Self::debug_db_get(self, &table, key, subkey).await.map_err(Into::into)
     }
 
     async fn debug_dump_block(&self, _number: BlockId) -> RpcResult<()> {
@@ -1151,44 +1988,58 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_getModifiedAccountsByHash`
     async fn debug_get_modified_accounts_by_hash(
         &self,
-        _start_hash: B256,
-        _end_hash: B256,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_get_modified_accounts_by_hash(self, start_hash, end_hash)
+            .await
+            .map_err(Into::into)
     }
 
+    /// Handler for `debug_getModifiedAccountsByNumber`
     async fn debug_get_modified_accounts_by_number(
         &self,
-        _start_number: u64,
-        _end_number: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_number: u64,
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_get_modified_accounts(self, start_number, end_number.unwrap_or(start_number))
+            .await
+            .map_err(Into::into)
     }
 
     async fn debug_go_trace(&self, _file: String, _seconds: u64) -> RpcResult<()> {
         Ok(())
     }
 
+    /// Handler for `debug_intermediateRoots`
     async fn debug_intermediate_roots(
         &self,
-        _block_hash: B256,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<IntermediateRootsConfig>,
+    ) -> RpcResult<Vec<B256>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_intermediate_roots(self, block_hash, opts.unwrap_or_default())
+            .await
+            .map_err(Into::into)
     }
 
-    async fn debug_mem_stats(&self) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_memStats`
+    async fn debug_mem_stats(&self) -> RpcResult<MemStats> {
+        Self::debug_mem_stats(self).map_err(Into::into)
     }
 
     async fn debug_mutex_profile(&self, _file: String, _nsec: u64) -> RpcResult<()> {
         Ok(())
     }
 
-    async fn debug_preimage(&self, _hash: B256) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_preimage`
+    async fn debug_preimage(&self, hash: B256) -> RpcResult<Bytes> {
+        Self::debug_preimage(self, hash).map_err(Into::into)
     }
 
     async fn debug_print_block(&self, _number: u64) -> RpcResult<()> {
@@ -1207,8 +2058,9 @@ where
         Ok(())
     }
 
-    async fn debug_set_head(&self, _number: u64) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_setHead`
+    async fn debug_set_head(&self, number: u64) -> RpcResult<()> {
+        Self::debug_set_head(self, number).await.map_err(Into::into)
     }
 
     async fn debug_set_mutex_profile_fraction(&self, _rate: i32) -> RpcResult<()> {
@@ -1223,24 +2075,40 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_standardTraceBadBlockToFile`
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        _block: BlockNumberOrTag,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>> {
+        let _permit = self.acquire_trace_permit().await;
+        let files = Self::debug_standard_trace_bad_block_to_file(
+            self,
+            block_hash,
+            opts.unwrap_or_default(),
+        )
+        .await
+        .map_err(Into::into)?;
+        Ok(files.into_iter().map(|path| path.display().to_string()).collect())
     }
 
+    /// Handler for `debug_standardTraceBlockToFile`
     async fn debug_standard_trace_block_to_file(
         &self,
-        _block: BlockNumberOrTag,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>> {
+        let _permit = self.acquire_trace_permit().await;
+        let files =
+            Self::debug_standard_trace_block_to_file(self, block_hash, opts.unwrap_or_default())
+                .await
+                .map_err(Into::into)?;
+        Ok(files.into_iter().map(|path| path.display().to_string()).collect())
     }
 
-    async fn debug_start_cpu_profile(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_startCPUProfile`
+    async fn debug_start_cpu_profile(&self, file: String) -> RpcResult<()> {
+        Self::debug_start_cpu_profile(self, file.into()).map_err(Into::into)
     }
 
     async fn debug_start_go_trace(&self, _file: String) -> RpcResult<()> {
@@ -1255,7 +2123,9 @@ where
         Self::debug_state_root_with_updates(self, hashed_state, block_id).await.map_err(Into::into)
     }
 
+    /// Handler for `debug_stopCPUProfile`
     async fn debug_stop_cpu_profile(&self) -> RpcResult<()> {
+        Self::debug_stop_cpu_profile(self).await.map_err(Into::into)?;
         Ok(())
     }
 
@@ -1263,39 +2133,57 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
-    }
-
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            max_result,
+        )
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Handler for `debug_traceBadBlock`
     async fn debug_trace_bad_block(
         &self,
-        _block_hash: B256,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_trace_bad_block(self, block_hash, opts.unwrap_or_default())
+            .await
+            .map_err(Into::into)
     }
 
-    async fn debug_verbosity(&self, _level: usize) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_verbosity`
+    async fn debug_verbosity(&self, level: usize) -> RpcResult<()> {
+        Self::debug_verbosity(self, level).map_err(Into::into)
     }
 
-    async fn debug_vmodule(&self, _pattern: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_vmodule`
+    async fn debug_vmodule(&self, pattern: String) -> RpcResult<()> {
+        Self::debug_vmodule(self, &pattern).map_err(Into::into)
     }
 
     async fn debug_write_block_profile(&self, _file: String) -> RpcResult<()> {
         Ok(())
     }
 
-    async fn debug_write_mem_profile(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_writeMemProfile`
+    async fn debug_write_mem_profile(&self, file: String) -> RpcResult<()> {
+        Self::debug_write_mem_profile(self, file.into()).await.map_err(Into::into)
     }
 
     async fn debug_write_mutex_profile(&self, _file: String) -> RpcResult<()> {
@@ -1320,4 +2208,84 @@ struct DebugApiInner<Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
+    /// Store of recently rejected blocks
+    bad_blocks: BadBlockStore,
+    /// Handle to rewind the canonical chain, if the node runs an engine
+    engine_set_head: Option<Arc<dyn EngineSetHead>>,
+    /// Runtime for user supplied JS tracers
+    #[cfg(feature = "js-tracer")]
+    js_tracers: crate::js_tracer::JsTracers,
+    /// Settings of the `debug` namespace
+    config: DebugApiConfig,
+}
+
+/// Settings for the `debug` namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugApiConfig {
+    /// Directory that `debug_standardTrace*ToFile` writes its traces to.
+    ///
+    /// Defaults to the system's temporary directory.
+    pub trace_dir: Option<PathBuf>,
+    /// Maximum number of blocks for `debug_getModifiedAccountsBy*` requests.
+    pub max_modified_accounts_blocks: u64,
+    /// Limits applied to JS tracers.
+    pub js_tracer: JsTracerConfig,
+}
+
+impl DebugApiConfig {
+    /// Creates a new config with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            trace_dir: None,
+            max_modified_accounts_blocks: DEFAULT_MAX_MODIFIED_ACCOUNTS_BLOCKS,
+            js_tracer: JsTracerConfig::new(),
+        }
+    }
+}
+
+impl Default for DebugApiConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Limits applied to user supplied JS tracers.
+///
+/// Only takes effect if the `js-tracer` feature is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsTracerConfig {
+    /// Maximum time a single transaction may spend in the tracer.
+    ///
+    /// The `timeout` tracing option of a request can only shorten it.
+    pub timeout: Duration,
+    /// Maximum number of loop iterations of a single tracer invocation.
+    pub loop_iteration_limit: u64,
+    /// Maximum call depth of the tracer's functions.
+    pub recursion_limit: usize,
+    /// Maximum number of values on the JS VM stack.
+    ///
+    /// Together with the other limits this bounds the memory a tracer can use. Objects the tracer
+    /// keeps across invocations are not accounted for.
+    pub stack_size_limit: usize,
+    /// Number of tracer sources whose evaluation outcome is cached.
+    pub cache_size: u32,
+}
+
+impl JsTracerConfig {
+    /// Creates a new config with the default limits.
+    pub const fn new() -> Self {
+        Self {
+            timeout: DEFAULT_JS_TRACER_TIMEOUT,
+            loop_iteration_limit: 200_000,
+            recursion_limit: 10_000,
+            stack_size_limit: 10 * 1024,
+            cache_size: 256,
+        }
+    }
+}
+
+impl Default for JsTracerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -31,6 +31,7 @@ pub struct EthApiBuilder<N: RpcNodeCore, Rpc, NextEnv = ()> {
     gas_cap: GasCap,
     max_simulate_blocks: u64,
     eth_proof_window: u64,
+    eth_proof_max_depth: Option<u64>,
     fee_history_cache_config: FeeHistoryCacheConfig,
     proof_permits: usize,
     eth_state_cache_config: EthStateCacheConfig,
@@ -69,6 +70,7 @@ impl<N: RpcNodeCore, Rpc, NextEnv> EthApiBuilder<N, Rpc, NextEnv> {
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             fee_history_cache_config,
             proof_permits,
             eth_state_cache_config,
@@ -85,6 +87,7 @@ impl<N: RpcNodeCore, Rpc, NextEnv> EthApiBuilder<N, Rpc, NextEnv> {
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             fee_history_cache_config,
             proof_permits,
             eth_state_cache_config,
@@ -114,6 +117,7 @@ where
             gas_cap: GasCap::default(),
             max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
             eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+            eth_proof_max_depth: None,
             blocking_task_pool: None,
             fee_history_cache_config: FeeHistoryCacheConfig::default(),
             proof_permits: DEFAULT_PROOF_PERMITS,
@@ -146,6 +150,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             fee_history_cache_config,
             proof_permits,
             eth_state_cache_config,
@@ -162,6 +167,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             fee_history_cache_config,
             proof_permits,
             eth_state_cache_config,
@@ -185,6 +191,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             fee_history_cache_config,
             proof_permits,
             eth_state_cache_config,
@@ -201,6 +208,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             fee_history_cache_config,
             proof_permits,
             eth_state_cache_config,
@@ -260,6 +268,15 @@ where
         self
     }
 
+    /// Sets the maximum number of blocks into the past for generating state proofs beyond the
+    /// proof window.
+    ///
+    /// Unless set, proofs are only generated within the proof window.
+    pub const fn eth_proof_max_depth(mut self, eth_proof_max_depth: Option<u64>) -> Self {
+        self.eth_proof_max_depth = eth_proof_max_depth;
+        self
+    }
+
     /// Sets the blocking task pool.
     pub fn blocking_task_pool(mut self, blocking_task_pool: BlockingTaskPool) -> Self {
         self.blocking_task_pool = Some(blocking_task_pool);
@@ -304,6 +321,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             blocking_task_pool,
             fee_history_cache_config,
             proof_permits,
@@ -337,6 +355,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             blocking_task_pool.unwrap_or_else(|| {
                 BlockingTaskPool::build().expect("failed to build blocking task pool")
             }),
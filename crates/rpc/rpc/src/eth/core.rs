@@ -21,7 +21,7 @@ use reth_rpc_eth_api::{
 };
 use reth_rpc_eth_types::{
     receipt::EthReceiptConverter, EthApiError, EthStateCache, FeeHistoryCache, GasCap,
-    GasPriceOracle, PendingBlock,
+    GasPriceOracle, HistoricalProofs, PendingBlock,
 };
 use reth_storage_api::{noop::NoopProvider, BlockReaderIdExt, ProviderHeader};
 use reth_tasks::{
@@ -143,6 +143,7 @@ where
         gas_cap: impl Into<GasCap>,
         max_simulate_blocks: u64,
         eth_proof_window: u64,
+        eth_proof_max_depth: Option<u64>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache<ProviderHeader<N::Provider>>,
         proof_permits: usize,
@@ -155,6 +156,7 @@ where
             gas_cap,
             max_simulate_blocks,
             eth_proof_window,
+            eth_proof_max_depth,
             blocking_task_pool,
             fee_history_cache,
             TokioTaskExecutor::default().boxed(),
@@ -268,6 +270,8 @@ pub struct EthApiInner<N: RpcNodeCore, Rpc: RpcConvert> {
     max_simulate_blocks: u64,
     /// The maximum number of blocks into the past for generating state proofs.
     eth_proof_window: u64,
+    /// Proofs of state beyond the proof window, if enabled.
+    historical_proofs: Option<HistoricalProofs>,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
@@ -306,6 +310,7 @@ where
         gas_cap: impl Into<GasCap>,
         max_simulate_blocks: u64,
         eth_proof_window: u64,
+        eth_proof_max_depth: Option<u64>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache<ProviderHeader<N::Provider>>,
         task_spawner: Box<dyn TaskSpawner + 'static>,
@@ -335,6 +340,7 @@ where
             gas_cap: gas_cap.into().into(),
             max_simulate_blocks,
             eth_proof_window,
+            historical_proofs: eth_proof_max_depth.map(HistoricalProofs::new),
             starting_block,
             task_spawner,
             pending_block: Default::default(),
@@ -456,6 +462,12 @@ where
         self.eth_proof_window
     }
 
+    /// Returns the proofs of state beyond the proof window, if enabled.
+    #[inline]
+    pub const fn historical_proofs(&self) -> Option<&HistoricalProofs> {
+        self.historical_proofs.as_ref()
+    }
+
     /// Returns reference to [`BlockingTaskGuard`].
     #[inline]
     pub const fn blocking_task_guard(&self) -> &BlockingTaskGuard {
@@ -491,7 +503,8 @@ mod tests {
     use reth_network_api::noop::NoopNetwork;
     use reth_provider::{
         test_utils::{MockEthProvider, NoopProvider},
-        StageCheckpointReader,
+        CallIndexReader, ChangeSetReader, PreimageReader, PruneCheckpointReader, RawDatabaseReader,
+        StageCheckpointReader, StorageChangeSetReader,
     };
     use reth_rpc_eth_api::{node::RpcNodeCoreAdapter, EthApiServer};
     use reth_storage_api::{BlockReader, BlockReaderIdExt, StateProviderFactory};
@@ -514,6 +527,12 @@ mod tests {
             + StateProviderFactory
             + CanonStateSubscriptions<Primitives = reth_ethereum_primitives::EthPrimitives>
             + StageCheckpointReader
+            + ChangeSetReader
+            + StorageChangeSetReader
+            + PruneCheckpointReader
+            + PreimageReader
+            + RawDatabaseReader
+            + CallIndexReader
             + Unpin
             + Clone
             + 'static,
@@ -5,6 +5,7 @@ use reth_rpc_eth_api::{
     helpers::{EthState, LoadState},
     RpcNodeCore,
 };
+use reth_rpc_eth_types::HistoricalProofs;
 
 use crate::EthApi;
 
@@ -16,6 +17,10 @@ where
     fn max_proof_window(&self) -> u64 {
         self.inner.eth_proof_window()
     }
+
+    fn historical_proofs(&self) -> Option<&HistoricalProofs> {
+        self.inner.historical_proofs()
+    }
 }
 
 impl<N, Rpc> LoadState for EthApi<N, Rpc>
@@ -7,6 +7,7 @@ pub mod filter;
 pub mod helpers;
 pub mod pubsub;
 pub mod sim_bundle;
+pub mod sim_session;
 
 /// Implementation of `eth` namespace API.
 pub use builder::EthApiBuilder;
@@ -14,6 +15,7 @@ pub use bundle::EthBundle;
 pub use core::{EthApi, EthApiFor};
 pub use filter::EthFilter;
 pub use pubsub::EthPubSub;
+pub use sim_session::EthSimulationSessions;
 
 pub use helpers::{signer::DevSigner, sync_listener::SyncListener};
 
@@ -0,0 +1,361 @@
+//! `reth` simulation sessions implementation.
+
+use alloy_eips::BlockId;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types_eth::{
+    simulate::{SimulatePayload, SimulatedBlock},
+    state::EvmOverrides,
+};
+use alloy_serde::JsonStorageKey;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use parking_lot::Mutex;
+use reth_errors::RethError;
+use reth_evm::ConfigureEvm;
+use reth_primitives_traits::SealedHeader;
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::{CacheState, State},
+};
+use reth_rpc_api::RethSimulationApiServer;
+use reth_rpc_convert::RpcTxReq;
+use reth_rpc_eth_api::{helpers::EthCall, FromEthApiError, RpcBlock, RpcNodeCore};
+use reth_rpc_eth_types::{
+    cache::db::StateProviderTraitObjWrapper, error::ensure_success, EthApiError,
+    EthSubscriptionIdProvider,
+};
+use reth_storage_api::{BlockReaderIdExt, ProviderHeader};
+use reth_tasks::TaskSpawner;
+use revm::Database;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::MissedTickBehavior;
+use tracing::trace;
+
+/// The state of a simulation session, on top of the state of its base block.
+type SessionState<'a> = State<StateProviderDatabase<StateProviderTraitObjWrapper<'a>>>;
+
+/// `reth` simulation sessions implementation.
+///
+/// A session keeps the state changes of the blocks simulated with it, so that successive
+/// `eth_simulateV1` requests continue from the previous ones instead of re-executing all blocks.
+/// Sessions that have not been used for longer than the configured ttl are dropped.
+pub struct EthSimulationSessions<Eth: RpcNodeCore> {
+    /// All nested fields bundled together.
+    inner: Arc<EthSimulationSessionsInner<Eth>>,
+}
+
+impl<Eth> EthSimulationSessions<Eth>
+where
+    Eth: RpcNodeCore + 'static,
+{
+    /// Creates a new instance that drops sessions after they have not been used for `ttl`.
+    ///
+    /// This also spawns a task that periodically drops stale sessions.
+    pub fn new(eth_api: Eth, ttl: Duration, task_spawner: Box<dyn TaskSpawner>) -> Self {
+        let inner = EthSimulationSessionsInner {
+            eth_api,
+            sessions: Default::default(),
+            id_provider: EthSubscriptionIdProvider::default(),
+            ttl,
+        };
+        let sessions = Self { inner: Arc::new(inner) };
+
+        let this = sessions.clone();
+        task_spawner.spawn_critical(
+            "reth-simulation-sessions_stale-sessions-clean",
+            Box::pin(async move {
+                this.watch_and_clear_stale_sessions().await;
+            }),
+        );
+
+        sessions
+    }
+
+    /// Access the underlying `Eth` API.
+    pub fn eth_api(&self) -> &Eth {
+        &self.inner.eth_api
+    }
+
+    /// Endless future that [`Self::clear_stale_sessions`] every `ttl` interval.
+    async fn watch_and_clear_stale_sessions(&self) {
+        let mut interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + self.inner.ttl, self.inner.ttl);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.clear_stale_sessions(Instant::now());
+        }
+    }
+
+    /// Drops all sessions that have not been used for longer than the configured ttl at the given
+    /// instant.
+    pub fn clear_stale_sessions(&self, now: Instant) {
+        trace!(target: "rpc::reth", "clear stale simulation sessions");
+        self.inner.sessions.lock().retain(|id, session| {
+            let is_valid = (now - session.last_used) < self.inner.ttl;
+
+            if !is_valid {
+                trace!(target: "rpc::reth", "evict simulation session with id: {:?}", id);
+            }
+
+            is_valid
+        })
+    }
+
+    /// Drops the session with the given id and returns `true` if it existed.
+    pub fn drop_session(&self, id: &str) -> bool {
+        self.inner.sessions.lock().remove(id).is_some()
+    }
+
+    /// Returns the session with the given id and marks it as used.
+    fn session(
+        &self,
+        id: &str,
+    ) -> Result<SharedSession<ProviderHeader<Eth::Provider>>, EthApiError> {
+        let mut sessions = self.inner.sessions.lock();
+        let active = sessions
+            .get_mut(id)
+            .ok_or_else(|| EthApiError::InvalidParams(format!("session not found: {id}")))?;
+        active.last_used = Instant::now();
+        Ok(active.session.clone())
+    }
+}
+
+impl<Eth> EthSimulationSessions<Eth>
+where
+    Eth: EthCall + 'static,
+{
+    /// Creates a new session on top of the given block and returns its id.
+    pub async fn new_session(&self, block_id: Option<BlockId>) -> Result<String, Eth::Error> {
+        let block_id = block_id.unwrap_or_default();
+        let parent = self
+            .eth_api()
+            .provider()
+            .sealed_header_by_id(block_id)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or(EthApiError::HeaderNotFound(block_id))?;
+
+        // ensure the state of the block is available
+        self.eth_api().spawn_with_state_at_block(parent.hash().into(), |_| Ok(())).await?;
+
+        let id = match jsonrpsee::core::traits::IdProvider::next_id(&self.inner.id_provider) {
+            jsonrpsee::types::SubscriptionId::Num(n) => n.to_string(),
+            jsonrpsee::types::SubscriptionId::Str(s) => s.into_owned(),
+        };
+        let session = SimulationSession {
+            base_block: parent.hash(),
+            parent,
+            cache: CacheState::default(),
+            block_hashes: BTreeMap::new(),
+        };
+        self.inner.sessions.lock().insert(
+            id.clone(),
+            ActiveSession {
+                last_used: Instant::now(),
+                session: Arc::new(tokio::sync::Mutex::new(session)),
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Simulates the given blocks on top of the blocks already simulated in the session.
+    ///
+    /// The state changes are only kept if all blocks were simulated successfully.
+    pub async fn simulate(
+        &self,
+        id: &str,
+        payload: SimulatePayload<RpcTxReq<Eth::NetworkTypes>>,
+    ) -> Result<Vec<SimulatedBlock<RpcBlock<Eth::NetworkTypes>>>, Eth::Error> {
+        self.with_session_state(id, true, move |eth_api, db, parent| {
+            eth_api.simulate_blocks(db, parent, payload)
+        })
+        .await
+    }
+
+    /// Executes the call request on top of the blocks simulated in the session.
+    pub async fn call(
+        &self,
+        id: &str,
+        request: RpcTxReq<Eth::NetworkTypes>,
+    ) -> Result<Bytes, Eth::Error> {
+        self.with_session_state(id, false, move |eth_api, db, parent| {
+            let evm_env = eth_api
+                .evm_config()
+                .next_evm_env(parent, &eth_api.next_env_attributes(parent)?)
+                .map_err(RethError::other)
+                .map_err(Eth::Error::from_eth_err)?;
+            let (evm_env, tx_env) =
+                eth_api.prepare_call_env(evm_env, request, db, EvmOverrides::default())?;
+            let res = eth_api.transact(db, evm_env, tx_env)?;
+            ensure_success(res.result)
+        })
+        .await
+    }
+
+    /// Returns the balance of the account after the blocks simulated in the session.
+    pub async fn balance(&self, id: &str, address: Address) -> Result<U256, Eth::Error> {
+        self.with_session_state(id, false, move |_, db, _| {
+            Ok(db.basic(address)?.map(|account| account.balance).unwrap_or_default())
+        })
+        .await
+    }
+
+    /// Returns the value of the storage slot after the blocks simulated in the session.
+    pub async fn storage_at(
+        &self,
+        id: &str,
+        address: Address,
+        index: JsonStorageKey,
+    ) -> Result<B256, Eth::Error> {
+        self.with_session_state(id, false, move |_, db, _| {
+            let index = U256::from_be_bytes(index.as_b256().0);
+            Ok(B256::new(db.storage(address, index)?.to_be_bytes()))
+        })
+        .await
+    }
+
+    /// Executes the closure with the state of the session and the header of its last simulated
+    /// block on a new task.
+    ///
+    /// If `commit` is set, the changes to the state and header are only kept if the closure
+    /// succeeds. Otherwise, the closure must not change the state and only the state loaded from
+    /// the database is kept.
+    async fn with_session_state<F, R>(&self, id: &str, commit: bool, f: F) -> Result<R, Eth::Error>
+    where
+        F: FnOnce(
+                &Eth,
+                &mut SessionState<'_>,
+                &mut SealedHeader<ProviderHeader<Eth::Provider>>,
+            ) -> Result<R, Eth::Error>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        // requests of the same session are applied one after another
+        let mut session = self.session(id)?.lock_owned().await;
+        let eth_api = self.eth_api().clone();
+
+        self.eth_api()
+            .spawn_with_state_at_block(session.base_block.into(), move |state| {
+                let cache =
+                    if commit { session.cache.clone() } else { std::mem::take(&mut session.cache) };
+                let mut db = State::builder()
+                    .with_database(StateProviderDatabase::new(state))
+                    .with_cached_prestate(cache)
+                    .with_block_hashes(session.block_hashes.clone())
+                    .build();
+                let mut parent = session.parent.clone();
+
+                let res = f(&eth_api, &mut db, &mut parent);
+                if res.is_ok() || !commit {
+                    session.cache = db.cache;
+                    session.block_hashes = db.block_hashes;
+                    session.parent = parent;
+                }
+                res
+            })
+            .await
+    }
+}
+
+impl<Eth: RpcNodeCore> Clone for EthSimulationSessions<Eth> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<Eth: RpcNodeCore> std::fmt::Debug for EthSimulationSessions<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EthSimulationSessions").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<Eth> RethSimulationApiServer<RpcTxReq<Eth::NetworkTypes>, RpcBlock<Eth::NetworkTypes>>
+    for EthSimulationSessions<Eth>
+where
+    Eth: EthCall + 'static,
+{
+    /// Handler for `reth_newSimulation`
+    async fn reth_new_simulation(&self, block_id: Option<BlockId>) -> RpcResult<String> {
+        Ok(self.new_session(block_id).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `reth_simulate`
+    async fn reth_simulate(
+        &self,
+        id: String,
+        payload: SimulatePayload<RpcTxReq<Eth::NetworkTypes>>,
+    ) -> RpcResult<Vec<SimulatedBlock<RpcBlock<Eth::NetworkTypes>>>> {
+        Ok(self.simulate(&id, payload).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `reth_simulationCall`
+    async fn reth_simulation_call(
+        &self,
+        id: String,
+        request: RpcTxReq<Eth::NetworkTypes>,
+    ) -> RpcResult<Bytes> {
+        Ok(self.call(&id, request).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `reth_simulationGetBalance`
+    async fn reth_simulation_get_balance(&self, id: String, address: Address) -> RpcResult<U256> {
+        Ok(self.balance(&id, address).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `reth_simulationGetStorageAt`
+    async fn reth_simulation_get_storage_at(
+        &self,
+        id: String,
+        address: Address,
+        index: JsonStorageKey,
+    ) -> RpcResult<B256> {
+        Ok(self.storage_at(&id, address, index).await.map_err(Into::into)?)
+    }
+
+    /// Handler for `reth_dropSimulation`
+    async fn reth_drop_simulation(&self, id: String) -> RpcResult<bool> {
+        Ok(self.drop_session(&id))
+    }
+}
+
+/// Container type for `EthSimulationSessions`
+struct EthSimulationSessionsInner<Eth: RpcNodeCore> {
+    /// The `eth` API that simulates the blocks.
+    eth_api: Eth,
+    /// All active sessions, by id.
+    sessions: Mutex<HashMap<String, ActiveSession<ProviderHeader<Eth::Provider>>>>,
+    /// Id provider for new sessions.
+    id_provider: EthSubscriptionIdProvider,
+    /// Duration since the last use of a session, after which it is dropped.
+    ttl: Duration,
+}
+
+/// A session that is currently installed.
+struct ActiveSession<H> {
+    /// The last time the session was used.
+    last_used: Instant,
+    /// The session, locked while a request is applied to it.
+    session: SharedSession<H>,
+}
+
+/// A session that is shared between its requests.
+type SharedSession<H> = Arc<tokio::sync::Mutex<SimulationSession<H>>>;
+
+/// The state of the blocks simulated in a session.
+struct SimulationSession<H> {
+    /// The hash of the block the session was created on.
+    base_block: B256,
+    /// The header of the last simulated block, or the base block if nothing was simulated yet.
+    parent: SealedHeader<H>,
+    /// All accounts that were loaded or changed on top of the base block.
+    cache: CacheState,
+    /// The block hashes that were loaded or overridden.
+    block_hashes: BTreeMap<u64, B256>,
+}
@@ -0,0 +1,58 @@
+use crate::AnvilApi;
+use alloy_primitives::U256;
+use alloy_rpc_types_anvil::MineOptions;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_rpc_api::{AnvilApiServer, GanacheApiServer};
+use reth_rpc_eth_api::{
+    helpers::{EthBlocks, SpawnBlocking},
+    FullEthApiTypes, RpcNodeCore,
+};
+
+/// `evm` API implementation, as served by Ganache.
+///
+/// This shares the chain controls of the [`AnvilApi`], so that Truffle test suites can run
+/// against a reth dev node.
+#[derive(Debug, Clone)]
+pub struct GanacheApi<Eth> {
+    anvil: AnvilApi<Eth>,
+}
+
+impl<Eth> GanacheApi<Eth> {
+    /// Creates a new instance of `GanacheApi` on top of the given [`AnvilApi`].
+    pub const fn new(anvil: AnvilApi<Eth>) -> Self {
+        Self { anvil }
+    }
+}
+
+#[async_trait]
+impl<Eth> GanacheApiServer for GanacheApi<Eth>
+where
+    Eth: RpcNodeCore + FullEthApiTypes + EthBlocks + SpawnBlocking,
+{
+    /// Handler for `evm_increaseTime`
+    async fn evm_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        self.anvil.anvil_increase_time(seconds).await
+    }
+
+    /// Handler for `evm_mine`
+    async fn evm_mine(&self, opts: Option<MineOptions>) -> RpcResult<String> {
+        self.anvil.mine_with_options(opts).await.map_err(Into::into)?;
+        Ok("0x0".to_string())
+    }
+
+    /// Handler for `evm_revert`
+    async fn evm_revert(&self, snapshot_id: U256) -> RpcResult<bool> {
+        self.anvil.anvil_revert(snapshot_id).await
+    }
+
+    /// Handler for `evm_setTime`
+    async fn evm_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        self.anvil.anvil_set_time(timestamp).await
+    }
+
+    /// Handler for `evm_snapshot`
+    async fn evm_snapshot(&self) -> RpcResult<U256> {
+        self.anvil.anvil_snapshot().await
+    }
+}
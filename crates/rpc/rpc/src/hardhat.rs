@@ -0,0 +1,114 @@
+use crate::AnvilApi;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types_anvil::{Forking, Metadata};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_rpc_api::{AnvilApiServer, HardhatApiServer};
+use reth_rpc_eth_api::{
+    helpers::{EthBlocks, SpawnBlocking},
+    FullEthApiTypes, RpcNodeCore,
+};
+use reth_rpc_eth_types::EthApiError;
+
+/// `hardhat` API implementation.
+///
+/// This shares the chain controls of the [`AnvilApi`], so that test suites written against
+/// Hardhat Network can run against a reth dev node.
+#[derive(Debug, Clone)]
+pub struct HardhatApi<Eth> {
+    anvil: AnvilApi<Eth>,
+}
+
+impl<Eth> HardhatApi<Eth> {
+    /// Creates a new instance of `HardhatApi` on top of the given [`AnvilApi`].
+    pub const fn new(anvil: AnvilApi<Eth>) -> Self {
+        Self { anvil }
+    }
+}
+
+#[async_trait]
+impl<Eth> HardhatApiServer for HardhatApi<Eth>
+where
+    Eth: RpcNodeCore + FullEthApiTypes + EthBlocks + SpawnBlocking,
+{
+    /// Handler for `hardhat_dropTransaction`
+    async fn hardhat_drop_transaction(&self, tx_hash: B256) -> RpcResult<bool> {
+        Ok(self.anvil.anvil_drop_transaction(tx_hash).await?.is_some())
+    }
+
+    async fn hardhat_impersonate_account(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_impersonateAccount is not supported").into())
+    }
+
+    /// Handler for `hardhat_getAutomine`
+    async fn hardhat_get_automine(&self) -> RpcResult<bool> {
+        self.anvil.anvil_get_automine().await
+    }
+
+    /// Handler for `hardhat_metadata`
+    async fn hardhat_metadata(&self) -> RpcResult<Metadata> {
+        self.anvil.anvil_metadata().await
+    }
+
+    /// Handler for `hardhat_mine`
+    async fn hardhat_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        self.anvil.anvil_mine(blocks, interval).await
+    }
+
+    async fn hardhat_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_reset is not supported").into())
+    }
+
+    /// Handler for `hardhat_setBalance`
+    async fn hardhat_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.anvil.anvil_set_balance(address, balance).await
+    }
+
+    /// Handler for `hardhat_setCode`
+    async fn hardhat_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.anvil.anvil_set_code(address, code).await
+    }
+
+    async fn hardhat_set_coinbase(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_setCoinbase is not supported").into())
+    }
+
+    async fn hardhat_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_setLoggingEnabled is not supported").into())
+    }
+
+    async fn hardhat_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_setMinGasPrice is not supported").into())
+    }
+
+    async fn hardhat_set_next_block_base_fee_per_gas(
+        &self,
+        _base_fee_per_gas: U256,
+    ) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_setNextBlockBaseFeePerGas is not supported").into())
+    }
+
+    async fn hardhat_set_prev_randao(&self, _prev_randao: B256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_setPrevRandao is not supported").into())
+    }
+
+    /// Handler for `hardhat_setNonce`
+    async fn hardhat_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        self.anvil.anvil_set_nonce(address, nonce).await
+    }
+
+    /// Handler for `hardhat_setStorageAt`
+    async fn hardhat_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<()> {
+        self.anvil.anvil_set_storage_at(address, slot, value).await?;
+        Ok(())
+    }
+
+    async fn hardhat_stop_impersonating_account(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("hardhat_stopImpersonatingAccount is not supported").into())
+    }
+}
@@ -0,0 +1,246 @@
+//! Sandboxed runtime for geth style JS tracers.
+
+use crate::debug::JsTracerConfig;
+use alloy_primitives::{keccak256, Address, Log, B256, U256};
+use parking_lot::Mutex;
+use reth_rpc_eth_types::EthApiError;
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, InstructionResult, Interpreter,
+    },
+    Inspector,
+};
+use revm_inspectors::tracing::{
+    js::{JsInspector, JsInspectorError, RuntimeLimits},
+    TransactionContext,
+};
+use schnellru::{ByLength, LruMap};
+use std::time::{Duration, Instant};
+
+/// Creates the [`JsInspector`]s for `debug_trace*` requests.
+///
+/// The compiled form of a tracer is bound to the (`!Send`) JS context it was evaluated in, so
+/// every traced transaction gets a fresh context. What is shared across requests is the outcome
+/// of evaluating a tracer source, keyed by its hash: a tracer that is known to be broken is
+/// rejected before any state is replayed, and a known good one skips the upfront evaluation.
+pub(crate) struct JsTracers {
+    /// Limits applied to every tracer.
+    config: JsTracerConfig,
+    /// Evaluation outcome per tracer source hash, `Some` holds the error of a broken tracer.
+    compiled: Mutex<LruMap<B256, Option<String>, ByLength>>,
+}
+
+impl JsTracers {
+    /// Creates a new instance with the given limits.
+    pub(crate) fn new(config: JsTracerConfig) -> Self {
+        Self { compiled: Mutex::new(LruMap::new(ByLength::new(config.cache_size))), config }
+    }
+
+    /// Returns the time a single transaction may spend in the tracer.
+    ///
+    /// The `timeout` tracing option of a request can only shorten the configured timeout.
+    pub(crate) fn timeout(&self, requested: Option<&str>) -> Result<Duration, EthApiError> {
+        let Some(requested) = requested else { return Ok(self.config.timeout) };
+        let requested = humantime::parse_duration(requested)
+            .map_err(|err| EthApiError::InvalidParams(format!("invalid tracer timeout: {err}")))?;
+        Ok(requested.min(self.config.timeout))
+    }
+
+    /// Ensures the given tracer can be evaluated, before any state is replayed for it.
+    pub(crate) fn ensure_valid(
+        &self,
+        code: &str,
+        config: &serde_json::Value,
+    ) -> Result<(), EthApiError> {
+        let hash = keccak256(code);
+        if let Some(outcome) = self.compiled.lock().get(&hash) {
+            return match outcome {
+                Some(err) => Err(EthApiError::InvalidParams(err.clone())),
+                None => Ok(()),
+            }
+        }
+
+        let outcome = match JsInspector::new(code.to_string(), config.clone()) {
+            Ok(_) => None,
+            // these depend on the tracer config or the runtime rather than the source alone
+            Err(
+                err @ (JsInspectorError::JsError(_) |
+                JsInspectorError::SetupCallFailed(_) |
+                JsInspectorError::InvalidJsonConfig(_)),
+            ) => return Err(err.into()),
+            Err(err) => Some(err.to_string()),
+        };
+        self.compiled.lock().insert(hash, outcome.clone());
+
+        match outcome {
+            Some(err) => Err(EthApiError::InvalidParams(err)),
+            None => Ok(()),
+        }
+    }
+
+    /// Instantiates the tracer for a single transaction, bounded by the configured limits and the
+    /// given timeout.
+    pub(crate) fn inspector(
+        &self,
+        code: String,
+        config: serde_json::Value,
+        transaction_context: TransactionContext,
+        timeout: Duration,
+    ) -> Result<TimedInspector<JsInspector>, EthApiError> {
+        let mut inspector =
+            JsInspector::with_transaction_context(code, config, transaction_context)?;
+
+        let mut limits = RuntimeLimits::default();
+        limits.set_loop_iteration_limit(self.config.loop_iteration_limit);
+        limits.set_recursion_limit(self.config.recursion_limit);
+        limits.set_stack_size_limit(self.config.stack_size_limit);
+        inspector.set_runtime_limits(limits);
+
+        Ok(TimedInspector::new(inspector, timeout))
+    }
+}
+
+impl std::fmt::Debug for JsTracers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsTracers").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+/// An [`Inspector`] that halts execution once its deadline has passed.
+///
+/// Until then all hooks are forwarded to the wrapped inspector, afterwards it is no longer called
+/// and every frame halts on its next instruction.
+#[derive(Debug)]
+pub(crate) struct TimedInspector<I> {
+    inner: I,
+    timeout: Duration,
+    deadline: Instant,
+    timed_out: bool,
+}
+
+impl<I> TimedInspector<I> {
+    /// Wraps the inspector, the deadline starts now.
+    pub(crate) fn new(inner: I, timeout: Duration) -> Self {
+        Self { inner, timeout, deadline: Instant::now() + timeout, timed_out: false }
+    }
+
+    /// Returns the wrapped inspector, or [`EthApiError::ExecutionTimedOut`] if execution was
+    /// halted because the deadline passed.
+    pub(crate) fn into_inner(self) -> Result<I, EthApiError> {
+        if self.timed_out {
+            return Err(EthApiError::ExecutionTimedOut(self.timeout))
+        }
+        Ok(self.inner)
+    }
+
+    /// Returns `true` if the deadline has passed.
+    fn check_deadline(&mut self) -> bool {
+        if !self.timed_out && Instant::now() >= self.deadline {
+            self.timed_out = true;
+        }
+        self.timed_out
+    }
+}
+
+impl<CTX, I> Inspector<CTX> for TimedInspector<I>
+where
+    I: Inspector<CTX>,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut CTX) {
+        if !self.timed_out {
+            self.inner.initialize_interp(interp, context);
+        }
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut CTX) {
+        if self.check_deadline() {
+            interp.halt(InstructionResult::OutOfGas);
+            return
+        }
+        self.inner.step(interp, context);
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut CTX) {
+        if !self.timed_out {
+            self.inner.step_end(interp, context);
+        }
+    }
+
+    fn log(&mut self, interp: &mut Interpreter, context: &mut CTX, log: Log) {
+        if !self.timed_out {
+            self.inner.log(interp, context, log);
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        if self.check_deadline() {
+            return None
+        }
+        self.inner.call(context, inputs)
+    }
+
+    fn call_end(&mut self, context: &mut CTX, inputs: &CallInputs, outcome: &mut CallOutcome) {
+        if !self.timed_out {
+            self.inner.call_end(context, inputs, outcome);
+        }
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        if self.check_deadline() {
+            return None
+        }
+        self.inner.create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if !self.timed_out {
+            self.inner.create_end(context, inputs, outcome);
+        }
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if !self.timed_out {
+            self.inner.selfdestruct(contract, target, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACER: &str = "{
+        count: 0,
+        step: function() { this.count++ },
+        fault: function() {},
+        result: function() { return this.count }
+    }";
+
+    #[test]
+    fn caches_evaluation_outcome() {
+        let tracers = JsTracers::new(JsTracerConfig::default());
+        let config = serde_json::Value::Null;
+
+        assert!(tracers.ensure_valid(TRACER, &config).is_ok());
+        assert!(matches!(tracers.compiled.lock().get(&keccak256(TRACER)), Some(None)));
+
+        let broken = "{ result: function() { return 0 } }";
+        let err = tracers.ensure_valid(broken, &config).unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+        assert!(matches!(tracers.compiled.lock().get(&keccak256(broken)), Some(Some(_))));
+    }
+
+    #[test]
+    fn requested_timeout_is_capped() {
+        let tracers = JsTracers::new(JsTracerConfig::default());
+        assert_eq!(tracers.timeout(None).unwrap(), tracers.config.timeout);
+        assert_eq!(tracers.timeout(Some("300ms")).unwrap(), Duration::from_millis(300));
+        assert_eq!(tracers.timeout(Some("1h")).unwrap(), tracers.config.timeout);
+        assert!(tracers.timeout(Some("soon")).is_err());
+    }
+}
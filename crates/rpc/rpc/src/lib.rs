@@ -33,12 +33,18 @@ use pin_project as _;
 use tower as _;
 
 mod admin;
+mod anvil;
 mod debug;
 mod engine;
 pub mod eth;
+mod ganache;
+mod hardhat;
+#[cfg(feature = "js-tracer")]
+mod js_tracer;
 mod miner;
 mod net;
 mod otterscan;
+mod profiling;
 mod reth;
 mod rpc;
 mod trace;
@@ -47,13 +53,21 @@ mod validation;
 mod web3;
 
 pub use admin::AdminApi;
-pub use debug::DebugApi;
+pub use anvil::AnvilApi;
+pub use debug::{
+    DebugApi, DebugApiConfig, JsTracerConfig, ACCOUNT_RANGE_MAX_RESULTS, DEFAULT_JS_TRACER_TIMEOUT,
+};
 pub use engine::{EngineApi, EngineEthApi};
-pub use eth::{helpers::SyncListener, EthApi, EthApiBuilder, EthBundle, EthFilter, EthPubSub};
+pub use eth::{
+    helpers::SyncListener, EthApi, EthApiBuilder, EthBundle, EthFilter, EthPubSub,
+    EthSimulationSessions,
+};
+pub use ganache::GanacheApi;
+pub use hardhat::HardhatApi;
 pub use miner::MinerApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
-pub use reth::RethApi;
+pub use reth::{RethApi, STATE_DIFF_MAX_BLOCKS};
 pub use reth_rpc_convert::RpcTypes;
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
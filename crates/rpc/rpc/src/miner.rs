@@ -1,25 +1,59 @@
+use alloy_consensus::constants::MAXIMUM_EXTRA_DATA_SIZE;
 use alloy_primitives::{Bytes, U128};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
+use reth_payload_builder::MinerSettings;
 use reth_rpc_api::MinerApiServer;
+use reth_rpc_eth_types::EthApiError;
 
 /// `miner` API implementation.
 ///
-/// This type provides the functionality for handling `miner` related requests.
+/// This type provides the functionality for handling `miner` related requests. The parameters
+/// are applied to the blocks built by the node's payload builder from then on.
 #[derive(Clone, Debug, Default)]
-pub struct MinerApi {}
+pub struct MinerApi {
+    /// The settings of the payload builder, if it supports changing them.
+    settings: Option<MinerSettings>,
+}
+
+impl MinerApi {
+    /// Creates a new instance of `MinerApi` that changes the given [`MinerSettings`].
+    ///
+    /// Without settings, all methods return `false`.
+    pub const fn new(settings: Option<MinerSettings>) -> Self {
+        Self { settings }
+    }
+}
 
 #[async_trait]
 impl MinerApiServer for MinerApi {
-    fn set_extra(&self, _record: Bytes) -> RpcResult<bool> {
-        Ok(false)
+    /// Handler for `miner_setExtra`
+    fn set_extra(&self, record: Bytes) -> RpcResult<bool> {
+        if record.len() > MAXIMUM_EXTRA_DATA_SIZE {
+            return Err(EthApiError::InvalidParams(format!(
+                "extra data exceeds {MAXIMUM_EXTRA_DATA_SIZE}-byte limit"
+            ))
+            .into())
+        }
+        let Some(settings) = &self.settings else { return Ok(false) };
+        settings.set_extra_data(record);
+        Ok(true)
     }
 
-    fn set_gas_price(&self, _gas_price: U128) -> RpcResult<bool> {
-        Ok(false)
+    /// Handler for `miner_setGasPrice`
+    fn set_gas_price(&self, gas_price: U128) -> RpcResult<bool> {
+        let Some(settings) = &self.settings else { return Ok(false) };
+        settings.set_min_priority_fee(gas_price.to());
+        Ok(true)
     }
 
-    fn set_gas_limit(&self, _gas_price: U128) -> RpcResult<bool> {
-        Ok(false)
+    /// Handler for `miner_setGasLimit`
+    fn set_gas_limit(&self, gas_limit: U128) -> RpcResult<bool> {
+        let gas_limit = gas_limit
+            .try_into()
+            .map_err(|_| EthApiError::InvalidParams("gas limit exceeds u64".to_string()))?;
+        let Some(settings) = &self.settings else { return Ok(false) };
+        settings.set_gas_limit(gas_limit);
+        Ok(true)
     }
 }
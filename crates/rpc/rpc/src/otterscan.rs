@@ -1,7 +1,7 @@
 use alloy_consensus::{BlockHeader, Typed2718};
 use alloy_eips::{eip1898::LenientBlockNumberOrTag, BlockId};
 use alloy_network::{ReceiptResponse, TransactionResponse};
-use alloy_primitives::{Address, Bytes, TxHash, B256, U256};
+use alloy_primitives::{Address, BlockNumber, Bytes, TxHash, B256, U256};
 use alloy_rpc_types_eth::{BlockTransactions, TransactionReceipt};
 use alloy_rpc_types_trace::{
     otterscan::{
@@ -20,11 +20,13 @@ use reth_rpc_eth_api::{
 };
 use reth_rpc_eth_types::{utils::binary_search, EthApiError};
 use reth_rpc_server_types::result::internal_rpc_err;
+use reth_storage_api::{CallIndexKind, CallIndexReader};
 use revm::context_interface::result::ExecutionResult;
 use revm_inspectors::{
     tracing::{types::CallTraceNode, TracingInspectorConfig},
     transfer::{TransferInspector, TransferKind},
 };
+use std::ops::RangeInclusive;
 
 const API_LEVEL: u64 = 8;
 
@@ -63,6 +65,147 @@ where
     }
 }
 
+impl<Eth> OtterscanApi<Eth>
+where
+    Eth: EthApiServer<
+            RpcTxReq<Eth::NetworkTypes>,
+            RpcTransaction<Eth::NetworkTypes>,
+            RpcBlock<Eth::NetworkTypes>,
+            RpcReceipt<Eth::NetworkTypes>,
+            RpcHeader<Eth::NetworkTypes>,
+        > + EthTransactions
+        + TraceExt
+        + 'static,
+{
+    /// Returns the number of the last block that is included in the call index.
+    fn call_index_head(&self) -> RpcResult<BlockNumber> {
+        let head = self.eth.provider().call_index_head().map_err(EthApiError::from)?;
+        head.map(|head| head.number).ok_or_else(|| {
+            EthApiError::Unsupported("searching transactions requires the call index").into()
+        })
+    }
+
+    /// Returns the first block in `range` in which `address` took part in a call, or the last one
+    /// if `reverse` is set.
+    fn next_call_block(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+    ) -> RpcResult<Option<BlockNumber>> {
+        let provider = self.eth.provider();
+        let mut blocks = provider
+            .call_index_blocks(CallIndexKind::From, address, range.clone(), reverse, 1)
+            .map_err(EthApiError::from)?;
+        blocks.extend(
+            provider
+                .call_index_blocks(CallIndexKind::To, address, range, reverse, 1)
+                .map_err(EthApiError::from)?,
+        );
+        Ok(if reverse { blocks.into_iter().max() } else { blocks.into_iter().min() })
+    }
+
+    /// Searches the transactions in which `address` took part in a call, scanning the blocks of
+    /// `range` from the newest if `reverse` is set, or from the oldest otherwise.
+    ///
+    /// Blocks are always included as a whole, so that the next page can start at a block
+    /// boundary. The scan stops once at least `page_size` transactions are found. The transactions
+    /// are returned from newest to oldest, together with whether there are more matching blocks
+    /// left in the range.
+    async fn search_transactions(
+        &self,
+        address: Address,
+        mut range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+        page_size: usize,
+    ) -> RpcResult<(Vec<RpcTransaction<Eth::NetworkTypes>>, Vec<OtsTransactionReceipt>, bool)> {
+        let mut blocks = Vec::new();
+        let mut found = 0;
+        let mut has_more = false;
+
+        while !range.is_empty() {
+            let Some(block_number) = self.next_call_block(address, range.clone(), reverse)? else {
+                break
+            };
+            if found >= page_size {
+                has_more = true;
+                break
+            }
+
+            let block = self.call_transactions_in_block(address, block_number).await?;
+            found += block.0.len();
+            blocks.push(block);
+
+            range = if reverse {
+                let Some(end) = block_number.checked_sub(1) else { break };
+                *range.start()..=end
+            } else {
+                block_number + 1..=*range.end()
+            };
+        }
+
+        if !reverse {
+            blocks.reverse();
+        }
+        let (txs, receipts) = blocks.into_iter().fold(
+            (Vec::with_capacity(found), Vec::with_capacity(found)),
+            |(mut txs, mut receipts), (block_txs, block_receipts)| {
+                txs.extend(block_txs);
+                receipts.extend(block_receipts);
+                (txs, receipts)
+            },
+        );
+        Ok((txs, receipts, has_more))
+    }
+
+    /// Returns the transactions of the block in which `address` took part in a call, from newest
+    /// to oldest.
+    async fn call_transactions_in_block(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> RpcResult<(Vec<RpcTransaction<Eth::NetworkTypes>>, Vec<OtsTransactionReceipt>)> {
+        let block_id = BlockId::from(block_number);
+        let matches = self.eth.trace_block_with(
+            block_id,
+            None,
+            TracingInspectorConfig::none(),
+            move |_tx_info, mut ctx| {
+                Ok(ctx.take_inspector().traces().nodes().iter().any(|node| {
+                    node.trace.caller == address ||
+                        node.trace.address == address ||
+                        node.trace.selfdestruct_refund_target == Some(address)
+                }))
+            },
+        );
+        let block = self.eth.block_by_number(block_number.into(), true);
+        let receipts = self.eth.block_receipts(block_id);
+        let (matches, block, receipts) =
+            futures::try_join!(async { matches.await.map_err(Into::into) }, block, receipts)?;
+
+        let matches = matches.ok_or(EthApiError::HeaderNotFound(block_id))?;
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_id))?;
+        let receipts = receipts.ok_or(EthApiError::ReceiptsNotFound(block_id))?;
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+
+        let timestamp = Some(block.header.timestamp());
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .zip(matches)
+            .filter_map(|((tx, receipt), is_match)| {
+                is_match.then(|| {
+                    let receipt = ots_receipt(&receipt, tx.ty(), timestamp);
+                    (tx, receipt)
+                })
+            })
+            .rev()
+            .unzip())
+    }
+}
+
 #[async_trait]
 impl<Eth> OtterscanServer<RpcTransaction<Eth::NetworkTypes>, RpcHeader<Eth::NetworkTypes>>
     for OtterscanApi<Eth>
@@ -247,32 +390,7 @@ where
         let receipts = receipts
             .drain(page_start..page_end)
             .zip(transactions.iter().map(Typed2718::ty))
-            .map(|(receipt, tx_ty)| {
-                let inner = OtsReceipt {
-                    status: receipt.status(),
-                    cumulative_gas_used: receipt.cumulative_gas_used(),
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: tx_ty,
-                };
-
-                let receipt = TransactionReceipt {
-                    inner,
-                    transaction_hash: receipt.transaction_hash(),
-                    transaction_index: receipt.transaction_index(),
-                    block_hash: receipt.block_hash(),
-                    block_number: receipt.block_number(),
-                    gas_used: receipt.gas_used(),
-                    effective_gas_price: receipt.effective_gas_price(),
-                    blob_gas_used: receipt.blob_gas_used(),
-                    blob_gas_price: receipt.blob_gas_price(),
-                    from: receipt.from(),
-                    to: receipt.to(),
-                    contract_address: receipt.contract_address(),
-                };
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|(receipt, tx_ty)| ots_receipt(&receipt, tx_ty, timestamp))
             .collect();
 
         // use `transaction_count` to indicate the paginate information
@@ -284,21 +402,47 @@ where
     /// Handler for `ots_searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: LenientBlockNumberOrTag,
-        _page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        address: Address,
+        block_number: LenientBlockNumberOrTag,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts<RpcTransaction<Eth::NetworkTypes>>> {
+        let head = self.call_index_head()?;
+        // block 0 requests the first page, which starts at the newest block
+        let block_number = block_number.into_inner().as_number().unwrap_or_default();
+        let end = match block_number.checked_sub(1) {
+            Some(end) => end.min(head),
+            None => head,
+        };
+
+        let (txs, receipts, has_more) =
+            self.search_transactions(address, 0..=end, true, page_size).await?;
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: block_number == 0,
+            last_page: !has_more,
+        })
     }
 
     /// Handler for `ots_searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: LenientBlockNumberOrTag,
-        _page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        address: Address,
+        block_number: LenientBlockNumberOrTag,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts<RpcTransaction<Eth::NetworkTypes>>> {
+        let head = self.call_index_head()?;
+        // block 0 requests the last page, which starts at the oldest block
+        let block_number = block_number.into_inner().as_number().unwrap_or_default();
+
+        let (txs, receipts, has_more) =
+            self.search_transactions(address, block_number + 1..=head, false, page_size).await?;
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: !has_more,
+            last_page: block_number == 0,
+        })
     }
 
     /// Handler for `ots_getTransactionBySenderAndNonce`
@@ -382,3 +526,35 @@ where
         Ok(found)
     }
 }
+
+/// Converts a receipt into the reduced receipt that is returned to Otterscan.
+fn ots_receipt<R: ReceiptResponse>(
+    receipt: &R,
+    tx_ty: u8,
+    timestamp: Option<u64>,
+) -> OtsTransactionReceipt {
+    let inner = OtsReceipt {
+        status: receipt.status(),
+        cumulative_gas_used: receipt.cumulative_gas_used(),
+        logs: None,
+        logs_bloom: None,
+        r#type: tx_ty,
+    };
+
+    let receipt = TransactionReceipt {
+        inner,
+        transaction_hash: receipt.transaction_hash(),
+        transaction_index: receipt.transaction_index(),
+        block_hash: receipt.block_hash(),
+        block_number: receipt.block_number(),
+        gas_used: receipt.gas_used(),
+        effective_gas_price: receipt.effective_gas_price(),
+        blob_gas_used: receipt.blob_gas_used(),
+        blob_gas_price: receipt.blob_gas_price(),
+        from: receipt.from(),
+        to: receipt.to(),
+        contract_address: receipt.contract_address(),
+    };
+
+    OtsTransactionReceipt { receipt, timestamp }
+}
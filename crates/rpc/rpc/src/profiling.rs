@@ -0,0 +1,166 @@
+//! In-process CPU and heap profiling used by the `debug` namespace.
+
+#[cfg(unix)]
+use reth_errors::RethError;
+use reth_rpc_api::MemStats;
+use reth_rpc_eth_types::EthApiError;
+use std::path::{Path, PathBuf};
+
+/// Sampling frequency of the CPU profiler, in Hz.
+#[cfg(unix)]
+const CPU_PROFILE_FREQUENCY: i32 = 100;
+
+/// Libraries that are not unwound from the `SIGPROF` handler, since unwinding through them can
+/// deadlock.
+#[cfg(unix)]
+const CPU_PROFILE_BLOCKLIST: &[&str] = &["libc", "libgcc", "pthread", "vdso"];
+
+/// The running CPU profile.
+///
+/// The profiler is driven by the process-wide `SIGPROF` timer, so there can only be one profile
+/// per process.
+#[cfg(unix)]
+static CPU_PROFILE: parking_lot::Mutex<Option<CpuProfile>> = parking_lot::Mutex::new(None);
+
+/// A running CPU profile and the file it is written to once stopped.
+#[cfg(unix)]
+struct CpuProfile {
+    guard: pprof::ProfilerGuard<'static>,
+    file: PathBuf,
+}
+
+/// Starts sampling the CPU usage of all threads of the process.
+///
+/// The profile is written to `file` by [`stop_cpu_profile`].
+#[cfg(unix)]
+pub(crate) fn start_cpu_profile(file: PathBuf) -> Result<(), EthApiError> {
+    let mut profile = CPU_PROFILE.lock();
+    if profile.is_some() {
+        return Err(EthApiError::InvalidParams("CPU profiling already in progress".to_string()))
+    }
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(CPU_PROFILE_FREQUENCY)
+        .blocklist(CPU_PROFILE_BLOCKLIST)
+        .build()
+        .map_err(internal)?;
+    *profile = Some(CpuProfile { guard, file });
+    Ok(())
+}
+
+/// Starts sampling the CPU usage of all threads of the process.
+#[cfg(not(unix))]
+pub(crate) fn start_cpu_profile(_file: PathBuf) -> Result<(), EthApiError> {
+    Err(EthApiError::Unsupported("CPU profiling is only supported on unix"))
+}
+
+/// Stops the running CPU profile and writes it to its file in pprof format.
+///
+/// Returns the path of the written file. This symbolizes the collected samples and should not be
+/// called on an async task.
+#[cfg(unix)]
+pub(crate) fn stop_cpu_profile() -> Result<PathBuf, EthApiError> {
+    use pprof::protos::Message;
+
+    // keep the lock until the profiler is stopped, so a new profile can't start in between
+    let mut profile = CPU_PROFILE.lock();
+    let CpuProfile { guard, file } = profile
+        .take()
+        .ok_or_else(|| EthApiError::InvalidParams("CPU profiling not in progress".to_string()))?;
+
+    let report = guard.report().build().and_then(|report| report.pprof()).map_err(internal)?;
+    drop(guard);
+
+    reth_fs_util::write(&file, report.encode_to_vec())
+        .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+    Ok(file)
+}
+
+/// Stops the running CPU profile and writes it to its file in pprof format.
+#[cfg(not(unix))]
+pub(crate) fn stop_cpu_profile() -> Result<PathBuf, EthApiError> {
+    Err(EthApiError::Unsupported("CPU profiling is only supported on unix"))
+}
+
+/// Dumps a jemalloc heap profile to `file`.
+///
+/// This requires jemalloc to be built with profiling support and profiling to be enabled at
+/// startup, e.g. with `MALLOC_CONF=prof:true`.
+#[cfg(all(feature = "jemalloc", unix))]
+pub(crate) fn write_heap_profile(file: &Path) -> Result<(), EthApiError> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+    use tikv_jemalloc_ctl::raw;
+
+    // SAFETY: `opt.prof` is a `bool`, the key is unknown if jemalloc is built without profiling
+    if !matches!(unsafe { raw::read::<bool>(b"opt.prof\0") }, Ok(true)) {
+        return Err(EthApiError::Unsupported(
+            "heap profiling is not enabled, see jemalloc's `opt.prof`",
+        ))
+    }
+
+    let path = CString::new(file.as_os_str().as_bytes())
+        .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+    // SAFETY: `prof.dump` takes a nul-terminated path that is only read during the call
+    unsafe { raw::write(b"prof.dump\0", path.as_ptr()) }.map_err(internal)
+}
+
+/// Dumps a jemalloc heap profile to `file`.
+#[cfg(not(all(feature = "jemalloc", unix)))]
+pub(crate) const fn write_heap_profile(_file: &Path) -> Result<(), EthApiError> {
+    Err(EthApiError::Unsupported("heap profiling requires the `jemalloc` feature"))
+}
+
+/// Returns the current jemalloc allocator statistics.
+#[cfg(all(feature = "jemalloc", unix))]
+pub(crate) fn mem_stats() -> Result<MemStats, EthApiError> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    // jemalloc caches its statistics, advancing the epoch refreshes them
+    epoch::advance().map_err(internal)?;
+
+    Ok(MemStats {
+        allocated: stats::allocated::read().map_err(internal)? as u64,
+        active: stats::active::read().map_err(internal)? as u64,
+        metadata: stats::metadata::read().map_err(internal)? as u64,
+        resident: stats::resident::read().map_err(internal)? as u64,
+        mapped: stats::mapped::read().map_err(internal)? as u64,
+        retained: stats::retained::read().map_err(internal)? as u64,
+    })
+}
+
+/// Returns the current jemalloc allocator statistics.
+#[cfg(not(all(feature = "jemalloc", unix)))]
+pub(crate) const fn mem_stats() -> Result<MemStats, EthApiError> {
+    Err(EthApiError::Unsupported("memory statistics require the `jemalloc` feature"))
+}
+
+/// Converts an error of the profiler or allocator into an internal error.
+#[cfg(unix)]
+fn internal(err: impl std::fmt::Display) -> EthApiError {
+    EthApiError::Internal(RethError::msg(err))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_profile_roundtrip() {
+        let file = std::env::temp_dir().join(format!("reth-cpu-{}.pb", std::process::id()));
+
+        assert!(stop_cpu_profile().is_err());
+        start_cpu_profile(file.clone()).unwrap();
+        assert!(start_cpu_profile(file.clone()).is_err());
+
+        let mut acc = 0u64;
+        for i in 0..10_000_000u64 {
+            acc = acc.wrapping_mul(31).wrapping_add(i);
+        }
+        std::hint::black_box(acc);
+
+        assert_eq!(stop_cpu_profile().unwrap(), file);
+        assert!(std::fs::metadata(&file).unwrap().len() > 0);
+        assert!(stop_cpu_profile().is_err());
+        std::fs::remove_file(file).unwrap();
+    }
+}
@@ -1,21 +1,38 @@
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
+    future::Future,
+    sync::Arc,
+};
 
+use alloy_consensus::BlockHeader;
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, BlockNumber, B256, U256};
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
 use jsonrpsee_types::ErrorObject;
-use reth_chain_state::{CanonStateNotificationStream, CanonStateSubscriptions};
+use reth_chain_state::{CanonStateNotification, CanonStateSubscriptions};
 use reth_errors::RethResult;
-use reth_primitives_traits::NodePrimitives;
-use reth_rpc_api::RethApiServer;
+use reth_execution_types::Chain;
+use reth_primitives_traits::{Account, NodePrimitives};
+use reth_revm::db::BundleState;
+use reth_rpc_api::{
+    AccountState, AccountStateDiff, BlockStateDiff, RethApiServer, RethSubscriptionKind,
+    StateDiffFilter, StateDiffNotification, StorageSlotDiff,
+};
 use reth_rpc_eth_types::{EthApiError, EthResult};
 use reth_rpc_server_types::result::internal_rpc_err;
-use reth_storage_api::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_storage_api::{
+    BlockReaderIdExt, ChangeSetReader, StateProviderFactory, StorageChangeSetReader,
+};
 use reth_tasks::TaskSpawner;
+use revm::state::AccountInfo;
+use serde::Serialize;
 use tokio::sync::oneshot;
 
+/// The maximum number of blocks for `reth_getStateDiffRange` requests.
+pub const STATE_DIFF_MAX_BLOCKS: u64 = 100;
+
 /// `reth` API implementation.
 ///
 /// This type provides the functionality for handling `reth` prototype RPC requests.
@@ -40,7 +57,11 @@ impl<Provider> RethApi<Provider> {
 
 impl<Provider> RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider: BlockReaderIdExt
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + StateProviderFactory
+        + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -88,6 +109,109 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns all account and storage changes of a block.
+    pub async fn state_diff(&self, block_id: BlockId) -> EthResult<BlockStateDiff> {
+        self.on_blocking_task(|this| async move {
+            let Some(block_number) = this.provider().block_number_for_id(block_id)? else {
+                return Err(EthApiError::HeaderNotFound(block_id))
+            };
+            this.try_state_diff(block_number)
+        })
+        .await
+    }
+
+    /// Returns all account and storage changes of the blocks between `start` and `end`, both
+    /// inclusive.
+    ///
+    /// The range is limited to [`STATE_DIFF_MAX_BLOCKS`] blocks.
+    pub async fn state_diff_range(
+        &self,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> EthResult<Vec<BlockStateDiff>> {
+        if start > end {
+            return Err(EthApiError::InvalidParams(
+                "start block must not be after end block".to_string(),
+            ))
+        }
+        if end - start >= STATE_DIFF_MAX_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "block range exceeds the limit of {STATE_DIFF_MAX_BLOCKS} blocks"
+            )))
+        }
+
+        self.on_blocking_task(|this| async move {
+            (start..=end).map(|number| this.try_state_diff(number)).collect()
+        })
+        .await
+    }
+
+    /// Computes the state diff of a block from its changesets, which hold the state before the
+    /// block, and the state after the block.
+    fn try_state_diff(&self, block_number: BlockNumber) -> EthResult<BlockStateDiff> {
+        let block_hash = self
+            .provider()
+            .block_hash(block_number)?
+            .ok_or(EthApiError::HeaderNotFound(block_number.into()))?;
+        let state = self.provider().state_by_block_hash(block_hash)?;
+
+        let mut accounts = BTreeMap::new();
+        for account_before in self.provider().account_block_changeset(block_number)? {
+            let after = state.basic_account(&account_before.address)?;
+            accounts.insert(
+                account_before.address,
+                AccountStateDiff {
+                    before: account_before.info.map(account_state),
+                    after: after.map(account_state),
+                    storage: BTreeMap::new(),
+                },
+            );
+        }
+
+        for (block_address, entry) in self.provider().storage_changeset(block_number)? {
+            let address = block_address.address();
+            let after = state.storage(address, entry.key)?.unwrap_or_default();
+            if entry.value == after {
+                continue
+            }
+
+            let account = match accounts.entry(address) {
+                Entry::Occupied(occupied) => occupied.into_mut(),
+                Entry::Vacant(vacant) => {
+                    // only the storage of the account changed
+                    let account = state.basic_account(&address)?.map(account_state);
+                    vacant.insert(AccountStateDiff {
+                        before: account,
+                        after: account,
+                        storage: BTreeMap::new(),
+                    })
+                }
+            };
+            account.storage.insert(
+                entry.key,
+                StorageSlotDiff {
+                    before: B256::new(entry.value.to_be_bytes()),
+                    after: B256::new(after.to_be_bytes()),
+                },
+            );
+        }
+
+        // changesets may include accounts that were touched but not changed
+        accounts
+            .retain(|_, account| account.before != account.after || !account.storage.is_empty());
+
+        Ok(BlockStateDiff { block_number, block_hash, accounts })
+    }
+}
+
+/// Returns the fields of the account tracked by state diffs.
+fn account_state(account: Account) -> AccountState {
+    AccountState {
+        nonce: account.nonce,
+        balance: account.balance,
+        code_hash: account.get_bytecode_hash(),
+    }
 }
 
 #[async_trait]
@@ -95,6 +219,7 @@ impl<Provider> RethApiServer for RethApi<Provider>
 where
     Provider: BlockReaderIdExt
         + ChangeSetReader
+        + StorageChangeSetReader
         + StateProviderFactory
         + CanonStateSubscriptions
         + 'static,
@@ -107,6 +232,20 @@ where
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
 
+    /// Handler for `reth_getStateDiff`
+    async fn reth_get_state_diff(&self, block_id: BlockId) -> RpcResult<BlockStateDiff> {
+        Ok(Self::state_diff(self, block_id).await?)
+    }
+
+    /// Handler for `reth_getStateDiffRange`
+    async fn reth_get_state_diff_range(
+        &self,
+        start_number: u64,
+        end_number: u64,
+    ) -> RpcResult<Vec<BlockStateDiff>> {
+        Ok(Self::state_diff_range(self, start_number, end_number).await?)
+    }
+
     /// Handler for `reth_subscribeChainNotifications`
     async fn reth_subscribe_chain_notifications(
         &self,
@@ -120,13 +259,158 @@ where
 
         Ok(())
     }
+
+    /// Handler for `reth_subscribe`
+    async fn reth_subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: RethSubscriptionKind,
+        filter: Option<StateDiffFilter>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let sink = pending.accept().await?;
+        match kind {
+            RethSubscriptionKind::StateDiffs => {
+                let addresses: HashSet<_> =
+                    filter.unwrap_or_default().addresses.into_iter().collect();
+                let stream =
+                    self.provider().canonical_state_stream().flat_map(move |notification| {
+                        futures::stream::iter(state_diff_notifications(&notification, &addresses))
+                    });
+                self.inner.task_spawner.spawn(Box::pin(async move {
+                    let _ = pipe_from_stream(sink, stream).await;
+                }));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the state diffs of a canonical state notification.
+///
+/// The blocks of a reverted chain segment are returned first, from the highest to the lowest
+/// block, followed by the blocks of the committed chain segment. If `addresses` is not empty, only
+/// the changes of these accounts are included.
+fn state_diff_notifications<N: NodePrimitives>(
+    notification: &CanonStateNotification<N>,
+    addresses: &HashSet<Address>,
+) -> Vec<StateDiffNotification> {
+    let reverted =
+        notification.reverted().map(|chain| chain_state_diffs(&chain)).unwrap_or_default();
+    let committed = chain_state_diffs(&notification.committed());
+
+    reverted
+        .into_iter()
+        .rev()
+        .map(|diff| (true, diff))
+        .chain(committed.into_iter().map(|diff| (false, diff)))
+        .map(|(removed, mut diff)| {
+            if !addresses.is_empty() {
+                diff.accounts.retain(|address, _| addresses.contains(address));
+            }
+            StateDiffNotification { removed, diff }
+        })
+        .collect()
+}
+
+/// Returns the state diffs of the blocks of a chain segment, from the lowest to the highest block.
+///
+/// The diffs are derived from the reverts of the bundle state of the chain segment, which hold the
+/// state before each block. For self-destructed accounts, only the storage slots known to the
+/// bundle state are included.
+fn chain_state_diffs<N: NodePrimitives>(chain: &Chain<N>) -> Vec<BlockStateDiff> {
+    let bundle = &chain.execution_outcome().bundle;
+    let reverts = bundle.reverts.to_plain_state_reverts();
+
+    // the state after the currently processed block, for all accounts and slots that are changed
+    // by a later block of the segment
+    let mut accounts_after = HashMap::new();
+    let mut storage_after = HashMap::new();
+
+    let blocks = chain.blocks_iter().collect::<Vec<_>>();
+    let mut diffs = Vec::with_capacity(blocks.len());
+    for ((block, account_reverts), storage_reverts) in
+        blocks.into_iter().zip(reverts.accounts).zip(reverts.storage).rev()
+    {
+        let mut accounts = BTreeMap::new();
+        for (address, info) in account_reverts {
+            let before = info.as_ref().map(account_info_state);
+            let after = accounts_after
+                .insert(address, before)
+                .unwrap_or_else(|| present_account_state(bundle, &address));
+            accounts.insert(address, AccountStateDiff { before, after, storage: BTreeMap::new() });
+        }
+
+        for revert in storage_reverts {
+            for (slot, value) in revert.storage_revert {
+                let before = value.to_previous_value();
+                let after = storage_after
+                    .insert((revert.address, slot), before)
+                    .unwrap_or_else(|| present_storage_value(bundle, &revert.address, &slot));
+                if before == after {
+                    continue
+                }
+
+                let account = accounts.entry(revert.address).or_insert_with(|| {
+                    // only the storage of the account changed
+                    let account = accounts_after
+                        .get(&revert.address)
+                        .copied()
+                        .unwrap_or_else(|| present_account_state(bundle, &revert.address));
+                    AccountStateDiff { before: account, after: account, storage: BTreeMap::new() }
+                });
+                account.storage.insert(
+                    B256::new(slot.to_be_bytes()),
+                    StorageSlotDiff {
+                        before: B256::new(before.to_be_bytes()),
+                        after: B256::new(after.to_be_bytes()),
+                    },
+                );
+            }
+        }
+
+        accounts
+            .retain(|_, account| account.before != account.after || !account.storage.is_empty());
+
+        diffs.push(BlockStateDiff {
+            block_number: block.number(),
+            block_hash: block.hash(),
+            accounts,
+        });
+    }
+
+    diffs.reverse();
+    diffs
+}
+
+/// Returns the state of the account at the end of the bundle.
+fn present_account_state(bundle: &BundleState, address: &Address) -> Option<AccountState> {
+    bundle.account(address).and_then(|account| account.info.as_ref()).map(account_info_state)
+}
+
+/// Returns the value of the storage slot at the end of the bundle.
+fn present_storage_value(bundle: &BundleState, address: &Address, slot: &U256) -> U256 {
+    bundle
+        .account(address)
+        .and_then(|account| account.storage.get(slot))
+        .map(|slot| slot.present_value)
+        .unwrap_or_default()
+}
+
+/// Returns the fields of the account tracked by state diffs.
+const fn account_info_state(info: &AccountInfo) -> AccountState {
+    AccountState { nonce: info.nonce, balance: info.balance, code_hash: info.code_hash }
 }
 
 /// Pipes all stream items to the subscription sink.
-async fn pipe_from_stream<N: NodePrimitives>(
+async fn pipe_from_stream<T, St>(
     sink: SubscriptionSink,
-    mut stream: CanonStateNotificationStream<N>,
-) -> Result<(), ErrorObject<'static>> {
+    mut stream: St,
+) -> Result<(), ErrorObject<'static>>
+where
+    St: Stream<Item = T> + Unpin,
+    T: Serialize,
+{
     loop {
         tokio::select! {
             _ = sink.closed() => {
@@ -170,3 +454,113 @@ struct RethApiInner<Provider> {
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_primitives::address;
+    use reth_ethereum_primitives::{Block, EthPrimitives};
+    use reth_execution_types::ExecutionOutcome;
+    use reth_primitives_traits::RecoveredBlock;
+
+    fn block(number: BlockNumber) -> RecoveredBlock<Block> {
+        let header = Header { number, ..Default::default() };
+        RecoveredBlock::new_unhashed(Block { header, body: Default::default() }, vec![])
+    }
+
+    fn info(nonce: u64, balance: u64) -> AccountInfo {
+        AccountInfo { nonce, balance: U256::from(balance), ..Default::default() }
+    }
+
+    fn slot(value: u64) -> B256 {
+        B256::with_last_byte(value as u8)
+    }
+
+    #[test]
+    fn chain_state_diffs_of_bundle_reverts() {
+        let created = address!("0x00000000000000000000000000000000000000aa");
+        let storage_only = address!("0x00000000000000000000000000000000000000bb");
+
+        // `created` is created in block 1 and changed in block 2, only the storage of
+        // `storage_only` is changed in block 2
+        let bundle = BundleState::new(
+            [
+                (
+                    created,
+                    None,
+                    Some(info(1, 2)),
+                    std::iter::once((U256::from(1), (U256::ZERO, U256::from(6)))).collect(),
+                ),
+                (
+                    storage_only,
+                    Some(info(0, 7)),
+                    Some(info(0, 7)),
+                    std::iter::once((U256::from(2), (U256::ZERO, U256::from(3)))).collect(),
+                ),
+            ],
+            [
+                vec![(created, Some(None), vec![(U256::from(1), U256::ZERO)])],
+                vec![
+                    (created, Some(Some(info(0, 1))), vec![(U256::from(1), U256::from(5))]),
+                    (storage_only, None, vec![(U256::from(2), U256::ZERO)]),
+                ],
+            ],
+            [],
+        );
+        let chain = Chain::<EthPrimitives>::new(
+            [block(1), block(2)],
+            ExecutionOutcome::new(bundle, vec![vec![], vec![]], 1, vec![]),
+            None,
+        );
+
+        let diffs = chain_state_diffs(&chain);
+        assert_eq!(diffs.len(), 2);
+
+        let [first, second] = &diffs[..] else { unreachable!() };
+        assert_eq!(first.block_number, 1);
+        assert_eq!(
+            first.accounts,
+            BTreeMap::from([(
+                created,
+                AccountStateDiff {
+                    before: None,
+                    after: Some(account_info_state(&info(0, 1))),
+                    storage: BTreeMap::from([(
+                        slot(1),
+                        StorageSlotDiff { before: slot(0), after: slot(5) }
+                    )]),
+                }
+            )])
+        );
+
+        assert_eq!(second.block_number, 2);
+        assert_eq!(
+            second.accounts,
+            BTreeMap::from([
+                (
+                    created,
+                    AccountStateDiff {
+                        before: Some(account_info_state(&info(0, 1))),
+                        after: Some(account_info_state(&info(1, 2))),
+                        storage: BTreeMap::from([(
+                            slot(1),
+                            StorageSlotDiff { before: slot(5), after: slot(6) }
+                        )]),
+                    }
+                ),
+                (
+                    storage_only,
+                    AccountStateDiff {
+                        before: Some(account_info_state(&info(0, 7))),
+                        after: Some(account_info_state(&info(0, 7))),
+                        storage: BTreeMap::from([(
+                            slot(2),
+                            StorageSlotDiff { before: slot(0), after: slot(3) }
+                        )]),
+                    }
+                ),
+            ])
+        );
+    }
+}
@@ -1,13 +1,13 @@
 use alloy_consensus::BlockHeader as _;
 use alloy_eips::BlockId;
 use alloy_evm::block::calc::{base_block_reward_pre_merge, block_reward, ommer_reward};
-use alloy_primitives::{map::HashSet, Bytes, B256, U256};
+use alloy_primitives::{map::HashSet, Address, Bytes, B256, U256};
 use alloy_rpc_types_eth::{
     state::{EvmOverrides, StateOverride},
     BlockOverrides, Index,
 };
 use alloy_rpc_types_trace::{
-    filter::TraceFilter,
+    filter::{TraceFilter, TraceFilterMode},
     opcode::{BlockOpcodeGas, TransactionOpcodeGas},
     parity::*,
     tracerequest::TraceCallRequest,
@@ -25,7 +25,9 @@ use reth_rpc_eth_api::{
     FromEthApiError, RpcNodeCore,
 };
 use reth_rpc_eth_types::{error::EthApiError, utils::recover_raw_transaction, EthConfig};
-use reth_storage_api::{BlockNumReader, BlockReader};
+use reth_storage_api::{
+    BlockNumReader, BlockReader, CallIndexKind, CallIndexReader, TransactionVariant,
+};
 use reth_tasks::pool::BlockingTaskGuard;
 use reth_transaction_pool::{PoolPooledTx, PoolTransaction, TransactionPool};
 use revm::DatabaseCommit;
@@ -33,7 +35,7 @@ use revm_inspectors::{
     opcode::OpcodeGasInspector,
     tracing::{parity::populate_state_diff, TracingInspector, TracingInspectorConfig},
 };
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
 /// `trace` API implementation.
@@ -287,20 +289,85 @@ where
         &self,
         header: &H,
     ) -> Result<Option<u128>, Eth::Error> {
+        if self.is_paris_activated(header.number()) {
+            return Ok(None)
+        }
+
+        Ok(Some(base_block_reward_pre_merge(self.provider().chain_spec(), header.number())))
+    }
+
+    /// Returns `true` if the Paris hardfork is activated at the given block, which is assumed if
+    /// its activation block is unknown.
+    fn is_paris_activated(&self, block_number: u64) -> bool {
         let chain_spec = self.provider().chain_spec();
-        let is_paris_activated = if chain_spec.chain() == MAINNET.chain() {
-            Some(header.number()) >= EthereumHardfork::Paris.mainnet_activation_block()
+        if chain_spec.chain() == MAINNET.chain() {
+            Some(block_number) >= EthereumHardfork::Paris.mainnet_activation_block()
         } else if chain_spec.chain() == SEPOLIA.chain() {
-            Some(header.number()) >= EthereumHardfork::Paris.sepolia_activation_block()
+            Some(block_number) >= EthereumHardfork::Paris.sepolia_activation_block()
         } else {
             true
-        };
+        }
+    }
 
-        if is_paris_activated {
+    /// Returns the numbers of the blocks in the range that can contain traces matching the filter,
+    /// as looked up in the call index.
+    ///
+    /// Returns `None` if the call index is disabled or the filter matches all addresses. Blocks
+    /// that are not indexed yet and blocks that can contain matching reward traces, which the index
+    /// does not cover, are always included.
+    fn call_index_blocks(
+        &self,
+        filter: &TraceFilter,
+        start: u64,
+        end: u64,
+    ) -> Result<Option<Vec<u64>>, Eth::Error> {
+        if filter.from_address.is_empty() && filter.to_address.is_empty() {
+            return Ok(None)
+        }
+        let Some(head) = self.provider().call_index_head().map_err(Eth::Error::from_eth_err)?
+        else {
             return Ok(None)
+        };
+
+        let lookup = |kind, addresses: &[Address]| {
+            let mut blocks = BTreeSet::new();
+            for address in addresses {
+                blocks.extend(
+                    self.provider()
+                        .call_index_blocks(
+                            kind,
+                            *address,
+                            start..=end.min(head.number),
+                            false,
+                            usize::MAX,
+                        )
+                        .map_err(Eth::Error::from_eth_err)?,
+                );
+            }
+            Ok::<_, Eth::Error>(blocks)
+        };
+        let mut blocks = if filter.to_address.is_empty() {
+            lookup(CallIndexKind::From, &filter.from_address)?
+        } else if filter.from_address.is_empty() {
+            lookup(CallIndexKind::To, &filter.to_address)?
+        } else {
+            let from = lookup(CallIndexKind::From, &filter.from_address)?;
+            let to = lookup(CallIndexKind::To, &filter.to_address)?;
+            match filter.mode {
+                TraceFilterMode::Union => from.union(&to).copied().collect(),
+                TraceFilterMode::Intersection => from.intersection(&to).copied().collect(),
+            }
+        };
+
+        // reward traces only have a `to` address
+        if !filter.to_address.is_empty() &&
+            (filter.from_address.is_empty() || filter.mode == TraceFilterMode::Union)
+        {
+            blocks.extend((start..=end).take_while(|block| !self.is_paris_activated(*block)));
         }
+        blocks.extend(start.max(head.number + 1)..=end);
 
-        Ok(Some(base_block_reward_pre_merge(&chain_spec, header.number())))
+        Ok(Some(blocks.into_iter().collect()))
     }
 
     /// Extracts the reward traces for the given block:
@@ -375,23 +442,45 @@ where
             .into())
         }
 
-        // ensure that the range is not too large, since we need to fetch all blocks in the range
-        let distance = end.saturating_sub(start);
-        if distance > self.inner.eth_config.max_trace_filter_blocks {
-            return Err(EthApiError::InvalidParams(
-                "Block range too large; currently limited to 100 blocks".to_string(),
-            )
-            .into())
-        }
+        let max_blocks = self.inner.eth_config.max_trace_filter_blocks;
+        let blocks = if let Some(block_numbers) = self.call_index_blocks(&filter, start, end)? {
+            // only the blocks that can contain matching traces need to be fetched
+            if block_numbers.len() as u64 > max_blocks.saturating_add(1) {
+                return Err(EthApiError::InvalidParams(format!(
+                    "Too many matching blocks; currently limited to {max_blocks} blocks"
+                ))
+                .into())
+            }
 
-        // fetch all blocks in that range
-        let blocks = self
-            .provider()
-            .recovered_block_range(start..=end)
-            .map_err(Eth::Error::from_eth_err)?
-            .into_iter()
-            .map(Arc::new)
-            .collect::<Vec<_>>();
+            let mut blocks = Vec::with_capacity(block_numbers.len());
+            for number in block_numbers {
+                let block = self
+                    .provider()
+                    .recovered_block(number.into(), TransactionVariant::WithHash)
+                    .map_err(Eth::Error::from_eth_err)?
+                    .ok_or(EthApiError::HeaderNotFound(number.into()))?;
+                blocks.push(Arc::new(block));
+            }
+            blocks
+        } else {
+            // ensure that the range is not too large, since we need to fetch all blocks in the
+            // range
+            let distance = end.saturating_sub(start);
+            if distance > max_blocks {
+                return Err(EthApiError::InvalidParams(
+                    "Block range too large; currently limited to 100 blocks".to_string(),
+                )
+                .into())
+            }
+
+            // fetch all blocks in that range
+            self.provider()
+                .recovered_block_range(start..=end)
+                .map_err(Eth::Error::from_eth_err)?
+                .into_iter()
+                .map(Arc::new)
+                .collect::<Vec<_>>()
+        };
 
         // trace all blocks
         let mut block_traces = Vec::with_capacity(blocks.len());
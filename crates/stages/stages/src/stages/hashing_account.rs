@@ -9,7 +9,7 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives_traits::Account;
-use reth_provider::{AccountExtReader, DBProvider, HashingWriter, StatsReader};
+use reth_provider::{AccountExtReader, DBProvider, HashingWriter, PreimageWriter, StatsReader};
 use reth_stages_api::{
     AccountHashingCheckpoint, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint,
     StageError, StageId, UnwindInput, UnwindOutput,
@@ -22,6 +22,8 @@ use std::{
 };
 use tracing::*;
 
+use super::snap_sync::snap_sync_pivot;
+
 /// Maximum number of channels that can exist in memory.
 const MAXIMUM_CHANNELS: usize = 10_000;
 
@@ -134,7 +136,8 @@ impl Default for AccountHashingStage {
 
 impl<Provider> Stage<Provider> for AccountHashingStage
 where
-    Provider: DBProvider<Tx: DbTxMut> + HashingWriter + AccountExtReader + StatsReader,
+    Provider:
+        DBProvider<Tx: DbTxMut> + HashingWriter + AccountExtReader + StatsReader + PreimageWriter,
 {
     /// Return the id of the stage
     fn id(&self) -> StageId {
@@ -153,7 +156,10 @@ where
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
         // genesis accounts are not in changeset.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        // The plain state of a snap synced node is incomplete, the hashed state is kept.
+        if (to_block - from_block > self.clean_threshold && snap_sync_pivot(provider)?.is_none()) ||
+            from_block == 1
+        {
             let tx = provider.tx_ref();
 
             // clear table, load all accounts and hash it
@@ -171,6 +177,10 @@ where
                 channels.push(rx);
 
                 let chunk = chunk.collect::<Result<Vec<_>, _>>()?;
+                if provider.records_preimages() {
+                    provider.write_preimages(chunk.iter().map(|(address, _)| address.raw_key()))?;
+                }
+
                 // Spawn the hashing task onto the global rayon pool
                 rayon::spawn(move || {
                     for (address, account) in chunk {
@@ -209,6 +219,9 @@ where
             // Aggregate all transition changesets and make a list of accounts that have been
             // changed.
             let lists = provider.changed_accounts_with_range(from_block..=to_block)?;
+            if provider.records_preimages() {
+                provider.write_preimages(&lists)?;
+            }
             // Iterate over plain state and get newest value.
             // Assumption we are okay to make is that plainstate represent
             // `previous_stage_progress` state.
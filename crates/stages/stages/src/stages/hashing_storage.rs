@@ -10,7 +10,7 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives_traits::StorageEntry;
-use reth_provider::{DBProvider, HashingWriter, StatsReader, StorageReader};
+use reth_provider::{DBProvider, HashingWriter, PreimageWriter, StatsReader, StorageReader};
 use reth_stages_api::{
     EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
     StorageHashingCheckpoint, UnwindInput, UnwindOutput,
@@ -22,6 +22,8 @@ use std::{
 };
 use tracing::*;
 
+use super::snap_sync::snap_sync_pivot;
+
 /// Maximum number of channels that can exist in memory.
 const MAXIMUM_CHANNELS: usize = 10_000;
 
@@ -64,7 +66,8 @@ impl Default for StorageHashingStage {
 
 impl<Provider> Stage<Provider> for StorageHashingStage
 where
-    Provider: DBProvider<Tx: DbTxMut> + StorageReader + HashingWriter + StatsReader,
+    Provider:
+        DBProvider<Tx: DbTxMut> + StorageReader + HashingWriter + StatsReader + PreimageWriter,
 {
     /// Return the id of the stage
     fn id(&self) -> StageId {
@@ -84,7 +87,10 @@ where
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
         // genesis accounts are not in changeset, along with their storages.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        // The plain state of a snap synced node is incomplete, the hashed state is kept.
+        if (to_block - from_block > self.clean_threshold && snap_sync_pivot(provider)?.is_none()) ||
+            from_block == 1
+        {
             // clear table, load all accounts and hash it
             tx.clear::<tables::HashedStorages>()?;
 
@@ -99,6 +105,14 @@ where
                 channels.push(rx);
 
                 let chunk = chunk.collect::<Result<Vec<_>, _>>()?;
+                if provider.records_preimages() {
+                    provider.write_preimages(
+                        chunk
+                            .iter()
+                            .flat_map(|(address, slot)| [address.as_slice(), slot.key.as_slice()]),
+                    )?;
+                }
+
                 // Spawn the hashing task onto the global rayon pool
                 rayon::spawn(move || {
                     for (address, slot) in chunk {
@@ -142,6 +156,12 @@ where
             // Aggregate all changesets and make list of storages that have been
             // changed.
             let lists = provider.changed_storages_with_range(from_block..=to_block)?;
+            if provider.records_preimages() {
+                provider.write_preimages(lists.iter().flat_map(|(address, slots)| {
+                    core::iter::once(address.as_slice())
+                        .chain(slots.iter().map(|slot| slot.as_slice()))
+                }))?;
+            }
             // iterate over plain state and get newest storage value.
             // Assumption we are okay with is that plain state represent
             // `previous_stage_progress` state.
@@ -0,0 +1,1522 @@
+use alloy_consensus::{constants::KECCAK_EMPTY, BlockHeader};
+use alloy_primitives::{keccak256, map::B256Map, BlockNumber, Bytes, B256, U256};
+use alloy_rlp::{Decodable, RlpDecodable, RlpEncodable};
+use futures_util::{stream::FuturesUnordered, FutureExt, StreamExt};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW},
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_eth_wire_types::snap::{
+    AccountData, AccountRangeMessage, ByteCodesMessage, GetAccountRangeMessage,
+    GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage, StorageData,
+    StorageRangesMessage, TrieNodesMessage, TriePath,
+};
+use reth_network_p2p::{
+    error::{PeerRequestResult, RequestError},
+    snap::client::{SnapClient, SnapResponse},
+};
+use reth_network_peers::WithPeerId;
+use reth_primitives_traits::{Account, Bytecode};
+use reth_provider::{
+    DBProvider, HeaderProvider, ProviderError, PruneCheckpointWriter, StageCheckpointReader,
+    StageCheckpointWriter, StateWriter, StatsReader, TrieWriter,
+};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages_api::{
+    EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
+    UnwindInput, UnwindOutput,
+};
+use reth_storage_errors::provider::ProviderResult;
+use reth_trie::{
+    encode_path_leaf, prefix_set::TriePrefixSetsMut, proof::Proof, HashedPostState, HashedStorage,
+    IntermediateStateRootState, MultiProofTargets, Nibbles, RlpNode, StateRoot, StateRootProgress,
+    StorageRoot, TrieAccount, TrieNode, EMPTY_ROOT_HASH,
+};
+use reth_trie_db::{DatabaseProof, DatabaseStateRoot, DatabaseStorageRoot};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::time::Sleep;
+use tracing::*;
+
+/// The number of ranges the account keyspace is split into, which are downloaded concurrently.
+const ACCOUNT_RANGES: u8 = 16;
+
+/// The maximum number of requests in flight.
+const MAX_CONCURRENT_REQUESTS: usize = 32;
+
+/// The soft limit for the size of a response, as requested from the peer.
+const RESPONSE_BYTES: u64 = 512 * 1024;
+
+/// The maximum number of accounts whose storage is requested at once.
+const STORAGE_ACCOUNTS_PER_REQUEST: usize = 128;
+
+/// The maximum number of bytecodes requested at once.
+const BYTECODES_PER_REQUEST: usize = 64;
+
+/// The maximum number of trie nodes requested at once.
+const TRIE_NODES_PER_REQUEST: usize = 128;
+
+/// The number of consecutive responses without any data after which the pivot is considered
+/// stale, peers only serve the state of recent blocks.
+const MAX_EMPTY_RESPONSES: usize = 64;
+
+/// The number of times the trie is healed against the same pivot before it's considered stale.
+const MAX_HEAL_ROUNDS: usize = 4;
+
+/// The delay before sending requests again if there was no peer to send them to.
+const NO_PEERS_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The snap sync stage downloads the state of the pivot block from peers over the `snap/1`
+/// protocol, instead of executing all blocks from genesis.
+///
+/// The pivot is the target of the pipeline, the stage
+///
+/// 1. downloads all accounts, storage slots and bytecodes of the pivot state, writing them
+///    directly to the hashed state tables,
+/// 2. computes the state trie of the downloaded state, and
+/// 3. heals the trie by requesting the trie nodes that differ from the pivot trie, which fixes
+///    whatever changed while the state was downloaded.
+///
+/// Once the state root matches the pivot header, the checkpoints of the stages that build the
+/// state are set to the pivot, so the pipeline continues by executing the blocks after it. There
+/// are no changesets below the pivot, so the account and storage history is only available from
+/// the pivot onwards.
+///
+/// Peers only serve the state of recent blocks. If they no longer serve the pivot state, the stage
+/// finishes at block zero and continues with the more recent target of the next pipeline run,
+/// keeping the state downloaded so far. Downloaded ranges survive restarts, the trie is computed
+/// again.
+///
+/// The stage is a no-op if blocks were executed already.
+///
+/// # Tables
+///
+/// The downloaded state is written to these tables:
+///
+/// - [`HashedAccounts`][reth_db_api::tables::HashedAccounts]
+/// - [`HashedStorages`][reth_db_api::tables::HashedStorages]
+/// - [`Bytecodes`][reth_db_api::tables::Bytecodes]
+/// - [`AccountsTrie`][reth_db_api::tables::AccountsTrie]
+/// - [`StoragesTrie`][reth_db_api::tables::StoragesTrie]
+#[derive(Debug)]
+pub struct SnapSyncStage<C: SnapClient> {
+    /// The client to request the state from.
+    client: C,
+    /// The state of the sync, set up on the first execution.
+    sync: Option<SnapSync>,
+    /// The requests in flight.
+    inflight: FuturesUnordered<SnapRequestFuture<C::Output>>,
+    /// Responses that are yet to be written.
+    responses: Vec<(SnapRequest, WithPeerId<SnapResponse>)>,
+    /// Set if there was no peer to send the requests to.
+    retry_delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl<C: SnapClient> SnapSyncStage<C> {
+    /// Create a new stage that downloads the state from the peers of the client.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            sync: None,
+            inflight: FuturesUnordered::new(),
+            responses: Vec::new(),
+            retry_delay: None,
+        }
+    }
+
+    /// Finishes the sync, the pipeline continues with the blocks after the pivot.
+    fn finish<Provider>(
+        &mut self,
+        provider: &Provider,
+        pivot: BlockNumber,
+    ) -> Result<ExecOutput, StageError>
+    where
+        Provider: StageCheckpointWriter + PruneCheckpointWriter + StatsReader,
+    {
+        info!(target: "sync::stages::snap_sync", pivot, "Snap sync finished");
+        self.sync = None;
+
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, pivot.to_be_bytes().into())?;
+        for stage_id in [
+            StageId::Execution,
+            StageId::MerkleUnwind,
+            StageId::AccountHashing,
+            StageId::StorageHashing,
+            StageId::MerkleExecute,
+            StageId::IndexStorageHistory,
+            StageId::IndexAccountHistory,
+        ] {
+            provider.save_stage_checkpoint(stage_id, StageCheckpoint::new(pivot))?;
+        }
+
+        // There are no changesets below the pivot, so the state of those blocks is unavailable.
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            provider.save_prune_checkpoint(
+                segment,
+                PruneCheckpoint {
+                    block_number: Some(pivot),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(pivot + 1),
+                },
+            )?;
+        }
+
+        let accounts = provider.count_entries::<tables::HashedAccounts>()? as u64;
+        Ok(ExecOutput {
+            checkpoint: StageCheckpoint::new(pivot).with_entities_stage_checkpoint(
+                EntitiesCheckpoint { processed: accounts, total: accounts },
+            ),
+            done: true,
+        })
+    }
+}
+
+impl<C, Provider> Stage<Provider> for SnapSyncStage<C>
+where
+    C: SnapClient + 'static,
+    Provider: DBProvider<Tx: DbTxMut>
+        + HeaderProvider
+        + StageCheckpointReader
+        + StageCheckpointWriter
+        + PruneCheckpointWriter
+        + StateWriter
+        + TrieWriter
+        + StatsReader,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::SnapSync
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        let Self { client, sync, inflight, responses, retry_delay } = self;
+
+        // The sync is set up by the execution, which also moves it to the next phase.
+        let Some(sync) = sync.as_mut().filter(|sync| sync.pivot == input.target()) else {
+            return Poll::Ready(Ok(()))
+        };
+        if sync.stale || matches!(sync.phase, Phase::StateRoot(_)) {
+            return Poll::Ready(Ok(()))
+        }
+
+        loop {
+            if let Some(delay) = retry_delay {
+                if delay.poll_unpin(cx).is_pending() {
+                    break
+                }
+                *retry_delay = None;
+            }
+
+            while inflight.len() < MAX_CONCURRENT_REQUESTS {
+                let Some(request) = sync.next_request() else { break };
+                inflight.push(send_request(client, sync.root, request));
+            }
+
+            match inflight.poll_next_unpin(cx) {
+                Poll::Ready(Some((request, Ok(response)))) => responses.push((request, response)),
+                Poll::Ready(Some((request, Err(err)))) => {
+                    trace!(target: "sync::stages::snap_sync", %err, "Snap request failed");
+                    sync.requeue(request);
+                    if err == RequestError::UnsupportedCapability {
+                        debug!(target: "sync::stages::snap_sync", "No snap peers, retrying");
+                        *retry_delay = Some(Box::pin(tokio::time::sleep(NO_PEERS_RETRY_DELAY)));
+                    }
+                }
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
+        // Responses are written in batches, or once everything that was requested is answered.
+        if responses.len() >= MAX_CONCURRENT_REQUESTS ||
+            (inflight.is_empty() && (!responses.is_empty() || sync.is_complete()))
+        {
+            return Poll::Ready(Ok(()))
+        }
+        Poll::Pending
+    }
+
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        let target = input.target();
+        let executed = provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default();
+        if input.checkpoint().block_number > 0 || executed.block_number > 0 || target == 0 {
+            // The state was either downloaded or built by executing blocks.
+            self.sync = None;
+            return Ok(ExecOutput::done(StageCheckpoint::new(target)))
+        }
+
+        let root = provider
+            .header_by_number(target)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(target.into()))?
+            .state_root();
+
+        match &mut self.sync {
+            Some(sync) if sync.pivot == target && !sync.stale => {}
+            Some(sync) => {
+                // Either there's a more recent pivot, or the peers are asked for the stale pivot
+                // again.
+                info!(target: "sync::stages::snap_sync", pivot = target, previous_pivot = sync.pivot, "Restarting snap sync at pivot");
+                for request in
+                    std::mem::take(&mut self.inflight).into_iter().filter_map(|fut| fut.request)
+                {
+                    sync.requeue(request);
+                }
+                // The trie nodes of the previous pivot don't belong to the new trie.
+                self.responses.retain(|(request, _)| !matches!(request, SnapRequest::TrieNodes(_)));
+                self.retry_delay = None;
+                sync.set_pivot(target, root);
+            }
+            None => {
+                if input.checkpoint().entities_stage_checkpoint().is_none() {
+                    // This is a new sync, remove the genesis state.
+                    let tx = provider.tx_ref();
+                    tx.clear::<tables::PlainAccountState>()?;
+                    tx.clear::<tables::PlainStorageState>()?;
+                    tx.clear::<tables::HashedAccounts>()?;
+                    tx.clear::<tables::HashedStorages>()?;
+                }
+                info!(target: "sync::stages::snap_sync", pivot = target, "Starting snap sync");
+                self.sync = Some(SnapSync::new(provider, target, root)?);
+            }
+        }
+        let Some(sync) = self.sync.as_mut() else { unreachable!("sync is set up above") };
+
+        for (request, response) in std::mem::take(&mut self.responses) {
+            let (peer_id, response) = response.split();
+            if !sync.on_response(provider, &request, response)? {
+                debug!(target: "sync::stages::snap_sync", %peer_id, "Invalid snap response");
+                self.client.report_bad_message(peer_id);
+                sync.requeue(request);
+            }
+        }
+
+        let idle = self.inflight.is_empty() && sync.is_complete();
+        match &mut sync.phase {
+            Phase::Download if idle => {
+                info!(target: "sync::stages::snap_sync", pivot = target, "Downloaded state, computing the state root");
+                provider.tx_ref().clear::<tables::AccountsTrie>()?;
+                provider.tx_ref().clear::<tables::StoragesTrie>()?;
+                sync.phase = Phase::StateRoot(None);
+            }
+            Phase::StateRoot(state) => {
+                let progress = StateRoot::from_tx(provider.tx_ref())
+                    .with_intermediate_state(state.take().map(|state| *state))
+                    .root_with_progress()
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                match progress {
+                    StateRootProgress::Progress(state, _, updates) => {
+                        provider.write_trie_updates(&updates)?;
+                        sync.phase = Phase::StateRoot(Some(state));
+                    }
+                    StateRootProgress::Complete(root, _, updates) => {
+                        provider.write_trie_updates(&updates)?;
+                        if root == sync.root {
+                            return self.finish(provider, target)
+                        }
+                        debug!(target: "sync::stages::snap_sync", pivot = target, ?root, expected = ?sync.root, "Healing state trie");
+                        sync.heal(0);
+                    }
+                }
+            }
+            Phase::Heal(round) if idle => {
+                let round = *round;
+                let prefix_sets = std::mem::take(&mut sync.healed).freeze();
+                let (root, updates) = StateRoot::from_tx(provider.tx_ref())
+                    .with_prefix_sets(prefix_sets)
+                    .root_with_updates()
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                provider.write_trie_updates(&updates)?;
+                if root == sync.root {
+                    return self.finish(provider, target)
+                }
+
+                debug!(target: "sync::stages::snap_sync", pivot = target, round, ?root, expected = ?sync.root, "State trie still differs after healing");
+                if round + 1 < MAX_HEAL_ROUNDS {
+                    sync.heal(round + 1);
+                } else {
+                    sync.stale = true;
+                }
+            }
+            _ => {}
+        }
+
+        let progress = sync.progress(provider)?;
+        if sync.stale {
+            warn!(target: "sync::stages::snap_sync", pivot = target, "Peers no longer serve the pivot state, waiting for a more recent pivot");
+            // Finishing at block zero runs the pipeline again once there's a more recent target.
+            return Ok(ExecOutput {
+                checkpoint: StageCheckpoint::new(0).with_entities_stage_checkpoint(progress),
+                done: true,
+            })
+        }
+
+        Ok(ExecOutput {
+            checkpoint: StageCheckpoint::new(0).with_entities_stage_checkpoint(progress),
+            done: false,
+        })
+    }
+
+    fn unwind(
+        &mut self,
+        provider: &Provider,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        self.sync = None;
+        self.inflight.clear();
+        self.responses.clear();
+
+        if let Some(pivot) = snap_sync_pivot(provider)? {
+            if input.unwind_to < pivot {
+                return Err(StageError::Fatal(
+                    format!(
+                        "cannot unwind to block {} below the snap sync pivot {pivot}",
+                        input.unwind_to
+                    )
+                    .into(),
+                ))
+            }
+        }
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// Sends the request to a peer of the client.
+fn send_request<C: SnapClient>(
+    client: &C,
+    root_hash: B256,
+    request: SnapRequest,
+) -> SnapRequestFuture<C::Output> {
+    let fut = match &request {
+        SnapRequest::AccountRange { start, limit, .. } => {
+            client.get_account_range(GetAccountRangeMessage {
+                request_id: 0,
+                root_hash,
+                starting_hash: *start,
+                limit_hash: *limit,
+                response_bytes: RESPONSE_BYTES,
+            })
+        }
+        SnapRequest::StorageRanges(task) => client.get_storage_ranges(GetStorageRangesMessage {
+            request_id: 0,
+            root_hash,
+            account_hashes: task.accounts.clone(),
+            starting_hash: task.start,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: RESPONSE_BYTES,
+        }),
+        SnapRequest::ByteCodes(hashes) => client.get_byte_codes(GetByteCodesMessage {
+            request_id: 0,
+            hashes: hashes.clone(),
+            response_bytes: RESPONSE_BYTES,
+        }),
+        SnapRequest::TrieNodes(nodes) => client.get_trie_nodes(GetTrieNodesMessage {
+            request_id: 0,
+            root_hash,
+            paths: nodes
+                .iter()
+                .map(|(path, _)| TriePath {
+                    account_path: Bytes::copy_from_slice(&encode_path_leaf(path, false)),
+                    slot_paths: Vec::new(),
+                })
+                .collect(),
+            response_bytes: RESPONSE_BYTES,
+        }),
+    };
+    SnapRequestFuture { request: Some(request), fut }
+}
+
+/// Returns the pivot block if the state was downloaded by the [`SnapSyncStage`].
+pub(crate) fn snap_sync_pivot<Provider: DBProvider>(
+    provider: &Provider,
+) -> Result<Option<BlockNumber>, DatabaseError> {
+    Ok(provider
+        .tx_ref()
+        .get::<tables::StageCheckpointProgresses>(StageId::SnapSync.to_string())?
+        .and_then(|pivot| pivot.try_into().ok())
+        .map(u64::from_be_bytes))
+}
+
+/// The phases of the sync.
+#[derive(Debug)]
+enum Phase {
+    /// Downloading the accounts, storage and bytecodes.
+    Download,
+    /// Computing the state trie of the downloaded state.
+    StateRoot(Option<Box<IntermediateStateRootState>>),
+    /// Healing the state trie, in the given round.
+    Heal(usize),
+}
+
+/// A range of the account keyspace.
+#[derive(Debug)]
+struct AccountRange {
+    /// The first key of the range.
+    start: B256,
+    /// The last key of the range.
+    limit: B256,
+    /// The next key to request, `None` if the range is downloaded.
+    next: Option<B256>,
+    /// Whether the range is requested.
+    inflight: bool,
+}
+
+/// Storage to download.
+#[derive(Debug, Clone)]
+struct StorageTask {
+    /// The hashed addresses of the accounts.
+    accounts: Vec<B256>,
+    /// The first slot to request of the first account.
+    start: B256,
+}
+
+/// A request sent to a peer.
+#[derive(Debug)]
+enum SnapRequest {
+    /// Accounts of a range.
+    AccountRange { range: usize, start: B256, limit: B256 },
+    /// Storage slots of accounts.
+    StorageRanges(StorageTask),
+    /// Bytecodes by hash.
+    ByteCodes(Vec<B256>),
+    /// Account trie nodes by path and hash.
+    TrieNodes(Vec<(Nibbles, B256)>),
+}
+
+/// A request in flight, resolves to the request and the response.
+#[derive(Debug)]
+struct SnapRequestFuture<F> {
+    request: Option<SnapRequest>,
+    fut: F,
+}
+
+impl<F> Future for SnapRequestFuture<F>
+where
+    F: Future<Output = PeerRequestResult<SnapResponse>> + Unpin,
+{
+    type Output = (SnapRequest, PeerRequestResult<SnapResponse>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let response = ready!(this.fut.poll_unpin(cx));
+        Poll::Ready((this.request.take().expect("polled after completion"), response))
+    }
+}
+
+/// The state of the sync.
+#[derive(Debug)]
+struct SnapSync {
+    /// The pivot block.
+    pivot: BlockNumber,
+    /// The state root of the pivot block.
+    root: B256,
+    /// The current phase.
+    phase: Phase,
+    /// The ranges of the account keyspace.
+    ranges: Vec<AccountRange>,
+    /// Storage left to request.
+    storages: VecDeque<StorageTask>,
+    /// Bytecodes left to request.
+    codes: VecDeque<B256>,
+    /// Accounts that are written once their bytecode is downloaded, by code hash.
+    accounts_by_code: B256Map<Vec<(B256, Account)>>,
+    /// Trie nodes left to request, by path and hash.
+    nodes: VecDeque<(Nibbles, B256)>,
+    /// The changes to the hashed state while healing, the trie is updated at the end of a round.
+    healed: TriePrefixSetsMut,
+    /// The number of consecutive responses without any data.
+    empty_responses: usize,
+    /// Whether the peers no longer serve the pivot state.
+    stale: bool,
+}
+
+impl SnapSync {
+    /// Sets up the sync, resuming the ranges after the last downloaded account of each.
+    fn new<Provider: DBProvider>(
+        provider: &Provider,
+        pivot: BlockNumber,
+        root: B256,
+    ) -> Result<Self, DatabaseError> {
+        let mut cursor = provider.tx_ref().cursor_read::<tables::HashedAccounts>()?;
+        let mut ranges = Vec::with_capacity(ACCOUNT_RANGES as usize);
+        for range in 0..ACCOUNT_RANGES {
+            let step = u8::MAX / ACCOUNT_RANGES + 1;
+            let mut start = B256::ZERO;
+            start[0] = range * step;
+            let mut limit = B256::repeat_byte(0xff);
+            limit[0] = start[0] + (step - 1);
+
+            // The last downloaded account of the range.
+            let last = match next_key(limit) {
+                Some(end) if cursor.seek(end)?.is_some() => cursor.prev()?,
+                _ => cursor.last()?,
+            }
+            .map(|(key, _)| key)
+            .filter(|key| *key >= start);
+
+            ranges.push(AccountRange {
+                start,
+                limit,
+                next: Some(last.unwrap_or(start)),
+                inflight: false,
+            });
+        }
+
+        Ok(Self {
+            pivot,
+            root,
+            phase: Phase::Download,
+            ranges,
+            storages: VecDeque::new(),
+            codes: VecDeque::new(),
+            accounts_by_code: B256Map::default(),
+            nodes: VecDeque::new(),
+            healed: TriePrefixSetsMut::default(),
+            empty_responses: 0,
+            stale: false,
+        })
+    }
+
+    /// Moves the sync to a more recent pivot.
+    fn set_pivot(&mut self, pivot: BlockNumber, root: B256) {
+        self.pivot = pivot;
+        self.root = root;
+        self.empty_responses = 0;
+        self.stale = false;
+        if matches!(self.phase, Phase::Heal(_)) {
+            self.heal(0);
+        }
+    }
+
+    /// Starts a round of healing the trie, from the root down.
+    fn heal(&mut self, round: usize) {
+        self.phase = Phase::Heal(round);
+        self.nodes.clear();
+        self.nodes.push_back((Nibbles::default(), self.root));
+    }
+
+    /// Returns `true` if there's nothing left to request.
+    fn is_complete(&self) -> bool {
+        self.codes.is_empty() &&
+            self.storages.is_empty() &&
+            self.nodes.is_empty() &&
+            self.accounts_by_code.is_empty() &&
+            self.ranges.iter().all(|range| range.next.is_none())
+    }
+
+    /// Returns the next request to send.
+    ///
+    /// Bytecodes and storage are requested first, to bound the accounts held in memory.
+    fn next_request(&mut self) -> Option<SnapRequest> {
+        if !self.codes.is_empty() {
+            let count = self.codes.len().min(BYTECODES_PER_REQUEST);
+            return Some(SnapRequest::ByteCodes(self.codes.drain(..count).collect()))
+        }
+        if !self.nodes.is_empty() {
+            let count = self.nodes.len().min(TRIE_NODES_PER_REQUEST);
+            return Some(SnapRequest::TrieNodes(self.nodes.drain(..count).collect()))
+        }
+        if let Some(task) = self.storages.pop_front() {
+            return Some(SnapRequest::StorageRanges(task))
+        }
+
+        let (index, range) = self
+            .ranges
+            .iter_mut()
+            .enumerate()
+            .find(|(_, range)| !range.inflight && range.next.is_some())?;
+        range.inflight = true;
+        Some(SnapRequest::AccountRange { range: index, start: range.next?, limit: range.limit })
+    }
+
+    /// Requests the data of the request again.
+    fn requeue(&mut self, request: SnapRequest) {
+        match request {
+            SnapRequest::AccountRange { range, .. } => self.ranges[range].inflight = false,
+            SnapRequest::StorageRanges(task) => self.storages.push_back(task),
+            SnapRequest::ByteCodes(hashes) => self.codes.extend(hashes),
+            SnapRequest::TrieNodes(nodes) => {
+                if matches!(self.phase, Phase::Heal(_)) {
+                    self.nodes.extend(nodes)
+                }
+            }
+        }
+    }
+
+    /// Counts a response without any data.
+    const fn on_empty_response(&mut self) {
+        self.empty_responses += 1;
+        if self.empty_responses >= MAX_EMPTY_RESPONSES {
+            self.stale = true;
+        }
+    }
+
+    /// Writes the response, returns `false` if it's invalid.
+    fn on_response<Provider>(
+        &mut self,
+        provider: &Provider,
+        request: &SnapRequest,
+        response: SnapResponse,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut> + StateWriter,
+    {
+        match (request, response) {
+            (SnapRequest::AccountRange { range, start, .. }, SnapResponse::AccountRange(msg)) => {
+                self.on_account_range(provider, *range, *start, msg)
+            }
+            (SnapRequest::StorageRanges(task), SnapResponse::StorageRanges(msg)) => {
+                self.on_storage_ranges(provider, task, msg)
+            }
+            (SnapRequest::ByteCodes(hashes), SnapResponse::ByteCodes(msg)) => {
+                self.on_byte_codes(provider, hashes, msg)
+            }
+            (SnapRequest::TrieNodes(nodes), SnapResponse::TrieNodes(msg)) => {
+                self.on_trie_nodes(provider, nodes, msg)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn on_account_range<Provider>(
+        &mut self,
+        provider: &Provider,
+        range: usize,
+        start: B256,
+        response: AccountRangeMessage,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        let limit = self.ranges[range].limit;
+        let mut accounts = Vec::with_capacity(response.accounts.len());
+        let mut last = None;
+        for AccountData { hash, body } in &response.accounts {
+            if *hash < start || last.is_some_and(|last| last >= *hash) {
+                return Ok(false)
+            }
+            last = Some(*hash);
+
+            let Some(account) = SlimAccount::decode_account(body) else { return Ok(false) };
+            if *hash <= limit {
+                accounts.push((*hash, account));
+            }
+        }
+
+        let range = &mut self.ranges[range];
+        range.inflight = false;
+        match last {
+            Some(last) => {
+                range.next = next_key(last).filter(|_| last < limit);
+                self.empty_responses = 0;
+            }
+            // An empty response with a proof means there are no more accounts in the range.
+            None if !response.proof.is_empty() => range.next = None,
+            None => {
+                self.on_empty_response();
+                return Ok(true)
+            }
+        }
+
+        let mut state = HashedPostState::default();
+        let mut storages = Vec::new();
+        for (hashed_address, (account, storage_root)) in accounts {
+            if storage_root != EMPTY_ROOT_HASH {
+                storages.push(hashed_address);
+            }
+            self.add_account(provider.tx_ref(), &mut state, hashed_address, account)?;
+        }
+        self.storages.extend(
+            storages
+                .chunks(STORAGE_ACCOUNTS_PER_REQUEST)
+                .map(|accounts| StorageTask { accounts: accounts.to_vec(), start: B256::ZERO }),
+        );
+        self.write_state(provider, state)?;
+
+        Ok(true)
+    }
+
+    fn on_storage_ranges<Provider>(
+        &mut self,
+        provider: &Provider,
+        task: &StorageTask,
+        response: StorageRangesMessage,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        if response.slots.len() > task.accounts.len() {
+            return Ok(false)
+        }
+
+        let mut state = HashedPostState::default();
+        let mut last_slot = None;
+        for (index, slots) in response.slots.iter().enumerate() {
+            let start = if index == 0 { task.start } else { B256::ZERO };
+            // The first part of the storage replaces the local storage of the account.
+            let mut storage = HashedStorage::new(start == B256::ZERO);
+            last_slot = None;
+            for StorageData { hash, data } in slots {
+                if *hash < start || last_slot.is_some_and(|last| last >= *hash) {
+                    return Ok(false)
+                }
+                last_slot = Some(*hash);
+
+                let Ok(value) = U256::decode(&mut data.as_ref()) else { return Ok(false) };
+                storage.storage.insert(*hash, value);
+            }
+            state.storages.insert(task.accounts[index], storage);
+        }
+
+        if response.slots.is_empty() && response.proof.is_empty() {
+            self.on_empty_response();
+            self.storages.push_back(task.clone());
+            return Ok(true)
+        }
+        self.empty_responses = 0;
+
+        // A proof means that the storage of the last account is incomplete, without slots it
+        // proves that the first account has no slots after the start.
+        let mut served = response.slots.len();
+        if !response.proof.is_empty() {
+            if served == 0 {
+                served = 1;
+            } else if let Some(start) = last_slot.and_then(next_key) {
+                self.storages
+                    .push_back(StorageTask { accounts: vec![task.accounts[served - 1]], start });
+            }
+        }
+        if served < task.accounts.len() {
+            self.storages.push_back(StorageTask {
+                accounts: task.accounts[served..].to_vec(),
+                start: B256::ZERO,
+            });
+        }
+
+        self.write_state(provider, state)?;
+        Ok(true)
+    }
+
+    fn on_byte_codes<Provider>(
+        &mut self,
+        provider: &Provider,
+        hashes: &[B256],
+        response: ByteCodesMessage,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut> + StateWriter,
+    {
+        // Peers may skip codes, so they're matched by hash.
+        let mut missing = hashes.to_vec();
+        let mut codes = Vec::with_capacity(response.codes.len());
+        for code in response.codes {
+            let hash = keccak256(&code);
+            let Some(index) = missing.iter().position(|missing| *missing == hash) else {
+                return Ok(false)
+            };
+            missing.swap_remove(index);
+            codes.push((hash, code));
+        }
+
+        if codes.is_empty() {
+            self.on_empty_response();
+        } else {
+            self.empty_responses = 0;
+        }
+        self.codes.extend(missing);
+
+        let mut state = HashedPostState::default();
+        let mut cursor = provider.tx_ref().cursor_write::<tables::Bytecodes>()?;
+        for (hash, code) in codes {
+            cursor.upsert(hash, &Bytecode::new_raw(code))?;
+            for (hashed_address, account) in self.accounts_by_code.remove(&hash).unwrap_or_default()
+            {
+                state.accounts.insert(hashed_address, Some(account));
+            }
+        }
+        drop(cursor);
+
+        self.write_state(provider, state)?;
+        Ok(true)
+    }
+
+    fn on_trie_nodes<Provider>(
+        &mut self,
+        provider: &Provider,
+        nodes: &[(Nibbles, B256)],
+        response: TrieNodesMessage,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        if !matches!(self.phase, Phase::Heal(_)) {
+            return Ok(true)
+        }
+        if response.nodes.len() > nodes.len() {
+            return Ok(false)
+        }
+
+        let mut healed = Vec::with_capacity(response.nodes.len());
+        for (&(path, hash), node) in nodes.iter().zip(response.nodes) {
+            // The peer doesn't have the node, it's requested again.
+            if node.is_empty() {
+                break
+            }
+            if keccak256(&node) != hash {
+                return Ok(false)
+            }
+            healed.push((path, node));
+        }
+
+        if healed.is_empty() {
+            self.on_empty_response();
+        } else {
+            self.empty_responses = 0;
+        }
+        self.nodes.extend(nodes[healed.len()..].iter().copied());
+
+        // A subtree is only healed if the local node at the same path differs.
+        let targets = MultiProofTargets::accounts(healed.iter().map(|(path, _)| path_key(path)));
+        let local = Proof::from_tx(provider.tx_ref())
+            .multiproof(targets)
+            .map_err(|err| StageError::Fatal(Box::new(err)))?
+            .account_subtree;
+        for (path, node) in healed {
+            self.heal_node(provider, path, &node, local.get(&path))?;
+        }
+
+        Ok(true)
+    }
+
+    /// Heals the subtree of the pivot trie node at the path.
+    fn heal_node<Provider>(
+        &mut self,
+        provider: &Provider,
+        path: Nibbles,
+        node: &[u8],
+        local: Option<&Bytes>,
+    ) -> Result<(), StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        if local.is_some_and(|local| local.as_ref() == node) {
+            return Ok(())
+        }
+        let local_branch = local.and_then(|local| match TrieNode::decode(&mut local.as_ref()) {
+            Ok(TrieNode::Branch(branch)) => Some(branch),
+            _ => None,
+        });
+
+        match TrieNode::decode(&mut &node[..]).map_err(|err| StageError::Fatal(Box::new(err)))? {
+            TrieNode::EmptyRoot => self.delete_accounts(provider, path, None)?,
+            TrieNode::Branch(branch) => {
+                let local_children = local_branch
+                    .as_ref()
+                    .map(|local| {
+                        local.as_ref().children().map(|(_, child)| child.cloned()).collect()
+                    })
+                    .unwrap_or_else(|| vec![None; 16]);
+                for ((nibble, child), local_child) in branch.as_ref().children().zip(local_children)
+                {
+                    let mut child_path = path;
+                    child_path.push(nibble);
+                    match child {
+                        Some(child) if local_child.as_ref() == Some(child) => {}
+                        Some(child) => self.heal_child(provider, child_path, child)?,
+                        None if local_branch.is_some() && local_child.is_none() => {}
+                        None => self.delete_accounts(provider, child_path, None)?,
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                let child_path = path.join(&extension.key);
+                self.delete_accounts(provider, path, Some(child_path))?;
+                self.heal_child(provider, child_path, &extension.child)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                let key = path.join(&leaf.key);
+                if key.len() != 64 {
+                    return Err(StageError::Fatal(format!("invalid leaf path {key:?}").into()))
+                }
+                self.delete_accounts(provider, path, Some(key))?;
+
+                let account = TrieAccount::decode(&mut leaf.value.as_slice())
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                self.heal_account(provider, B256::from_slice(&key.pack()), account)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Heals the child of a pivot trie node.
+    fn heal_child<Provider>(
+        &mut self,
+        provider: &Provider,
+        path: Nibbles,
+        child: &RlpNode,
+    ) -> Result<(), StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        match child.as_hash() {
+            Some(hash) => self.nodes.push_back((path, hash)),
+            // Nodes shorter than a hash are embedded in their parent.
+            None => self.heal_node(provider, path, child, None)?,
+        }
+        Ok(())
+    }
+
+    /// Writes the account of a pivot trie leaf, requesting its storage if it differs.
+    fn heal_account<Provider>(
+        &mut self,
+        provider: &Provider,
+        hashed_address: B256,
+        account: TrieAccount,
+    ) -> Result<(), StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        let mut state = HashedPostState::default();
+        if account.storage_root == EMPTY_ROOT_HASH {
+            state.storages.insert(hashed_address, HashedStorage::new(true));
+        } else {
+            let storage_root = StorageRoot::from_tx_hashed(provider.tx_ref(), hashed_address)
+                .root()
+                .map_err(|err| StageError::Fatal(Box::new(err)))?;
+            if storage_root != account.storage_root {
+                self.storages
+                    .push_back(StorageTask { accounts: vec![hashed_address], start: B256::ZERO });
+            }
+        }
+
+        let account = Account {
+            nonce: account.nonce,
+            balance: account.balance,
+            bytecode_hash: Some(account.code_hash).filter(|hash| *hash != KECCAK_EMPTY),
+        };
+        self.add_account(provider.tx_ref(), &mut state, hashed_address, account)?;
+        self.write_state(provider, state)?;
+        Ok(())
+    }
+
+    /// Deletes the local accounts below the path, except the ones below `keep`.
+    fn delete_accounts<Provider>(
+        &mut self,
+        provider: &Provider,
+        path: Nibbles,
+        keep: Option<Nibbles>,
+    ) -> Result<(), StageError>
+    where
+        Provider: DBProvider + StateWriter,
+    {
+        let mut state = HashedPostState::default();
+        let mut cursor = provider.tx_ref().cursor_read::<tables::HashedAccounts>()?;
+        let mut entry = cursor.seek(path_key(&path))?;
+        while let Some((hashed_address, _)) = entry {
+            let key = Nibbles::unpack(hashed_address);
+            if !key.starts_with(&path) {
+                break
+            }
+            if !keep.is_some_and(|keep| key.starts_with(&keep)) {
+                state.accounts.insert(hashed_address, None);
+                state.storages.insert(hashed_address, HashedStorage::new(true));
+            }
+            entry = cursor.next()?;
+        }
+        drop(cursor);
+
+        if !state.accounts.is_empty() {
+            trace!(target: "sync::stages::snap_sync", ?path, accounts = state.accounts.len(), "Deleting accounts");
+            self.write_state(provider, state)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the account to the state, or holds it back until its bytecode is downloaded.
+    fn add_account<TX: DbTx>(
+        &mut self,
+        tx: &TX,
+        state: &mut HashedPostState,
+        hashed_address: B256,
+        account: Account,
+    ) -> Result<(), DatabaseError> {
+        if let Some(code_hash) = account.bytecode_hash {
+            if let Some(accounts) = self.accounts_by_code.get_mut(&code_hash) {
+                accounts.push((hashed_address, account));
+                return Ok(())
+            }
+            if tx.get::<tables::Bytecodes>(code_hash)?.is_none() {
+                self.accounts_by_code.insert(code_hash, vec![(hashed_address, account)]);
+                self.codes.push_back(code_hash);
+                return Ok(())
+            }
+        }
+        state.accounts.insert(hashed_address, Some(account));
+        Ok(())
+    }
+
+    /// Writes the state, keeping track of the changes while healing.
+    fn write_state<Provider: StateWriter>(
+        &mut self,
+        provider: &Provider,
+        state: HashedPostState,
+    ) -> ProviderResult<()> {
+        if matches!(self.phase, Phase::Heal(_)) {
+            self.healed.extend(state.construct_prefix_sets());
+        }
+        provider.write_hashed_state(&state.into_sorted())
+    }
+
+    /// Returns the number of downloaded accounts and an estimate of the total, extrapolated from
+    /// the downloaded share of the keyspace.
+    fn progress<Provider: StatsReader>(
+        &self,
+        provider: &Provider,
+    ) -> ProviderResult<EntitiesCheckpoint> {
+        let processed = provider.count_entries::<tables::HashedAccounts>()? as u64;
+        let prefix = |key: B256| u64::from_be_bytes(key[..8].try_into().unwrap()) as u128;
+        let downloaded: u128 = self
+            .ranges
+            .iter()
+            .map(|range| {
+                range.next.map_or_else(|| prefix(range.limit) + 1, prefix) - prefix(range.start)
+            })
+            .sum();
+
+        let total = (processed as u128 * (u64::MAX as u128 + 1))
+            .checked_div(downloaded)
+            .map_or(processed, |total| total as u64);
+        Ok(EntitiesCheckpoint { processed, total: total.max(processed) })
+    }
+}
+
+/// An account in the slim format of the snap protocol, which omits the empty storage root and
+/// code hash.
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct SlimAccount {
+    nonce: u64,
+    balance: U256,
+    storage_root: Bytes,
+    code_hash: Bytes,
+}
+
+impl SlimAccount {
+    /// Decodes the account and its storage root, returns `None` if it's malformed.
+    fn decode_account(mut body: &[u8]) -> Option<(Account, B256)> {
+        let slim = Self::decode(&mut body).ok()?;
+        let storage_root = match slim.storage_root.len() {
+            0 => EMPTY_ROOT_HASH,
+            32 => B256::from_slice(&slim.storage_root),
+            _ => return None,
+        };
+        let code_hash = match slim.code_hash.len() {
+            0 => None,
+            32 => Some(B256::from_slice(&slim.code_hash)).filter(|hash| *hash != KECCAK_EMPTY),
+            _ => return None,
+        };
+        Some((
+            Account { nonce: slim.nonce, balance: slim.balance, bytecode_hash: code_hash },
+            storage_root,
+        ))
+    }
+}
+
+/// Returns the key following the given one, if any.
+fn next_key(key: B256) -> Option<B256> {
+    U256::from_be_bytes(key.0).checked_add(U256::from(1)).map(|key| B256::from(key.to_be_bytes()))
+}
+
+/// Returns the first key below the path.
+fn path_key(path: &Nibbles) -> B256 {
+    let mut nibbles = path.to_vec();
+    nibbles.resize(64, 0);
+    B256::from_slice(&Nibbles::from_nibbles(nibbles).pack())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestStageDB;
+    use alloy_consensus::Header;
+    use futures_util::future::{ready, Ready};
+    use reth_db_api::cursor::DbDupCursorRO;
+    use reth_network_p2p::{
+        download::DownloadClient, priority::Priority, snap::client::NoopSnapClient,
+    };
+    use reth_network_peers::PeerId;
+    use reth_primitives_traits::{SealedHeader, StorageEntry};
+    use reth_provider::{
+        test_utils::MockNodeTypesWithDB, DatabaseProviderFactory, ProviderFactory,
+    };
+    use reth_stages_api::StageExt;
+
+    const PIVOT: BlockNumber = 10;
+
+    /// Serves the state of a database, in responses of at most `max_entries` accounts or slots.
+    #[derive(Debug)]
+    struct TestSnapClient {
+        factory: ProviderFactory<MockNodeTypesWithDB>,
+        max_entries: usize,
+    }
+
+    impl TestSnapClient {
+        fn respond(&self, response: SnapResponse) -> Ready<PeerRequestResult<SnapResponse>> {
+            ready(Ok(WithPeerId::new(PeerId::random(), response)))
+        }
+    }
+
+    impl DownloadClient for TestSnapClient {
+        fn report_bad_message(&self, _peer_id: PeerId) {
+            panic!("unexpected invalid response")
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl SnapClient for TestSnapClient {
+        type Output = Ready<PeerRequestResult<SnapResponse>>;
+
+        fn get_account_range_with_priority(
+            &self,
+            request: GetAccountRangeMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            let provider = self.factory.provider().unwrap();
+            let tx = provider.tx_ref();
+            let accounts = tx
+                .cursor_read::<tables::HashedAccounts>()
+                .unwrap()
+                .walk(Some(request.starting_hash))
+                .unwrap()
+                .map(Result::unwrap)
+                .take_while(|(hash, _)| *hash <= request.limit_hash)
+                .take(self.max_entries)
+                .map(|(hash, account)| {
+                    let storage_root = StorageRoot::from_tx_hashed(tx, hash).root().unwrap();
+                    let slim = SlimAccount {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage_root: if storage_root == EMPTY_ROOT_HASH {
+                            Bytes::new()
+                        } else {
+                            Bytes::copy_from_slice(storage_root.as_slice())
+                        },
+                        code_hash: account
+                            .bytecode_hash
+                            .map(|hash| Bytes::copy_from_slice(hash.as_slice()))
+                            .unwrap_or_default(),
+                    };
+                    AccountData { hash, body: alloy_rlp::encode(slim).into() }
+                })
+                .collect();
+
+            self.respond(SnapResponse::AccountRange(AccountRangeMessage {
+                request_id: request.request_id,
+                accounts,
+                proof: vec![Bytes::from_static(&[alloy_rlp::EMPTY_STRING_CODE])],
+            }))
+        }
+
+        fn get_storage_ranges_with_priority(
+            &self,
+            request: GetStorageRangesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            let provider = self.factory.provider().unwrap();
+            let mut cursor = provider.tx_ref().cursor_dup_read::<tables::HashedStorages>().unwrap();
+            let mut slots = Vec::new();
+            let mut served = 0;
+            let mut proof = Vec::new();
+            for (index, hashed_address) in request.account_hashes.iter().enumerate() {
+                if served == self.max_entries {
+                    break
+                }
+
+                let start = if index == 0 { request.starting_hash } else { B256::ZERO };
+                let mut storage = Vec::new();
+                let mut entry = cursor.seek_by_key_subkey(*hashed_address, start).unwrap();
+                while let Some(StorageEntry { key, value }) = entry {
+                    if served == self.max_entries {
+                        break
+                    }
+                    served += 1;
+                    storage.push(StorageData { hash: key, data: alloy_rlp::encode(value).into() });
+                    entry = cursor.next_dup_val().unwrap();
+                }
+
+                let partial = !start.is_zero() || entry.is_some();
+                slots.push(storage);
+                if partial {
+                    proof.push(Bytes::from_static(&[alloy_rlp::EMPTY_STRING_CODE]));
+                    break
+                }
+            }
+
+            self.respond(SnapResponse::StorageRanges(StorageRangesMessage {
+                request_id: request.request_id,
+                slots,
+                proof,
+            }))
+        }
+
+        fn get_byte_codes_with_priority(
+            &self,
+            request: GetByteCodesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            let provider = self.factory.provider().unwrap();
+            let codes = request
+                .hashes
+                .iter()
+                .filter_map(|hash| provider.tx_ref().get::<tables::Bytecodes>(*hash).unwrap())
+                .map(|code| code.original_bytes())
+                .collect();
+
+            self.respond(SnapResponse::ByteCodes(ByteCodesMessage {
+                request_id: request.request_id,
+                codes,
+            }))
+        }
+
+        fn get_trie_nodes_with_priority(
+            &self,
+            request: GetTrieNodesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            let provider = self.factory.provider().unwrap();
+            let paths = request
+                .paths
+                .iter()
+                .map(|path| compact_to_nibbles(&path.account_path))
+                .collect::<Vec<_>>();
+            let proof = Proof::from_tx(provider.tx_ref())
+                .multiproof(MultiProofTargets::accounts(paths.iter().map(path_key)))
+                .unwrap()
+                .account_subtree;
+
+            self.respond(SnapResponse::TrieNodes(TrieNodesMessage {
+                request_id: request.request_id,
+                nodes: paths
+                    .iter()
+                    .map(|path| proof.get(path).cloned().unwrap_or_default())
+                    .collect(),
+            }))
+        }
+    }
+
+    /// Decodes a path in the compact encoding of the snap protocol.
+    fn compact_to_nibbles(compact: &[u8]) -> Nibbles {
+        let Some((first, rest)) = compact.split_first() else { return Nibbles::default() };
+        let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+        if first & 0x10 != 0 {
+            nibbles.push(first & 0x0f);
+        }
+        nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+        Nibbles::from_nibbles(nibbles)
+    }
+
+    /// Creates a database with accounts, some with storage and bytecode, and their trie. Returns
+    /// the database and the state root.
+    fn source_state() -> (TestStageDB, B256) {
+        let db = TestStageDB::default();
+        db.commit(|tx| {
+            for i in 0..64u64 {
+                let hashed_address = keccak256(U256::from(i).to_be_bytes::<32>());
+                let code = (i % 5 == 0).then(|| Bytes::from(vec![0x60, i as u8]));
+                if let Some(code) = &code {
+                    tx.put::<tables::Bytecodes>(keccak256(code), Bytecode::new_raw(code.clone()))?;
+                }
+                let account = Account {
+                    nonce: i,
+                    balance: U256::from(i * 1000),
+                    bytecode_hash: code.as_ref().map(keccak256),
+                };
+                tx.put::<tables::HashedAccounts>(hashed_address, account)?;
+
+                if i % 4 == 0 {
+                    for slot in 1..=3u64 {
+                        let key = keccak256(U256::from(slot).to_be_bytes::<32>());
+                        let value = U256::from(i + slot);
+                        tx.put::<tables::HashedStorages>(
+                            hashed_address,
+                            StorageEntry { key, value },
+                        )?;
+                    }
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let provider = db.factory.database_provider_rw().unwrap();
+        let (root, updates) = StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        provider.write_trie_updates(&updates).unwrap();
+        provider.commit().unwrap();
+
+        (db, root)
+    }
+
+    /// Creates a database with the pivot header.
+    fn target_db(root: B256) -> TestStageDB {
+        let db = TestStageDB::default();
+        let header = SealedHeader::seal_slow(Header {
+            number: PIVOT,
+            state_root: root,
+            ..Default::default()
+        });
+        db.insert_headers(std::iter::once(&header)).unwrap();
+        db
+    }
+
+    /// Waits until the stage is ready to execute with the provider.
+    async fn execute_ready<S: Stage<P>, P>(stage: &mut S, _provider: &P, input: ExecInput) {
+        StageExt::<P>::execute_ready(stage, input).await.unwrap();
+    }
+
+    /// Runs the stage until it's done, returns the checkpoint.
+    async fn run_stage(
+        stage: &mut SnapSyncStage<TestSnapClient>,
+        db: &TestStageDB,
+        checkpoint: Option<StageCheckpoint>,
+    ) -> StageCheckpoint {
+        let provider = db.factory.database_provider_rw().unwrap();
+        let mut input = ExecInput { target: Some(PIVOT), checkpoint };
+        loop {
+            execute_ready(stage, &provider, input).await;
+            let output = stage.execute(&provider, input).unwrap();
+            input.checkpoint = Some(output.checkpoint);
+            if output.done {
+                provider.commit().unwrap();
+                return output.checkpoint
+            }
+        }
+    }
+
+    fn assert_same_state(source: &TestStageDB, target: &TestStageDB) {
+        assert_eq!(
+            source.table::<tables::HashedAccounts>().unwrap(),
+            target.table::<tables::HashedAccounts>().unwrap()
+        );
+        assert_eq!(
+            source.table::<tables::HashedStorages>().unwrap(),
+            target.table::<tables::HashedStorages>().unwrap()
+        );
+        assert_eq!(
+            source.table::<tables::Bytecodes>().unwrap(),
+            target.table::<tables::Bytecodes>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn downloads_pivot_state() {
+        let (source, root) = source_state();
+        let db = target_db(root);
+        let client = TestSnapClient { factory: source.factory.clone(), max_entries: 8 };
+        let mut stage = SnapSyncStage::new(client);
+
+        let checkpoint = run_stage(&mut stage, &db, None).await;
+        assert_eq!(checkpoint.block_number, PIVOT);
+        assert_same_state(&source, &db);
+
+        let provider = db.factory.provider().unwrap();
+        assert_eq!(snap_sync_pivot(&provider).unwrap(), Some(PIVOT));
+        assert_eq!(
+            provider.get_stage_checkpoint(StageId::Execution).unwrap(),
+            Some(StageCheckpoint::new(PIVOT))
+        );
+        assert_eq!(
+            StateRoot::from_tx(provider.tx_ref()).root().unwrap(),
+            root,
+            "the trie is written"
+        );
+    }
+
+    #[tokio::test]
+    async fn heals_resumed_state() {
+        let (source, root) = source_state();
+        let db = target_db(root);
+
+        // The state of a previous pivot, with an account that no longer exists and a partially
+        // downloaded last range.
+        db.commit(|tx| {
+            let account = Account { nonce: 1, ..Default::default() };
+            tx.put::<tables::HashedAccounts>(B256::repeat_byte(0xff), account)?;
+            Ok(())
+        })
+        .unwrap();
+
+        let client = TestSnapClient { factory: source.factory.clone(), max_entries: 8 };
+        let mut stage = SnapSyncStage::new(client);
+        let checkpoint =
+            StageCheckpoint::new(0).with_entities_stage_checkpoint(EntitiesCheckpoint::default());
+
+        let checkpoint = run_stage(&mut stage, &db, Some(checkpoint)).await;
+        assert_eq!(checkpoint.block_number, PIVOT);
+        assert_same_state(&source, &db);
+    }
+
+    #[tokio::test]
+    async fn skips_executed_state() {
+        let db = TestStageDB::default();
+        let provider = db.factory.database_provider_rw().unwrap();
+        provider.save_stage_checkpoint(StageId::Execution, StageCheckpoint::new(1)).unwrap();
+
+        let mut stage = SnapSyncStage::new(NoopSnapClient);
+        let input = ExecInput { target: Some(PIVOT), checkpoint: None };
+        execute_ready(&mut stage, &provider, input).await;
+        assert_eq!(
+            stage.execute(&provider, input).unwrap(),
+            ExecOutput::done(StageCheckpoint::new(PIVOT))
+        );
+        assert_eq!(snap_sync_pivot(&provider).unwrap(), None);
+    }
+
+    #[test]
+    fn decodes_slim_accounts() {
+        let code_hash = B256::repeat_byte(1);
+        let slim = SlimAccount {
+            nonce: 1,
+            balance: U256::from(2),
+            storage_root: Bytes::new(),
+            code_hash: Bytes::copy_from_slice(code_hash.as_slice()),
+        };
+        assert_eq!(
+            SlimAccount::decode_account(&alloy_rlp::encode(slim)),
+            Some((
+                Account { nonce: 1, balance: U256::from(2), bytecode_hash: Some(code_hash) },
+                EMPTY_ROOT_HASH
+            ))
+        );
+
+        let invalid = SlimAccount {
+            nonce: 1,
+            balance: U256::ZERO,
+            storage_root: Bytes::from_static(&[1]),
+            code_hash: Bytes::new(),
+        };
+        assert_eq!(SlimAccount::decode_account(&alloy_rlp::encode(invalid)), None);
+    }
+
+    #[test]
+    fn path_keys() {
+        assert_eq!(path_key(&Nibbles::default()), B256::ZERO);
+        let mut key = B256::ZERO;
+        key[0] = 0xa0;
+        assert_eq!(path_key(&Nibbles::from_nibbles([0xa])), key);
+        assert_eq!(next_key(B256::repeat_byte(0xff)), None);
+        assert_eq!(next_key(B256::ZERO), Some(B256::with_last_byte(1)));
+    }
+}
@@ -11,6 +11,9 @@ pub enum StageId {
     Era,
     Headers,
     Bodies,
+    /// Only part of the pipeline if snap sync is enabled, so it's not included in
+    /// [`StageId::ALL`].
+    SnapSync,
     SenderRecovery,
     Execution,
     PruneSenderRecovery,
@@ -68,6 +71,7 @@ impl StageId {
             Self::Era => "Era",
             Self::Headers => "Headers",
             Self::Bodies => "Bodies",
+            Self::SnapSync => "SnapSync",
             Self::SenderRecovery => "SenderRecovery",
             Self::Execution => "Execution",
             Self::PruneSenderRecovery => "PruneSenderRecovery",
@@ -115,6 +119,7 @@ mod tests {
         assert_eq!(StageId::Era.to_string(), "Era");
         assert_eq!(StageId::Headers.to_string(), "Headers");
         assert_eq!(StageId::Bodies.to_string(), "Bodies");
+        assert_eq!(StageId::SnapSync.to_string(), "SnapSync");
         assert_eq!(StageId::SenderRecovery.to_string(), "SenderRecovery");
         assert_eq!(StageId::Execution.to_string(), "Execution");
         assert_eq!(StageId::MerkleUnwind.to_string(), "MerkleUnwind");
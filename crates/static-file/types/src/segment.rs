@@ -309,6 +309,24 @@ impl SegmentHeader {
     }
 }
 
+/// Statistics of a single static file.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticFileStats {
+    /// Segment type
+    pub segment: StaticFileSegment,
+    /// Block range of data on the static file
+    pub block_range: SegmentRangeInclusive,
+    /// Transaction range of data on the static file, if it contains any transactions
+    pub tx_range: Option<SegmentRangeInclusive>,
+    /// Number of columns of the static file
+    pub columns: usize,
+    /// Number of rows of the static file
+    pub rows: usize,
+    /// Total size of the data, index, offsets and config files, in bytes
+    pub size: u64,
+}
+
 /// Configuration used on the segment.
 #[derive(Debug, Clone, Copy)]
 pub struct SegmentConfig {
@@ -1,6 +1,50 @@
+use crate::DatabaseError;
 use metrics::{counter, gauge, histogram, Label};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Page statistics of the database and all of its tables.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStats {
+    /// Size of a database page, in bytes.
+    pub page_size: usize,
+    /// Statistics of each table, ordered by table name.
+    pub tables: Vec<TableStats>,
+    /// Number of pages on the freelist, i.e. pages that are allocated but unused.
+    pub freelist_pages: usize,
+}
+
+impl DatabaseStats {
+    /// Returns the total size of all tables, in bytes.
+    pub fn tables_size(&self) -> usize {
+        self.tables.iter().map(|table| table.size).sum()
+    }
+
+    /// Returns the size of the freelist, in bytes.
+    pub const fn freelist_size(&self) -> usize {
+        self.freelist_pages * self.page_size
+    }
+}
+
+/// Page statistics of a single database table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableStats {
+    /// Name of the table.
+    pub name: String,
+    /// Number of entries in the table.
+    pub entries: usize,
+    /// Number of branch pages.
+    pub branch_pages: usize,
+    /// Number of leaf pages.
+    pub leaf_pages: usize,
+    /// Number of overflow pages.
+    pub overflow_pages: usize,
+    /// Total size of all pages of the table, in bytes.
+    pub size: usize,
+}
+
 /// Represents a type that can report metrics, used mainly with the database. The `report_metrics`
 /// method can be used as a prometheus hook.
 pub trait DatabaseMetrics {
@@ -33,6 +77,11 @@ pub trait DatabaseMetrics {
     fn histogram_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
         vec![]
     }
+
+    /// Returns the page statistics of the database and all of its tables.
+    fn database_stats(&self) -> Result<DatabaseStats, DatabaseError> {
+        Err(DatabaseError::Other("database statistics are not supported".to_string()))
+    }
 }
 
 impl<DB: DatabaseMetrics> DatabaseMetrics for Arc<DB> {
@@ -51,4 +100,8 @@ impl<DB: DatabaseMetrics> DatabaseMetrics for Arc<DB> {
     fn histogram_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
         <DB as DatabaseMetrics>::histogram_metrics(self)
     }
+
+    fn database_stats(&self) -> Result<DatabaseStats, DatabaseError> {
+        <DB as DatabaseMetrics>::database_stats(self)
+    }
 }
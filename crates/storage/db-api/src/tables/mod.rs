@@ -27,7 +27,7 @@ use crate::{
     table::{Decode, DupSort, Encode, Table, TableInfo},
 };
 use alloy_consensus::Header;
-use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256};
+use alloy_primitives::{Address, BlockHash, BlockNumber, Bytes, TxHash, TxNumber, B256};
 use reth_ethereum_primitives::{Receipt, TransactionSigned};
 use reth_primitives_traits::{Account, Bytecode, StorageEntry};
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
@@ -523,6 +523,35 @@ tables! {
         type Key = ChainStateKey;
         type Value = BlockNumber;
     }
+
+    /// Stores the keccak256 preimages of hashed addresses and storage keys.
+    ///
+    /// This table is only populated if preimage recording is enabled, and only contains preimages
+    /// of keys that were hashed after it was enabled.
+    table Preimages {
+        type Key = B256;
+        type Value = Bytes;
+    }
+
+    /// Stores pointers to the blocks in which an address made a call, including the transactions
+    /// it sent.
+    ///
+    /// This table is only populated if the call index is enabled. Shards are laid out like the
+    /// ones of [`AccountsHistory`].
+    table CallFromIndex {
+        type Key = ShardedKey<Address>;
+        type Value = BlockNumberList;
+    }
+
+    /// Stores pointers to the blocks in which an address received a call, including the
+    /// transactions sent to it, the contracts it created and the logs it emitted.
+    ///
+    /// This table is only populated if the call index is enabled. Shards are laid out like the
+    /// ones of [`AccountsHistory`].
+    table CallToIndex {
+        type Key = ShardedKey<Address>;
+        type Value = BlockNumberList;
+    }
 }
 
 /// Keys for the `ChainState` table.
@@ -7,12 +7,11 @@ use crate::{
     utils::default_page_size,
     DatabaseError, TableSet,
 };
-use eyre::Context;
 use metrics::{gauge, Label};
 use reth_db_api::{
     cursor::{DbCursorRO, DbCursorRW},
     database::Database,
-    database_metrics::DatabaseMetrics,
+    database_metrics::{DatabaseMetrics, DatabaseStats, TableStats},
     models::ClientVersion,
     transaction::{DbTx, DbTxMut},
 };
@@ -227,63 +226,40 @@ impl DatabaseMetrics for DatabaseEnv {
     fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
         let mut metrics = Vec::new();
 
-        let _ = self
-            .view(|tx| {
-                for table in Tables::ALL.iter().map(Tables::name) {
-                    let table_db = tx.inner.open_db(Some(table)).wrap_err("Could not open db.")?;
-
-                    let stats = tx
-                        .inner
-                        .db_stat(&table_db)
-                        .wrap_err(format!("Could not find table: {table}"))?;
-
-                    let page_size = stats.page_size() as usize;
-                    let leaf_pages = stats.leaf_pages();
-                    let branch_pages = stats.branch_pages();
-                    let overflow_pages = stats.overflow_pages();
-                    let num_pages = leaf_pages + branch_pages + overflow_pages;
-                    let table_size = page_size * num_pages;
-                    let entries = stats.entries();
-
-                    metrics.push((
-                        "db.table_size",
-                        table_size as f64,
-                        vec![Label::new("table", table)],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        leaf_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "leaf")],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        branch_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "branch")],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        overflow_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "overflow")],
-                    ));
-                    metrics.push((
-                        "db.table_entries",
-                        entries as f64,
-                        vec![Label::new("table", table)],
-                    ));
-                }
-
-                Ok::<(), eyre::Report>(())
-            })
-            .map_err(|error| error!(%error, "Failed to read db table stats"));
-
-        if let Ok(freelist) =
-            self.freelist().map_err(|error| error!(%error, "Failed to read db.freelist"))
+        if let Ok(stats) =
+            self.database_stats().map_err(|error| error!(%error, "Failed to read db stats"))
         {
-            metrics.push(("db.freelist", freelist as f64, vec![]));
-        }
+            for table in stats.tables {
+                let name = table.name;
+                metrics.push((
+                    "db.table_size",
+                    table.size as f64,
+                    vec![Label::new("table", name.clone())],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    table.leaf_pages as f64,
+                    vec![Label::new("table", name.clone()), Label::new("type", "leaf")],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    table.branch_pages as f64,
+                    vec![Label::new("table", name.clone()), Label::new("type", "branch")],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    table.overflow_pages as f64,
+                    vec![Label::new("table", name.clone()), Label::new("type", "overflow")],
+                ));
+                metrics.push((
+                    "db.table_entries",
+                    table.entries as f64,
+                    vec![Label::new("table", name)],
+                ));
+            }
 
-        if let Ok(stat) = self.stat().map_err(|error| error!(%error, "Failed to read db.stat")) {
-            metrics.push(("db.page_size", stat.page_size() as f64, vec![]));
+            metrics.push(("db.freelist", stats.freelist_pages as f64, vec![]));
+            metrics.push(("db.page_size", stats.page_size as f64, vec![]));
         }
 
         metrics.push((
@@ -294,6 +270,37 @@ impl DatabaseMetrics for DatabaseEnv {
 
         metrics
     }
+
+    fn database_stats(&self) -> Result<DatabaseStats, DatabaseError> {
+        let tx = self.tx()?;
+
+        let mut tables = Vec::with_capacity(Tables::ALL.len());
+        for table in Tables::ALL.iter().map(Tables::name) {
+            let table_db =
+                tx.inner.open_db(Some(table)).map_err(|e| DatabaseError::Open(e.into()))?;
+            let stats = tx.inner.db_stat(&table_db).map_err(|e| DatabaseError::Stats(e.into()))?;
+
+            let leaf_pages = stats.leaf_pages();
+            let branch_pages = stats.branch_pages();
+            let overflow_pages = stats.overflow_pages();
+            let size = stats.page_size() as usize * (leaf_pages + branch_pages + overflow_pages);
+
+            tables.push(TableStats {
+                name: table.to_string(),
+                entries: stats.entries(),
+                branch_pages,
+                leaf_pages,
+                overflow_pages,
+                size,
+            });
+        }
+        tables.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        let freelist_pages = self.freelist().map_err(|e| DatabaseError::Stats(e.into()))?;
+        let page_size = self.stat().map_err(|e| DatabaseError::Stats(e.into()))?.page_size();
+
+        Ok(DatabaseStats { page_size: page_size as usize, tables, freelist_pages })
+    }
 }
 
 impl DatabaseEnv {
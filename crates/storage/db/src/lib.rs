@@ -44,7 +44,9 @@ pub mod test_utils {
     use crate::mdbx::DatabaseArguments;
     use parking_lot::RwLock;
     use reth_db_api::{
-        database::Database, database_metrics::DatabaseMetrics, models::ClientVersion,
+        database::Database,
+        database_metrics::{DatabaseMetrics, DatabaseStats},
+        models::ClientVersion,
     };
     use reth_fs_util;
     use reth_libmdbx::MaxReadTransactionDuration;
@@ -149,6 +151,10 @@ pub mod test_utils {
         fn report_metrics(&self) {
             self.db().report_metrics()
         }
+
+        fn database_stats(&self) -> Result<DatabaseStats, DatabaseError> {
+            self.db().database_stats()
+        }
     }
 
     /// Create `static_files` path for testing
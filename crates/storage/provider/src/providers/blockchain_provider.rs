@@ -4,17 +4,19 @@ use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockSource, CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions,
     ChainSpecProvider, ChainStateBlockReader, ChangeSetReader, DatabaseProvider,
-    DatabaseProviderFactory, FullProvider, HashedPostStateProvider, HeaderProvider, ProviderError,
-    ProviderFactory, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StateReader,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
+    DatabaseProviderFactory, FullProvider, HashedPostStateProvider, HeaderProvider, PreimageReader,
+    ProviderError, ProviderFactory, PruneCheckpointReader, RawDatabaseReader, ReceiptProvider,
+    ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox, StateProviderFactory,
+    StateReader, StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
 };
 use alloy_consensus::{transaction::TransactionMeta, Header};
 use alloy_eips::{
     eip4895::{Withdrawal, Withdrawals},
     BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag,
 };
-use alloy_primitives::{Address, BlockHash, BlockNumber, Sealable, TxHash, TxNumber, B256, U256};
+use alloy_primitives::{
+    Address, BlockHash, BlockNumber, Bytes, Sealable, TxHash, TxNumber, B256, U256,
+};
 use alloy_rpc_types_engine::ForkchoiceState;
 use reth_chain_state::{
     BlockState, CanonicalInMemoryState, ForkChoiceNotifications, ForkChoiceSubscriptions,
@@ -22,9 +24,10 @@ use reth_chain_state::{
 };
 use reth_chainspec::{ChainInfo, EthereumHardforks};
 use reth_db_api::{
+    database_metrics::DatabaseStats,
     models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices},
     transaction::DbTx,
-    Database,
+    Database, Tables,
 };
 use reth_ethereum_primitives::{Block, EthPrimitives, Receipt, TransactionSigned};
 use reth_evm::{ConfigureEvm, EvmEnv};
@@ -35,9 +38,10 @@ use reth_primitives_traits::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
+use reth_static_file_types::{StaticFileSegment, StaticFileStats};
 use reth_storage_api::{
-    BlockBodyIndicesProvider, DBProvider, NodePrimitivesProvider, StateCommitmentProvider,
-    StorageChangeSetReader,
+    BlockBodyIndicesProvider, CallIndexKind, CallIndexReader, DBProvider, NodePrimitivesProvider,
+    StateCommitmentProvider, StorageChangeSetReader,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::HashedPostState;
@@ -485,6 +489,56 @@ impl<N: ProviderNodeTypes> StageCheckpointReader for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> CallIndexReader for BlockchainProvider<N> {
+    fn call_index_head(&self) -> ProviderResult<Option<BlockNumHash>> {
+        self.database.call_index_head()
+    }
+
+    fn call_index_blocks(
+        &self,
+        kind: CallIndexKind,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.call_index_blocks(kind, address, range, reverse, limit)
+    }
+}
+
+impl<N: ProviderNodeTypes> PreimageReader for BlockchainProvider<N> {
+    fn preimage(&self, hash: B256) -> ProviderResult<Option<Bytes>> {
+        self.database.preimage(hash)
+    }
+}
+
+impl<N: ProviderNodeTypes> RawDatabaseReader for BlockchainProvider<N> {
+    fn raw_table_value(
+        &self,
+        table: Tables,
+        key: &[u8],
+        subkey: Option<&[u8]>,
+    ) -> ProviderResult<Option<Bytes>> {
+        self.database.raw_table_value(table, key, subkey)
+    }
+
+    fn raw_static_file_rows(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<Vec<Bytes>>> {
+        self.database.raw_static_file_rows(segment, block)
+    }
+
+    fn database_stats(&self) -> ProviderResult<DatabaseStats> {
+        self.database.database_stats()
+    }
+
+    fn static_file_stats(&self) -> ProviderResult<Vec<StaticFileStats>> {
+        self.database.static_file_stats()
+    }
+}
+
 impl<N: ProviderNodeTypes> PruneCheckpointReader for BlockchainProvider<N> {
     fn get_prune_checkpoint(
         &self,
@@ -3,17 +3,25 @@ use crate::{
     to_range,
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    HashedPostStateProvider, HeaderProvider, HeaderSyncGapProvider, ProviderError,
-    PruneCheckpointReader, StageCheckpointReader, StateProviderBox, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider,
+    HashedPostStateProvider, HeaderProvider, HeaderSyncGapProvider, PreimageReader, ProviderError,
+    PruneCheckpointReader, RawDatabaseReader, StageCheckpointReader, StateProviderBox,
+    StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
 };
 use alloy_consensus::transaction::TransactionMeta;
-use alloy_eips::BlockHashOrNumber;
-use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
+use alloy_eips::{BlockHashOrNumber, BlockNumHash};
+use alloy_primitives::{Address, BlockHash, BlockNumber, Bytes, TxHash, TxNumber, B256, U256};
 use core::fmt;
 use reth_chainspec::ChainInfo;
 use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
-use reth_db_api::{database::Database, models::StoredBlockBodyIndices};
+use reth_db_api::{
+    cursor::DbDupCursorRO,
+    database::Database,
+    database_metrics::{DatabaseMetrics, DatabaseStats},
+    models::StoredBlockBodyIndices,
+    table::{DupSort, Table},
+    transaction::DbTx,
+    DatabaseError, RawDupSort, RawKey, RawTable, TableViewer, Tables,
+};
 use reth_errors::{RethError, RethResult};
 use reth_node_types::{
     BlockTy, HeaderTy, NodeTypes, NodeTypesWithDB, NodeTypesWithDBAdapter, ReceiptTy, TxTy,
@@ -21,10 +29,10 @@ use reth_node_types::{
 use reth_primitives_traits::{RecoveredBlock, SealedHeader};
 use reth_prune_types::{PruneCheckpoint, PruneModes, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_static_file_types::StaticFileSegment;
+use reth_static_file_types::{StaticFileSegment, StaticFileStats};
 use reth_storage_api::{
-    BlockBodyIndicesProvider, NodePrimitivesProvider, StateCommitmentProvider,
-    TryIntoHistoricalStateProvider,
+    BlockBodyIndicesProvider, CallIndexKind, CallIndexReader, NodePrimitivesProvider,
+    StateCommitmentProvider, TryIntoHistoricalStateProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::HashedPostState;
@@ -65,6 +73,8 @@ pub struct ProviderFactory<N: NodeTypesWithDB> {
     prune_modes: PruneModes,
     /// The node storage handler.
     storage: Arc<N::Storage>,
+    /// Whether keccak256 preimages of hashed keys should be recorded.
+    record_preimages: bool,
 }
 
 impl<N: NodeTypes> ProviderFactory<NodeTypesWithDBAdapter<N, Arc<DatabaseEnv>>> {
@@ -87,6 +97,7 @@ impl<N: NodeTypesWithDB> ProviderFactory<N> {
             static_file_provider,
             prune_modes: PruneModes::none(),
             storage: Default::default(),
+            record_preimages: false,
         }
     }
 
@@ -102,6 +113,13 @@ impl<N: NodeTypesWithDB> ProviderFactory<N> {
         self
     }
 
+    /// Sets whether keccak256 preimages of hashed addresses and storage keys should be recorded
+    /// when writing hashed state.
+    pub const fn with_record_preimages(mut self, record_preimages: bool) -> Self {
+        self.record_preimages = record_preimages;
+        self
+    }
+
     /// Returns reference to the underlying database.
     pub const fn db_ref(&self) -> &N::DB {
         &self.db
@@ -129,6 +147,7 @@ impl<N: NodeTypesWithDB<DB = Arc<DatabaseEnv>>> ProviderFactory<N> {
             static_file_provider,
             prune_modes: PruneModes::none(),
             storage: Default::default(),
+            record_preimages: false,
         })
     }
 }
@@ -148,6 +167,7 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
             self.static_file_provider.clone(),
             self.prune_modes.clone(),
             self.storage.clone(),
+            self.record_preimages,
         ))
     }
 
@@ -163,6 +183,7 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
             self.static_file_provider.clone(),
             self.prune_modes.clone(),
             self.storage.clone(),
+            self.record_preimages,
         )))
     }
 
@@ -587,6 +608,117 @@ impl<N: NodeTypesWithDB> ChainSpecProvider for ProviderFactory<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> CallIndexReader for ProviderFactory<N> {
+    fn call_index_head(&self) -> ProviderResult<Option<BlockNumHash>> {
+        self.provider()?.call_index_head()
+    }
+
+    fn call_index_blocks(
+        &self,
+        kind: CallIndexKind,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.call_index_blocks(kind, address, range, reverse, limit)
+    }
+}
+
+impl<N: ProviderNodeTypes> PreimageReader for ProviderFactory<N> {
+    fn preimage(&self, hash: B256) -> ProviderResult<Option<Bytes>> {
+        self.provider()?.preimage(hash)
+    }
+}
+
+impl<N: ProviderNodeTypes> RawDatabaseReader for ProviderFactory<N> {
+    fn raw_table_value(
+        &self,
+        table: Tables,
+        key: &[u8],
+        subkey: Option<&[u8]>,
+    ) -> ProviderResult<Option<Bytes>> {
+        let tx = self.db.tx()?;
+        Ok(table.view(&RawValueViewer { tx: &tx, key, subkey })?)
+    }
+
+    fn raw_static_file_rows(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<Vec<Bytes>>> {
+        let numbers = if segment.is_tx_based() {
+            let Some(indices) = self.block_body_indices(block)? else { return Ok(Vec::new()) };
+            indices.tx_num_range()
+        } else {
+            block..block + 1
+        };
+
+        let mut rows = Vec::new();
+        for number in numbers {
+            let provider = if segment.is_tx_based() {
+                self.static_file_provider
+                    .get_segment_provider_from_transaction(segment, number, None)
+            } else {
+                self.static_file_provider.get_segment_provider_from_block(segment, number, None)
+            };
+            let provider = match provider {
+                Ok(provider) => provider,
+                Err(
+                    ProviderError::MissingStaticFileBlock(..) |
+                    ProviderError::MissingStaticFileTx(..),
+                ) => break,
+                Err(err) => return Err(err),
+            };
+
+            let mut cursor = provider.cursor()?;
+            let Some(columns) = cursor.get(number.into(), (1 << segment.columns()) - 1)? else {
+                break
+            };
+            rows.push(columns.into_iter().map(Bytes::copy_from_slice).collect());
+        }
+
+        Ok(rows)
+    }
+
+    fn database_stats(&self) -> ProviderResult<DatabaseStats> {
+        Ok(self.db.database_stats()?)
+    }
+
+    fn static_file_stats(&self) -> ProviderResult<Vec<StaticFileStats>> {
+        self.static_file_provider.static_file_stats()
+    }
+}
+
+/// [`TableViewer`] that reads the raw value of an encoded key.
+struct RawValueViewer<'a, TX> {
+    tx: &'a TX,
+    key: &'a [u8],
+    subkey: Option<&'a [u8]>,
+}
+
+impl<TX: DbTx> TableViewer<Option<Bytes>> for RawValueViewer<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<Option<Bytes>, Self::Error> {
+        let value = self.tx.get::<RawTable<T>>(RawKey::from_vec(self.key.to_vec()))?;
+        Ok(value.map(|value| value.into_value().into()))
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<Option<Bytes>, Self::Error> {
+        let Some(subkey) = self.subkey else { return self.view::<T>() };
+
+        let mut cursor = self.tx.cursor_dup_read::<RawDupSort<T>>()?;
+        let value = cursor.seek_by_key_subkey(
+            RawKey::from_vec(self.key.to_vec()),
+            RawKey::from_vec(subkey.to_vec()),
+        )?;
+        Ok(value
+            .filter(|value| value.raw_value().starts_with(subkey))
+            .map(|value| value.into_value().into()))
+    }
+}
+
 impl<N: ProviderNodeTypes> PruneCheckpointReader for ProviderFactory<N> {
     fn get_prune_checkpoint(
         &self,
@@ -613,13 +745,15 @@ where
     N: NodeTypesWithDB<DB: fmt::Debug, ChainSpec: fmt::Debug, Storage: fmt::Debug>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self { db, chain_spec, static_file_provider, prune_modes, storage } = self;
+        let Self { db, chain_spec, static_file_provider, prune_modes, storage, record_preimages } =
+            self;
         f.debug_struct("ProviderFactory")
             .field("db", &db)
             .field("chain_spec", &chain_spec)
             .field("static_file_provider", &static_file_provider)
             .field("prune_modes", &prune_modes)
             .field("storage", &storage)
+            .field("record_preimages", &record_preimages)
             .finish()
     }
 }
@@ -632,6 +766,7 @@ impl<N: NodeTypesWithDB> Clone for ProviderFactory<N> {
             static_file_provider: self.static_file_provider.clone(),
             prune_modes: self.prune_modes.clone(),
             storage: self.storage.clone(),
+            record_preimages: self.record_preimages,
         }
     }
 }
@@ -643,17 +778,17 @@ mod tests {
         providers::{StaticFileProvider, StaticFileWriter},
         test_utils::{blocks::TEST_BLOCK, create_test_provider_factory, MockNodeTypesWithDB},
         BlockHashReader, BlockNumReader, BlockWriter, DBProvider, HeaderSyncGapProvider,
-        StorageLocation, TransactionsProvider,
+        CallIndexWriter, PreimageWriter, StorageLocation, TransactionsProvider,
     };
-    use alloy_primitives::{TxNumber, B256, U256};
+    use alloy_primitives::{keccak256, TxNumber, B256, U256};
     use assert_matches::assert_matches;
     use reth_chainspec::ChainSpecBuilder;
     use reth_db::{
         mdbx::DatabaseArguments,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
     };
-    use reth_db_api::tables;
-    use reth_primitives_traits::SignerRecoverable;
+    use reth_db_api::{tables, transaction::DbTxMut};
+    use reth_primitives_traits::{SignerRecoverable, StorageEntry};
     use reth_prune_types::{PruneMode, PruneModes};
     use reth_storage_errors::provider::ProviderError;
     use reth_testing_utils::generators::{self, random_block, random_header, BlockParams};
@@ -685,6 +820,113 @@ mod tests {
         provider.block_hash(0).unwrap();
     }
 
+    #[test]
+    fn record_preimages() {
+        let factory = create_test_provider_factory();
+        assert!(!factory.provider_rw().unwrap().records_preimages());
+
+        let factory = factory.with_record_preimages(true);
+        let address = Address::random();
+        let slot = B256::random();
+        {
+            let provider = factory.provider_rw().unwrap();
+            assert!(provider.records_preimages());
+            provider.write_preimages([address.as_slice(), slot.as_slice()]).unwrap();
+            provider.commit().unwrap();
+        }
+
+        assert_eq!(
+            factory.preimage(keccak256(address)).unwrap(),
+            Some(Bytes::copy_from_slice(address.as_slice()))
+        );
+        assert_eq!(factory.preimage(keccak256(slot)).unwrap(), Some(slot.into()));
+        assert_eq!(factory.preimage(B256::ZERO).unwrap(), None);
+    }
+
+    #[test]
+    fn raw_table_value() {
+        let factory = create_test_provider_factory();
+        let address = Address::random();
+        let hash = B256::random();
+        let (first, second) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        {
+            let provider = factory.provider_rw().unwrap();
+            let tx = provider.tx_ref();
+            tx.put::<tables::CanonicalHeaders>(1, hash).unwrap();
+            for (key, value) in [(first, U256::from(1)), (second, U256::from(2))] {
+                tx.put::<tables::PlainStorageState>(address, StorageEntry { key, value }).unwrap();
+            }
+            provider.commit().unwrap();
+        }
+
+        let number = 1u64.to_be_bytes();
+        assert_eq!(
+            factory.raw_table_value(Tables::CanonicalHeaders, &number, None).unwrap(),
+            Some(hash.into())
+        );
+        assert_eq!(factory.raw_table_value(Tables::CanonicalHeaders, &[0; 8], None).unwrap(), None);
+
+        let value = factory
+            .raw_table_value(Tables::PlainStorageState, address.as_slice(), Some(second.as_slice()))
+            .unwrap()
+            .unwrap();
+        assert!(value.starts_with(second.as_slice()));
+        let value =
+            factory.raw_table_value(Tables::PlainStorageState, address.as_slice(), None).unwrap();
+        assert!(value.unwrap().starts_with(first.as_slice()));
+        assert_eq!(
+            factory
+                .raw_table_value(
+                    Tables::PlainStorageState,
+                    address.as_slice(),
+                    Some(B256::with_last_byte(3).as_slice())
+                )
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn call_index() {
+        let factory = create_test_provider_factory();
+        let address = Address::random();
+        assert_eq!(factory.call_index_head().unwrap(), None);
+
+        let head = BlockNumHash::new(10, B256::random());
+        {
+            let provider = factory.provider_rw().unwrap();
+            provider
+                .insert_call_index(CallIndexKind::From, [(address, vec![1, 3, 5, 7, 9])].into())
+                .unwrap();
+            provider.insert_call_index(CallIndexKind::To, [(address, vec![2])].into()).unwrap();
+            provider.save_call_index_head(head).unwrap();
+            provider.commit().unwrap();
+        }
+        assert_eq!(factory.call_index_head().unwrap(), Some(head));
+
+        let blocks = |range, reverse, limit| {
+            factory
+                .call_index_blocks(CallIndexKind::From, address, range, reverse, limit)
+                .unwrap()
+        };
+        assert_eq!(blocks(0..=10, false, 10), vec![1, 3, 5, 7, 9]);
+        assert_eq!(blocks(2..=7, false, 10), vec![3, 5, 7]);
+        assert_eq!(blocks(2..=10, false, 2), vec![3, 5]);
+        assert_eq!(blocks(0..=8, true, 3), vec![7, 5, 3]);
+        assert_eq!(blocks(0..=0, true, 3), Vec::<u64>::new());
+        assert_eq!(
+            factory.call_index_blocks(CallIndexKind::To, address, 0..=10, false, 10).unwrap(),
+            vec![2]
+        );
+
+        {
+            let provider = factory.provider_rw().unwrap();
+            provider.unwind_call_index(CallIndexKind::From, vec![address], 5).unwrap();
+            provider.commit().unwrap();
+        }
+        assert_eq!(blocks(0..=10, false, 10), vec![1, 3]);
+    }
+
     #[test]
     fn provider_factory_with_database_path() {
         let chain_spec = ChainSpecBuilder::mainnet().build();
@@ -10,10 +10,11 @@ use crate::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockBodyWriter, BlockExecutionWriter, BlockHashReader, BlockNumReader,
-    BlockReader, BlockWriter, BundleStateInit, ChainStateBlockReader, ChainStateBlockWriter,
-    DBProvider, HashingWriter, HeaderProvider, HeaderSyncGapProvider, HistoricalStateProvider,
-    HistoricalStateProviderRef, HistoryWriter, LatestStateProvider, LatestStateProviderRef,
-    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter, RevertsInit,
+    BlockReader, BlockWriter, BundleStateInit, CallIndexKind, CallIndexReader, CallIndexWriter,
+    ChainStateBlockReader, ChainStateBlockWriter, DBProvider, HashingWriter, HeaderProvider,
+    HeaderSyncGapProvider, HistoricalStateProvider, HistoricalStateProviderRef, HistoryWriter,
+    LatestStateProvider, LatestStateProviderRef, OriginalValuesKnown, PreimageReader,
+    PreimageWriter, ProviderError, PruneCheckpointReader, PruneCheckpointWriter, RevertsInit,
     StageCheckpointReader, StateCommitmentProvider, StateProviderBox, StateWriter,
     StaticFileProviderFactory, StatsReader, StorageLocation, StorageReader, StorageTrieWriter,
     TransactionVariant, TransactionsProvider, TransactionsProviderExt, TrieWriter,
@@ -22,13 +23,13 @@ use alloy_consensus::{
     transaction::{SignerRecoverable, TransactionMeta},
     BlockHeader, Header, TxReceipt,
 };
-use alloy_eips::{eip2718::Encodable2718, BlockHashOrNumber};
+use alloy_eips::{eip2718::Encodable2718, BlockHashOrNumber, BlockNumHash};
 use alloy_primitives::{
     keccak256,
     map::{hash_map, B256Map, HashMap, HashSet},
-    Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256,
+    Address, BlockHash, BlockNumber, Bytes, TxHash, TxNumber, B256, U256,
 };
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use rayon::slice::ParallelSliceMut;
 use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec, EthereumHardforks};
 use reth_db_api::{
@@ -145,6 +146,8 @@ pub struct DatabaseProvider<TX, N: NodeTypes> {
     prune_modes: PruneModes,
     /// Node storage handler.
     storage: Arc<N::Storage>,
+    /// Whether keccak256 preimages of hashed keys should be recorded.
+    record_preimages: bool,
 }
 
 impl<TX, N: NodeTypes> DatabaseProvider<TX, N> {
@@ -240,8 +243,9 @@ impl<TX: DbTxMut, N: NodeTypes> DatabaseProvider<TX, N> {
         static_file_provider: StaticFileProvider<N::Primitives>,
         prune_modes: PruneModes,
         storage: Arc<N::Storage>,
+        record_preimages: bool,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes, storage }
+        Self { tx, chain_spec, static_file_provider, prune_modes, storage, record_preimages }
     }
 }
 
@@ -523,8 +527,9 @@ impl<TX: DbTx + 'static, N: NodeTypesForProvider> DatabaseProvider<TX, N> {
         static_file_provider: StaticFileProvider<N::Primitives>,
         prune_modes: PruneModes,
         storage: Arc<N::Storage>,
+        record_preimages: bool,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes, storage }
+        Self { tx, chain_spec, static_file_provider, prune_modes, storage, record_preimages }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -3129,6 +3134,181 @@ impl<TX: DbTxMut, N: NodeTypes> PruneCheckpointWriter for DatabaseProvider<TX, N
     }
 }
 
+impl<TX: DbTx + 'static, N: NodeTypes> PreimageReader for DatabaseProvider<TX, N> {
+    fn preimage(&self, hash: B256) -> ProviderResult<Option<Bytes>> {
+        Ok(self.tx.get::<tables::Preimages>(hash)?)
+    }
+}
+
+impl<TX: DbTxMut, N: NodeTypes> PreimageWriter for DatabaseProvider<TX, N> {
+    fn records_preimages(&self) -> bool {
+        self.record_preimages
+    }
+
+    fn write_preimages<I, T>(&self, preimages: I) -> ProviderResult<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut cursor = self.tx.cursor_write::<tables::Preimages>()?;
+        for preimage in preimages {
+            let preimage = preimage.as_ref();
+            cursor.upsert(keccak256(preimage), &Bytes::copy_from_slice(preimage))?;
+        }
+        Ok(())
+    }
+}
+
+/// The id under which the head of the call index is stored in the stage checkpoint tables.
+const CALL_INDEX_ID: StageId = StageId::Other("CallIndex");
+
+impl<TX: DbTx + 'static, N: NodeTypes> DatabaseProvider<TX, N> {
+    /// Returns the numbers of at most `limit` blocks in `range` that are indexed for `address` in
+    /// the given sharded block number table, see [`CallIndexReader::call_index_blocks`].
+    fn indexed_blocks<T>(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>>
+    where
+        T: Table<Key = ShardedKey<Address>, Value = BlockNumberList>,
+    {
+        let mut blocks = Vec::new();
+        if range.is_empty() || limit == 0 {
+            return Ok(blocks)
+        }
+
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        // the first shard with a highest block number at or above the seeked block contains it
+        let mut item = cursor
+            .seek(ShardedKey::new(address, if reverse { *range.end() } else { *range.start() }))?;
+        while let Some((key, list)) = item {
+            if key.key != address {
+                break
+            }
+
+            let shard =
+                if reverse { Either::Left(list.iter().rev()) } else { Either::Right(list.iter()) };
+            for block in shard {
+                if range.contains(&block) {
+                    blocks.push(block);
+                    if blocks.len() == limit {
+                        return Ok(blocks)
+                    }
+                } else if (reverse && block < *range.start()) || (!reverse && block > *range.end())
+                {
+                    return Ok(blocks)
+                }
+            }
+
+            item = if reverse { cursor.prev()? } else { cursor.next()? };
+        }
+
+        Ok(blocks)
+    }
+}
+
+impl<TX: DbTx + 'static, N: NodeTypes> CallIndexReader for DatabaseProvider<TX, N> {
+    fn call_index_head(&self) -> ProviderResult<Option<BlockNumHash>> {
+        let Some(checkpoint) = self.get_stage_checkpoint(CALL_INDEX_ID)? else { return Ok(None) };
+        let hash = self
+            .get_stage_checkpoint_progress(CALL_INDEX_ID)?
+            .filter(|hash| hash.len() == B256::len_bytes())
+            .map(|hash| B256::from_slice(&hash))
+            .unwrap_or_default();
+        Ok(Some(BlockNumHash::new(checkpoint.block_number, hash)))
+    }
+
+    fn call_index_blocks(
+        &self,
+        kind: CallIndexKind,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        match kind {
+            CallIndexKind::From => {
+                self.indexed_blocks::<tables::CallFromIndex>(address, range, reverse, limit)
+            }
+            CallIndexKind::To => {
+                self.indexed_blocks::<tables::CallToIndex>(address, range, reverse, limit)
+            }
+        }
+    }
+}
+
+impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes> DatabaseProvider<TX, N> {
+    /// Removes all blocks starting at `first_block` from the shards of the given addresses in a
+    /// sharded block number table.
+    fn unwind_indexed_blocks<T>(
+        &self,
+        addresses: Vec<Address>,
+        first_block: BlockNumber,
+    ) -> ProviderResult<()>
+    where
+        T: Table<Key = ShardedKey<Address>, Value = BlockNumberList>,
+    {
+        let mut cursor = self.tx.cursor_write::<T>()?;
+        for address in addresses {
+            let partial_shard = unwind_history_shards::<_, T, _>(
+                &mut cursor,
+                ShardedKey::last(address),
+                first_block,
+                |sharded_key| sharded_key.key == address,
+            )?;
+
+            // the boundary shard becomes the last shard of the address
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(address),
+                    &BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes> CallIndexWriter for DatabaseProvider<TX, N> {
+    fn insert_call_index(
+        &self,
+        kind: CallIndexKind,
+        index_updates: BTreeMap<Address, Vec<BlockNumber>>,
+    ) -> ProviderResult<()> {
+        match kind {
+            CallIndexKind::From => self
+                .append_history_index::<_, tables::CallFromIndex>(index_updates, ShardedKey::new),
+            CallIndexKind::To => {
+                self.append_history_index::<_, tables::CallToIndex>(index_updates, ShardedKey::new)
+            }
+        }
+    }
+
+    fn unwind_call_index(
+        &self,
+        kind: CallIndexKind,
+        addresses: Vec<Address>,
+        first_block: BlockNumber,
+    ) -> ProviderResult<()> {
+        match kind {
+            CallIndexKind::From => {
+                self.unwind_indexed_blocks::<tables::CallFromIndex>(addresses, first_block)
+            }
+            CallIndexKind::To => {
+                self.unwind_indexed_blocks::<tables::CallToIndex>(addresses, first_block)
+            }
+        }
+    }
+
+    fn save_call_index_head(&self, head: BlockNumHash) -> ProviderResult<()> {
+        self.save_stage_checkpoint(CALL_INDEX_ID, StageCheckpoint::new(head.number))?;
+        self.save_stage_checkpoint_progress(CALL_INDEX_ID, head.hash.to_vec())
+    }
+}
+
 impl<TX: DbTx + 'static, N: NodeTypesForProvider> StatsReader for DatabaseProvider<TX, N> {
     fn count_entries<T: Table>(&self) -> ProviderResult<usize> {
         let db_entries = self.tx.entries::<T>()?;
@@ -1,9 +1,13 @@
 use crate::{
-    providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader,
-    HashedPostStateProvider, ProviderError, StateProvider, StateRootProvider,
+    providers::state::{
+        latest::{plain_or_hashed_account, plain_or_hashed_storage},
+        macros::delegate_provider_impls,
+    },
+    AccountReader, BlockHashReader, HashedPostStateProvider, ProviderError, StateProvider,
+    StateRootProvider,
 };
 use alloy_eips::merge::EPOCH_SLOTS;
-use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue, B256};
+use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue, B256, U256};
 use reth_db_api::{
     cursor::{DbCursorRO, DbDupCursorRO},
     models::{storage_sharded_key::StorageShardedKey, ShardedKey},
@@ -14,20 +18,21 @@ use reth_db_api::{
 };
 use reth_primitives_traits::{Account, Bytecode};
 use reth_storage_api::{
-    BlockNumReader, BytecodeReader, DBProvider, StateCommitmentProvider, StateProofProvider,
-    StorageRootProvider,
+    BlockNumReader, BytecodeReader, DBProvider, HashedStateRangeProvider, StateCommitmentProvider,
+    StateProofProvider, StorageRootProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
     proof::{Proof, StorageProof},
     updates::TrieUpdates,
     witness::TrieWitness,
-    AccountProof, HashedPostState, HashedStorage, MultiProof, MultiProofTargets, StateRoot,
-    StorageMultiProof, StorageRoot, TrieInput,
+    AccountProof, HashedPostState, HashedStateRange, HashedStorage, MultiProof, MultiProofTargets,
+    StateRoot, StorageMultiProof, StorageRoot, TrieInput,
 };
 use reth_trie_db::{
-    DatabaseHashedPostState, DatabaseHashedStorage, DatabaseProof, DatabaseStateRoot,
-    DatabaseStorageProof, DatabaseStorageRoot, DatabaseTrieWitness, StateCommitment,
+    DatabaseHashedPostState, DatabaseHashedStateRange, DatabaseHashedStorage, DatabaseProof,
+    DatabaseStateRoot, DatabaseStorageProof, DatabaseStorageRoot, DatabaseTrieWitness,
+    StateCommitment,
 };
 use std::fmt::Debug;
 
@@ -157,6 +162,13 @@ impl<'b, Provider: DBProvider + BlockNumReader + StateCommitmentProvider>
         Ok(HashedStorage::from_reverts(self.tx(), address, self.block_number)?)
     }
 
+    /// Retrieve revert hashed storage for this history provider and target hashed address.
+    ///
+    /// Storage changesets are keyed by plain address, so this has to revert the entire state.
+    fn revert_hashed_storage(&self, hashed_address: B256) -> ProviderResult<HashedStorage> {
+        Ok(self.revert_state()?.storages.remove(&hashed_address).unwrap_or_default())
+    }
+
     fn history_info<T, K>(
         &self,
         key: K,
@@ -263,7 +275,7 @@ impl<Provider: DBProvider + BlockNumReader + StateCommitmentProvider> AccountRea
                 })?
                 .info),
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
-                Ok(self.tx().get_by_encoded_key::<tables::PlainAccountState>(address)?)
+                plain_or_hashed_account(self.tx(), address)
             }
         }
     }
@@ -392,6 +404,50 @@ impl<Provider: DBProvider + BlockNumReader + StateCommitmentProvider> StateProof
     }
 }
 
+impl<Provider: DBProvider + BlockNumReader + StateCommitmentProvider> HashedStateRangeProvider
+    for HistoricalStateProviderRef<'_, Provider>
+{
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        let mut revert_state = self.revert_state()?;
+        revert_state.extend(hashed_state);
+        Ok(HashedStateRange::overlay_accounts(self.tx(), revert_state, start, limit)?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        let mut revert_storage = self.revert_hashed_storage(hashed_address)?;
+        revert_storage.extend(&hashed_storage);
+        Ok(HashedStateRange::overlay_storage(
+            self.tx(),
+            hashed_address,
+            revert_storage,
+            start,
+            limit,
+        )?)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        let mut revert_storage = self.revert_hashed_storage(hashed_address)?;
+        revert_storage.extend(&hashed_storage);
+        StorageRoot::overlay_root_hashed(self.tx(), hashed_address, revert_storage)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+}
+
 impl<Provider: StateCommitmentProvider> HashedPostStateProvider
     for HistoricalStateProviderRef<'_, Provider>
 {
@@ -425,13 +481,10 @@ impl<Provider: DBProvider + BlockNumReader + BlockHashReader + StateCommitmentPr
                     })?
                     .value,
             )),
-            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
-                .tx()
-                .cursor_dup_read::<tables::PlainStorageState>()?
-                .seek_by_key_subkey(address, storage_key)?
-                .filter(|entry| entry.key == storage_key)
-                .map(|entry| entry.value)
-                .or(Some(StorageValue::ZERO))),
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
+                Ok(plain_or_hashed_storage(self.tx(), address, storage_key)?
+                    .or(Some(StorageValue::ZERO)))
+            }
         }
     }
 }
@@ -2,23 +2,27 @@ use crate::{
     providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader,
     HashedPostStateProvider, StateProvider, StateRootProvider,
 };
-use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue, B256};
+use alloy_primitives::{
+    keccak256, Address, BlockNumber, Bytes, StorageKey, StorageValue, B256, U256,
+};
 use reth_db_api::{cursor::DbDupCursorRO, tables, transaction::DbTx};
 use reth_primitives_traits::{Account, Bytecode};
+use reth_stages_types::StageId;
 use reth_storage_api::{
-    BytecodeReader, DBProvider, StateCommitmentProvider, StateProofProvider, StorageRootProvider,
+    BytecodeReader, DBProvider, HashedStateRangeProvider, StateCommitmentProvider,
+    StateProofProvider, StorageRootProvider,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{
     proof::{Proof, StorageProof},
     updates::TrieUpdates,
     witness::TrieWitness,
-    AccountProof, HashedPostState, HashedStorage, MultiProof, MultiProofTargets, StateRoot,
-    StorageMultiProof, StorageRoot, TrieInput,
+    AccountProof, HashedPostState, HashedStateRange, HashedStorage, MultiProof, MultiProofTargets,
+    StateRoot, StorageMultiProof, StorageRoot, TrieInput,
 };
 use reth_trie_db::{
-    DatabaseProof, DatabaseStateRoot, DatabaseStorageProof, DatabaseStorageRoot,
-    DatabaseTrieWitness, StateCommitment,
+    DatabaseHashedStateRange, DatabaseProof, DatabaseStateRoot, DatabaseStorageProof,
+    DatabaseStorageRoot, DatabaseTrieWitness, StateCommitment,
 };
 
 /// State provider over latest state that takes tx reference.
@@ -41,7 +45,7 @@ impl<'b, Provider: DBProvider> LatestStateProviderRef<'b, Provider> {
 impl<Provider: DBProvider> AccountReader for LatestStateProviderRef<'_, Provider> {
     /// Get basic account information.
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
-        self.tx().get_by_encoded_key::<tables::PlainAccountState>(address).map_err(Into::into)
+        plain_or_hashed_account(self.tx(), address)
     }
 }
 
@@ -150,6 +154,44 @@ impl<Provider: DBProvider + StateCommitmentProvider> StateProofProvider
     }
 }
 
+impl<Provider: DBProvider + StateCommitmentProvider> HashedStateRangeProvider
+    for LatestStateProviderRef<'_, Provider>
+{
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        Ok(HashedStateRange::overlay_accounts(self.tx(), hashed_state, start, limit)?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        Ok(HashedStateRange::overlay_storage(
+            self.tx(),
+            hashed_address,
+            hashed_storage,
+            start,
+            limit,
+        )?)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        StorageRoot::overlay_root_hashed(self.tx(), hashed_address, hashed_storage)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+}
+
 impl<Provider: DBProvider + StateCommitmentProvider> HashedPostStateProvider
     for LatestStateProviderRef<'_, Provider>
 {
@@ -169,13 +211,7 @@ impl<Provider: DBProvider + BlockHashReader + StateCommitmentProvider> StateProv
         account: Address,
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>> {
-        let mut cursor = self.tx().cursor_dup_read::<tables::PlainStorageState>()?;
-        if let Some(entry) = cursor.seek_by_key_subkey(account, storage_key)? {
-            if entry.key == storage_key {
-                return Ok(Some(entry.value))
-            }
-        }
-        Ok(None)
+        plain_or_hashed_storage(self.tx(), account, storage_key)
     }
 }
 
@@ -194,6 +230,54 @@ impl<Provider: StateCommitmentProvider> StateCommitmentProvider
     type StateCommitment = Provider::StateCommitment;
 }
 
+/// Returns `true` if the state was downloaded with snap sync.
+///
+/// The downloaded state is only written to the hashed state tables, the plain state tables only
+/// contain the accounts and storage changed by the blocks executed after the snap sync pivot.
+fn is_snap_synced<TX: DbTx>(tx: &TX) -> ProviderResult<bool> {
+    Ok(tx.get::<tables::StageCheckpointProgresses>(StageId::SnapSync.to_string())?.is_some())
+}
+
+/// Returns the account from the plain state, falling back to the hashed state if the state was
+/// downloaded with snap sync.
+pub(super) fn plain_or_hashed_account<TX: DbTx>(
+    tx: &TX,
+    address: &Address,
+) -> ProviderResult<Option<Account>> {
+    if let Some(account) = tx.get_by_encoded_key::<tables::PlainAccountState>(address)? {
+        return Ok(Some(account))
+    }
+    if !is_snap_synced(tx)? {
+        return Ok(None)
+    }
+    Ok(tx.get::<tables::HashedAccounts>(keccak256(address))?)
+}
+
+/// Returns the storage value from the plain state, falling back to the hashed state if the state
+/// was downloaded with snap sync.
+pub(super) fn plain_or_hashed_storage<TX: DbTx>(
+    tx: &TX,
+    address: Address,
+    storage_key: StorageKey,
+) -> ProviderResult<Option<StorageValue>> {
+    let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+    if let Some(entry) = cursor.seek_by_key_subkey(address, storage_key)? {
+        if entry.key == storage_key {
+            return Ok(Some(entry.value))
+        }
+    }
+    if !is_snap_synced(tx)? {
+        return Ok(None)
+    }
+
+    let hashed_slot = keccak256(storage_key);
+    Ok(tx
+        .cursor_dup_read::<tables::HashedStorages>()?
+        .seek_by_key_subkey(keccak256(address), hashed_slot)?
+        .filter(|entry| entry.key == hashed_slot)
+        .map(|entry| entry.value))
+}
+
 /// State provider for the latest state.
 #[derive(Debug)]
 pub struct LatestStateProvider<Provider>(Provider);
@@ -221,6 +305,9 @@ delegate_provider_impls!(LatestStateProvider<Provider> where [Provider: DBProvid
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{test_utils::create_test_provider_factory, StageCheckpointWriter};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives_traits::StorageEntry;
 
     const fn assert_state_provider<T: StateProvider>() {}
     #[expect(dead_code)]
@@ -229,4 +316,27 @@ mod tests {
     >() {
         assert_state_provider::<LatestStateProvider<T>>();
     }
+
+    #[test]
+    fn snap_synced_state_falls_back_to_hashed_state() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        let address = Address::with_last_byte(1);
+        let slot = B256::with_last_byte(2);
+        let account = Account { nonce: 1, ..Default::default() };
+        let entry = StorageEntry { key: keccak256(slot), value: U256::from(3) };
+        provider.tx_ref().put::<tables::HashedAccounts>(keccak256(address), account).unwrap();
+        provider.tx_ref().put::<tables::HashedStorages>(keccak256(address), entry).unwrap();
+
+        let state = LatestStateProviderRef::new(&*provider);
+        assert_eq!(state.basic_account(&address).unwrap(), None);
+        assert_eq!(state.storage(address, slot).unwrap(), None);
+
+        provider
+            .save_stage_checkpoint_progress(StageId::SnapSync, 1u64.to_be_bytes().into())
+            .unwrap();
+        assert_eq!(state.basic_account(&address).unwrap(), Some(account));
+        assert_eq!(state.storage(address, slot).unwrap(), Some(U256::from(3)));
+    }
 }
@@ -59,6 +59,11 @@ macro_rules! delegate_provider_impls {
                 fn multiproof(&self, input: reth_trie::TrieInput, targets: reth_trie::MultiProofTargets) -> reth_storage_errors::provider::ProviderResult<reth_trie::MultiProof>;
                 fn witness(&self, input: reth_trie::TrieInput, target: reth_trie::HashedPostState) -> reth_storage_errors::provider::ProviderResult<Vec<alloy_primitives::Bytes>>;
             }
+            HashedStateRangeProvider $(where [$($generics)*])? {
+                fn hashed_account_range(&self, hashed_state: reth_trie::HashedPostState, start: alloy_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<Vec<(alloy_primitives::B256, reth_primitives_traits::Account)>>;
+                fn hashed_storage_range(&self, hashed_address: alloy_primitives::B256, hashed_storage: reth_trie::HashedStorage, start: alloy_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<Vec<(alloy_primitives::B256, alloy_primitives::U256)>>;
+                fn hashed_storage_root(&self, hashed_address: alloy_primitives::B256, hashed_storage: reth_trie::HashedStorage) -> reth_storage_errors::provider::ProviderResult<alloy_primitives::B256>;
+            }
             HashedPostStateProvider $(where [$($generics)*])? {
                 fn hashed_post_state(&self, bundle_state: &revm_database::BundleState) -> reth_trie::HashedPostState;
             }
@@ -16,6 +16,7 @@ use alloy_primitives::{
     b256, keccak256, Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256,
 };
 use dashmap::DashMap;
+use itertools::Itertools;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use parking_lot::RwLock;
 use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec};
@@ -40,7 +41,7 @@ use reth_primitives_traits::{RecoveredBlock, SealedHeader, SignedTransaction};
 use reth_stages_types::{PipelineTarget, StageId};
 use reth_static_file_types::{
     find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive, StaticFileSegment,
-    DEFAULT_BLOCKS_PER_STATIC_FILE,
+    StaticFileStats, DEFAULT_BLOCKS_PER_STATIC_FILE,
 };
 use reth_storage_api::{BlockBodyIndicesProvider, DBProvider};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -330,12 +331,28 @@ impl<N: NodePrimitives> StaticFileProvider<N> {
     pub fn report_metrics(&self) -> ProviderResult<()> {
         let Some(metrics) = &self.metrics else { return Ok(()) };
 
-        let static_files = iter_static_files(&self.path).map_err(ProviderError::other)?;
-        for (segment, ranges) in static_files {
-            let mut entries = 0;
-            let mut size = 0;
+        let stats = self.static_file_stats()?;
+        for (segment, files) in &stats.into_iter().chunk_by(|stats| stats.segment) {
+            let (mut files_count, mut entries, mut size) = (0, 0, 0);
+            for file in files {
+                files_count += 1;
+                entries += file.rows;
+                size += file.size;
+            }
+
+            metrics.record_segment(segment, size, files_count, entries);
+        }
+
+        Ok(())
+    }
 
-            for (block_range, _) in &ranges {
+    /// Returns the statistics of all static files, ordered by segment and block range.
+    pub fn static_file_stats(&self) -> ProviderResult<Vec<StaticFileStats>> {
+        let mut stats = Vec::new();
+
+        let static_files = iter_static_files(&self.path).map_err(ProviderError::other)?;
+        for (segment, ranges) in static_files.into_iter().sorted_by_key(|(segment, _)| *segment) {
+            for (block_range, tx_range) in ranges {
                 let fixed_block_range = self.find_fixed_range(block_range.start());
                 let jar_provider = self
                     .get_segment_provider(segment, || Some(fixed_block_range), None)?
@@ -343,8 +360,6 @@ impl<N: NodePrimitives> StaticFileProvider<N> {
                         ProviderError::MissingStaticFileBlock(segment, block_range.start())
                     })?;
 
-                entries += jar_provider.rows();
-
                 let data_size = reth_fs_util::metadata(jar_provider.data_path())
                     .map(|metadata| metadata.len())
                     .unwrap_or_default();
@@ -358,13 +373,18 @@ impl<N: NodePrimitives> StaticFileProvider<N> {
                     .map(|metadata| metadata.len())
                     .unwrap_or_default();
 
-                size += data_size + index_size + offsets_size + config_size;
+                stats.push(StaticFileStats {
+                    segment,
+                    block_range,
+                    tx_range,
+                    columns: jar_provider.columns(),
+                    rows: jar_provider.rows(),
+                    size: data_size + index_size + offsets_size + config_size,
+                });
             }
-
-            metrics.record_segment(segment, size, ranges.len(), entries);
         }
 
-        Ok(())
+        Ok(stats)
     }
 
     /// Gets the [`StaticFileJarProvider`] of the requested segment and block.
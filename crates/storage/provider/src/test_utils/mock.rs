@@ -6,7 +6,7 @@ use crate::{
     TransactionVariant, TransactionsProvider,
 };
 use alloy_consensus::{constants::EMPTY_ROOT_HASH, transaction::TransactionMeta, Header};
-use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
+use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag};
 use alloy_primitives::{
     keccak256, map::HashMap, Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue,
     TxHash, TxNumber, B256, U256,
@@ -15,22 +15,28 @@ use parking_lot::Mutex;
 use reth_chain_state::{CanonStateNotifications, CanonStateSubscriptions};
 use reth_chainspec::{ChainInfo, EthChainSpec};
 use reth_db_api::{
+    database_metrics::DatabaseStats,
     mock::{DatabaseMock, TxMock},
-    models::{AccountBeforeTx, StoredBlockBodyIndices},
+    models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices},
+    Tables,
 };
 use reth_ethereum_engine_primitives::EthEngineTypes;
 use reth_ethereum_primitives::{EthPrimitives, Receipt};
 use reth_execution_types::ExecutionOutcome;
 use reth_node_types::NodeTypes;
 use reth_primitives_traits::{
-    Account, Bytecode, GotExpected, NodePrimitives, RecoveredBlock, SealedHeader, SignerRecoverable,
+    Account, Bytecode, GotExpected, NodePrimitives, RecoveredBlock, SealedHeader,
+    SignerRecoverable, StorageEntry,
 };
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneCheckpoint, PruneModes, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
+use reth_static_file_types::{StaticFileSegment, StaticFileStats};
 use reth_storage_api::{
-    BlockBodyIndicesProvider, BytecodeReader, DBProvider, DatabaseProviderFactory,
-    HashedPostStateProvider, NodePrimitivesProvider, StageCheckpointReader,
-    StateCommitmentProvider, StateProofProvider, StorageRootProvider,
+    BlockBodyIndicesProvider, BytecodeReader, CallIndexKind, CallIndexReader, DBProvider,
+    DatabaseProviderFactory, HashedPostStateProvider, HashedStateRangeProvider,
+    NodePrimitivesProvider, PreimageReader, PruneCheckpointReader, RawDatabaseReader,
+    StageCheckpointReader, StateCommitmentProvider, StateProofProvider, StorageChangeSetReader,
+    StorageRootProvider,
 };
 use reth_storage_errors::provider::{ConsistentViewError, ProviderError, ProviderResult};
 use reth_trie::{
@@ -841,6 +847,39 @@ where
     }
 }
 
+impl<T, ChainSpec> HashedStateRangeProvider for MockEthProvider<T, ChainSpec>
+where
+    T: NodePrimitives,
+    ChainSpec: Send + Sync,
+{
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        Ok(Vec::new())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        Ok(Vec::new())
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        Ok(EMPTY_ROOT_HASH)
+    }
+}
+
 impl<T, ChainSpec> StateProofProvider for MockEthProvider<T, ChainSpec>
 where
     T: NodePrimitives,
@@ -988,6 +1027,84 @@ impl<T: NodePrimitives, ChainSpec: Send + Sync> ChangeSetReader for MockEthProvi
     }
 }
 
+impl<T: NodePrimitives, ChainSpec: Send + Sync> StorageChangeSetReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn storage_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        Ok(Vec::default())
+    }
+}
+
+impl<T: NodePrimitives, ChainSpec: Send + Sync> CallIndexReader for MockEthProvider<T, ChainSpec> {
+    fn call_index_head(&self) -> ProviderResult<Option<BlockNumHash>> {
+        Ok(None)
+    }
+
+    fn call_index_blocks(
+        &self,
+        _kind: CallIndexKind,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+        _reverse: bool,
+        _limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+}
+
+impl<T: NodePrimitives, ChainSpec: Send + Sync> PreimageReader for MockEthProvider<T, ChainSpec> {
+    fn preimage(&self, _hash: B256) -> ProviderResult<Option<Bytes>> {
+        Ok(None)
+    }
+}
+
+impl<T: NodePrimitives, ChainSpec: Send + Sync> RawDatabaseReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn raw_table_value(
+        &self,
+        _table: Tables,
+        _key: &[u8],
+        _subkey: Option<&[u8]>,
+    ) -> ProviderResult<Option<Bytes>> {
+        Ok(None)
+    }
+
+    fn raw_static_file_rows(
+        &self,
+        _segment: StaticFileSegment,
+        _block: BlockNumber,
+    ) -> ProviderResult<Vec<Vec<Bytes>>> {
+        Ok(Vec::new())
+    }
+
+    fn database_stats(&self) -> ProviderResult<DatabaseStats> {
+        Ok(Default::default())
+    }
+
+    fn static_file_stats(&self) -> ProviderResult<Vec<StaticFileStats>> {
+        Ok(Vec::new())
+    }
+}
+
+impl<T: NodePrimitives, ChainSpec: Send + Sync> PruneCheckpointReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn get_prune_checkpoint(
+        &self,
+        _segment: PruneSegment,
+    ) -> ProviderResult<Option<PruneCheckpoint>> {
+        Ok(None)
+    }
+
+    fn get_prune_checkpoints(&self) -> ProviderResult<Vec<(PruneSegment, PruneCheckpoint)>> {
+        Ok(Vec::new())
+    }
+}
+
 impl<T: NodePrimitives, ChainSpec: Send + Sync> StateReader for MockEthProvider<T, ChainSpec> {
     type Receipt = Receipt;
 
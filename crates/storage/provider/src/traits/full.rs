@@ -1,8 +1,10 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
+    AccountReader, BlockReaderIdExt, CallIndexReader, CallIndexWriter, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, PreimageReader, PruneCheckpointReader,
+    RawDatabaseReader, StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
+    StorageChangeSetReader,
 };
 use reth_chain_state::{CanonStateSubscriptions, ForkChoiceSubscriptions};
 use reth_node_types::{BlockTy, HeaderTy, NodeTypesWithDB, ReceiptTy, TxTy};
@@ -11,7 +13,7 @@ use std::fmt::Debug;
 
 /// Helper trait to unify all provider traits for simplicity.
 pub trait FullProvider<N: NodeTypesWithDB>:
-    DatabaseProviderFactory<DB = N::DB>
+    DatabaseProviderFactory<DB = N::DB, ProviderRW: CallIndexWriter>
     + NodePrimitivesProvider<Primitives = N::Primitives>
     + StaticFileProviderFactory<Primitives = N::Primitives>
     + BlockReaderIdExt<
@@ -23,9 +25,14 @@ pub trait FullProvider<N: NodeTypesWithDB>:
     + StateProviderFactory
     + ChainSpecProvider<ChainSpec = N::ChainSpec>
     + ChangeSetReader
+    + StorageChangeSetReader
     + CanonStateSubscriptions
     + ForkChoiceSubscriptions<Header = HeaderTy<N>>
     + StageCheckpointReader
+    + PruneCheckpointReader
+    + CallIndexReader
+    + PreimageReader
+    + RawDatabaseReader
     + Clone
     + Debug
     + Unpin
@@ -34,7 +41,7 @@ pub trait FullProvider<N: NodeTypesWithDB>:
 }
 
 impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
-    T: DatabaseProviderFactory<DB = N::DB>
+    T: DatabaseProviderFactory<DB = N::DB, ProviderRW: CallIndexWriter>
         + NodePrimitivesProvider<Primitives = N::Primitives>
         + StaticFileProviderFactory<Primitives = N::Primitives>
         + BlockReaderIdExt<
@@ -46,9 +53,14 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
         + StateProviderFactory
         + ChainSpecProvider<ChainSpec = N::ChainSpec>
         + ChangeSetReader
+        + StorageChangeSetReader
         + CanonStateSubscriptions
         + ForkChoiceSubscriptions<Header = HeaderTy<N>>
         + StageCheckpointReader
+        + PruneCheckpointReader
+        + CallIndexReader
+        + PreimageReader
+        + RawDatabaseReader
         + Clone
         + Debug
         + Unpin
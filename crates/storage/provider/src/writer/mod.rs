@@ -1,9 +1,10 @@
 use crate::{
     providers::{StaticFileProvider, StaticFileWriter as SfWriter},
-    BlockExecutionWriter, BlockWriter, HistoryWriter, StateWriter, StaticFileProviderFactory,
-    StorageLocation, TrieWriter,
+    BlockExecutionWriter, BlockWriter, HistoryWriter, PreimageWriter, StateWriter,
+    StaticFileProviderFactory, StorageLocation, TrieWriter,
 };
 use alloy_consensus::BlockHeader;
+use alloy_primitives::B256;
 use reth_chain_state::{ExecutedBlock, ExecutedBlockWithTrieUpdates};
 use reth_db_api::transaction::{DbTx, DbTxMut};
 use reth_errors::{ProviderError, ProviderResult};
@@ -128,6 +129,7 @@ where
         + HistoryWriter
         + StageCheckpointWriter
         + BlockExecutionWriter
+        + PreimageWriter
         + AsRef<ProviderDB>
         + StaticFileProviderFactory,
 {
@@ -177,6 +179,17 @@ where
                 StorageLocation::StaticFiles,
             )?;
 
+            // record preimages of the keys that are about to be hashed
+            if self.database().records_preimages() {
+                let state = execution_output.bundle.state();
+                self.database().write_preimages(state.keys())?;
+                self.database().write_preimages(
+                    state
+                        .values()
+                        .flat_map(|account| account.storage.keys().map(|slot| B256::from(*slot))),
+                )?;
+            }
+
             // insert hashes and intermediate merkle nodes
             self.database()
                 .write_hashed_state(&Arc::unwrap_or_clone(hashed_state).into_sorted())?;
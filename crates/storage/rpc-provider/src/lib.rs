@@ -1311,6 +1311,41 @@ where
     }
 }
 
+impl<P, Node, N> reth_storage_api::HashedStateRangeProvider
+    for RpcBlockchainStateProvider<P, Node, N>
+where
+    P: Provider<N> + Clone + 'static,
+    N: Network,
+    Node: NodeTypes,
+{
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> Result<Vec<(B256, Account)>, ProviderError> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: reth_trie::HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> Result<Vec<(B256, U256)>, ProviderError> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: reth_trie::HashedStorage,
+    ) -> Result<B256, ProviderError> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
 impl<P, Node, N> reth_storage_api::HashedPostStateProvider
     for RpcBlockchainStateProvider<P, Node, N>
 where
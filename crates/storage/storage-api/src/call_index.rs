@@ -0,0 +1,62 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{Address, BlockNumber};
+use core::ops::RangeInclusive;
+use reth_storage_errors::provider::ProviderResult;
+
+/// The side of the calls that an address is looked up by in the call index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallIndexKind {
+    /// Calls made by the address, including the transactions it sent and its selfdestructs.
+    From,
+    /// Calls received by the address, including the transactions sent to it, the contracts it
+    /// created and the logs it emitted.
+    To,
+}
+
+/// The trait for looking up the blocks in which an address took part in a call.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait CallIndexReader: Send + Sync {
+    /// Returns the last block that is included in the call index.
+    ///
+    /// Returns `None` if the call index is not enabled.
+    fn call_index_head(&self) -> ProviderResult<Option<BlockNumHash>>;
+
+    /// Returns the numbers of at most `limit` blocks in `range` in which `address` took part in a
+    /// call on the given side.
+    ///
+    /// Block numbers are returned in ascending order, or in descending order if `reverse` is set,
+    /// starting at the respective end of the range.
+    fn call_index_blocks(
+        &self,
+        kind: CallIndexKind,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
+
+/// The trait for updating the call index.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait CallIndexWriter: Send + Sync {
+    /// Appends the given block numbers to the call index of each address.
+    ///
+    /// The block numbers must be sorted and above all blocks that are already indexed.
+    fn insert_call_index(
+        &self,
+        kind: CallIndexKind,
+        index_updates: BTreeMap<Address, Vec<BlockNumber>>,
+    ) -> ProviderResult<()>;
+
+    /// Removes all blocks starting at `first_block` from the call index of the given addresses.
+    fn unwind_call_index(
+        &self,
+        kind: CallIndexKind,
+        addresses: Vec<Address>,
+        first_block: BlockNumber,
+    ) -> ProviderResult<()>;
+
+    /// Saves the last block that is included in the call index.
+    fn save_call_index_head(&self, head: BlockNumHash) -> ProviderResult<()>;
+}
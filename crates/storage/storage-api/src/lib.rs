@@ -25,6 +25,9 @@ pub use block_id::*;
 mod block_hash;
 pub use block_hash::*;
 
+mod call_index;
+pub use call_index::*;
+
 #[cfg(feature = "db-api")]
 mod chain;
 #[cfg(feature = "db-api")]
@@ -33,6 +36,9 @@ pub use chain::*;
 mod header;
 pub use header::*;
 
+mod preimage;
+pub use preimage::*;
+
 mod prune_checkpoint;
 pub use prune_checkpoint::*;
 
@@ -79,6 +85,11 @@ mod stats;
 #[cfg(feature = "db-api")]
 pub use stats::*;
 
+#[cfg(feature = "db-api")]
+mod raw;
+#[cfg(feature = "db-api")]
+pub use raw::*;
+
 mod primitives;
 pub use primitives::*;
 
@@ -2,18 +2,19 @@
 
 use crate::{
     AccountReader, BlockBodyIndicesProvider, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, BytecodeReader, ChangeSetReader,
-    HashedPostStateProvider, HeaderProvider, NodePrimitivesProvider, PruneCheckpointReader,
-    ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StateProofProvider,
-    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider, StorageRootProvider,
+    BlockReader, BlockReaderIdExt, BlockSource, BytecodeReader, CallIndexKind, CallIndexReader,
+    CallIndexWriter, ChangeSetReader, HashedPostStateProvider, HashedStateRangeProvider,
+    HeaderProvider, NodePrimitivesProvider, PreimageReader, PruneCheckpointReader, ReceiptProvider,
+    ReceiptProviderIdExt, StageCheckpointReader, StateProofProvider, StateProvider,
+    StateProviderBox, StateProviderFactory, StateRootProvider, StorageRootProvider,
     TransactionVariant, TransactionsProvider,
 };
 
 #[cfg(feature = "db-api")]
-use crate::{DBProvider, DatabaseProviderFactory};
-use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use crate::{DBProvider, DatabaseProviderFactory, RawDatabaseReader, StorageChangeSetReader};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
 use alloy_consensus::transaction::TransactionMeta;
-use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
+use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag};
 use alloy_primitives::{
     Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue, TxHash, TxNumber, B256, U256,
 };
@@ -24,14 +25,23 @@ use core::{
 };
 use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec, MAINNET};
 #[cfg(feature = "db-api")]
-use reth_db_api::mock::{DatabaseMock, TxMock};
+use reth_db_api::{
+    database_metrics::DatabaseStats,
+    mock::{DatabaseMock, TxMock},
+    models::BlockNumberAddress,
+    Tables,
+};
 use reth_db_models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_ethereum_primitives::EthPrimitives;
+#[cfg(feature = "db-api")]
+use reth_primitives_traits::StorageEntry;
 use reth_primitives_traits::{Account, Bytecode, NodePrimitives, RecoveredBlock, SealedHeader};
 #[cfg(feature = "db-api")]
 use reth_prune_types::PruneModes;
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
+#[cfg(feature = "db-api")]
+use reth_static_file_types::{StaticFileSegment, StaticFileStats};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie_common::{
     updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof,
@@ -396,6 +406,16 @@ impl<C: Send + Sync, N: NodePrimitives> ChangeSetReader for NoopProvider<C, N> {
     }
 }
 
+#[cfg(feature = "db-api")]
+impl<C: Send + Sync, N: NodePrimitives> StorageChangeSetReader for NoopProvider<C, N> {
+    fn storage_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        Ok(Vec::default())
+    }
+}
+
 impl<C: Send + Sync, N: NodePrimitives> StateRootProvider for NoopProvider<C, N> {
     fn state_root(&self, _state: HashedPostState) -> ProviderResult<B256> {
         Ok(B256::default())
@@ -471,6 +491,35 @@ impl<C: Send + Sync, N: NodePrimitives> StateProofProvider for NoopProvider<C, N
     }
 }
 
+impl<C: Send + Sync, N: NodePrimitives> HashedStateRangeProvider for NoopProvider<C, N> {
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
+}
+
 impl<C: Send + Sync, N: NodePrimitives> HashedPostStateProvider for NoopProvider<C, N> {
     fn hashed_post_state(&self, _bundle_state: &revm_database::BundleState) -> HashedPostState {
         HashedPostState::default()
@@ -561,6 +610,52 @@ impl<C: Send + Sync, N: NodePrimitives> StageCheckpointReader for NoopProvider<C
     }
 }
 
+impl<C: Send + Sync, N: NodePrimitives> CallIndexReader for NoopProvider<C, N> {
+    fn call_index_head(&self) -> ProviderResult<Option<BlockNumHash>> {
+        Ok(None)
+    }
+
+    fn call_index_blocks(
+        &self,
+        _kind: CallIndexKind,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+        _reverse: bool,
+        _limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+}
+
+impl<C: Send + Sync, N: NodePrimitives> CallIndexWriter for NoopProvider<C, N> {
+    fn insert_call_index(
+        &self,
+        _kind: CallIndexKind,
+        _index_updates: BTreeMap<Address, Vec<BlockNumber>>,
+    ) -> ProviderResult<()> {
+        Ok(())
+    }
+
+    fn unwind_call_index(
+        &self,
+        _kind: CallIndexKind,
+        _addresses: Vec<Address>,
+        _first_block: BlockNumber,
+    ) -> ProviderResult<()> {
+        Ok(())
+    }
+
+    fn save_call_index_head(&self, _head: BlockNumHash) -> ProviderResult<()> {
+        Ok(())
+    }
+}
+
+impl<C: Send + Sync, N: NodePrimitives> PreimageReader for NoopProvider<C, N> {
+    fn preimage(&self, _hash: B256) -> ProviderResult<Option<Bytes>> {
+        Ok(None)
+    }
+}
+
 impl<C: Send + Sync, N: NodePrimitives> PruneCheckpointReader for NoopProvider<C, N> {
     fn get_prune_checkpoint(
         &self,
@@ -628,3 +723,31 @@ impl<ChainSpec: Send + Sync, N: NodePrimitives> DatabaseProviderFactory
         Ok(self.clone())
     }
 }
+
+#[cfg(feature = "db-api")]
+impl<ChainSpec: Send + Sync, N: NodePrimitives> RawDatabaseReader for NoopProvider<ChainSpec, N> {
+    fn raw_table_value(
+        &self,
+        _table: Tables,
+        _key: &[u8],
+        _subkey: Option<&[u8]>,
+    ) -> ProviderResult<Option<Bytes>> {
+        Ok(None)
+    }
+
+    fn raw_static_file_rows(
+        &self,
+        _segment: StaticFileSegment,
+        _block: BlockNumber,
+    ) -> ProviderResult<Vec<Vec<Bytes>>> {
+        Ok(Vec::new())
+    }
+
+    fn database_stats(&self) -> ProviderResult<DatabaseStats> {
+        Ok(Default::default())
+    }
+
+    fn static_file_stats(&self) -> ProviderResult<Vec<StaticFileStats>> {
+        Ok(Vec::new())
+    }
+}
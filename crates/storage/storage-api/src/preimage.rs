@@ -0,0 +1,22 @@
+use alloy_primitives::{Bytes, B256};
+use reth_storage_errors::provider::ProviderResult;
+
+/// The trait for fetching keccak256 preimages of hashed addresses and storage keys.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait PreimageReader: Send + Sync {
+    /// Returns the preimage of the given keccak256 hash, if it was recorded.
+    fn preimage(&self, hash: B256) -> ProviderResult<Option<Bytes>>;
+}
+
+/// The trait for recording keccak256 preimages of hashed addresses and storage keys.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait PreimageWriter: Send + Sync {
+    /// Returns `true` if preimages of hashed keys should be recorded.
+    fn records_preimages(&self) -> bool;
+
+    /// Records the given preimages, keyed by their keccak256 hash.
+    fn write_preimages<I, T>(&self, preimages: I) -> ProviderResult<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>;
+}
@@ -0,0 +1,37 @@
+use alloc::vec::Vec;
+use alloy_primitives::{BlockNumber, Bytes};
+use reth_db_api::{database_metrics::DatabaseStats, Tables};
+use reth_static_file_types::{StaticFileSegment, StaticFileStats};
+use reth_storage_errors::provider::ProviderResult;
+
+/// The trait for read-only access to the raw contents and statistics of the database and the
+/// static files.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait RawDatabaseReader: Send + Sync {
+    /// Returns the raw value stored under the encoded `key` in the given table.
+    ///
+    /// For dupsort tables, `subkey` selects the first duplicate whose value starts with the
+    /// encoded subkey. Without a subkey, the first duplicate of the key is returned.
+    fn raw_table_value(
+        &self,
+        table: Tables,
+        key: &[u8],
+        subkey: Option<&[u8]>,
+    ) -> ProviderResult<Option<Bytes>>;
+
+    /// Returns the raw columns of all rows of the given block in the static file segment.
+    ///
+    /// For transaction based segments, these are the rows of all transactions of the block.
+    /// Returns an empty list if the block is not in the static files.
+    fn raw_static_file_rows(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<Vec<Bytes>>>;
+
+    /// Returns the page statistics of the database and all of its tables.
+    fn database_stats(&self) -> ProviderResult<DatabaseStats>;
+
+    /// Returns the statistics of all static files, ordered by segment and block range.
+    fn static_file_stats(&self) -> ProviderResult<Vec<StaticFileStats>>;
+}
@@ -1,5 +1,6 @@
 use alloc::vec::Vec;
-use alloy_primitives::{map::B256Map, Address, Bytes, B256};
+use alloy_primitives::{map::B256Map, Address, Bytes, B256, U256};
+use reth_primitives_traits::Account;
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie_common::{
     updates::{StorageTrieUpdates, TrieUpdates},
@@ -89,6 +90,42 @@ pub trait StateProofProvider: Send + Sync {
     fn witness(&self, input: TrieInput, target: HashedPostState) -> ProviderResult<Vec<Bytes>>;
 }
 
+/// A type that can page through the hashed state in the order of the hashed keys.
+///
+/// Unlike the other state provider traits, accounts and storages are identified by their hashed
+/// address, since the preimages of the hashed keys are generally not known when iterating the
+/// hashed state.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait HashedStateRangeProvider: Send + Sync {
+    /// Returns up to `limit` accounts of the `HashedPostState` on top of the current state,
+    /// starting at the first hashed address that is greater than or equal to `start`.
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>>;
+
+    /// Returns up to `limit` storage slots of the `HashedStorage` for target hashed address on top
+    /// of the current state, starting at the first hashed slot that is greater than or equal to
+    /// `start`.
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>>;
+
+    /// Returns the storage root of the `HashedStorage` for target hashed address on top of the
+    /// current state.
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256>;
+}
+
 /// Trie Writer
 #[auto_impl::auto_impl(&, Arc, Box)]
 pub trait TrieWriter: Send + Sync {
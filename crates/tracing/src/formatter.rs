@@ -2,7 +2,7 @@ use crate::layers::BoxedLayer;
 use clap::ValueEnum;
 use std::{fmt, fmt::Display};
 use tracing_appender::non_blocking::NonBlocking;
-use tracing_subscriber::{EnvFilter, Layer, Registry};
+use tracing_subscriber::{layer::Filter, Layer, Registry};
 
 /// Represents the logging format.
 ///
@@ -31,18 +31,21 @@ impl LogFormat {
     /// along with additional configurations for filtering and output.
     ///
     /// # Arguments
-    /// * `filter` - An `EnvFilter` used to determine which log records to output.
+    /// * `filter` - A filter, e.g. an `EnvFilter`, used to determine which log records to output.
     /// * `color` - An optional string that enables or disables ANSI color codes in the logs.
     /// * `file_writer` - An optional `NonBlocking` writer for directing logs to a file.
     ///
     /// # Returns
     /// A `BoxedLayer<Registry>` that can be added to a tracing subscriber.
-    pub fn apply(
+    pub fn apply<F>(
         &self,
-        filter: EnvFilter,
+        filter: F,
         color: Option<String>,
         file_writer: Option<NonBlocking>,
-    ) -> BoxedLayer<Registry> {
+    ) -> BoxedLayer<Registry>
+    where
+        F: Filter<Registry> + Send + Sync + 'static,
+    {
         let ansi = if let Some(color) = color {
             std::env::var("RUST_LOG_STYLE").map(|val| val != "never").unwrap_or(color != "never")
         } else {
@@ -54,7 +57,7 @@ impl LogFormat {
             .unwrap_or_else(|_|
                 // If `RUST_LOG_TARGET` is not set, show target in logs only if the max enabled
                 // level is higher than INFO (DEBUG, TRACE)
-                filter.max_level_hint().is_none_or(|max_level| max_level > tracing::Level::INFO));
+                Filter::max_level_hint(&filter).is_none_or(|max_level| max_level > tracing::Level::INFO));
 
         match self {
             Self::Json => {
@@ -5,9 +5,9 @@ use std::{
 
 use rolling_file::{RollingConditionBasic, RollingFileAppender};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{filter::Directive, EnvFilter, Layer, Registry};
+use tracing_subscriber::{filter::Directive, reload, EnvFilter, Layer, Registry};
 
-use crate::formatter::LogFormat;
+use crate::{formatter::LogFormat, log_filter::ReloadableFilter};
 
 /// A worker guard returned by the file layer.
 ///
@@ -37,11 +37,16 @@ const DEFAULT_ENV_FILTER_DIRECTIVES: [&str; 5] = [
 #[derive(Default)]
 pub struct Layers {
     inner: Vec<BoxedLayer<Registry>>,
+    /// Filters of the stdout and file layers that can be changed at runtime.
+    reloadable_filters: Vec<ReloadableFilter>,
 }
 
 impl fmt::Debug for Layers {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Layers").field("layers_count", &self.inner.len()).finish()
+        f.debug_struct("Layers")
+            .field("layers_count", &self.inner.len())
+            .field("reloadable_filters", &self.reloadable_filters)
+            .finish()
     }
 }
 
@@ -64,6 +69,27 @@ impl Layers {
         self.inner
     }
 
+    /// Takes the filters of the layers that can be changed at runtime.
+    pub(crate) fn take_reloadable_filters(&mut self) -> Vec<ReloadableFilter> {
+        std::mem::take(&mut self.reloadable_filters)
+    }
+
+    /// Wraps the filter built from the given directives so that it can be changed at runtime.
+    fn reloadable_filter(
+        &mut self,
+        default_directive: Option<Directive>,
+        filters: &str,
+    ) -> eyre::Result<reload::Layer<EnvFilter, Registry>> {
+        let env_filter = build_env_filter(default_directive.clone(), filters)?;
+        let (filter, handle) = reload::Layer::new(env_filter);
+        self.reloadable_filters.push(ReloadableFilter {
+            default_directive,
+            filters: filters.to_string(),
+            handle,
+        });
+        Ok(filter)
+    }
+
     /// Adds a journald layer to the layers collection.
     ///
     /// # Arguments
@@ -98,7 +124,7 @@ impl Layers {
         filters: &str,
         color: Option<String>,
     ) -> eyre::Result<()> {
-        let filter = build_env_filter(Some(default_directive), filters)?;
+        let filter = self.reloadable_filter(Some(default_directive), filters)?;
         let layer = format.apply(filter, color, None);
         self.add_layer(layer);
         Ok(())
@@ -120,7 +146,7 @@ impl Layers {
         file_info: FileInfo,
     ) -> eyre::Result<FileWorkerGuard> {
         let (writer, guard) = file_info.create_log_writer();
-        let file_filter = build_env_filter(None, filter)?;
+        let file_filter = self.reloadable_filter(None, filter)?;
         let layer = format.apply(file_filter, None, Some(writer));
         self.add_layer(layer);
         Ok(guard)
@@ -184,7 +210,7 @@ impl FileInfo {
 ///
 /// # Returns
 /// An `eyre::Result<EnvFilter>` that can be used to configure a tracing subscriber.
-fn build_env_filter(
+pub(crate) fn build_env_filter(
     default_directive: Option<Directive>,
     directives: &str,
 ) -> eyre::Result<EnvFilter> {
@@ -51,10 +51,12 @@ pub use tracing_subscriber;
 // Re-export our types
 pub use formatter::LogFormat;
 pub use layers::{FileInfo, FileWorkerGuard, Layers};
+pub use log_filter::{log_filter_handle, LogFilterHandle};
 pub use test_tracer::TestTracer;
 
 mod formatter;
 mod layers;
+mod log_filter;
 mod test_tracer;
 
 use tracing::level_filters::LevelFilter;
@@ -224,9 +226,13 @@ impl Tracer for RethTracer {
             None
         };
 
+        let reloadable_filters = layers.take_reloadable_filters();
+
         // The error is returned if the global default subscriber is already set,
         // so it's safe to ignore it
-        let _ = tracing_subscriber::registry().with(layers.into_inner()).try_init();
+        if tracing_subscriber::registry().with(layers.into_inner()).try_init().is_ok() {
+            log_filter::install_log_filter_handle(LogFilterHandle::new(reloadable_filters));
+        }
         Ok(file_guard)
     }
 }
@@ -0,0 +1,140 @@
+use std::sync::{Mutex, OnceLock};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{filter::Directive, reload, EnvFilter, Registry};
+
+use crate::layers::build_env_filter;
+
+/// The handle of the globally installed tracing subscriber, see [`log_filter_handle`].
+static LOG_FILTER_HANDLE: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// Returns the [`LogFilterHandle`] of the global tracing subscriber.
+///
+/// Returns `None` if the global subscriber was not installed by [`RethTracer`](crate::RethTracer).
+pub fn log_filter_handle() -> Option<&'static LogFilterHandle> {
+    LOG_FILTER_HANDLE.get()
+}
+
+/// Installs the handle of the global tracing subscriber.
+pub(crate) fn install_log_filter_handle(handle: LogFilterHandle) {
+    let _ = LOG_FILTER_HANDLE.set(handle);
+}
+
+/// Handle to change the filters of the stdout and file layers at runtime.
+///
+/// The filters are rebuilt from the directives the layers were configured with, followed by the
+/// verbosity and the per-target directives set through this handle.
+#[derive(Debug)]
+pub struct LogFilterHandle {
+    inner: Mutex<LogFilterState>,
+}
+
+impl LogFilterHandle {
+    /// Creates a new handle for the given reloadable filters.
+    pub(crate) fn new(filters: Vec<ReloadableFilter>) -> Self {
+        Self { inner: Mutex::new(LogFilterState { filters, ..Default::default() }) }
+    }
+
+    /// Sets the global log level of all reloadable layers.
+    ///
+    /// Per-target directives still take precedence for their targets.
+    pub fn set_verbosity(&self, level: LevelFilter) -> eyre::Result<()> {
+        let mut state = self.inner.lock().map_err(|_| eyre::eyre!("log filter lock poisoned"))?;
+        state.verbosity = Some(level);
+        state.reload()
+    }
+
+    /// Replaces the per-target directives of all reloadable layers, e.g. `engine::tree=trace`.
+    ///
+    /// Multiple directives are separated by commas. An empty string removes all directives that
+    /// were previously set through this handle.
+    pub fn set_vmodule(&self, directives: &str) -> eyre::Result<()> {
+        // validate the directives before touching the filters
+        for directive in directives.split(',').filter(|d| !d.is_empty()) {
+            directive.parse::<Directive>()?;
+        }
+
+        let mut state = self.inner.lock().map_err(|_| eyre::eyre!("log filter lock poisoned"))?;
+        state.vmodule = directives.to_string();
+        state.reload()
+    }
+
+    /// Returns the global log level set through [`Self::set_verbosity`], if any.
+    pub fn verbosity(&self) -> Option<LevelFilter> {
+        self.inner.lock().ok().and_then(|state| state.verbosity)
+    }
+
+    /// Returns the per-target directives set through [`Self::set_vmodule`].
+    pub fn vmodule(&self) -> String {
+        self.inner.lock().map(|state| state.vmodule.clone()).unwrap_or_default()
+    }
+}
+
+/// The state behind a [`LogFilterHandle`].
+#[derive(Debug, Default)]
+struct LogFilterState {
+    filters: Vec<ReloadableFilter>,
+    verbosity: Option<LevelFilter>,
+    vmodule: String,
+}
+
+impl LogFilterState {
+    /// Rebuilds and swaps the filters of all layers.
+    fn reload(&self) -> eyre::Result<()> {
+        for filter in &self.filters {
+            let mut env_filter =
+                build_env_filter(filter.default_directive.clone(), &filter.filters)?;
+            if let Some(verbosity) = self.verbosity {
+                env_filter = env_filter.add_directive(verbosity.into());
+            }
+            for directive in self.vmodule.split(',').filter(|d| !d.is_empty()) {
+                env_filter = env_filter.add_directive(directive.parse()?);
+            }
+            filter.handle.reload(env_filter)?;
+        }
+        Ok(())
+    }
+}
+
+/// The [`EnvFilter`] of a layer that can be swapped at runtime, together with the configuration
+/// it was built from.
+#[derive(Debug)]
+pub(crate) struct ReloadableFilter {
+    /// The default directive of the layer.
+    pub(crate) default_directive: Option<Directive>,
+    /// Additional filter directives of the layer.
+    pub(crate) filters: String,
+    /// Handle to swap the filter of the layer.
+    pub(crate) handle: reload::Handle<EnvFilter, Registry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layers, LogFormat};
+    use tracing::Level;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn reload_verbosity_and_vmodule() {
+        let mut layers = Layers::new();
+        layers.stdout(LogFormat::Terminal, LevelFilter::INFO.into(), "", None).unwrap();
+        let handle = LogFilterHandle::new(layers.take_reloadable_filters());
+        let subscriber = tracing_subscriber::registry().with(layers.into_inner());
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(!tracing::enabled!(target: "engine::tree", Level::DEBUG));
+
+            handle.set_vmodule("engine::tree=trace").unwrap();
+            assert!(tracing::enabled!(target: "engine::tree", Level::TRACE));
+            assert!(!tracing::enabled!(target: "net", Level::DEBUG));
+
+            handle.set_verbosity(LevelFilter::DEBUG).unwrap();
+            assert!(tracing::enabled!(target: "net", Level::DEBUG));
+            assert!(tracing::enabled!(target: "engine::tree", Level::TRACE));
+
+            assert!(handle.set_vmodule("engine::tree=loud").is_err());
+            assert_eq!(handle.vmodule(), "engine::tree=trace");
+        });
+    }
+}
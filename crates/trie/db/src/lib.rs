@@ -7,6 +7,7 @@ mod hashed_cursor;
 mod prefix_set;
 mod proof;
 mod state;
+mod state_range;
 mod storage;
 mod trie_cursor;
 mod witness;
@@ -18,6 +19,7 @@ pub use hashed_cursor::{
 pub use prefix_set::PrefixSetLoader;
 pub use proof::{DatabaseProof, DatabaseStorageProof};
 pub use state::{DatabaseHashedPostState, DatabaseStateRoot};
+pub use state_range::DatabaseHashedStateRange;
 pub use storage::{DatabaseHashedStorage, DatabaseStorageRoot};
 pub use trie_cursor::{
     DatabaseAccountTrieCursor, DatabaseStorageTrieCursor, DatabaseTrieCursorFactory,
@@ -0,0 +1,73 @@
+use crate::DatabaseHashedCursorFactory;
+use alloy_primitives::{B256, U256};
+use reth_db_api::{transaction::DbTx, DatabaseError};
+use reth_primitives_traits::Account;
+use reth_trie::{
+    hashed_cursor::HashedPostStateCursorFactory, HashedPostState, HashedStateRange, HashedStorage,
+};
+
+/// Extends [`HashedStateRange`] with operations specific for working with a database transaction.
+pub trait DatabaseHashedStateRange<'a, TX> {
+    /// Create a new [`HashedStateRange`] from database transaction.
+    fn from_tx(tx: &'a TX) -> Self;
+
+    /// Returns up to `limit` accounts of the [`HashedPostState`] on top of the database state,
+    /// starting at the first hashed address that is greater than or equal to `start`.
+    fn overlay_accounts(
+        tx: &'a TX,
+        post_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> Result<Vec<(B256, Account)>, DatabaseError>;
+
+    /// Returns up to `limit` storage slots of the [`HashedStorage`] on top of the database storage
+    /// of the given hashed address, starting at the first hashed slot that is greater than or equal
+    /// to `start`.
+    fn overlay_storage(
+        tx: &'a TX,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> Result<Vec<(B256, U256)>, DatabaseError>;
+}
+
+impl<'a, TX: DbTx> DatabaseHashedStateRange<'a, TX>
+    for HashedStateRange<DatabaseHashedCursorFactory<'a, TX>>
+{
+    fn from_tx(tx: &'a TX) -> Self {
+        Self::new(DatabaseHashedCursorFactory::new(tx))
+    }
+
+    fn overlay_accounts(
+        tx: &'a TX,
+        post_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> Result<Vec<(B256, Account)>, DatabaseError> {
+        let state_sorted = post_state.into_sorted();
+        Self::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(tx),
+                &state_sorted,
+            ))
+            .accounts(start, limit)
+    }
+
+    fn overlay_storage(
+        tx: &'a TX,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> Result<Vec<(B256, U256)>, DatabaseError> {
+        let state_sorted =
+            HashedPostState::from_hashed_storage(hashed_address, hashed_storage).into_sorted();
+        Self::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(tx),
+                &state_sorted,
+            ))
+            .storage(hashed_address, start, limit)
+    }
+}
@@ -25,6 +25,14 @@ pub trait DatabaseStorageRoot<'a, TX> {
         address: Address,
         hashed_storage: HashedStorage,
     ) -> Result<B256, StorageRootError>;
+
+    /// Calculates the storage root for this [`HashedStorage`] of the given hashed address and
+    /// returns it.
+    fn overlay_root_hashed(
+        tx: &'a TX,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> Result<B256, StorageRootError>;
 }
 
 /// Extends [`HashedStorage`] with operations specific for working with a database transaction.
@@ -77,6 +85,25 @@ impl<'a, TX: DbTx> DatabaseStorageRoot<'a, TX>
         )
         .root()
     }
+
+    fn overlay_root_hashed(
+        tx: &'a TX,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> Result<B256, StorageRootError> {
+        let prefix_set = hashed_storage.construct_prefix_set().freeze();
+        let state_sorted =
+            HashedPostState::from_hashed_storage(hashed_address, hashed_storage).into_sorted();
+        StorageRoot::new_hashed(
+            DatabaseTrieCursorFactory::new(tx),
+            HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(tx), &state_sorted),
+            hashed_address,
+            prefix_set,
+            #[cfg(feature = "metrics")]
+            TrieRootMetrics::new(reth_trie::TrieType::Storage),
+        )
+        .root()
+    }
 }
 
 impl<TX: DbTx> DatabaseHashedStorage<TX> for HashedStorage {
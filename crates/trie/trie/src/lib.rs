@@ -35,6 +35,10 @@ pub mod proof;
 /// Trie witness generation.
 pub mod witness;
 
+/// Paging through the hashed state.
+mod state_range;
+pub use state_range::HashedStateRange;
+
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
 pub use trie::{StateRoot, StorageRoot, TrieType};
@@ -0,0 +1,112 @@
+use crate::hashed_cursor::{HashedCursor, HashedCursorFactory};
+use alloy_primitives::{B256, U256};
+use reth_primitives_traits::Account;
+use reth_storage_errors::db::DatabaseError;
+
+/// Pages through the hashed state in the order of the hashed keys.
+#[derive(Debug)]
+pub struct HashedStateRange<H> {
+    /// The factory for hashed cursors.
+    hashed_cursor_factory: H,
+}
+
+impl<H> HashedStateRange<H> {
+    /// Create a new [`HashedStateRange`] instance.
+    pub const fn new(hashed_cursor_factory: H) -> Self {
+        Self { hashed_cursor_factory }
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> HashedStateRange<HF> {
+        HashedStateRange { hashed_cursor_factory }
+    }
+}
+
+impl<H: HashedCursorFactory> HashedStateRange<H> {
+    /// Returns up to `limit` accounts, starting at the first hashed address that is greater than or
+    /// equal to `start`.
+    pub fn accounts(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> Result<Vec<(B256, Account)>, DatabaseError> {
+        collect_range(self.hashed_cursor_factory.hashed_account_cursor()?, start, limit)
+    }
+
+    /// Returns up to `limit` storage slots of the given account, starting at the first hashed slot
+    /// that is greater than or equal to `start`.
+    pub fn storage(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> Result<Vec<(B256, U256)>, DatabaseError> {
+        collect_range(
+            self.hashed_cursor_factory.hashed_storage_cursor(hashed_address)?,
+            start,
+            limit,
+        )
+    }
+}
+
+fn collect_range<C: HashedCursor>(
+    mut cursor: C,
+    start: B256,
+    limit: usize,
+) -> Result<Vec<(B256, C::Value)>, DatabaseError> {
+    let mut entries = Vec::new();
+    if limit == 0 {
+        return Ok(entries)
+    }
+
+    let mut next = cursor.seek(start)?;
+    while let Some(entry) = next {
+        entries.push(entry);
+        if entries.len() == limit {
+            break
+        }
+        next = cursor.next()?;
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashed_cursor::mock::MockHashedCursorFactory;
+    use alloy_primitives::map::B256Map;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn pages_through_accounts_and_storage() {
+        let accounts = (1..=5u8)
+            .map(|i| (B256::with_last_byte(i), Account { nonce: i as u64, ..Default::default() }))
+            .collect::<BTreeMap<_, _>>();
+        let hashed_address = B256::with_last_byte(1);
+        let storage = (1..=3u8)
+            .map(|i| (B256::with_last_byte(i * 2), U256::from(i)))
+            .collect::<BTreeMap<_, _>>();
+        let range = HashedStateRange::new(MockHashedCursorFactory::new(
+            accounts,
+            B256Map::from_iter([(hashed_address, storage)]),
+        ));
+
+        let page = range.accounts(B256::with_last_byte(2), 2).unwrap();
+        assert_eq!(
+            page.iter().map(|(key, account)| (key[31], account.nonce)).collect::<Vec<_>>(),
+            vec![(2, 2), (3, 3)]
+        );
+        assert_eq!(range.accounts(B256::with_last_byte(5), 10).unwrap().len(), 1);
+        assert!(range.accounts(B256::ZERO, 0).unwrap().is_empty());
+
+        let slots = range.storage(hashed_address, B256::with_last_byte(3), 10).unwrap();
+        assert_eq!(
+            slots,
+            vec![
+                (B256::with_last_byte(4), U256::from(2)),
+                (B256::with_last_byte(6), U256::from(3))
+            ]
+        );
+    }
+}